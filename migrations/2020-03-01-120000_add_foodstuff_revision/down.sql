@@ -0,0 +1,5 @@
+DROP INDEX foodstuff_app_user_id_revision_index;
+DROP TRIGGER foodstuff_bump_revision ON foodstuff;
+DROP FUNCTION foodstuff_bump_revision();
+ALTER TABLE foodstuff DROP COLUMN revision;
+DROP SEQUENCE foodstuff_revision_seq;
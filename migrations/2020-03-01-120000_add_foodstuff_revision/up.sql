@@ -0,0 +1,17 @@
+CREATE SEQUENCE foodstuff_revision_seq;
+ALTER TABLE foodstuff ADD COLUMN revision BIGINT NOT NULL DEFAULT nextval('foodstuff_revision_seq');
+
+CREATE FUNCTION foodstuff_bump_revision() RETURNS trigger AS $$
+BEGIN
+    NEW.revision := nextval('foodstuff_revision_seq');
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER foodstuff_bump_revision BEFORE UPDATE ON foodstuff
+    FOR EACH ROW EXECUTE PROCEDURE foodstuff_bump_revision();
+
+GRANT SELECT ON TABLE foodstuff_revision_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE foodstuff_revision_seq TO recipe_calculator_client;
+
+CREATE INDEX foodstuff_app_user_id_revision_index ON foodstuff(app_user_id, revision);
@@ -0,0 +1,3 @@
+DROP INDEX history_entry_foodstuff_id_index;
+DROP INDEX history_entry_app_user_id_time_index;
+DROP TABLE history_entry;
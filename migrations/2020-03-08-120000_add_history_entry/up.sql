@@ -0,0 +1,17 @@
+CREATE TABLE history_entry (
+  id SERIAL PRIMARY KEY,
+  app_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  app_user_history_entry_id INTEGER NOT NULL,
+  foodstuff_id INTEGER NOT NULL REFERENCES foodstuff(id),
+  mass INTEGER NOT NULL,
+  time BIGINT NOT NULL,
+  unique(app_user_id, app_user_history_entry_id));
+
+GRANT SELECT ON TABLE history_entry TO recipe_calculator_client;
+GRANT INSERT ON TABLE history_entry TO recipe_calculator_client;
+GRANT DELETE ON TABLE history_entry TO recipe_calculator_client;
+GRANT SELECT ON TABLE history_entry_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE history_entry_id_seq TO recipe_calculator_client;
+
+CREATE INDEX history_entry_app_user_id_time_index ON history_entry(app_user_id, time);
+CREATE INDEX history_entry_foodstuff_id_index ON history_entry(foodstuff_id);
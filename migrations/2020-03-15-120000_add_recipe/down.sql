@@ -0,0 +1,4 @@
+DROP INDEX recipe_ingredient_foodstuff_id_index;
+DROP INDEX recipe_ingredient_recipe_id_index;
+DROP TABLE recipe_ingredient;
+DROP TABLE recipe;
@@ -0,0 +1,28 @@
+CREATE TABLE recipe (
+  id SERIAL PRIMARY KEY,
+  app_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  app_user_recipe_id INTEGER NOT NULL,
+  name TEXT NOT NULL,
+  unique(app_user_id, app_user_recipe_id));
+
+GRANT SELECT ON TABLE recipe TO recipe_calculator_client;
+GRANT INSERT ON TABLE recipe TO recipe_calculator_client;
+GRANT UPDATE ON TABLE recipe TO recipe_calculator_client;
+GRANT DELETE ON TABLE recipe TO recipe_calculator_client;
+GRANT SELECT ON TABLE recipe_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE recipe_id_seq TO recipe_calculator_client;
+
+CREATE TABLE recipe_ingredient (
+  id SERIAL PRIMARY KEY,
+  recipe_id INTEGER NOT NULL REFERENCES recipe(id),
+  foodstuff_id INTEGER NOT NULL REFERENCES foodstuff(id),
+  weight INTEGER NOT NULL);
+
+GRANT SELECT ON TABLE recipe_ingredient TO recipe_calculator_client;
+GRANT INSERT ON TABLE recipe_ingredient TO recipe_calculator_client;
+GRANT DELETE ON TABLE recipe_ingredient TO recipe_calculator_client;
+GRANT SELECT ON TABLE recipe_ingredient_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE recipe_ingredient_id_seq TO recipe_calculator_client;
+
+CREATE INDEX recipe_ingredient_recipe_id_index ON recipe_ingredient(recipe_id);
+CREATE INDEX recipe_ingredient_foodstuff_id_index ON recipe_ingredient(foodstuff_id);
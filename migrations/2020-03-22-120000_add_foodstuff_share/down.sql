@@ -0,0 +1,3 @@
+DROP INDEX foodstuff_share_partner_user_id_index;
+DROP INDEX foodstuff_share_owner_user_id_index;
+DROP TABLE foodstuff_share;
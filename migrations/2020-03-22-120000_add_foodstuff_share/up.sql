@@ -0,0 +1,17 @@
+CREATE TABLE foodstuff_share (
+  id SERIAL PRIMARY KEY,
+  foodstuff_id INTEGER NOT NULL REFERENCES foodstuff(id),
+  owner_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  partner_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  permission INTEGER NOT NULL,
+  unique(foodstuff_id, partner_user_id));
+
+GRANT SELECT ON TABLE foodstuff_share TO recipe_calculator_client;
+GRANT INSERT ON TABLE foodstuff_share TO recipe_calculator_client;
+GRANT UPDATE ON TABLE foodstuff_share TO recipe_calculator_client;
+GRANT DELETE ON TABLE foodstuff_share TO recipe_calculator_client;
+GRANT SELECT ON TABLE foodstuff_share_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE foodstuff_share_id_seq TO recipe_calculator_client;
+
+CREATE INDEX foodstuff_share_owner_user_id_index ON foodstuff_share(owner_user_id);
+CREATE INDEX foodstuff_share_partner_user_id_index ON foodstuff_share(partner_user_id);
@@ -0,0 +1,6 @@
+DROP INDEX meal_plan_item_recipe_id_index;
+DROP INDEX meal_plan_item_foodstuff_id_index;
+DROP INDEX meal_plan_item_meal_plan_id_index;
+DROP TABLE meal_plan_item;
+DROP INDEX meal_plan_partner2_user_id_index;
+DROP TABLE meal_plan;
@@ -0,0 +1,36 @@
+CREATE TABLE meal_plan (
+  id SERIAL PRIMARY KEY,
+  partner1_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  partner2_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  version BIGINT NOT NULL,
+  unique(partner1_user_id, partner2_user_id),
+  CHECK (partner1_user_id < partner2_user_id));
+
+GRANT SELECT ON TABLE meal_plan TO recipe_calculator_client;
+GRANT INSERT ON TABLE meal_plan TO recipe_calculator_client;
+GRANT UPDATE ON TABLE meal_plan TO recipe_calculator_client;
+GRANT DELETE ON TABLE meal_plan TO recipe_calculator_client;
+GRANT SELECT ON TABLE meal_plan_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE meal_plan_id_seq TO recipe_calculator_client;
+
+CREATE INDEX meal_plan_partner2_user_id_index ON meal_plan(partner2_user_id);
+
+CREATE TABLE meal_plan_item (
+  id SERIAL PRIMARY KEY,
+  meal_plan_id INTEGER NOT NULL REFERENCES meal_plan(id),
+  day INTEGER NOT NULL,
+  meal_type INTEGER NOT NULL,
+  foodstuff_id INTEGER REFERENCES foodstuff(id),
+  recipe_id INTEGER REFERENCES recipe(id),
+  weight INTEGER NOT NULL,
+  CHECK ((foodstuff_id IS NULL) <> (recipe_id IS NULL)));
+
+GRANT SELECT ON TABLE meal_plan_item TO recipe_calculator_client;
+GRANT INSERT ON TABLE meal_plan_item TO recipe_calculator_client;
+GRANT DELETE ON TABLE meal_plan_item TO recipe_calculator_client;
+GRANT SELECT ON TABLE meal_plan_item_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE meal_plan_item_id_seq TO recipe_calculator_client;
+
+CREATE INDEX meal_plan_item_meal_plan_id_index ON meal_plan_item(meal_plan_id);
+CREATE INDEX meal_plan_item_foodstuff_id_index ON meal_plan_item(foodstuff_id);
+CREATE INDEX meal_plan_item_recipe_id_index ON meal_plan_item(recipe_id);
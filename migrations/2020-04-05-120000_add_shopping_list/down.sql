@@ -0,0 +1,5 @@
+DROP INDEX shopping_list_share_partner_user_id_index;
+DROP TABLE shopping_list_share;
+DROP INDEX shopping_list_item_foodstuff_id_index;
+DROP TABLE shopping_list_item;
+DROP TABLE shopping_list;
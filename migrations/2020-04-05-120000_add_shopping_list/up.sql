@@ -0,0 +1,44 @@
+CREATE TABLE shopping_list (
+  id SERIAL PRIMARY KEY,
+  app_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  app_user_shopping_list_id INTEGER NOT NULL,
+  name TEXT NOT NULL,
+  unique(app_user_id, app_user_shopping_list_id));
+
+GRANT SELECT ON TABLE shopping_list TO recipe_calculator_client;
+GRANT INSERT ON TABLE shopping_list TO recipe_calculator_client;
+GRANT DELETE ON TABLE shopping_list TO recipe_calculator_client;
+GRANT SELECT ON TABLE shopping_list_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE shopping_list_id_seq TO recipe_calculator_client;
+
+CREATE TABLE shopping_list_item (
+  id SERIAL PRIMARY KEY,
+  shopping_list_id INTEGER NOT NULL REFERENCES shopping_list(id),
+  list_item_id INTEGER NOT NULL,
+  foodstuff_id INTEGER NOT NULL REFERENCES foodstuff(id),
+  weight INTEGER NOT NULL,
+  is_checked BOOLEAN NOT NULL,
+  unique(shopping_list_id, list_item_id));
+
+GRANT SELECT ON TABLE shopping_list_item TO recipe_calculator_client;
+GRANT INSERT ON TABLE shopping_list_item TO recipe_calculator_client;
+GRANT UPDATE ON TABLE shopping_list_item TO recipe_calculator_client;
+GRANT DELETE ON TABLE shopping_list_item TO recipe_calculator_client;
+GRANT SELECT ON TABLE shopping_list_item_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE shopping_list_item_id_seq TO recipe_calculator_client;
+
+CREATE INDEX shopping_list_item_foodstuff_id_index ON shopping_list_item(foodstuff_id);
+
+CREATE TABLE shopping_list_share (
+  id SERIAL PRIMARY KEY,
+  shopping_list_id INTEGER NOT NULL REFERENCES shopping_list(id),
+  partner_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  unique(shopping_list_id, partner_user_id));
+
+GRANT SELECT ON TABLE shopping_list_share TO recipe_calculator_client;
+GRANT INSERT ON TABLE shopping_list_share TO recipe_calculator_client;
+GRANT DELETE ON TABLE shopping_list_share TO recipe_calculator_client;
+GRANT SELECT ON TABLE shopping_list_share_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE shopping_list_share_id_seq TO recipe_calculator_client;
+
+CREATE INDEX shopping_list_share_partner_user_id_index ON shopping_list_share(partner_user_id);
@@ -0,0 +1 @@
+REVOKE SELECT ON TABLE __diesel_schema_migrations FROM recipe_calculator_client;
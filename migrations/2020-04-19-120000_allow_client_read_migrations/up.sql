@@ -0,0 +1 @@
+GRANT SELECT ON TABLE __diesel_schema_migrations TO recipe_calculator_client;
@@ -0,0 +1,12 @@
+CREATE TABLE failed_pairing_attempts (
+  id SERIAL PRIMARY KEY,
+  app_user_id INTEGER UNIQUE NOT NULL REFERENCES app_user(id),
+  attempts_count INTEGER NOT NULL,
+  last_attempt_time BIGINT NOT NULL);
+
+GRANT SELECT ON TABLE failed_pairing_attempts TO recipe_calculator_client;
+GRANT INSERT ON TABLE failed_pairing_attempts TO recipe_calculator_client;
+GRANT UPDATE ON TABLE failed_pairing_attempts TO recipe_calculator_client;
+GRANT DELETE ON TABLE failed_pairing_attempts TO recipe_calculator_client;
+GRANT SELECT ON TABLE failed_pairing_attempts_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE failed_pairing_attempts_id_seq TO recipe_calculator_client;
@@ -0,0 +1,10 @@
+DROP INDEX fcm_token_app_user_id_index;
+
+-- Only the first token of each user is kept
+DELETE FROM fcm_token WHERE id NOT IN (SELECT min(id) FROM fcm_token GROUP BY app_user_id);
+ALTER TABLE fcm_token DROP COLUMN device_id;
+ALTER TABLE fcm_token ADD CONSTRAINT fcm_token_app_user_id_key UNIQUE (app_user_id);
+
+DELETE FROM device WHERE legacy;
+DROP INDEX device_legacy_app_user_id_index;
+ALTER TABLE device DROP COLUMN legacy;
@@ -0,0 +1,17 @@
+ALTER TABLE fcm_token DROP CONSTRAINT fcm_token_app_user_id_key;
+ALTER TABLE fcm_token ADD COLUMN device_id INTEGER UNIQUE REFERENCES device(id);
+
+-- Legacy devices own tokens of clients which don't know about devices, see device::new_legacy
+ALTER TABLE device ADD COLUMN legacy BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE UNIQUE INDEX device_legacy_app_user_id_index ON device(app_user_id) WHERE legacy;
+
+-- Tokens set before devices were known (at most 1 per user) are moved
+-- to legacy devices of their users
+INSERT INTO device(uuid, app_user_id, legacy)
+  SELECT md5(random()::text || clock_timestamp()::text || id)::uuid, app_user_id, TRUE
+  FROM fcm_token;
+UPDATE fcm_token SET device_id = device.id
+  FROM device WHERE device.legacy AND device.app_user_id = fcm_token.app_user_id;
+ALTER TABLE fcm_token ALTER COLUMN device_id SET NOT NULL;
+
+CREATE INDEX fcm_token_app_user_id_index ON fcm_token(app_user_id);
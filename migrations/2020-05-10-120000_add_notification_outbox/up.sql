@@ -0,0 +1,22 @@
+CREATE TABLE notification_outbox (
+  id SERIAL PRIMARY KEY,
+  app_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  device_id INTEGER NOT NULL REFERENCES device(id),
+  msg VARCHAR NOT NULL,
+  state INTEGER NOT NULL,
+  attempts_count INTEGER NOT NULL,
+  next_attempt_time BIGINT NOT NULL,
+  last_error VARCHAR,
+  creation_time BIGINT NOT NULL);
+
+CREATE INDEX notification_outbox_state_next_attempt_time_index
+  ON notification_outbox(state, next_attempt_time);
+CREATE INDEX notification_outbox_app_user_id_index ON notification_outbox(app_user_id);
+CREATE INDEX notification_outbox_device_id_index ON notification_outbox(device_id);
+
+GRANT SELECT ON TABLE notification_outbox TO recipe_calculator_client;
+GRANT INSERT ON TABLE notification_outbox TO recipe_calculator_client;
+GRANT UPDATE ON TABLE notification_outbox TO recipe_calculator_client;
+GRANT DELETE ON TABLE notification_outbox TO recipe_calculator_client;
+GRANT SELECT ON TABLE notification_outbox_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE notification_outbox_id_seq TO recipe_calculator_client;
@@ -0,0 +1,13 @@
+CREATE TABLE inbox_message (
+  id SERIAL PRIMARY KEY,
+  app_user_id INTEGER NOT NULL REFERENCES app_user(id),
+  msg VARCHAR NOT NULL,
+  creation_time BIGINT NOT NULL);
+
+CREATE INDEX inbox_message_app_user_id_index ON inbox_message(app_user_id, id);
+
+GRANT SELECT ON TABLE inbox_message TO recipe_calculator_client;
+GRANT INSERT ON TABLE inbox_message TO recipe_calculator_client;
+GRANT DELETE ON TABLE inbox_message TO recipe_calculator_client;
+GRANT SELECT ON TABLE inbox_message_id_seq TO recipe_calculator_client;
+GRANT UPDATE ON TABLE inbox_message_id_seq TO recipe_calculator_client;
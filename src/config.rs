@@ -1,4 +1,7 @@
 use crate::error::Error;
+use crate::outside::google_oauth::ServiceAccountKey;
+use crate::server::constants;
+use std::collections::BTreeMap;
 use std::io::Read;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -8,6 +11,136 @@ pub struct Config {
     psql_url_user_server: String,
     psql_url_user_client: String,
     db_connection_attempts_timeout_seconds: i32,
+    /// When set, user credentials are accepted only in the Authorization header
+    /// or in a JSON body, requests with credentials in the query are rejected.
+    #[serde(default)]
+    reject_query_credentials: bool,
+    /// Max number of DB connections (both borrowed and idle) in a connections pool.
+    #[serde(default = "default_db_pool_max_size")]
+    db_pool_max_size: u32,
+    /// How long a borrower waits for a free connection when the pool has reached its max size.
+    #[serde(default = "default_db_pool_borrow_timeout_millis")]
+    db_pool_borrow_timeout_millis: u64,
+    /// Connections which were idle for longer are closed.
+    #[serde(default = "default_db_pool_max_idle_seconds")]
+    db_pool_max_idle_seconds: u64,
+    /// On shutdown, how long in-flight requests are waited for.
+    /// Should be less than the time the server is given to stop before being killed
+    /// (e.g. 10 seconds by default for `docker stop`).
+    #[serde(default = "default_shutdown_drain_timeout_seconds")]
+    shutdown_drain_timeout_seconds: u64,
+    /// Budgets of requests by commands (e.g. "/v1/user/start_pairing"),
+    /// commands without a budget are not limited.
+    #[serde(default = "default_rate_limits")]
+    rate_limits: BTreeMap<String, RateLimit>,
+    /// Header with the IP of a client set by a reverse proxy (e.g. "x-real-ip").
+    /// When not set, the IP of the connection is used.
+    #[serde(default)]
+    client_ip_header: Option<String>,
+    /// After this number of failed attempts to pair by a partner's pairing code,
+    /// the user cannot pair by codes until the lockout ends.
+    #[serde(default = "default_pairing_code_max_failed_attempts")]
+    pairing_code_max_failed_attempts: u32,
+    /// How long a lockout lasts since the last failed attempt.
+    #[serde(default = "default_pairing_code_lockout_seconds")]
+    pairing_code_lockout_seconds: u64,
+    /// Key of the service account notifications are sent by. When set, the FCM HTTP v1 API
+    /// is used, otherwise the legacy API with |fcm_server_token|.
+    #[serde(default)]
+    fcm_service_account_key: Option<ServiceAccountKey>,
+    /// After this number of failed attempts to send a notification,
+    /// the notification is not sent anymore.
+    #[serde(default = "default_notification_max_attempts")]
+    notification_max_attempts: u32,
+    /// Delay before the second attempt to send a notification,
+    /// each next delay is twice as long as the previous one.
+    #[serde(default = "default_notification_retry_base_delay_seconds")]
+    notification_retry_base_delay_seconds: u64,
+    /// How often the queued notifications are checked for being due.
+    #[serde(default = "default_notification_worker_interval_seconds")]
+    notification_worker_interval_seconds: u64,
+}
+
+/// Max number of requests to a command within a period - both by a single user
+/// and from a single IP.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RateLimit {
+    period_seconds: u64,
+    requests_per_user: u32,
+    requests_per_ip: u32,
+}
+
+impl RateLimit {
+    pub fn new(period_seconds: u64, requests_per_user: u32, requests_per_ip: u32) -> RateLimit {
+        RateLimit {
+            period_seconds,
+            requests_per_user,
+            requests_per_ip,
+        }
+    }
+
+    pub fn period_seconds(&self) -> u64 {
+        self.period_seconds
+    }
+
+    pub fn requests_per_user(&self) -> u32 {
+        self.requests_per_user
+    }
+
+    pub fn requests_per_ip(&self) -> u32 {
+        self.requests_per_ip
+    }
+}
+
+fn default_db_pool_max_size() -> u32 {
+    32
+}
+
+fn default_db_pool_borrow_timeout_millis() -> u64 {
+    10_000
+}
+
+fn default_db_pool_max_idle_seconds() -> u64 {
+    10 * 60
+}
+
+fn default_shutdown_drain_timeout_seconds() -> u64 {
+    8
+}
+
+fn default_pairing_code_max_failed_attempts() -> u32 {
+    10
+}
+
+fn default_pairing_code_lockout_seconds() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_notification_max_attempts() -> u32 {
+    10
+}
+
+fn default_notification_retry_base_delay_seconds() -> u64 {
+    30
+}
+
+fn default_notification_worker_interval_seconds() -> u64 {
+    5
+}
+
+/// Pairing commands are limited by default - otherwise pairing codes could be
+/// brute-forced, and the pool of the codes could be drained.
+fn default_rate_limits() -> BTreeMap<String, RateLimit> {
+    let mut rate_limits = BTreeMap::new();
+    rate_limits.insert(
+        constants::CMD_START_PAIRING.to_owned(),
+        RateLimit::new(60, 10, 30),
+    );
+    rate_limits.insert(
+        constants::CMD_PAIRING_REQUEST.to_owned(),
+        RateLimit::new(60, 10, 30),
+    );
+    rate_limits
 }
 
 impl Config {
@@ -24,9 +157,112 @@ impl Config {
             psql_url_user_server,
             psql_url_user_client,
             db_connection_attempts_timeout_seconds,
+            reject_query_credentials: false,
+            db_pool_max_size: default_db_pool_max_size(),
+            db_pool_borrow_timeout_millis: default_db_pool_borrow_timeout_millis(),
+            db_pool_max_idle_seconds: default_db_pool_max_idle_seconds(),
+            shutdown_drain_timeout_seconds: default_shutdown_drain_timeout_seconds(),
+            rate_limits: default_rate_limits(),
+            client_ip_header: None,
+            pairing_code_max_failed_attempts: default_pairing_code_max_failed_attempts(),
+            pairing_code_lockout_seconds: default_pairing_code_lockout_seconds(),
+            fcm_service_account_key: None,
+            notification_max_attempts: default_notification_max_attempts(),
+            notification_retry_base_delay_seconds: default_notification_retry_base_delay_seconds(),
+            notification_worker_interval_seconds: default_notification_worker_interval_seconds(),
         }
     }
 
+    pub fn with_reject_query_credentials(mut self, reject_query_credentials: bool) -> Config {
+        self.reject_query_credentials = reject_query_credentials;
+        self
+    }
+
+    pub fn with_db_pool_max_size(mut self, db_pool_max_size: u32) -> Config {
+        self.db_pool_max_size = db_pool_max_size;
+        self
+    }
+
+    pub fn with_db_pool_borrow_timeout_millis(
+        mut self,
+        db_pool_borrow_timeout_millis: u64,
+    ) -> Config {
+        self.db_pool_borrow_timeout_millis = db_pool_borrow_timeout_millis;
+        self
+    }
+
+    pub fn with_db_pool_max_idle_seconds(mut self, db_pool_max_idle_seconds: u64) -> Config {
+        self.db_pool_max_idle_seconds = db_pool_max_idle_seconds;
+        self
+    }
+
+    pub fn with_shutdown_drain_timeout_seconds(
+        mut self,
+        shutdown_drain_timeout_seconds: u64,
+    ) -> Config {
+        self.shutdown_drain_timeout_seconds = shutdown_drain_timeout_seconds;
+        self
+    }
+
+    /// Sets the budget of |cmd|, or removes it when |rate_limit| is None.
+    pub fn with_rate_limit(mut self, cmd: &str, rate_limit: Option<RateLimit>) -> Config {
+        match rate_limit {
+            Some(rate_limit) => self.rate_limits.insert(cmd.to_owned(), rate_limit),
+            None => self.rate_limits.remove(cmd),
+        };
+        self
+    }
+
+    pub fn with_client_ip_header(mut self, client_ip_header: Option<String>) -> Config {
+        self.client_ip_header = client_ip_header;
+        self
+    }
+
+    pub fn with_pairing_code_max_failed_attempts(
+        mut self,
+        pairing_code_max_failed_attempts: u32,
+    ) -> Config {
+        self.pairing_code_max_failed_attempts = pairing_code_max_failed_attempts;
+        self
+    }
+
+    pub fn with_pairing_code_lockout_seconds(
+        mut self,
+        pairing_code_lockout_seconds: u64,
+    ) -> Config {
+        self.pairing_code_lockout_seconds = pairing_code_lockout_seconds;
+        self
+    }
+
+    pub fn with_fcm_service_account_key(
+        mut self,
+        fcm_service_account_key: Option<ServiceAccountKey>,
+    ) -> Config {
+        self.fcm_service_account_key = fcm_service_account_key;
+        self
+    }
+
+    pub fn with_notification_max_attempts(mut self, notification_max_attempts: u32) -> Config {
+        self.notification_max_attempts = notification_max_attempts;
+        self
+    }
+
+    pub fn with_notification_retry_base_delay_seconds(
+        mut self,
+        notification_retry_base_delay_seconds: u64,
+    ) -> Config {
+        self.notification_retry_base_delay_seconds = notification_retry_base_delay_seconds;
+        self
+    }
+
+    pub fn with_notification_worker_interval_seconds(
+        mut self,
+        notification_worker_interval_seconds: u64,
+    ) -> Config {
+        self.notification_worker_interval_seconds = notification_worker_interval_seconds;
+        self
+    }
+
     pub fn from(reader: &mut dyn Read) -> Result<Config, Error> {
         let result: Config = serde_json::from_reader(reader)?;
         Ok(result)
@@ -51,6 +287,58 @@ impl Config {
     pub fn db_connection_attempts_timeout_seconds(&self) -> i32 {
         self.db_connection_attempts_timeout_seconds
     }
+
+    pub fn reject_query_credentials(&self) -> bool {
+        self.reject_query_credentials
+    }
+
+    pub fn db_pool_max_size(&self) -> u32 {
+        self.db_pool_max_size
+    }
+
+    pub fn db_pool_borrow_timeout_millis(&self) -> u64 {
+        self.db_pool_borrow_timeout_millis
+    }
+
+    pub fn db_pool_max_idle_seconds(&self) -> u64 {
+        self.db_pool_max_idle_seconds
+    }
+
+    pub fn shutdown_drain_timeout_seconds(&self) -> u64 {
+        self.shutdown_drain_timeout_seconds
+    }
+
+    pub fn rate_limit(&self, cmd: &str) -> Option<&RateLimit> {
+        self.rate_limits.get(cmd)
+    }
+
+    pub fn client_ip_header(&self) -> Option<&str> {
+        self.client_ip_header.as_deref()
+    }
+
+    pub fn pairing_code_max_failed_attempts(&self) -> u32 {
+        self.pairing_code_max_failed_attempts
+    }
+
+    pub fn pairing_code_lockout_seconds(&self) -> u64 {
+        self.pairing_code_lockout_seconds
+    }
+
+    pub fn fcm_service_account_key(&self) -> Option<&ServiceAccountKey> {
+        self.fcm_service_account_key.as_ref()
+    }
+
+    pub fn notification_max_attempts(&self) -> u32 {
+        self.notification_max_attempts
+    }
+
+    pub fn notification_retry_base_delay_seconds(&self) -> u64 {
+        self.notification_retry_base_delay_seconds
+    }
+
+    pub fn notification_worker_interval_seconds(&self) -> u64 {
+        self.notification_worker_interval_seconds
+    }
 }
 
 #[cfg(test)]
@@ -85,6 +85,25 @@ pub fn select_by_uid(uid: &Uuid, connection: &dyn DBConnection) -> Result<Option
     );
 }
 
+/// Locks the user until the end of the transaction, so that concurrent
+/// modifications of the user's data would be done one after another.
+/// Should be called in a transaction.
+pub fn lock_for_modification(
+    app_user: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = app_user_schema::table
+        .select(app_user_schema::id)
+        .filter(app_user_schema::id.eq(app_user.id()))
+        .for_update()
+        .get_results::<i32>(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
 /// Returns Option in case the user gets deleted while update operation is not finished yet
 #[allow(clippy::comparison_chain)]
 pub fn update_client_token(
@@ -134,3 +134,31 @@ fn update_user_name() {
         .unwrap();
     assert_eq!("name2", user.name());
 }
+
+#[test]
+fn locked_user_cannot_be_locked_by_another_transaction() {
+    use diesel::Connection;
+    use diesel::RunQueryDsl;
+
+    let uid = Uuid::from_str("00000000-0000-0000-0000-009000000009").unwrap();
+    delete_entry_with(&uid);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let other_connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    diesel::sql_query("SET lock_timeout = '100ms'")
+        .execute(diesel_connection(&other_connection))
+        .unwrap();
+
+    let user = app_user::new(uid, "name".to_string(), Uuid::new_v4());
+    let user = app_user::insert(user, &connection).unwrap();
+
+    diesel_connection(&connection)
+        .begin_test_transaction()
+        .unwrap();
+    app_user::lock_for_modification(&user, &connection).unwrap();
+    assert!(app_user::lock_for_modification(&user, &other_connection).is_err());
+
+    // The lock is released when the transaction ends
+    drop(connection);
+    app_user::lock_for_modification(&user, &other_connection).unwrap();
+}
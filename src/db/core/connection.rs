@@ -57,6 +57,15 @@ impl DBConnectionImpl {
             Err(diesel_error) => Err(ErrorKind::ConnectionError(diesel_error).into()),
         }
     }
+
+    /// Performs a trivial query to check whether the connection is still usable
+    /// (e.g. DB could've been restarted since the connection was established).
+    pub fn is_alive(&self) -> bool {
+        use diesel::RunQueryDsl;
+        diesel::sql_query("SELECT 1")
+            .execute(&self.underlying_connection_source.diesel_connection)
+            .is_ok()
+    }
 }
 
 #[cfg(test)]
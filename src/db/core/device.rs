@@ -11,6 +11,7 @@ table! {
         id -> Integer,
         uuid -> Uuid,
         app_user_id -> Integer,
+        legacy -> Bool,
     }
 }
 use self::device as device_schema;
@@ -20,6 +21,7 @@ use self::device as device_schema;
 pub struct NewDevice {
     uuid: Uuid,
     app_user_id: i32,
+    legacy: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Queryable)]
@@ -27,6 +29,7 @@ pub struct Device {
     id: i32,
     uuid: Uuid,
     app_user_id: i32,
+    legacy: bool,
 }
 
 impl Device {
@@ -41,12 +44,28 @@ impl Device {
     pub fn app_user_id(&self) -> i32 {
         self.app_user_id
     }
+
+    pub fn is_legacy(&self) -> bool {
+        self.legacy
+    }
 }
 
 pub fn new(uuid: Uuid, app_user: &AppUser) -> NewDevice {
     NewDevice {
         uuid,
         app_user_id: app_user.id(),
+        legacy: false,
+    }
+}
+
+/// Creates the legacy device of the user - the device which tokens of clients
+/// not knowing about devices belong to. Its UUID is random, so that clients
+/// couldn't name legacy devices of other users.
+pub fn new_legacy(app_user: &AppUser) -> NewDevice {
+    NewDevice {
+        uuid: Uuid::new_v4(),
+        app_user_id: app_user.id(),
+        legacy: true,
     }
 }
 
@@ -79,6 +98,39 @@ pub fn select_by_uuid(uuid: &Uuid, connection: &dyn DBConnection) -> Result<Opti
     );
 }
 
+/// Selects all devices of the user, ordered by their IDs.
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<Device>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = device_schema::table
+        .filter(device_schema::app_user_id.eq(app_user_id))
+        .order(device_schema::id.asc())
+        .get_results::<Device>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Selects the legacy device of the user, see |new_legacy|.
+pub fn select_legacy_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<Device>, Error> {
+    use super::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = device_schema::table
+        .filter(device_schema::app_user_id.eq(app_user_id))
+        .filter(device_schema::legacy.eq(true))
+        .first::<Device>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
 pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
     return delete_by_column!(
         device_schema::table,
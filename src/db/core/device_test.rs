@@ -151,3 +151,63 @@ fn cant_delete_device_with_client_connection() {
 
     assert!(device_deletion_result.is_err());
 }
+
+#[test]
+fn can_select_by_app_user_id() {
+    let uid1 = Uuid::from_str("00000000-0000-0000-0000-007000000013").unwrap();
+    let uid2 = Uuid::from_str("00000000-0000-0000-0000-007000000014").unwrap();
+    delete_entries_with(&uid1);
+    delete_entries_with(&uid2);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user1 = app_user::insert(
+        app_user::new(uid1, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    let app_user2 = app_user::insert(
+        app_user::new(uid2, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let device1 = device::insert(device::new(Uuid::new_v4(), &app_user1), &connection).unwrap();
+    let device2 = device::insert(device::new(Uuid::new_v4(), &app_user1), &connection).unwrap();
+    device::insert(device::new(Uuid::new_v4(), &app_user2), &connection).unwrap();
+
+    let selected = device::select_by_app_user_id(app_user1.id(), &connection).unwrap();
+    assert_eq!(vec![device1, device2], selected);
+}
+
+#[test]
+fn can_select_legacy_device_by_app_user_id() {
+    let uid1 = Uuid::from_str("00000000-0000-0000-0000-007000000015").unwrap();
+    let uid2 = Uuid::from_str("00000000-0000-0000-0000-007000000016").unwrap();
+    delete_entries_with(&uid1);
+    delete_entries_with(&uid2);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user1 = app_user::insert(
+        app_user::new(uid1, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    let app_user2 = app_user::insert(
+        app_user::new(uid2, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    device::insert(device::new(Uuid::new_v4(), &app_user1), &connection).unwrap();
+    let legacy_device1 = device::insert(device::new_legacy(&app_user1), &connection).unwrap();
+    let legacy_device2 = device::insert(device::new_legacy(&app_user2), &connection).unwrap();
+    assert!(legacy_device1.is_legacy());
+    assert_ne!(legacy_device1.uuid(), legacy_device2.uuid());
+
+    let selected = device::select_legacy_by_app_user_id(app_user1.id(), &connection).unwrap();
+    assert_eq!(Some(legacy_device1), selected);
+
+    // Only 1 legacy device per user is allowed
+    let result = device::insert(device::new_legacy(&app_user1), &connection);
+    assert!(result.is_err());
+}
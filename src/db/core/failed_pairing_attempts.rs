@@ -0,0 +1,105 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+
+table! {
+    failed_pairing_attempts {
+        id -> Integer,
+        app_user_id -> Integer,
+        attempts_count -> Integer,
+        last_attempt_time -> BigInt,
+    }
+}
+use self::failed_pairing_attempts as failed_pairing_attempts_schema;
+use diesel::RunQueryDsl;
+
+#[derive(Insertable)]
+#[table_name = "failed_pairing_attempts"]
+struct NewFailedPairingAttempts {
+    app_user_id: i32,
+    attempts_count: i32,
+    last_attempt_time: i64,
+}
+
+/// Failed attempts of a user to pair by a pairing code.
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct FailedPairingAttempts {
+    id: i32,
+    app_user_id: i32,
+    attempts_count: i32,
+    last_attempt_time: i64,
+}
+
+impl FailedPairingAttempts {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn app_user_id(&self) -> i32 {
+        self.app_user_id
+    }
+
+    pub fn attempts_count(&self) -> i32 {
+        self.attempts_count
+    }
+
+    pub fn last_attempt_time(&self) -> i64 {
+        self.last_attempt_time
+    }
+}
+
+/// Counts a failed attempt of the user - the first attempt inserts an entry,
+/// next attempts increase its count.
+pub fn increment(
+    app_user: &AppUser,
+    attempt_time: i64,
+    connection: &dyn DBConnection,
+) -> Result<FailedPairingAttempts, Error> {
+    use diesel::ExpressionMethods;
+
+    let new_attempts = NewFailedPairingAttempts {
+        app_user_id: app_user.id(),
+        attempts_count: 1,
+        last_attempt_time: attempt_time,
+    };
+    let result = diesel::insert_into(failed_pairing_attempts_schema::table)
+        .values(&new_attempts)
+        .on_conflict(failed_pairing_attempts_schema::app_user_id)
+        .do_update()
+        .set((
+            failed_pairing_attempts_schema::attempts_count
+                .eq(failed_pairing_attempts_schema::attempts_count + 1),
+            failed_pairing_attempts_schema::last_attempt_time.eq(attempt_time),
+        ))
+        .get_result::<FailedPairingAttempts>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<FailedPairingAttempts>, Error> {
+    select_by_column!(
+        FailedPairingAttempts,
+        failed_pairing_attempts_schema::table,
+        failed_pairing_attempts_schema::app_user_id,
+        app_user_id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn delete_by_app_user_id(app_user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        failed_pairing_attempts_schema::table,
+        failed_pairing_attempts_schema::app_user_id,
+        app_user_id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./failed_pairing_attempts_test.rs"]
+mod failed_pairing_attempts_test;
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::failed_pairing_attempts;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn increment_inserts_and_updates_attempts() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-0000-0000-0000-009100000000", &connection);
+
+    assert!(
+        failed_pairing_attempts::select_by_app_user_id(user.id(), &connection)
+            .unwrap()
+            .is_none()
+    );
+
+    let attempts1 = failed_pairing_attempts::increment(&user, 123, &connection).unwrap();
+    assert!(attempts1.id() > 0);
+    assert_eq!(user.id(), attempts1.app_user_id());
+    assert_eq!(1, attempts1.attempts_count());
+    assert_eq!(123, attempts1.last_attempt_time());
+
+    let attempts2 = failed_pairing_attempts::increment(&user, 124, &connection).unwrap();
+    assert_eq!(attempts1.id(), attempts2.id());
+    assert_eq!(2, attempts2.attempts_count());
+    assert_eq!(124, attempts2.last_attempt_time());
+
+    let selected = failed_pairing_attempts::select_by_app_user_id(user.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(attempts2, selected);
+}
+
+#[test]
+fn attempts_of_different_users_are_counted_separately() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-009100000001", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-009100000002", &connection);
+
+    failed_pairing_attempts::increment(&user1, 123, &connection).unwrap();
+    failed_pairing_attempts::increment(&user1, 123, &connection).unwrap();
+    let attempts2 = failed_pairing_attempts::increment(&user2, 123, &connection).unwrap();
+    assert_eq!(1, attempts2.attempts_count());
+
+    let attempts1 = failed_pairing_attempts::select_by_app_user_id(user1.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(2, attempts1.attempts_count());
+}
+
+#[test]
+fn deletion_by_user_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-009100000003", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-009100000004", &connection);
+
+    failed_pairing_attempts::increment(&user1, 123, &connection).unwrap();
+    failed_pairing_attempts::increment(&user2, 123, &connection).unwrap();
+
+    failed_pairing_attempts::delete_by_app_user_id(user1.id(), &connection).unwrap();
+    assert!(
+        failed_pairing_attempts::select_by_app_user_id(user1.id(), &connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        failed_pairing_attempts::select_by_app_user_id(user2.id(), &connection)
+            .unwrap()
+            .is_some()
+    );
+
+    // After a deletion attempts are counted from scratch
+    let attempts1 = failed_pairing_attempts::increment(&user1, 124, &connection).unwrap();
+    assert_eq!(1, attempts1.attempts_count());
+}
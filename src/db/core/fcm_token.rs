@@ -1,5 +1,6 @@
 use super::app_user::AppUser;
 use super::connection::DBConnection;
+use super::device::Device;
 use super::diesel_connection;
 use super::error::Error;
 
@@ -8,6 +9,7 @@ table! {
         id -> Integer,
         token_value -> VarChar,
         app_user_id -> Integer,
+        device_id -> Integer,
     }
 }
 use self::fcm_token as fcm_token_schema;
@@ -17,6 +19,7 @@ use self::fcm_token as fcm_token_schema;
 pub struct NewFcmToken {
     token_value: String,
     app_user_id: i32,
+    device_id: i32,
 }
 
 #[derive(Debug, PartialEq, Eq, Queryable)]
@@ -24,6 +27,7 @@ pub struct FcmToken {
     id: i32,
     token_value: String,
     app_user_id: i32,
+    device_id: i32,
 }
 
 impl FcmToken {
@@ -38,12 +42,18 @@ impl FcmToken {
     pub fn app_user_id(&self) -> i32 {
         self.app_user_id
     }
+
+    pub fn device_id(&self) -> i32 {
+        self.device_id
+    }
 }
 
-pub fn new(token_value: String, app_user: &AppUser) -> NewFcmToken {
+/// A user can have many devices, but each device has only 1 token.
+pub fn new(token_value: String, app_user: &AppUser, device: &Device) -> NewFcmToken {
     NewFcmToken {
         token_value,
         app_user_id: app_user.id(),
+        device_id: device.id(),
     }
 }
 
@@ -66,17 +76,97 @@ pub fn select_by_id(id: i32, connection: &dyn DBConnection) -> Result<Option<Fcm
     );
 }
 
+/// Selects tokens of all devices of the user, ordered by their IDs.
 pub fn select_by_user_id(
     user_id: i32,
     connection: &dyn DBConnection,
+) -> Result<Vec<FcmToken>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = fcm_token_schema::table
+        .filter(fcm_token_schema::app_user_id.eq(user_id))
+        .order(fcm_token_schema::id.asc())
+        .get_results::<FcmToken>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+pub fn select_by_device_id(
+    device_id: i32,
+    connection: &dyn DBConnection,
 ) -> Result<Option<FcmToken>, Error> {
-    return select_by_column!(
+    select_by_column!(
         FcmToken,
         fcm_token_schema::table,
-        fcm_token_schema::app_user_id,
-        user_id,
+        fcm_token_schema::device_id,
+        device_id,
         diesel_connection(connection)
-    );
+    )
+}
+
+pub fn select_by_token_value(
+    token_value: &str,
+    connection: &dyn DBConnection,
+) -> Result<Option<FcmToken>, Error> {
+    select_by_column!(
+        FcmToken,
+        fcm_token_schema::table,
+        fcm_token_schema::token_value,
+        token_value,
+        diesel_connection(connection)
+    )
+}
+
+/// Replaces the value of the token, the token stays attached to its device.
+/// If the new value is already used by another token, the replaced token is deleted and
+/// the other token is returned.
+/// Should be called in a transaction.
+pub fn replace_token_value(
+    fcm_token: FcmToken,
+    new_token_value: String,
+    connection: &dyn DBConnection,
+) -> Result<FcmToken, Error> {
+    delete_by_id(fcm_token.id(), connection)?;
+    if let Some(existing_token) = select_by_token_value(&new_token_value, connection)? {
+        return Ok(existing_token);
+    }
+    let new_fcm_token = NewFcmToken {
+        token_value: new_token_value,
+        app_user_id: fcm_token.app_user_id(),
+        device_id: fcm_token.device_id(),
+    };
+    insert(new_fcm_token, connection)
+}
+
+pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        fcm_token_schema::table,
+        fcm_token_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn delete_by_device_id(device_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        fcm_token_schema::table,
+        fcm_token_schema::device_id,
+        device_id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn delete_by_token_value(
+    token_value: &str,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    delete_by_column!(
+        fcm_token_schema::table,
+        fcm_token_schema::token_value,
+        token_value,
+        diesel_connection(connection)
+    )
 }
 
 pub fn delete_by_user_id(user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
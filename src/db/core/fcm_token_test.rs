@@ -2,6 +2,10 @@ use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::device;
+use crate::db::core::device::Device;
 use crate::db::core::fcm_token;
 use crate::db::core::testing_util as dbtesting_utils;
 
@@ -15,55 +19,60 @@ fn delete_entries_with(app_user_uid: &Uuid) {
     .unwrap();
 }
 
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_device(app_user: &AppUser, connection: &dyn DBConnection) -> Device {
+    device::insert(device::new(Uuid::new_v4(), app_user), connection).unwrap()
+}
+
 // NOTE: different UUIDs and token values must be used in each tests, because tests are run in parallel
 // and usage of same IDs and values would cause race conditions.
 
 #[test]
 fn insertion_and_selection_work() {
     let token_value = "1";
-    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-002300000000").unwrap();
-    delete_entries_with(&app_user_uid);
-
     let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000000", &connection);
+    let device = insert_device(&app_user, &connection);
 
-    let app_user = app_user::insert(
-        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
-        &connection,
-    )
-    .unwrap();
-
-    let new_fcm_token = fcm_token::new(token_value.to_string(), &app_user);
+    let new_fcm_token = fcm_token::new(token_value.to_string(), &app_user, &device);
 
     let inserted_fcm_token = fcm_token::insert(new_fcm_token, &connection).unwrap();
     assert!(inserted_fcm_token.id() > 0);
     assert_eq!(inserted_fcm_token.token_value(), token_value);
     assert_eq!(app_user.id(), inserted_fcm_token.app_user_id());
+    assert_eq!(device.id(), inserted_fcm_token.device_id());
 
     let selected_fcm_token = fcm_token::select_by_id(inserted_fcm_token.id(), &connection);
     let selected_fcm_token = selected_fcm_token.unwrap().unwrap(); // unwrapping Result and Option
     assert_eq!(inserted_fcm_token, selected_fcm_token);
 
-    let selected_by_fcm_token = fcm_token::select_by_user_id(app_user.id(), &connection);
-    let selected_by_fcm_token = selected_by_fcm_token.unwrap().unwrap();
-    assert_eq!(inserted_fcm_token, selected_by_fcm_token);
+    let selected_by_user_id = fcm_token::select_by_user_id(app_user.id(), &connection).unwrap();
+    assert_eq!(vec![selected_fcm_token], selected_by_user_id);
+
+    let selected_by_device_id = fcm_token::select_by_device_id(device.id(), &connection);
+    let selected_by_device_id = selected_by_device_id.unwrap().unwrap();
+    assert_eq!(inserted_fcm_token, selected_by_device_id);
 }
 
 #[test]
 fn cant_insert_fcm_token_with_already_used_token_value() {
     let token_value = "2";
-    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-002300000001").unwrap();
-    delete_entries_with(&app_user_uid);
-
     let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000001", &connection);
+    let device1 = insert_device(&app_user, &connection);
+    let device2 = insert_device(&app_user, &connection);
 
-    let app_user = app_user::insert(
-        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
-        &connection,
-    )
-    .unwrap();
-
-    let fcm_token_copy1 = fcm_token::new(token_value.to_string(), &app_user);
-    let fcm_token_copy2 = fcm_token::new(token_value.to_string(), &app_user);
+    let fcm_token_copy1 = fcm_token::new(token_value.to_string(), &app_user, &device1);
+    let fcm_token_copy2 = fcm_token::new(token_value.to_string(), &app_user, &device2);
 
     fcm_token::insert(fcm_token_copy1, &connection).unwrap();
 
@@ -72,51 +81,160 @@ fn cant_insert_fcm_token_with_already_used_token_value() {
 }
 
 #[test]
-fn multiple_fcm_tokens_cannot_depend_on_single_app_user() {
+fn multiple_fcm_tokens_cannot_depend_on_single_device() {
     let token_value1 = "3";
     let token_value2 = "4";
-    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-002300000002").unwrap();
-    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000002", &connection);
+    let device = insert_device(&app_user, &connection);
+
+    let fcm_token1 = fcm_token::new(token_value1.to_string(), &app_user, &device);
+    let fcm_token2 = fcm_token::new(token_value2.to_string(), &app_user, &device);
 
+    fcm_token::insert(fcm_token1, &connection).unwrap();
+
+    let second_insertion_result = fcm_token::insert(fcm_token2, &connection);
+    assert!(second_insertion_result.is_err());
+}
+
+#[test]
+fn multiple_devices_of_single_app_user_have_tokens() {
+    let token_value1 = "6";
+    let token_value2 = "7";
     let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000004", &connection);
+    let device1 = insert_device(&app_user, &connection);
+    let device2 = insert_device(&app_user, &connection);
 
-    let app_user = app_user::insert(
-        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+    let fcm_token1 = fcm_token::insert(
+        fcm_token::new(token_value1.to_string(), &app_user, &device1),
+        &connection,
+    )
+    .unwrap();
+    let fcm_token2 = fcm_token::insert(
+        fcm_token::new(token_value2.to_string(), &app_user, &device2),
         &connection,
     )
     .unwrap();
 
-    let fcm_token1 = fcm_token::new(token_value1.to_string(), &app_user);
-    let fcm_token2 = fcm_token::new(token_value2.to_string(), &app_user);
-
-    fcm_token::insert(fcm_token1, &connection).unwrap();
-
-    let second_user_selection_result = fcm_token::insert(fcm_token2, &connection);
-    assert!(second_user_selection_result.is_err());
+    let selected = fcm_token::select_by_user_id(app_user.id(), &connection).unwrap();
+    assert_eq!(vec![fcm_token1, fcm_token2], selected);
 }
 
 #[test]
 fn delete_by_user_id() {
     let token_value = "5";
-    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-002300000003").unwrap();
-    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000003", &connection);
+    let device = insert_device(&app_user, &connection);
 
+    let fcm_token = fcm_token::new(token_value.to_string(), &app_user, &device);
+    fcm_token::insert(fcm_token, &connection).unwrap();
+
+    assert!(!fcm_token::select_by_user_id(app_user.id(), &connection)
+        .unwrap()
+        .is_empty());
+    fcm_token::delete_by_user_id(app_user.id(), &connection).unwrap();
+    assert!(fcm_token::select_by_user_id(app_user.id(), &connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn delete_by_id_device_id_and_token_value() {
+    let token_value1 = "8";
+    let token_value2 = "9";
+    let token_value3 = "10";
     let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000005", &connection);
+    let device1 = insert_device(&app_user, &connection);
+    let device2 = insert_device(&app_user, &connection);
+    let device3 = insert_device(&app_user, &connection);
 
-    let app_user = app_user::insert(
-        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+    let fcm_token1 = fcm_token::insert(
+        fcm_token::new(token_value1.to_string(), &app_user, &device1),
+        &connection,
+    )
+    .unwrap();
+    fcm_token::insert(
+        fcm_token::new(token_value2.to_string(), &app_user, &device2),
+        &connection,
+    )
+    .unwrap();
+    let fcm_token3 = fcm_token::insert(
+        fcm_token::new(token_value3.to_string(), &app_user, &device3),
         &connection,
     )
     .unwrap();
 
-    let fcm_token = fcm_token::new(token_value.to_string(), &app_user);
-    fcm_token::insert(fcm_token, &connection).unwrap();
+    fcm_token::delete_by_id(fcm_token1.id(), &connection).unwrap();
+    fcm_token::delete_by_device_id(device2.id(), &connection).unwrap();
+    assert_eq!(
+        vec![fcm_token3],
+        fcm_token::select_by_user_id(app_user.id(), &connection).unwrap()
+    );
 
+    fcm_token::delete_by_token_value(token_value3, &connection).unwrap();
     assert!(fcm_token::select_by_user_id(app_user.id(), &connection)
         .unwrap()
-        .is_some());
-    fcm_token::delete_by_user_id(app_user.id(), &connection).unwrap();
-    assert!(fcm_token::select_by_user_id(app_user.id(), &connection)
+        .is_empty());
+}
+
+#[test]
+fn selection_by_token_value() {
+    let token_value = "11";
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000006", &connection);
+    let device = insert_device(&app_user, &connection);
+
+    let fcm_token = fcm_token::insert(
+        fcm_token::new(token_value.to_string(), &app_user, &device),
+        &connection,
+    )
+    .unwrap();
+
+    let selected = fcm_token::select_by_token_value(token_value, &connection);
+    assert_eq!(fcm_token, selected.unwrap().unwrap());
+    let selected = fcm_token::select_by_token_value("not_existing_token_value", &connection);
+    assert!(selected.unwrap().is_none());
+}
+
+#[test]
+fn token_value_replacement() {
+    let token_value1 = "12";
+    let token_value2 = "13";
+    let token_value3 = "14";
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-002300000007", &connection);
+    let device1 = insert_device(&app_user, &connection);
+    let device2 = insert_device(&app_user, &connection);
+
+    let fcm_token1 = fcm_token::insert(
+        fcm_token::new(token_value1.to_string(), &app_user, &device1),
+        &connection,
+    )
+    .unwrap();
+    let fcm_token2 = fcm_token::insert(
+        fcm_token::new(token_value2.to_string(), &app_user, &device2),
+        &connection,
+    )
+    .unwrap();
+
+    // Replacement by a new value
+    let replaced =
+        fcm_token::replace_token_value(fcm_token1, token_value3.to_string(), &connection).unwrap();
+    assert_eq!(token_value3, replaced.token_value());
+    assert_eq!(device1.id(), replaced.device_id());
+    assert!(fcm_token::select_by_token_value(token_value1, &connection)
         .unwrap()
         .is_none());
+
+    // Replacement by a value of another token
+    let replaced =
+        fcm_token::replace_token_value(replaced, token_value2.to_string(), &connection).unwrap();
+    assert_eq!(fcm_token2, replaced);
+    assert_eq!(
+        vec![fcm_token2],
+        fcm_token::select_by_user_id(app_user.id(), &connection).unwrap()
+    );
 }
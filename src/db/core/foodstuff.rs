@@ -16,6 +16,7 @@ table! {
         carbs -> Integer,
         calories -> Integer,
         is_listed -> Bool,
+        revision -> BigInt,
     }
 }
 use self::foodstuff as foodstuff_schema;
@@ -44,6 +45,7 @@ pub struct Foodstuff {
     carbs: i32,
     calories: i32,
     is_listed: bool,
+    revision: i64,
 }
 
 impl Foodstuff {
@@ -82,6 +84,12 @@ impl Foodstuff {
     pub fn is_listed(&self) -> bool {
         self.is_listed
     }
+
+    /// Revision is assigned by DB on each insertion and modification of the foodstuff,
+    /// revisions grow monotonically.
+    pub fn revision(&self) -> i64 {
+        self.revision
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -126,6 +134,136 @@ pub fn select_by_id(id: i32, connection: &dyn DBConnection) -> Result<Option<Foo
     );
 }
 
+pub fn select_by_app_user_foodstuff_id(
+    app_user_id: i32,
+    app_user_foodstuff_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<Foodstuff>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_schema::table
+        .filter(foodstuff_schema::app_user_id.eq(app_user_id))
+        .filter(foodstuff_schema::app_user_foodstuff_id.eq(app_user_foodstuff_id))
+        .first::<Foodstuff>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// Selects both listed and unlisted foodstuffs of the user,
+/// ordered by |app_user_foodstuff_id|.
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<Foodstuff>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_schema::table
+        .filter(foodstuff_schema::app_user_id.eq(app_user_id))
+        .order(foodstuff_schema::app_user_foodstuff_id.asc())
+        .get_results::<Foodstuff>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Selects both listed and unlisted foodstuffs of the user which were inserted or modified
+/// after given revision, ordered by revision.
+pub fn select_changed_since(
+    app_user_id: i32,
+    revision: i64,
+    connection: &dyn DBConnection,
+) -> Result<Vec<Foodstuff>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_schema::table
+        .filter(foodstuff_schema::app_user_id.eq(app_user_id))
+        .filter(foodstuff_schema::revision.gt(revision))
+        .order(foodstuff_schema::revision.asc())
+        .get_results::<Foodstuff>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Returns None if the user has no foodstuffs.
+pub fn select_max_revision(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<i64>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_schema::table
+        .filter(foodstuff_schema::app_user_id.eq(app_user_id))
+        .select(diesel::dsl::max(foodstuff_schema::revision))
+        .first::<Option<i64>>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Locks foodstuffs of the user until the end of current transaction.
+/// Revisions are taken from a sequence before the transaction is committed, so without
+/// the lock 2 concurrent transactions could commit their revisions in reverse order -
+/// a client syncing in between would then never see the smaller revision.
+pub fn lock_for_modification(app_user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    use super::app_user::app_user as app_user_schema;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = app_user_schema::table
+        .filter(app_user_schema::id.eq(app_user_id))
+        .select(app_user_schema::id)
+        .for_update()
+        .get_results::<i32>(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
+/// Returns Option in case the user gets deleted while update operation is not finished yet
+#[allow(clippy::comparison_chain)]
+pub fn update(
+    foodstuff: Foodstuff,
+    name: String,
+    protein: i32,
+    fats: i32,
+    carbs: i32,
+    calories: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<Foodstuff>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = foodstuff_schema::table.filter(foodstuff_schema::id.eq(foodstuff.id()));
+    let result = diesel::update(target)
+        .set((
+            foodstuff_schema::name.eq(name),
+            foodstuff_schema::protein.eq(protein),
+            foodstuff_schema::fats.eq(fats),
+            foodstuff_schema::carbs.eq(carbs),
+            foodstuff_schema::calories.eq(calories),
+        ))
+        .get_results::<Foodstuff>(diesel_connection(connection));
+
+    match result {
+        Ok(mut vec) => {
+            if vec.len() > 1 {
+                panic!(
+                    "Count of updated foodstuffs is {}! Data in DB most likely was just corrupted!",
+                    vec.len()
+                );
+            } else if vec.len() == 1 {
+                Ok(Some(vec.pop().expect("Expect 1 foodstuff")))
+            } else {
+                Ok(None)
+            }
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// Returns Option in case the user gets deleted while update operation is not finished yet
 #[allow(clippy::comparison_chain)]
 pub fn unlist(
@@ -0,0 +1,243 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+use super::foodstuff::Foodstuff;
+
+table! {
+    foodstuff_share {
+        id -> Integer,
+        foodstuff_id -> Integer,
+        owner_user_id -> Integer,
+        partner_user_id -> Integer,
+        permission -> Integer,
+    }
+}
+use self::foodstuff_share as foodstuff_share_schema;
+
+/// NOTE: the values are stored into DB, so think
+/// twice before reusing numeric values.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SharePermission {
+    ReadOnly = 0,
+    Editable = 1,
+}
+impl SharePermission {
+    fn from_number(number: i32) -> Result<Self, ()> {
+        match number {
+            _ if number == SharePermission::ReadOnly as i32 => Ok(SharePermission::ReadOnly),
+            _ if number == SharePermission::Editable as i32 => Ok(SharePermission::Editable),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "foodstuff_share"]
+pub struct NewFoodstuffShare {
+    foodstuff_id: i32,
+    owner_user_id: i32,
+    partner_user_id: i32,
+    permission: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct FoodstuffShare {
+    id: i32,
+    foodstuff_id: i32,
+    owner_user_id: i32,
+    partner_user_id: i32,
+    permission: i32,
+}
+
+impl FoodstuffShare {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn foodstuff_id(&self) -> i32 {
+        self.foodstuff_id
+    }
+
+    pub fn owner_user_id(&self) -> i32 {
+        self.owner_user_id
+    }
+
+    pub fn partner_user_id(&self) -> i32 {
+        self.partner_user_id
+    }
+
+    /// Unknown permission values (which can appear only if data in DB is corrupted)
+    /// are treated as the most restrictive permission.
+    pub fn permission(&self) -> SharePermission {
+        SharePermission::from_number(self.permission).unwrap_or(SharePermission::ReadOnly)
+    }
+}
+
+/// Shares the foodstuff of its owner with the |partner_user|.
+pub fn new(
+    foodstuff: &Foodstuff,
+    partner_user: &AppUser,
+    permission: SharePermission,
+) -> NewFoodstuffShare {
+    NewFoodstuffShare {
+        foodstuff_id: foodstuff.id(),
+        owner_user_id: foodstuff.app_user_id(),
+        partner_user_id: partner_user.id(),
+        permission: permission as i32,
+    }
+}
+
+pub fn insert(
+    share: NewFoodstuffShare,
+    connection: &dyn DBConnection,
+) -> Result<FoodstuffShare, Error> {
+    insert!(
+        FoodstuffShare,
+        share,
+        foodstuff_share_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_id(
+    id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<FoodstuffShare>, Error> {
+    select_by_column!(
+        FoodstuffShare,
+        foodstuff_share_schema::table,
+        foodstuff_share_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_foodstuff_id_and_partner_user_id(
+    foodstuff_id: i32,
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<FoodstuffShare>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_share_schema::table
+        .filter(foodstuff_share_schema::foodstuff_id.eq(foodstuff_id))
+        .filter(foodstuff_share_schema::partner_user_id.eq(partner_user_id))
+        .first::<FoodstuffShare>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// Selects all foodstuffs shares with the partner, ordered by their IDs.
+pub fn select_by_partner_user_id(
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<FoodstuffShare>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_share_schema::table
+        .filter(foodstuff_share_schema::partner_user_id.eq(partner_user_id))
+        .order(foodstuff_share_schema::id.asc())
+        .get_results::<FoodstuffShare>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Selects all shares of foodstuffs of the owner, ordered by their IDs.
+pub fn select_by_owner_user_id(
+    owner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<FoodstuffShare>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = foodstuff_share_schema::table
+        .filter(foodstuff_share_schema::owner_user_id.eq(owner_user_id))
+        .order(foodstuff_share_schema::id.asc())
+        .get_results::<FoodstuffShare>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Returns Option in case the share gets deleted while update operation is not finished yet
+#[allow(clippy::comparison_chain)]
+pub fn update_permission(
+    share: FoodstuffShare,
+    permission: SharePermission,
+    connection: &dyn DBConnection,
+) -> Result<Option<FoodstuffShare>, Error> {
+    let result = update_column!(
+        FoodstuffShare,
+        foodstuff_share_schema::table,
+        foodstuff_share_schema::id,
+        share.id(),
+        foodstuff_share_schema::permission,
+        permission as i32,
+        diesel_connection(connection)
+    );
+
+    match result {
+        Ok(mut vec) => {
+            if vec.len() > 1 {
+                panic!(
+                    "Count of updated shares is {}! Data in DB most likely was just corrupted!",
+                    vec.len()
+                );
+            } else if vec.len() == 1 {
+                Ok(Some(vec.pop().expect("Expect 1 share")))
+            } else {
+                Ok(None)
+            }
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Deletes all foodstuffs shares of the owner with the partner (but not of the partner
+/// with the owner).
+pub fn delete_by_owner_and_partner_user_ids(
+    owner_user_id: i32,
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = diesel::delete(
+        foodstuff_share_schema::table
+            .filter(foodstuff_share_schema::owner_user_id.eq(owner_user_id))
+            .filter(foodstuff_share_schema::partner_user_id.eq(partner_user_id)),
+    )
+    .execute(diesel_connection(connection));
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Deletes all shares both of the user's foodstuffs and of foodstuffs shared with the user.
+pub fn delete_by_user_id(user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        foodstuff_share_schema::table,
+        foodstuff_share_schema::owner_user_id,
+        user_id,
+        diesel_connection(connection)
+    )?;
+    delete_by_column!(
+        foodstuff_share_schema::table,
+        foodstuff_share_schema::partner_user_id,
+        user_id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./foodstuff_share_test.rs"]
+mod foodstuff_share_test;
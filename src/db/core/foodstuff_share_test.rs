@@ -0,0 +1,232 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::foodstuff_share;
+use crate::db::core::foodstuff_share::SharePermission;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_foodstuff(user: &AppUser, id: i32, connection: &dyn DBConnection) -> Foodstuff {
+    foodstuff::insert(
+        foodstuff::new(user, id, "apple".to_string(), 1, 2, 3, 4, true),
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner = insert_user("00000000-0000-0000-0000-005500000000", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005500000001", &connection);
+    let foodstuff = insert_foodstuff(&owner, 1, &connection);
+
+    let share = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff, &partner, SharePermission::Editable),
+        &connection,
+    )
+    .unwrap();
+    assert!(share.id() > 0);
+    assert_eq!(foodstuff.id(), share.foodstuff_id());
+    assert_eq!(owner.id(), share.owner_user_id());
+    assert_eq!(partner.id(), share.partner_user_id());
+    assert_eq!(SharePermission::Editable, share.permission());
+
+    let selected = foodstuff_share::select_by_id(share.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(share, selected);
+    let selected = foodstuff_share::select_by_foodstuff_id_and_partner_user_id(
+        foodstuff.id(),
+        partner.id(),
+        &connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(share, selected);
+    assert!(foodstuff_share::select_by_foodstuff_id_and_partner_user_id(
+        foodstuff.id(),
+        owner.id(),
+        &connection
+    )
+    .unwrap()
+    .is_none());
+}
+
+#[test]
+fn foodstuff_cannot_be_shared_twice_with_same_partner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner = insert_user("00000000-0000-0000-0000-005500000002", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005500000003", &connection);
+    let foodstuff = insert_foodstuff(&owner, 1, &connection);
+
+    foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff, &partner, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+    let result = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff, &partner, SharePermission::Editable),
+        &connection,
+    );
+    match result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Expected unique violation, got: {:?}", result),
+    }
+}
+
+#[test]
+fn selection_by_partner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner1 = insert_user("00000000-0000-0000-0000-005500000004", &connection);
+    let owner2 = insert_user("00000000-0000-0000-0000-005500000005", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005500000006", &connection);
+    let foodstuff1 = insert_foodstuff(&owner1, 1, &connection);
+    let foodstuff2 = insert_foodstuff(&owner2, 1, &connection);
+    let foodstuff3 = insert_foodstuff(&partner, 1, &connection);
+
+    let share1 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff1, &partner, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+    let share2 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff2, &partner, SharePermission::Editable),
+        &connection,
+    )
+    .unwrap();
+    // Shared by the partner, not with the partner
+    foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff3, &owner1, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+
+    let selected = foodstuff_share::select_by_partner_user_id(partner.id(), &connection).unwrap();
+    assert_eq!(vec![share1, share2], selected);
+}
+
+#[test]
+fn permission_update() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner = insert_user("00000000-0000-0000-0000-005500000007", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005500000008", &connection);
+    let foodstuff = insert_foodstuff(&owner, 1, &connection);
+
+    let share = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff, &partner, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+    let id = share.id();
+    let updated = foodstuff_share::update_permission(share, SharePermission::Editable, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(id, updated.id());
+    assert_eq!(SharePermission::Editable, updated.permission());
+    let selected = foodstuff_share::select_by_id(id, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated, selected);
+}
+
+#[test]
+fn deletion_by_owner_and_partner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005500000009", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-00550000000a", &connection);
+    let user3 = insert_user("00000000-0000-0000-0000-00550000000b", &connection);
+    let foodstuff1 = insert_foodstuff(&user1, 1, &connection);
+    let foodstuff2 = insert_foodstuff(&user2, 1, &connection);
+
+    let share_1_to_2 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff1, &user2, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+    let share_1_to_3 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff1, &user3, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+    let share_2_to_1 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff2, &user1, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+
+    foodstuff_share::delete_by_owner_and_partner_user_ids(user1.id(), user2.id(), &connection)
+        .unwrap();
+    assert!(
+        foodstuff_share::select_by_id(share_1_to_2.id(), &connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        foodstuff_share::select_by_id(share_1_to_3.id(), &connection)
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        foodstuff_share::select_by_id(share_2_to_1.id(), &connection)
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn selection_by_owner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner = insert_user("00000000-0000-0000-0000-005500000010", &connection);
+    let partner1 = insert_user("00000000-0000-0000-0000-005500000011", &connection);
+    let partner2 = insert_user("00000000-0000-0000-0000-005500000012", &connection);
+    let foodstuff1 = insert_foodstuff(&owner, 1, &connection);
+    let foodstuff2 = insert_foodstuff(&owner, 2, &connection);
+    let foodstuff3 = insert_foodstuff(&partner1, 1, &connection);
+
+    let share1 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff1, &partner1, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+    let share2 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff2, &partner2, SharePermission::Editable),
+        &connection,
+    )
+    .unwrap();
+    // Shared with the owner, not by the owner
+    foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff3, &owner, SharePermission::ReadOnly),
+        &connection,
+    )
+    .unwrap();
+
+    let selected = foodstuff_share::select_by_owner_user_id(owner.id(), &connection).unwrap();
+    assert_eq!(vec![share1, share2], selected);
+}
@@ -210,3 +210,204 @@ fn making_already_unlisted_foodstuff_unlisted_does_nothing() {
         .unwrap();
     assert!(!unlisted_foodstuff.is_listed());
 }
+
+#[test]
+fn can_select_by_app_user_foodstuff_id() {
+    let app_user_foodstuff_id = 7;
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005000000005").unwrap();
+
+    delete_entries_with(&app_user_uid);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let new_foodstuff = foodstuff::new(
+        &app_user,
+        app_user_foodstuff_id,
+        FOODSTUFF_NAME.to_string(),
+        FOODSTUFF_PROTEIN,
+        FOODSTUFF_FATS,
+        FOODSTUFF_CARBS,
+        FOODSTUFF_CALORIES,
+        true,
+    );
+    let inserted_foodstuff = foodstuff::insert(new_foodstuff, &connection).unwrap();
+
+    let selected_foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+        app_user.id(),
+        app_user_foodstuff_id,
+        &connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(inserted_foodstuff, selected_foodstuff);
+
+    let not_existing_foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+        app_user.id(),
+        app_user_foodstuff_id + 1,
+        &connection,
+    )
+    .unwrap();
+    assert!(not_existing_foodstuff.is_none());
+}
+
+#[test]
+fn can_select_all_foodstuffs_of_user() {
+    let app_user_uid1 = Uuid::from_str("00000000-0000-0000-0000-005000000006").unwrap();
+    let app_user_uid2 = Uuid::from_str("00000000-0000-0000-0000-005000000007").unwrap();
+
+    delete_entries_with(&app_user_uid1);
+    delete_entries_with(&app_user_uid2);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let app_user1 = app_user::insert(
+        app_user::new(app_user_uid1, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    let app_user2 = app_user::insert(
+        app_user::new(app_user_uid2, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let new_foodstuff = |app_user, app_user_foodstuff_id, is_listed| {
+        foodstuff::new(
+            app_user,
+            app_user_foodstuff_id,
+            FOODSTUFF_NAME.to_string(),
+            FOODSTUFF_PROTEIN,
+            FOODSTUFF_FATS,
+            FOODSTUFF_CARBS,
+            FOODSTUFF_CALORIES,
+            is_listed,
+        )
+    };
+    let foodstuff2 = foodstuff::insert(new_foodstuff(&app_user1, 2, false), &connection).unwrap();
+    let foodstuff1 = foodstuff::insert(new_foodstuff(&app_user1, 1, true), &connection).unwrap();
+    foodstuff::insert(new_foodstuff(&app_user2, 1, true), &connection).unwrap();
+
+    let selected_foodstuffs =
+        foodstuff::select_by_app_user_id(app_user1.id(), &connection).unwrap();
+    assert_eq!(vec![foodstuff1, foodstuff2], selected_foodstuffs);
+}
+
+#[test]
+fn can_update_foodstuff() {
+    let app_user_foodstuff_id = 8;
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005000000008").unwrap();
+
+    delete_entries_with(&app_user_uid);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let new_foodstuff = foodstuff::new(
+        &app_user,
+        app_user_foodstuff_id,
+        FOODSTUFF_NAME.to_string(),
+        FOODSTUFF_PROTEIN,
+        FOODSTUFF_FATS,
+        FOODSTUFF_CARBS,
+        FOODSTUFF_CALORIES,
+        true,
+    );
+    let inserted_foodstuff = foodstuff::insert(new_foodstuff, &connection).unwrap();
+    let inserted_foodstuff_id = inserted_foodstuff.id();
+
+    let updated_foodstuff = foodstuff::update(
+        inserted_foodstuff,
+        "new name".to_string(),
+        1,
+        2,
+        3,
+        4,
+        &connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(inserted_foodstuff_id, updated_foodstuff.id());
+    assert_eq!(
+        app_user_foodstuff_id,
+        updated_foodstuff.app_user_foodstuff_id()
+    );
+    assert_eq!("new name", updated_foodstuff.name());
+    assert_eq!(1, updated_foodstuff.protein());
+    assert_eq!(2, updated_foodstuff.fats());
+    assert_eq!(3, updated_foodstuff.carbs());
+    assert_eq!(4, updated_foodstuff.calories());
+    assert!(updated_foodstuff.is_listed());
+
+    let selected_foodstuff = foodstuff::select_by_id(inserted_foodstuff_id, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated_foodstuff, selected_foodstuff);
+}
+
+#[test]
+fn revision_grows_with_each_modification() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005000000009").unwrap();
+
+    delete_entries_with(&app_user_uid);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    assert_eq!(
+        None,
+        foodstuff::select_max_revision(app_user.id(), &connection).unwrap()
+    );
+
+    let new_foodstuff = |app_user_foodstuff_id| {
+        foodstuff::new(
+            &app_user,
+            app_user_foodstuff_id,
+            FOODSTUFF_NAME.to_string(),
+            FOODSTUFF_PROTEIN,
+            FOODSTUFF_FATS,
+            FOODSTUFF_CARBS,
+            FOODSTUFF_CALORIES,
+            true,
+        )
+    };
+    let foodstuff1 = foodstuff::insert(new_foodstuff(1), &connection).unwrap();
+    let foodstuff2 = foodstuff::insert(new_foodstuff(2), &connection).unwrap();
+    assert!(foodstuff1.revision() < foodstuff2.revision());
+    let revision1 = foodstuff1.revision();
+    let revision2 = foodstuff2.revision();
+
+    let updated_foodstuff1 =
+        foodstuff::update(foodstuff1, "new name".to_string(), 1, 2, 3, 4, &connection)
+            .unwrap()
+            .unwrap();
+    assert!(revision2 < updated_foodstuff1.revision());
+    let unlisted_foodstuff1 = foodstuff::unlist(updated_foodstuff1, &connection)
+        .unwrap()
+        .unwrap();
+    assert!(revision2 < unlisted_foodstuff1.revision());
+
+    assert_eq!(
+        Some(unlisted_foodstuff1.revision()),
+        foodstuff::select_max_revision(app_user.id(), &connection).unwrap()
+    );
+
+    let changed = foodstuff::select_changed_since(app_user.id(), revision1, &connection).unwrap();
+    assert_eq!(vec![foodstuff2, unlisted_foodstuff1], changed);
+    let changed = foodstuff::select_changed_since(app_user.id(), revision2, &connection).unwrap();
+    assert_eq!(1, changed.len());
+}
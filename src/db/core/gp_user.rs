@@ -76,6 +76,19 @@ pub fn select_by_gp_uid(uid: &str, connection: &dyn DBConnection) -> Result<Opti
     );
 }
 
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<GpUser>, Error> {
+    select_by_column!(
+        GpUser,
+        gp_user_schema::table,
+        gp_user_schema::app_user_id,
+        app_user_id,
+        diesel_connection(connection)
+    )
+}
+
 #[cfg(test)]
 #[path = "./gp_user_test.rs"]
 mod gp_user_test;
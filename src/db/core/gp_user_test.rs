@@ -46,6 +46,10 @@ fn insertion_and_selection_work() {
     let selected_by_uid_user = gp_user::select_by_gp_uid(inserted_gp_user.gp_uid(), &connection);
     let selected_by_uid_user = selected_by_uid_user.unwrap().unwrap();
     assert_eq!(inserted_gp_user, selected_by_uid_user);
+
+    let selected_by_app_user = gp_user::select_by_app_user_id(app_user.id(), &connection);
+    let selected_by_app_user = selected_by_app_user.unwrap().unwrap();
+    assert_eq!(inserted_gp_user, selected_by_app_user);
 }
 
 #[test]
@@ -0,0 +1,156 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+use super::foodstuff::Foodstuff;
+
+table! {
+    history_entry {
+        id -> Integer,
+        app_user_id -> Integer,
+        app_user_history_entry_id -> Integer,
+        foodstuff_id -> Integer,
+        mass -> Integer,
+        time -> BigInt,
+    }
+}
+use self::history_entry as history_entry_schema;
+
+#[derive(Insertable)]
+#[table_name = "history_entry"]
+pub struct NewHistoryEntry {
+    app_user_id: i32,
+    app_user_history_entry_id: i32,
+    foodstuff_id: i32,
+    mass: i32,
+    time: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct HistoryEntry {
+    id: i32,
+    app_user_id: i32,
+    app_user_history_entry_id: i32,
+    foodstuff_id: i32,
+    mass: i32,
+    time: i64,
+}
+
+impl HistoryEntry {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn app_user_id(&self) -> i32 {
+        self.app_user_id
+    }
+
+    pub fn app_user_history_entry_id(&self) -> i32 {
+        self.app_user_history_entry_id
+    }
+
+    /// Server-side ID of the foodstuff (not |app_user_foodstuff_id|).
+    pub fn foodstuff_id(&self) -> i32 {
+        self.foodstuff_id
+    }
+
+    /// Mass of eaten foodstuff in grams.
+    pub fn mass(&self) -> i32 {
+        self.mass
+    }
+
+    /// Time of the meal in seconds since UNIX epoch.
+    pub fn time(&self) -> i64 {
+        self.time
+    }
+}
+
+pub fn new(
+    app_user: &AppUser,
+    app_user_history_entry_id: i32,
+    foodstuff: &Foodstuff,
+    mass: i32,
+    time: i64,
+) -> NewHistoryEntry {
+    NewHistoryEntry {
+        app_user_id: app_user.id(),
+        app_user_history_entry_id,
+        foodstuff_id: foodstuff.id(),
+        mass,
+        time,
+    }
+}
+
+pub fn insert(
+    history_entry: NewHistoryEntry,
+    connection: &dyn DBConnection,
+) -> Result<HistoryEntry, Error> {
+    insert!(
+        HistoryEntry,
+        history_entry,
+        history_entry_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_id(id: i32, connection: &dyn DBConnection) -> Result<Option<HistoryEntry>, Error> {
+    select_by_column!(
+        HistoryEntry,
+        history_entry_schema::table,
+        history_entry_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+/// Selects history entries of the user with time in range [|time_from|, |time_to|),
+/// ordered by time.
+pub fn select_by_app_user_id_and_time_range(
+    app_user_id: i32,
+    time_from: i64,
+    time_to: i64,
+    connection: &dyn DBConnection,
+) -> Result<Vec<HistoryEntry>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = history_entry_schema::table
+        .filter(history_entry_schema::app_user_id.eq(app_user_id))
+        .filter(history_entry_schema::time.ge(time_from))
+        .filter(history_entry_schema::time.lt(time_to))
+        .order((
+            history_entry_schema::time.asc(),
+            history_entry_schema::app_user_history_entry_id.asc(),
+        ))
+        .get_results::<HistoryEntry>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Returns deleted entry, None if there was no entry with given IDs.
+pub fn delete_by_app_user_history_entry_id(
+    app_user_id: i32,
+    app_user_history_entry_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<HistoryEntry>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = diesel::delete(
+        history_entry_schema::table
+            .filter(history_entry_schema::app_user_id.eq(app_user_id))
+            .filter(history_entry_schema::app_user_history_entry_id.eq(app_user_history_entry_id)),
+    )
+    .get_results::<HistoryEntry>(diesel_connection(connection));
+    match result {
+        Ok(mut vec) => Ok(vec.pop()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+#[path = "./history_entry_test.rs"]
+mod history_entry_test;
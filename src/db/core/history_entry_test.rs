@@ -0,0 +1,140 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::history_entry;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user_and_foodstuff(app_user_uid: &Uuid) -> (AppUser, Foodstuff) {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = app_user::insert(
+        app_user::new(*app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    let foodstuff = foodstuff::insert(
+        foodstuff::new(&app_user, 1, "apple".to_string(), 1, 2, 3, 4, true),
+        &connection,
+    )
+    .unwrap();
+    (app_user, foodstuff)
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005100000000").unwrap();
+    delete_entries_with(&app_user_uid);
+    let (app_user, foodstuff) = insert_user_and_foodstuff(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let inserted = history_entry::insert(
+        history_entry::new(&app_user, 1, &foodstuff, 150, 123),
+        &connection,
+    )
+    .unwrap();
+    assert!(inserted.id() > 0);
+    assert_eq!(app_user.id(), inserted.app_user_id());
+    assert_eq!(1, inserted.app_user_history_entry_id());
+    assert_eq!(foodstuff.id(), inserted.foodstuff_id());
+    assert_eq!(150, inserted.mass());
+    assert_eq!(123, inserted.time());
+
+    let selected = history_entry::select_by_id(inserted.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(inserted, selected);
+}
+
+#[test]
+fn entries_with_same_id_cannot_depend_on_single_app_user() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005100000001").unwrap();
+    delete_entries_with(&app_user_uid);
+    let (app_user, foodstuff) = insert_user_and_foodstuff(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    history_entry::insert(
+        history_entry::new(&app_user, 1, &foodstuff, 150, 123),
+        &connection,
+    )
+    .unwrap();
+    let second_insertion_result = history_entry::insert(
+        history_entry::new(&app_user, 1, &foodstuff, 200, 321),
+        &connection,
+    );
+    match second_insertion_result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Unexpected result: {:?}", second_insertion_result),
+    }
+}
+
+#[test]
+fn selection_by_time_range() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005100000002").unwrap();
+    delete_entries_with(&app_user_uid);
+    let (app_user, foodstuff) = insert_user_and_foodstuff(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let entry1 = history_entry::insert(
+        history_entry::new(&app_user, 1, &foodstuff, 100, 300),
+        &connection,
+    )
+    .unwrap();
+    let entry2 = history_entry::insert(
+        history_entry::new(&app_user, 2, &foodstuff, 100, 100),
+        &connection,
+    )
+    .unwrap();
+    let entry3 = history_entry::insert(
+        history_entry::new(&app_user, 3, &foodstuff, 100, 200),
+        &connection,
+    )
+    .unwrap();
+
+    let selected =
+        history_entry::select_by_app_user_id_and_time_range(app_user.id(), 0, 1000, &connection)
+            .unwrap();
+    assert_eq!(vec![entry2, entry3, entry1], selected);
+
+    // Range start is inclusive, range end is exclusive
+    let selected =
+        history_entry::select_by_app_user_id_and_time_range(app_user.id(), 200, 300, &connection)
+            .unwrap();
+    assert_eq!(1, selected.len());
+    assert_eq!(3, selected[0].app_user_history_entry_id());
+}
+
+#[test]
+fn deletion() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005100000003").unwrap();
+    delete_entries_with(&app_user_uid);
+    let (app_user, foodstuff) = insert_user_and_foodstuff(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let inserted = history_entry::insert(
+        history_entry::new(&app_user, 1, &foodstuff, 100, 300),
+        &connection,
+    )
+    .unwrap();
+
+    let deleted =
+        history_entry::delete_by_app_user_history_entry_id(app_user.id(), 1, &connection).unwrap();
+    assert_eq!(Some(inserted), deleted);
+    let deleted =
+        history_entry::delete_by_app_user_history_entry_id(app_user.id(), 1, &connection).unwrap();
+    assert_eq!(None, deleted);
+}
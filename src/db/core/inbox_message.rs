@@ -0,0 +1,145 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+
+table! {
+    inbox_message {
+        id -> Integer,
+        app_user_id -> Integer,
+        msg -> VarChar,
+        creation_time -> BigInt,
+    }
+}
+use self::inbox_message as inbox_message_schema;
+
+#[derive(Insertable)]
+#[table_name = "inbox_message"]
+pub struct NewInboxMessage {
+    app_user_id: i32,
+    msg: String,
+    creation_time: i64,
+}
+
+/// A server message stored for a user until the user acknowledges it.
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct InboxMessage {
+    id: i32,
+    app_user_id: i32,
+    msg: String,
+    creation_time: i64,
+}
+
+impl InboxMessage {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn app_user_id(&self) -> i32 {
+        self.app_user_id
+    }
+
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn creation_time(&self) -> i64 {
+        self.creation_time
+    }
+}
+
+pub fn new(msg: String, app_user: &AppUser, creation_time: i64) -> NewInboxMessage {
+    NewInboxMessage {
+        app_user_id: app_user.id(),
+        msg,
+        creation_time,
+    }
+}
+
+pub fn insert(
+    message: NewInboxMessage,
+    connection: &dyn DBConnection,
+) -> Result<InboxMessage, Error> {
+    insert!(
+        InboxMessage,
+        message,
+        inbox_message_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+/// Selects all messages of the user, ordered by their IDs.
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<InboxMessage>, Error> {
+    select_first_by_app_user_id(app_user_id, i64::MAX, connection)
+}
+
+/// Selects up to |limit| messages of the user with the smallest IDs, ordered by their IDs.
+pub fn select_first_by_app_user_id(
+    app_user_id: i32,
+    limit: i64,
+    connection: &dyn DBConnection,
+) -> Result<Vec<InboxMessage>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = inbox_message_schema::table
+        .filter(inbox_message_schema::app_user_id.eq(app_user_id))
+        .order(inbox_message_schema::id.asc())
+        .limit(limit)
+        .get_results::<InboxMessage>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Deletes messages of the user with the given IDs, IDs of messages
+/// of other users are ignored.
+pub fn delete_by_ids(
+    app_user_id: i32,
+    ids: &[i32],
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = inbox_message_schema::table
+        .filter(inbox_message_schema::app_user_id.eq(app_user_id))
+        .filter(inbox_message_schema::id.eq_any(ids));
+    diesel::delete(target).execute(diesel_connection(connection))?;
+    Ok(())
+}
+
+/// Deletes messages of the user created before |time|.
+pub fn delete_created_before(
+    app_user_id: i32,
+    time: i64,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = inbox_message_schema::table
+        .filter(inbox_message_schema::app_user_id.eq(app_user_id))
+        .filter(inbox_message_schema::creation_time.lt(time));
+    diesel::delete(target).execute(diesel_connection(connection))?;
+    Ok(())
+}
+
+pub fn delete_by_app_user_id(app_user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        inbox_message_schema::table,
+        inbox_message_schema::app_user_id,
+        app_user_id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./inbox_message_test.rs"]
+mod inbox_message_test;
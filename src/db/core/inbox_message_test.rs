@@ -0,0 +1,131 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::inbox_message;
+use crate::db::core::inbox_message::InboxMessage;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        &app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_message(
+    msg: &str,
+    app_user: &AppUser,
+    time: i64,
+    connection: &dyn DBConnection,
+) -> InboxMessage {
+    inbox_message::insert(
+        inbox_message::new(msg.to_owned(), app_user, time),
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009300000000", &connection);
+
+    let message1 = insert_message("msg1", &app_user, 123, &connection);
+    assert!(message1.id() > 0);
+    assert_eq!(app_user.id(), message1.app_user_id());
+    assert_eq!("msg1", message1.msg());
+    assert_eq!(123, message1.creation_time());
+
+    let message2 = insert_message("msg2", &app_user, 124, &connection);
+    let message3 = insert_message("msg3", &app_user, 125, &connection);
+    let message1_id = message1.id();
+    let message2_id = message2.id();
+    assert_eq!(
+        vec![message1, message2, message3],
+        inbox_message::select_by_app_user_id(app_user.id(), &connection).unwrap()
+    );
+
+    let selected =
+        inbox_message::select_first_by_app_user_id(app_user.id(), 2, &connection).unwrap();
+    let selected_ids: Vec<i32> = selected.iter().map(|message| message.id()).collect();
+    assert_eq!(vec![message1_id, message2_id], selected_ids);
+}
+
+#[test]
+fn deletion_by_ids() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user1 = insert_user("00000000-0000-0000-0000-009300000001", &connection);
+    let app_user2 = insert_user("00000000-0000-0000-0000-009300000002", &connection);
+    let message1 = insert_message("msg1", &app_user1, 123, &connection);
+    let message2 = insert_message("msg2", &app_user1, 123, &connection);
+    let message3 = insert_message("msg3", &app_user1, 123, &connection);
+    let foreign_message = insert_message("msg4", &app_user2, 123, &connection);
+
+    inbox_message::delete_by_ids(
+        app_user1.id(),
+        &[message1.id(), message3.id(), foreign_message.id()],
+        &connection,
+    )
+    .unwrap();
+    assert_eq!(
+        vec![message2],
+        inbox_message::select_by_app_user_id(app_user1.id(), &connection).unwrap()
+    );
+    // Messages of other users are not deleted
+    assert_eq!(
+        vec![foreign_message],
+        inbox_message::select_by_app_user_id(app_user2.id(), &connection).unwrap()
+    );
+}
+
+#[test]
+fn deletion_of_old_messages() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009300000003", &connection);
+    insert_message("msg1", &app_user, 100, &connection);
+    let message2 = insert_message("msg2", &app_user, 200, &connection);
+
+    inbox_message::delete_created_before(app_user.id(), 200, &connection).unwrap();
+    assert_eq!(
+        vec![message2],
+        inbox_message::select_by_app_user_id(app_user.id(), &connection).unwrap()
+    );
+}
+
+#[test]
+fn deletion_by_app_user_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user1 = insert_user("00000000-0000-0000-0000-009300000004", &connection);
+    let app_user2 = insert_user("00000000-0000-0000-0000-009300000005", &connection);
+    insert_message("msg1", &app_user1, 123, &connection);
+    insert_message("msg2", &app_user2, 123, &connection);
+
+    inbox_message::delete_by_app_user_id(app_user1.id(), &connection).unwrap();
+    assert!(
+        inbox_message::select_by_app_user_id(app_user1.id(), &connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        1,
+        inbox_message::select_by_app_user_id(app_user2.id(), &connection)
+            .unwrap()
+            .len()
+    );
+}
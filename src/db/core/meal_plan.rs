@@ -0,0 +1,217 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+
+table! {
+    meal_plan {
+        id -> Integer,
+        partner1_user_id -> Integer,
+        partner2_user_id -> Integer,
+        version -> BigInt,
+    }
+}
+use self::meal_plan as meal_plan_schema;
+
+#[derive(Insertable)]
+#[table_name = "meal_plan"]
+pub struct NewMealPlan {
+    partner1_user_id: i32,
+    partner2_user_id: i32,
+    version: i64,
+}
+
+/// Meal plan shared by 2 partners.
+/// |partner1_user_id| is always less than |partner2_user_id|, so that there's
+/// only 1 meal plan per pair of partners.
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct MealPlan {
+    id: i32,
+    partner1_user_id: i32,
+    partner2_user_id: i32,
+    version: i64,
+}
+
+impl MealPlan {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn partner1_user_id(&self) -> i32 {
+        self.partner1_user_id
+    }
+
+    pub fn partner2_user_id(&self) -> i32 {
+        self.partner2_user_id
+    }
+
+    /// Incremented on each modification of the plan.
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+}
+
+/// Partners can be passed in any order.
+pub fn new(partner1: &AppUser, partner2: &AppUser) -> NewMealPlan {
+    let (partner1_user_id, partner2_user_id) = ordered_ids(partner1.id(), partner2.id());
+    NewMealPlan {
+        partner1_user_id,
+        partner2_user_id,
+        version: 0,
+    }
+}
+
+fn ordered_ids(user_id1: i32, user_id2: i32) -> (i32, i32) {
+    if user_id1 < user_id2 {
+        (user_id1, user_id2)
+    } else {
+        (user_id2, user_id1)
+    }
+}
+
+pub fn insert(meal_plan: NewMealPlan, connection: &dyn DBConnection) -> Result<MealPlan, Error> {
+    insert!(
+        MealPlan,
+        meal_plan,
+        meal_plan_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_id(id: i32, connection: &dyn DBConnection) -> Result<Option<MealPlan>, Error> {
+    select_by_column!(
+        MealPlan,
+        meal_plan_schema::table,
+        meal_plan_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+/// Partners can be passed in any order.
+pub fn select_by_partners_user_ids(
+    partner1_user_id: i32,
+    partner2_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<MealPlan>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let (partner1_user_id, partner2_user_id) = ordered_ids(partner1_user_id, partner2_user_id);
+    let result = meal_plan_schema::table
+        .filter(meal_plan_schema::partner1_user_id.eq(partner1_user_id))
+        .filter(meal_plan_schema::partner2_user_id.eq(partner2_user_id))
+        .first::<MealPlan>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// Selects all meal plans of the user with all of partners, ordered by their IDs.
+pub fn select_by_user_id(
+    user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<MealPlan>, Error> {
+    use diesel::BoolExpressionMethods;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = meal_plan_schema::table
+        .filter(
+            meal_plan_schema::partner1_user_id
+                .eq(user_id)
+                .or(meal_plan_schema::partner2_user_id.eq(user_id)),
+        )
+        .order(meal_plan_schema::id.asc())
+        .get_results::<MealPlan>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Increments version of the plan only if the version in DB is still the version
+/// of the given |meal_plan|.
+/// Returns None if the plan was modified (or deleted) after it was selected.
+#[allow(clippy::comparison_chain)]
+pub fn increment_version(
+    meal_plan: MealPlan,
+    connection: &dyn DBConnection,
+) -> Result<Option<MealPlan>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = meal_plan_schema::table
+        .filter(meal_plan_schema::id.eq(meal_plan.id()))
+        .filter(meal_plan_schema::version.eq(meal_plan.version()));
+    let result = diesel::update(target)
+        .set(meal_plan_schema::version.eq(meal_plan.version() + 1))
+        .get_results::<MealPlan>(diesel_connection(connection));
+
+    match result {
+        Ok(mut vec) => {
+            if vec.len() > 1 {
+                panic!(
+                    "Count of updated meal plans is {}! Data in DB most likely was just corrupted!",
+                    vec.len()
+                );
+            } else if vec.len() == 1 {
+                Ok(Some(vec.pop().expect("Expect 1 meal plan")))
+            } else {
+                Ok(None)
+            }
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Increments version of the plan whatever the version in DB is, for modifications
+/// which don't depend on the state of the plan seen by a client.
+/// Returns None if there's no such plan.
+pub fn force_increment_version(
+    id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<MealPlan>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = meal_plan_schema::table.filter(meal_plan_schema::id.eq(id));
+    let result = diesel::update(target)
+        .set(meal_plan_schema::version.eq(meal_plan_schema::version + 1))
+        .get_result::<MealPlan>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// NOTE: items of the plan must be deleted before the plan itself.
+pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        meal_plan_schema::table,
+        meal_plan_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+/// Deletes all meal plans of the user with all of partners.
+/// NOTE: items of the plans must be deleted before the plans themselves.
+pub fn delete_by_user_id(user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        meal_plan_schema::table,
+        meal_plan_schema::partner1_user_id,
+        user_id,
+        diesel_connection(connection)
+    )?;
+    delete_by_column!(
+        meal_plan_schema::table,
+        meal_plan_schema::partner2_user_id,
+        user_id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./meal_plan_test.rs"]
+mod meal_plan_test;
@@ -0,0 +1,246 @@
+use diesel;
+
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+use super::foodstuff::Foodstuff;
+use super::meal_plan::MealPlan;
+use super::recipe::Recipe;
+
+table! {
+    meal_plan_item {
+        id -> Integer,
+        meal_plan_id -> Integer,
+        day -> Integer,
+        meal_type -> Integer,
+        foodstuff_id -> Nullable<Integer>,
+        recipe_id -> Nullable<Integer>,
+        weight -> Integer,
+    }
+}
+use self::meal_plan_item as meal_plan_item_schema;
+
+/// NOTE: the values are stored into DB, so think
+/// twice before reusing numeric values.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MealType {
+    Breakfast = 0,
+    Lunch = 1,
+    Dinner = 2,
+    Snack = 3,
+}
+impl MealType {
+    fn from_number(number: i32) -> Result<Self, ()> {
+        match number {
+            _ if number == MealType::Breakfast as i32 => Ok(MealType::Breakfast),
+            _ if number == MealType::Lunch as i32 => Ok(MealType::Lunch),
+            _ if number == MealType::Dinner as i32 => Ok(MealType::Dinner),
+            _ if number == MealType::Snack as i32 => Ok(MealType::Snack),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "meal_plan_item"]
+pub struct NewMealPlanItem {
+    meal_plan_id: i32,
+    day: i32,
+    meal_type: i32,
+    foodstuff_id: Option<i32>,
+    recipe_id: Option<i32>,
+    weight: i32,
+}
+
+/// A foodstuff or a recipe planned for a meal of a day.
+/// Exactly one of |foodstuff_id| and |recipe_id| is set.
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct MealPlanItem {
+    id: i32,
+    meal_plan_id: i32,
+    day: i32,
+    meal_type: i32,
+    foodstuff_id: Option<i32>,
+    recipe_id: Option<i32>,
+    weight: i32,
+}
+
+impl MealPlanItem {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn meal_plan_id(&self) -> i32 {
+        self.meal_plan_id
+    }
+
+    /// Day of the week, 0 is Monday.
+    pub fn day(&self) -> i32 {
+        self.day
+    }
+
+    /// Unknown meal types (which can appear only if data in DB is corrupted)
+    /// are treated as snacks.
+    pub fn meal_type(&self) -> MealType {
+        MealType::from_number(self.meal_type).unwrap_or(MealType::Snack)
+    }
+
+    /// Server-side ID of the foodstuff (not |app_user_foodstuff_id|).
+    pub fn foodstuff_id(&self) -> Option<i32> {
+        self.foodstuff_id
+    }
+
+    /// Server-side ID of the recipe (not |app_user_recipe_id|).
+    pub fn recipe_id(&self) -> Option<i32> {
+        self.recipe_id
+    }
+
+    /// Weight of the item in grams.
+    pub fn weight(&self) -> i32 {
+        self.weight
+    }
+}
+
+pub fn new_foodstuff_item(
+    meal_plan: &MealPlan,
+    day: i32,
+    meal_type: MealType,
+    foodstuff: &Foodstuff,
+    weight: i32,
+) -> NewMealPlanItem {
+    NewMealPlanItem {
+        meal_plan_id: meal_plan.id(),
+        day,
+        meal_type: meal_type as i32,
+        foodstuff_id: Some(foodstuff.id()),
+        recipe_id: None,
+        weight,
+    }
+}
+
+pub fn new_recipe_item(
+    meal_plan: &MealPlan,
+    day: i32,
+    meal_type: MealType,
+    recipe: &Recipe,
+    weight: i32,
+) -> NewMealPlanItem {
+    NewMealPlanItem {
+        meal_plan_id: meal_plan.id(),
+        day,
+        meal_type: meal_type as i32,
+        foodstuff_id: None,
+        recipe_id: Some(recipe.id()),
+        weight,
+    }
+}
+
+pub fn insert(item: NewMealPlanItem, connection: &dyn DBConnection) -> Result<MealPlanItem, Error> {
+    insert!(
+        MealPlanItem,
+        item,
+        meal_plan_item_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+/// Selects items of the plan ordered by day, then by meal type, then by order of insertion.
+pub fn select_by_meal_plan_id(
+    meal_plan_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<MealPlanItem>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = meal_plan_item_schema::table
+        .filter(meal_plan_item_schema::meal_plan_id.eq(meal_plan_id))
+        .order((
+            meal_plan_item_schema::day.asc(),
+            meal_plan_item_schema::meal_type.asc(),
+            meal_plan_item_schema::id.asc(),
+        ))
+        .get_results::<MealPlanItem>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Deletes all items of the given meal of the given day.
+pub fn delete_by_meal_plan_id_and_meal(
+    meal_plan_id: i32,
+    day: i32,
+    meal_type: MealType,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = diesel::delete(
+        meal_plan_item_schema::table
+            .filter(meal_plan_item_schema::meal_plan_id.eq(meal_plan_id))
+            .filter(meal_plan_item_schema::day.eq(day))
+            .filter(meal_plan_item_schema::meal_type.eq(meal_type as i32)),
+    )
+    .execute(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
+pub fn delete_by_meal_plan_id(
+    meal_plan_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    delete_by_column!(
+        meal_plan_item_schema::table,
+        meal_plan_item_schema::meal_plan_id,
+        meal_plan_id,
+        diesel_connection(connection)
+    )
+}
+
+/// Removes the recipe from all meal plans.
+/// Returns IDs of the modified plans, ordered and without duplicates.
+pub fn delete_by_recipe_id(
+    recipe_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<i32>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target =
+        meal_plan_item_schema::table.filter(meal_plan_item_schema::recipe_id.eq(recipe_id));
+    let mut meal_plan_ids = diesel::delete(target)
+        .returning(meal_plan_item_schema::meal_plan_id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    meal_plan_ids.sort_unstable();
+    meal_plan_ids.dedup();
+    Ok(meal_plan_ids)
+}
+
+/// Deletes items of all meal plans of the user.
+pub fn delete_by_user_id(user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    use super::meal_plan::meal_plan as meal_plan_schema;
+    use diesel::BoolExpressionMethods;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let users_meal_plans = meal_plan_schema::table
+        .filter(
+            meal_plan_schema::partner1_user_id
+                .eq(user_id)
+                .or(meal_plan_schema::partner2_user_id.eq(user_id)),
+        )
+        .select(meal_plan_schema::id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    let result = diesel::delete(
+        meal_plan_item_schema::table
+            .filter(meal_plan_item_schema::meal_plan_id.eq_any(users_meal_plans)),
+    )
+    .execute(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
+#[cfg(test)]
+#[path = "./meal_plan_item_test.rs"]
+mod meal_plan_item_test;
@@ -0,0 +1,174 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan::MealPlan;
+use crate::db::core::meal_plan_item;
+use crate::db::core::meal_plan_item::MealType;
+use crate::db::core::recipe;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_foodstuff(user: &AppUser, id: i32, connection: &dyn DBConnection) -> Foodstuff {
+    foodstuff::insert(
+        foodstuff::new(user, id, "apple".to_string(), 1, 2, 3, 4, true),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_meal_plan(uid1: &str, uid2: &str, connection: &dyn DBConnection) -> MealPlan {
+    let user1 = insert_user(uid1, connection);
+    let user2 = insert_user(uid2, connection);
+    meal_plan::insert(meal_plan::new(&user1, &user2), connection).unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let meal_plan = insert_meal_plan(
+        "00000000-0000-0000-0000-005700000000",
+        "00000000-0000-0000-0000-005700000001",
+        &connection,
+    );
+    let user = app_user::select_by_id(meal_plan.partner1_user_id(), &connection)
+        .unwrap()
+        .unwrap();
+    let foodstuff = insert_foodstuff(&user, 1, &connection);
+    let recipe = recipe::insert(recipe::new(&user, 1, "recipe".to_string()), &connection).unwrap();
+
+    let dinner = meal_plan_item::insert(
+        meal_plan_item::new_recipe_item(&meal_plan, 0, MealType::Dinner, &recipe, 300),
+        &connection,
+    )
+    .unwrap();
+    let breakfast = meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 0, MealType::Breakfast, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+    let next_day_snack = meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 1, MealType::Snack, &foodstuff, 50),
+        &connection,
+    )
+    .unwrap();
+
+    assert_eq!(meal_plan.id(), breakfast.meal_plan_id());
+    assert_eq!(0, breakfast.day());
+    assert_eq!(MealType::Breakfast, breakfast.meal_type());
+    assert_eq!(Some(foodstuff.id()), breakfast.foodstuff_id());
+    assert_eq!(None, breakfast.recipe_id());
+    assert_eq!(100, breakfast.weight());
+    assert_eq!(None, dinner.foodstuff_id());
+    assert_eq!(Some(recipe.id()), dinner.recipe_id());
+
+    // Ordered by day and meal type
+    let selected = meal_plan_item::select_by_meal_plan_id(meal_plan.id(), &connection).unwrap();
+    assert_eq!(vec![breakfast, dinner, next_day_snack], selected);
+}
+
+#[test]
+fn deletion_of_meal() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let meal_plan = insert_meal_plan(
+        "00000000-0000-0000-0000-005700000002",
+        "00000000-0000-0000-0000-005700000003",
+        &connection,
+    );
+    let user = app_user::select_by_id(meal_plan.partner1_user_id(), &connection)
+        .unwrap()
+        .unwrap();
+    let foodstuff = insert_foodstuff(&user, 1, &connection);
+
+    meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 2, MealType::Lunch, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+    meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 2, MealType::Lunch, &foodstuff, 200),
+        &connection,
+    )
+    .unwrap();
+    let other_day_lunch = meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 3, MealType::Lunch, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+    let same_day_dinner = meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 2, MealType::Dinner, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+
+    meal_plan_item::delete_by_meal_plan_id_and_meal(
+        meal_plan.id(),
+        2,
+        MealType::Lunch,
+        &connection,
+    )
+    .unwrap();
+    let selected = meal_plan_item::select_by_meal_plan_id(meal_plan.id(), &connection).unwrap();
+    assert_eq!(vec![same_day_dinner, other_day_lunch], selected);
+}
+
+#[test]
+fn deletion_by_recipe_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let meal_plan = insert_meal_plan(
+        "00000000-0000-0000-0000-005700000004",
+        "00000000-0000-0000-0000-005700000005",
+        &connection,
+    );
+    let user = app_user::select_by_id(meal_plan.partner2_user_id(), &connection)
+        .unwrap()
+        .unwrap();
+    let foodstuff = insert_foodstuff(&user, 1, &connection);
+    let recipe = recipe::insert(recipe::new(&user, 1, "recipe".to_string()), &connection).unwrap();
+
+    let foodstuff_item = meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 0, MealType::Lunch, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+    meal_plan_item::insert(
+        meal_plan_item::new_recipe_item(&meal_plan, 0, MealType::Lunch, &recipe, 100),
+        &connection,
+    )
+    .unwrap();
+    meal_plan_item::insert(
+        meal_plan_item::new_recipe_item(&meal_plan, 1, MealType::Dinner, &recipe, 100),
+        &connection,
+    )
+    .unwrap();
+
+    let modified_meal_plans =
+        meal_plan_item::delete_by_recipe_id(recipe.id(), &connection).unwrap();
+    assert_eq!(vec![meal_plan.id()], modified_meal_plans);
+    let selected = meal_plan_item::select_by_meal_plan_id(meal_plan.id(), &connection).unwrap();
+    assert_eq!(vec![foodstuff_item], selected);
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::meal_plan;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005600000000", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005600000001", &connection);
+
+    // Partners are passed in reverse order
+    let meal_plan = meal_plan::insert(meal_plan::new(&user2, &user1), &connection).unwrap();
+    assert!(meal_plan.id() > 0);
+    assert_eq!(user1.id(), meal_plan.partner1_user_id());
+    assert_eq!(user2.id(), meal_plan.partner2_user_id());
+    assert_eq!(0, meal_plan.version());
+
+    let selected = meal_plan::select_by_id(meal_plan.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(meal_plan, selected);
+    let selected = meal_plan::select_by_partners_user_ids(user1.id(), user2.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(meal_plan, selected);
+    let selected = meal_plan::select_by_partners_user_ids(user2.id(), user1.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(meal_plan, selected);
+}
+
+#[test]
+fn partners_cannot_have_2_meal_plans() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005600000002", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005600000003", &connection);
+
+    meal_plan::insert(meal_plan::new(&user1, &user2), &connection).unwrap();
+    let result = meal_plan::insert(meal_plan::new(&user2, &user1), &connection);
+    match result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Expected unique violation, got: {:?}", result),
+    }
+}
+
+#[test]
+fn version_increment_fails_for_outdated_version() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005600000004", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005600000005", &connection);
+
+    let meal_plan = meal_plan::insert(meal_plan::new(&user1, &user2), &connection).unwrap();
+    let same_meal_plan = meal_plan::select_by_id(meal_plan.id(), &connection)
+        .unwrap()
+        .unwrap();
+
+    let updated = meal_plan::increment_version(meal_plan, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(1, updated.version());
+
+    // The plan was modified after |same_meal_plan| was selected
+    let updated = meal_plan::increment_version(same_meal_plan, &connection).unwrap();
+    assert!(updated.is_none());
+
+    let selected = meal_plan::select_by_partners_user_ids(user1.id(), user2.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(1, selected.version());
+}
+
+#[test]
+fn forced_version_increment() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005600000012", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005600000013", &connection);
+
+    let meal_plan = meal_plan::insert(meal_plan::new(&user1, &user2), &connection).unwrap();
+    let meal_plan_id = meal_plan.id();
+    meal_plan::increment_version(meal_plan, &connection)
+        .unwrap()
+        .unwrap();
+
+    let updated = meal_plan::force_increment_version(meal_plan_id, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(2, updated.version());
+
+    meal_plan::delete_by_id(meal_plan_id, &connection).unwrap();
+    let updated = meal_plan::force_increment_version(meal_plan_id, &connection).unwrap();
+    assert!(updated.is_none());
+}
+
+#[test]
+fn deletion_by_user_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005600000006", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005600000007", &connection);
+    let user3 = insert_user("00000000-0000-0000-0000-005600000008", &connection);
+
+    let meal_plan_1_2 = meal_plan::insert(meal_plan::new(&user1, &user2), &connection).unwrap();
+    let meal_plan_2_3 = meal_plan::insert(meal_plan::new(&user2, &user3), &connection).unwrap();
+    let meal_plan_1_3 = meal_plan::insert(meal_plan::new(&user1, &user3), &connection).unwrap();
+
+    meal_plan::delete_by_user_id(user2.id(), &connection).unwrap();
+    assert!(meal_plan::select_by_id(meal_plan_1_2.id(), &connection)
+        .unwrap()
+        .is_none());
+    assert!(meal_plan::select_by_id(meal_plan_2_3.id(), &connection)
+        .unwrap()
+        .is_none());
+    assert!(meal_plan::select_by_id(meal_plan_1_3.id(), &connection)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn selection_by_user_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005600000009", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005600000010", &connection);
+    let user3 = insert_user("00000000-0000-0000-0000-005600000011", &connection);
+
+    let meal_plan_1_2 = meal_plan::insert(meal_plan::new(&user1, &user2), &connection).unwrap();
+    let meal_plan_2_3 = meal_plan::insert(meal_plan::new(&user2, &user3), &connection).unwrap();
+    meal_plan::insert(meal_plan::new(&user1, &user3), &connection).unwrap();
+
+    let selected = meal_plan::select_by_user_id(user2.id(), &connection).unwrap();
+    assert_eq!(vec![meal_plan_1_2, meal_plan_2_3], selected);
+}
@@ -1,3 +1,4 @@
+use diesel_migrations::MigrationConnection;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{thread, time};
 
@@ -8,6 +9,10 @@ use super::error::ErrorKind;
 
 embed_migrations!("migrations");
 
+/// Version of the newest migration in the 'migrations' dir,
+/// must be updated when a migration is added.
+pub const LATEST_MIGRATION_VERSION: &str = "20200517120000";
+
 pub fn perform_migrations(connection: &dyn DBConnection) -> Result<(), Error> {
     embedded_migrations::run_with_output(
         connection
@@ -18,6 +23,15 @@ pub fn perform_migrations(connection: &dyn DBConnection) -> Result<(), Error> {
     Ok(())
 }
 
+/// Whether the DB is migrated to the LATEST_MIGRATION_VERSION.
+pub fn are_migrations_applied(connection: &dyn DBConnection) -> Result<bool, Error> {
+    let versions = connection
+        .underlying_connection_source()
+        .diesel_connection()
+        .previously_run_migration_versions()?;
+    Ok(versions.contains(LATEST_MIGRATION_VERSION))
+}
+
 pub fn migrate_with_timeout(raw_connection_params: &str, timeout_secs: i64) -> Result<(), Error> {
     let connection = get_connection_with_timeout(raw_connection_params, timeout_secs)?;
     perform_migrations(&connection)
@@ -59,3 +73,7 @@ fn now() -> i64 {
         .expect("If there's no system time, something has gone horribly wrong")
         .as_secs() as i64
 }
+
+#[cfg(test)]
+#[path = "./migrator_test.rs"]
+mod migrator_test;
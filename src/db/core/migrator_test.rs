@@ -0,0 +1,28 @@
+use std::fs;
+
+use super::are_migrations_applied;
+use super::LATEST_MIGRATION_VERSION;
+use crate::db::core::testing_util as dbtesting_utils;
+
+#[test]
+fn latest_migration_version_is_up_to_date() {
+    let migrations_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
+    let latest_migration_version = fs::read_dir(migrations_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| !name.starts_with('.'))
+        .map(|name| {
+            // '2020-04-19-120000_name' -> '20200419120000'
+            name.split('_').next().unwrap().replace("-", "")
+        })
+        .max()
+        .unwrap();
+    assert_eq!(latest_migration_version, LATEST_MIGRATION_VERSION);
+}
+
+#[test]
+fn migrations_are_applied() {
+    // NOTE: testing connections are migrated when created
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    assert!(are_migrations_applied(&connection).unwrap());
+}
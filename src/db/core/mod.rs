@@ -9,12 +9,24 @@ pub mod app_user;
 pub mod connection;
 pub mod device;
 pub mod error;
+pub mod failed_pairing_attempts;
 pub mod fcm_token;
 pub mod foodstuff;
+pub mod foodstuff_share;
 pub mod gp_user;
+pub mod history_entry;
+pub mod inbox_message;
+pub mod meal_plan;
+pub mod meal_plan_item;
 pub mod migrator;
+pub mod notification_outbox;
 pub mod paired_partners;
 pub mod pairing_code_range;
+pub mod recipe;
+pub mod recipe_ingredient;
+pub mod shopping_list;
+pub mod shopping_list_item;
+pub mod shopping_list_share;
 pub mod taken_pairing_code;
 pub mod transaction;
 pub mod util;
@@ -0,0 +1,270 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::device::Device;
+use super::diesel_connection;
+use super::error::Error;
+
+table! {
+    notification_outbox {
+        id -> Integer,
+        app_user_id -> Integer,
+        device_id -> Integer,
+        msg -> VarChar,
+        state -> Integer,
+        attempts_count -> Integer,
+        next_attempt_time -> BigInt,
+        last_error -> Nullable<VarChar>,
+        creation_time -> BigInt,
+    }
+}
+use self::notification_outbox as notification_outbox_schema;
+
+/// NOTE: the values are stored into DB, so think
+/// twice before reusing numeric values.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NotificationState {
+    /// The notification waits for its next sending attempt.
+    Pending = 0,
+    /// All sending attempts failed, the notification won't be sent anymore.
+    Dead = 1,
+}
+impl NotificationState {
+    fn from_number(number: i32) -> Result<Self, ()> {
+        match number {
+            _ if number == NotificationState::Pending as i32 => Ok(NotificationState::Pending),
+            _ if number == NotificationState::Dead as i32 => Ok(NotificationState::Dead),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "notification_outbox"]
+pub struct NewOutboxNotification {
+    app_user_id: i32,
+    device_id: i32,
+    msg: String,
+    state: i32,
+    attempts_count: i32,
+    next_attempt_time: i64,
+    creation_time: i64,
+}
+
+/// A notification queued for sending to a device of a user.
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct OutboxNotification {
+    id: i32,
+    app_user_id: i32,
+    device_id: i32,
+    msg: String,
+    state: i32,
+    attempts_count: i32,
+    next_attempt_time: i64,
+    last_error: Option<String>,
+    creation_time: i64,
+}
+
+impl OutboxNotification {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn app_user_id(&self) -> i32 {
+        self.app_user_id
+    }
+
+    pub fn device_id(&self) -> i32 {
+        self.device_id
+    }
+
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    /// Unknown state values (which can appear only if data in DB is corrupted)
+    /// are treated as dead, so that such notifications wouldn't be sent.
+    pub fn state(&self) -> NotificationState {
+        NotificationState::from_number(self.state).unwrap_or(NotificationState::Dead)
+    }
+
+    pub fn attempts_count(&self) -> i32 {
+        self.attempts_count
+    }
+
+    pub fn next_attempt_time(&self) -> i64 {
+        self.next_attempt_time
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn creation_time(&self) -> i64 {
+        self.creation_time
+    }
+}
+
+/// A pending notification which is due to be sent at |now|.
+pub fn new(msg: String, app_user: &AppUser, device: &Device, now: i64) -> NewOutboxNotification {
+    NewOutboxNotification {
+        app_user_id: app_user.id(),
+        device_id: device.id(),
+        msg,
+        state: NotificationState::Pending as i32,
+        attempts_count: 0,
+        next_attempt_time: now,
+        creation_time: now,
+    }
+}
+
+pub fn insert(
+    notification: NewOutboxNotification,
+    connection: &dyn DBConnection,
+) -> Result<OutboxNotification, Error> {
+    insert!(
+        OutboxNotification,
+        notification,
+        notification_outbox_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_id(
+    id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<OutboxNotification>, Error> {
+    select_by_column!(
+        OutboxNotification,
+        notification_outbox_schema::table,
+        notification_outbox_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+/// Selects all notifications of the user, ordered by their IDs.
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<OutboxNotification>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = notification_outbox_schema::table
+        .filter(notification_outbox_schema::app_user_id.eq(app_user_id))
+        .order(notification_outbox_schema::id.asc())
+        .get_results::<OutboxNotification>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Claims up to |limit| pending notifications due at |now| by postponing their next
+/// attempts to |lease_until|, so that other senders wouldn't pick them up while they're
+/// being sent. Notifications locked by other transactions are skipped.
+/// Should be called in a transaction.
+pub fn claim_due(
+    now: i64,
+    lease_until: i64,
+    limit: i64,
+    connection: &dyn DBConnection,
+) -> Result<Vec<OutboxNotification>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let ids = notification_outbox_schema::table
+        .select(notification_outbox_schema::id)
+        .filter(notification_outbox_schema::state.eq(NotificationState::Pending as i32))
+        .filter(notification_outbox_schema::next_attempt_time.le(now))
+        .order(notification_outbox_schema::id.asc())
+        .limit(limit)
+        .for_update()
+        .skip_locked()
+        .get_results::<i32>(diesel_connection(connection))?;
+    claim_by_ids(&ids, now, lease_until, connection)
+}
+
+/// Claims pending notifications with the given IDs which are due at |now|, see |claim_due|.
+/// Notifications which are not pending anymore or are leased by another sender
+/// are skipped.
+/// Should be called in a transaction.
+pub fn claim_by_ids(
+    ids: &[i32],
+    now: i64,
+    lease_until: i64,
+    connection: &dyn DBConnection,
+) -> Result<Vec<OutboxNotification>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = notification_outbox_schema::table
+        .filter(notification_outbox_schema::id.eq_any(ids))
+        .filter(notification_outbox_schema::state.eq(NotificationState::Pending as i32))
+        .filter(notification_outbox_schema::next_attempt_time.le(now));
+    let mut result = diesel::update(target)
+        .set(notification_outbox_schema::next_attempt_time.eq(lease_until))
+        .get_results::<OutboxNotification>(diesel_connection(connection))?;
+    result.sort_by_key(|notification| notification.id);
+    Ok(result)
+}
+
+/// Counts a failed sending attempt of the notification and moves it
+/// into the |new_state| with the |next_attempt_time|.
+pub fn record_failed_attempt(
+    notification: &OutboxNotification,
+    new_state: NotificationState,
+    next_attempt_time: i64,
+    error: &str,
+    connection: &dyn DBConnection,
+) -> Result<OutboxNotification, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = notification_outbox_schema::table
+        .filter(notification_outbox_schema::id.eq(notification.id()));
+    let result = diesel::update(target)
+        .set((
+            notification_outbox_schema::attempts_count
+                .eq(notification_outbox_schema::attempts_count + 1),
+            notification_outbox_schema::state.eq(new_state as i32),
+            notification_outbox_schema::next_attempt_time.eq(next_attempt_time),
+            notification_outbox_schema::last_error.eq(Some(error)),
+        ))
+        .get_result::<OutboxNotification>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        notification_outbox_schema::table,
+        notification_outbox_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn delete_by_device_id(device_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        notification_outbox_schema::table,
+        notification_outbox_schema::device_id,
+        device_id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn delete_by_app_user_id(app_user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        notification_outbox_schema::table,
+        notification_outbox_schema::app_user_id,
+        app_user_id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./notification_outbox_test.rs"]
+mod notification_outbox_test;
@@ -0,0 +1,278 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::device;
+use crate::db::core::device::Device;
+use crate::db::core::notification_outbox;
+use crate::db::core::notification_outbox::NotificationState;
+use crate::db::core::notification_outbox::OutboxNotification;
+use crate::db::core::testing_util as dbtesting_utils;
+use crate::db::core::testing_util::lock_due_notifications;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        &app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_device(app_user: &AppUser, connection: &dyn DBConnection) -> Device {
+    device::insert(device::new(Uuid::new_v4(), app_user), connection).unwrap()
+}
+
+fn insert_notification(
+    msg: &str,
+    app_user: &AppUser,
+    device: &Device,
+    now: i64,
+    connection: &dyn DBConnection,
+) -> OutboxNotification {
+    notification_outbox::insert(
+        notification_outbox::new(msg.to_owned(), app_user, device, now),
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009200000000", &connection);
+    let device = insert_device(&app_user, &connection);
+
+    let notification = insert_notification("msg1", &app_user, &device, 123, &connection);
+    assert!(notification.id() > 0);
+    assert_eq!(app_user.id(), notification.app_user_id());
+    assert_eq!(device.id(), notification.device_id());
+    assert_eq!("msg1", notification.msg());
+    assert_eq!(NotificationState::Pending, notification.state());
+    assert_eq!(0, notification.attempts_count());
+    assert_eq!(123, notification.next_attempt_time());
+    assert_eq!(None, notification.last_error());
+    assert_eq!(123, notification.creation_time());
+
+    let selected = notification_outbox::select_by_id(notification.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(notification, selected);
+
+    let notification2 = insert_notification("msg2", &app_user, &device, 124, &connection);
+    let selected = notification_outbox::select_by_app_user_id(app_user.id(), &connection).unwrap();
+    assert_eq!(vec![notification, notification2], selected);
+}
+
+#[test]
+fn due_notifications_claiming() {
+    let _lock = lock_due_notifications();
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009200000001", &connection);
+    let device = insert_device(&app_user, &connection);
+
+    let notification1 = insert_notification("msg1", &app_user, &device, 10, &connection);
+    let notification2 = insert_notification("msg2", &app_user, &device, 11, &connection);
+    let notification3 = insert_notification("msg3", &app_user, &device, 12, &connection);
+    let notification4 = insert_notification("msg4", &app_user, &device, 13, &connection);
+    notification_outbox::record_failed_attempt(
+        &notification3,
+        NotificationState::Dead,
+        11,
+        "error",
+        &connection,
+    )
+    .unwrap();
+
+    // Limit is respected
+    let claimed = notification_outbox::claim_due(12, 100, 1, &connection).unwrap();
+    assert_eq!(1, claimed.len());
+    assert_eq!(notification1.id(), claimed[0].id());
+    assert_eq!(100, claimed[0].next_attempt_time());
+
+    // Claimed, dead and not yet due notifications are not claimed
+    let claimed = notification_outbox::claim_due(12, 100, 10, &connection).unwrap();
+    assert_eq!(1, claimed.len());
+    assert_eq!(notification2.id(), claimed[0].id());
+
+    let claimed = notification_outbox::claim_due(99, 100, 10, &connection).unwrap();
+    assert_eq!(1, claimed.len());
+    assert_eq!(notification4.id(), claimed[0].id());
+
+    // The lease expires
+    let claimed = notification_outbox::claim_due(100, 200, 10, &connection).unwrap();
+    let claimed_ids: Vec<i32> = claimed.iter().map(|claimed| claimed.id()).collect();
+    assert_eq!(
+        vec![notification1.id(), notification2.id(), notification4.id()],
+        claimed_ids
+    );
+
+    delete_entries_with(app_user.uid());
+}
+
+#[test]
+fn claiming_by_ids() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009200000002", &connection);
+    let device = insert_device(&app_user, &connection);
+
+    let notification1 = insert_notification("msg1", &app_user, &device, 1000000, &connection);
+    let notification2 = insert_notification("msg2", &app_user, &device, 1000000, &connection);
+    let notification3 = insert_notification("msg3", &app_user, &device, 1000000, &connection);
+    notification_outbox::record_failed_attempt(
+        &notification3,
+        NotificationState::Dead,
+        1000000,
+        "error",
+        &connection,
+    )
+    .unwrap();
+
+    let notification4 = insert_notification("msg4", &app_user, &device, 1000050, &connection);
+
+    // Dead and not yet due notifications are not claimed
+    let ids = vec![
+        notification2.id(),
+        notification1.id(),
+        notification3.id(),
+        notification4.id(),
+    ];
+    let claimed = notification_outbox::claim_by_ids(&ids, 1000000, 1000100, &connection).unwrap();
+    let claimed_ids: Vec<i32> = claimed.iter().map(|claimed| claimed.id()).collect();
+    assert_eq!(vec![notification1.id(), notification2.id()], claimed_ids);
+    assert!(claimed
+        .iter()
+        .all(|claimed| claimed.next_attempt_time() == 1000100));
+}
+
+#[test]
+fn notifications_claimed_as_due_are_not_claimed_by_ids() {
+    let _lock = lock_due_notifications();
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009200000007", &connection);
+    let device = insert_device(&app_user, &connection);
+
+    let notification = insert_notification("msg", &app_user, &device, 300, &connection);
+
+    // The worker leases the notification right after it's enqueued
+    let claimed = notification_outbox::claim_due(300, 400, 10, &connection).unwrap();
+    assert_eq!(1, claimed.len());
+    assert_eq!(notification.id(), claimed[0].id());
+
+    // So the request which enqueued it must not send it too
+    let claimed =
+        notification_outbox::claim_by_ids(&[notification.id()], 300, 400, &connection).unwrap();
+    assert!(claimed.is_empty());
+
+    // But it can be claimed when the lease expires
+    let claimed =
+        notification_outbox::claim_by_ids(&[notification.id()], 400, 500, &connection).unwrap();
+    assert_eq!(1, claimed.len());
+
+    delete_entries_with(app_user.uid());
+}
+
+#[test]
+fn failed_attempts_recording() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009200000003", &connection);
+    let device = insert_device(&app_user, &connection);
+    let notification = insert_notification("msg", &app_user, &device, 1000000, &connection);
+
+    let notification = notification_outbox::record_failed_attempt(
+        &notification,
+        NotificationState::Pending,
+        1000030,
+        "error1",
+        &connection,
+    )
+    .unwrap();
+    assert_eq!(1, notification.attempts_count());
+    assert_eq!(NotificationState::Pending, notification.state());
+    assert_eq!(1000030, notification.next_attempt_time());
+    assert_eq!(Some("error1"), notification.last_error());
+
+    let notification = notification_outbox::record_failed_attempt(
+        &notification,
+        NotificationState::Dead,
+        1000090,
+        "error2",
+        &connection,
+    )
+    .unwrap();
+    assert_eq!(2, notification.attempts_count());
+    assert_eq!(NotificationState::Dead, notification.state());
+    assert_eq!(Some("error2"), notification.last_error());
+    assert_eq!(
+        notification,
+        notification_outbox::select_by_id(notification.id(), &connection)
+            .unwrap()
+            .unwrap()
+    );
+}
+
+#[test]
+fn deletion_by_device_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = insert_user("00000000-0000-0000-0000-009200000004", &connection);
+    let device1 = insert_device(&app_user, &connection);
+    let device2 = insert_device(&app_user, &connection);
+    let notification1 = insert_notification("msg1", &app_user, &device1, 1000000, &connection);
+    let notification2 = insert_notification("msg2", &app_user, &device2, 1000000, &connection);
+
+    notification_outbox::delete_by_device_id(device1.id(), &connection).unwrap();
+    assert!(
+        notification_outbox::select_by_id(notification1.id(), &connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        notification_outbox::select_by_id(notification2.id(), &connection)
+            .unwrap()
+            .is_some()
+    );
+
+    notification_outbox::delete_by_id(notification2.id(), &connection).unwrap();
+    assert!(
+        notification_outbox::select_by_id(notification2.id(), &connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn deletion_by_app_user_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user1 = insert_user("00000000-0000-0000-0000-009200000005", &connection);
+    let app_user2 = insert_user("00000000-0000-0000-0000-009200000006", &connection);
+    let device1 = insert_device(&app_user1, &connection);
+    let device2 = insert_device(&app_user2, &connection);
+    insert_notification("msg1", &app_user1, &device1, 1000000, &connection);
+    insert_notification("msg2", &app_user2, &device2, 1000000, &connection);
+
+    notification_outbox::delete_by_app_user_id(app_user1.id(), &connection).unwrap();
+    assert!(
+        notification_outbox::select_by_app_user_id(app_user1.id(), &connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        1,
+        notification_outbox::select_by_app_user_id(app_user2.id(), &connection)
+            .unwrap()
+            .len()
+    );
+}
@@ -261,6 +261,28 @@ pub fn select_by_partner_user_id_and_state(
     validate_selection_results(result, connection)
 }
 
+/// Selects pairs of the partner in all pairing states, ordered by their IDs.
+pub fn select_by_partner_user_id(
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<PairedPartners>, Error> {
+    use diesel::BoolExpressionMethods;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+
+    let result = paired_partners_schema::table
+        .filter(
+            paired_partners_schema::partner1_user_id
+                .eq(partner_user_id)
+                .or(paired_partners_schema::partner2_user_id.eq(partner_user_id)),
+        )
+        .order(paired_partners_schema::id.asc())
+        .get_results::<PairedPartners>(diesel_connection(connection));
+
+    let result = result.map_err(|err| err.into());
+    validate_selection_results(result, connection)
+}
+
 pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
     delete_by_column!(
         paired_partners_schema::table,
@@ -302,3 +302,30 @@ fn selection_of_all_paired_partners_and_data_corruption() {
             .unwrap();
     assert!(spp.is_empty());
 }
+
+#[test]
+fn selection_by_user_in_all_states() {
+    let uid1 = Uuid::from_str("00000000-0000-0000-0000-002210000021").unwrap();
+    let uid2 = Uuid::from_str("00000000-0000-0000-0000-002210000022").unwrap();
+    let uid3 = Uuid::from_str("00000000-0000-0000-0000-002210000023").unwrap();
+    delete_user_with_uid(&uid1);
+    delete_user_with_uid(&uid2);
+    delete_user_with_uid(&uid3);
+    let conn = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 =
+        app_user::insert(app_user::new(uid1, "".to_owned(), Uuid::new_v4()), &conn).unwrap();
+    let user2 =
+        app_user::insert(app_user::new(uid2, "".to_owned(), Uuid::new_v4()), &conn).unwrap();
+    let user3 =
+        app_user::insert(app_user::new(uid3, "".to_owned(), Uuid::new_v4()), &conn).unwrap();
+
+    let pp1 = paired_partners::new(&user1, &user2, PairingState::Done, 123);
+    let pp2 = paired_partners::new(&user3, &user1, PairingState::NotConfirmed, 321);
+    let pp3 = paired_partners::new(&user2, &user3, PairingState::Done, 123);
+    let pp1 = paired_partners::insert(pp1, &conn).unwrap();
+    let pp2 = paired_partners::insert(pp2, &conn).unwrap();
+    paired_partners::insert(pp3, &conn).unwrap();
+
+    let selected = paired_partners::select_by_partner_user_id(user1.id(), &conn).unwrap();
+    assert_eq!(vec![pp1, pp2], selected);
+}
@@ -0,0 +1,158 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+
+table! {
+    recipe {
+        id -> Integer,
+        app_user_id -> Integer,
+        app_user_recipe_id -> Integer,
+        name -> VarChar,
+    }
+}
+use self::recipe as recipe_schema;
+
+#[derive(Insertable)]
+#[table_name = "recipe"]
+pub struct NewRecipe {
+    app_user_id: i32,
+    app_user_recipe_id: i32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct Recipe {
+    id: i32,
+    app_user_id: i32,
+    app_user_recipe_id: i32,
+    name: String,
+}
+
+impl Recipe {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn app_user_id(&self) -> i32 {
+        self.app_user_id
+    }
+
+    pub fn app_user_recipe_id(&self) -> i32 {
+        self.app_user_recipe_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+pub fn new(app_user: &AppUser, app_user_recipe_id: i32, name: String) -> NewRecipe {
+    NewRecipe {
+        app_user_id: app_user.id(),
+        app_user_recipe_id,
+        name,
+    }
+}
+
+pub fn insert(recipe: NewRecipe, connection: &dyn DBConnection) -> Result<Recipe, Error> {
+    insert!(
+        Recipe,
+        recipe,
+        recipe_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_id(id: i32, connection: &dyn DBConnection) -> Result<Option<Recipe>, Error> {
+    select_by_column!(
+        Recipe,
+        recipe_schema::table,
+        recipe_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_app_user_recipe_id(
+    app_user_id: i32,
+    app_user_recipe_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<Recipe>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = recipe_schema::table
+        .filter(recipe_schema::app_user_id.eq(app_user_id))
+        .filter(recipe_schema::app_user_recipe_id.eq(app_user_recipe_id))
+        .first::<Recipe>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// Selects all recipes of the user, ordered by |app_user_recipe_id|.
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<Recipe>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = recipe_schema::table
+        .filter(recipe_schema::app_user_id.eq(app_user_id))
+        .order(recipe_schema::app_user_recipe_id.asc())
+        .get_results::<Recipe>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Returns Option in case the recipe gets deleted while update operation is not finished yet
+#[allow(clippy::comparison_chain)]
+pub fn update_name(
+    recipe: Recipe,
+    name: String,
+    connection: &dyn DBConnection,
+) -> Result<Option<Recipe>, Error> {
+    let result = update_column!(
+        Recipe,
+        recipe_schema::table,
+        recipe_schema::id,
+        recipe.id(),
+        recipe_schema::name,
+        name,
+        diesel_connection(connection)
+    );
+
+    match result {
+        Ok(mut vec) => {
+            if vec.len() > 1 {
+                panic!(
+                    "Count of updated recipes is {}! Data in DB most likely was just corrupted!",
+                    vec.len()
+                );
+            } else if vec.len() == 1 {
+                Ok(Some(vec.pop().expect("Expect 1 recipe")))
+            } else {
+                Ok(None)
+            }
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// NOTE: ingredients of the recipe must be deleted before the recipe itself.
+pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        recipe_schema::table,
+        recipe_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./recipe_test.rs"]
+mod recipe_test;
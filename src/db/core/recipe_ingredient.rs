@@ -0,0 +1,121 @@
+use diesel;
+
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+use super::foodstuff::Foodstuff;
+use super::recipe::Recipe;
+
+table! {
+    recipe_ingredient {
+        id -> Integer,
+        recipe_id -> Integer,
+        foodstuff_id -> Integer,
+        weight -> Integer,
+    }
+}
+use self::recipe_ingredient as recipe_ingredient_schema;
+
+#[derive(Insertable)]
+#[table_name = "recipe_ingredient"]
+pub struct NewRecipeIngredient {
+    recipe_id: i32,
+    foodstuff_id: i32,
+    weight: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct RecipeIngredient {
+    id: i32,
+    recipe_id: i32,
+    foodstuff_id: i32,
+    weight: i32,
+}
+
+impl RecipeIngredient {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn recipe_id(&self) -> i32 {
+        self.recipe_id
+    }
+
+    /// Server-side ID of the foodstuff (not |app_user_foodstuff_id|).
+    pub fn foodstuff_id(&self) -> i32 {
+        self.foodstuff_id
+    }
+
+    /// Weight of the ingredient in grams.
+    pub fn weight(&self) -> i32 {
+        self.weight
+    }
+}
+
+pub fn new(recipe: &Recipe, foodstuff: &Foodstuff, weight: i32) -> NewRecipeIngredient {
+    NewRecipeIngredient {
+        recipe_id: recipe.id(),
+        foodstuff_id: foodstuff.id(),
+        weight,
+    }
+}
+
+pub fn insert(
+    ingredient: NewRecipeIngredient,
+    connection: &dyn DBConnection,
+) -> Result<RecipeIngredient, Error> {
+    insert!(
+        RecipeIngredient,
+        ingredient,
+        recipe_ingredient_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+/// Selects ingredients of the recipe in the order of their insertion.
+pub fn select_by_recipe_id(
+    recipe_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<RecipeIngredient>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = recipe_ingredient_schema::table
+        .filter(recipe_ingredient_schema::recipe_id.eq(recipe_id))
+        .order(recipe_ingredient_schema::id.asc())
+        .get_results::<RecipeIngredient>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+pub fn delete_by_recipe_id(recipe_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        recipe_ingredient_schema::table,
+        recipe_ingredient_schema::recipe_id,
+        recipe_id,
+        diesel_connection(connection)
+    )
+}
+
+/// Deletes ingredients of all recipes of the user.
+pub fn delete_by_app_user_id(app_user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    use super::recipe::recipe as recipe_schema;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let users_recipes = recipe_schema::table
+        .filter(recipe_schema::app_user_id.eq(app_user_id))
+        .select(recipe_schema::id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    let result = diesel::delete(
+        recipe_ingredient_schema::table
+            .filter(recipe_ingredient_schema::recipe_id.eq_any(users_recipes)),
+    )
+    .execute(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
+#[cfg(test)]
+#[path = "./recipe_ingredient_test.rs"]
+mod recipe_ingredient_test;
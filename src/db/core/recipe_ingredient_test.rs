@@ -0,0 +1,77 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::foodstuff;
+use crate::db::core::recipe;
+use crate::db::core::recipe_ingredient;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn insertion_selection_and_deletion_work() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005300000000").unwrap();
+    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    let foodstuff1 = foodstuff::insert(
+        foodstuff::new(&app_user, 1, "apple".to_string(), 1, 2, 3, 4, true),
+        &connection,
+    )
+    .unwrap();
+    let foodstuff2 = foodstuff::insert(
+        foodstuff::new(&app_user, 2, "pear".to_string(), 1, 2, 3, 4, true),
+        &connection,
+    )
+    .unwrap();
+    let recipe1 =
+        recipe::insert(recipe::new(&app_user, 1, "salad".to_string()), &connection).unwrap();
+    let recipe2 =
+        recipe::insert(recipe::new(&app_user, 2, "jam".to_string()), &connection).unwrap();
+
+    let ingredient1 = recipe_ingredient::insert(
+        recipe_ingredient::new(&recipe1, &foodstuff2, 200),
+        &connection,
+    )
+    .unwrap();
+    let ingredient2 = recipe_ingredient::insert(
+        recipe_ingredient::new(&recipe1, &foodstuff1, 100),
+        &connection,
+    )
+    .unwrap();
+    let ingredient3 = recipe_ingredient::insert(
+        recipe_ingredient::new(&recipe2, &foodstuff1, 300),
+        &connection,
+    )
+    .unwrap();
+    assert_eq!(recipe1.id(), ingredient1.recipe_id());
+    assert_eq!(foodstuff2.id(), ingredient1.foodstuff_id());
+    assert_eq!(200, ingredient1.weight());
+
+    let selected = recipe_ingredient::select_by_recipe_id(recipe1.id(), &connection).unwrap();
+    assert_eq!(vec![ingredient1, ingredient2], selected);
+
+    recipe_ingredient::delete_by_recipe_id(recipe1.id(), &connection).unwrap();
+    assert!(
+        recipe_ingredient::select_by_recipe_id(recipe1.id(), &connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        vec![ingredient3],
+        recipe_ingredient::select_by_recipe_id(recipe2.id(), &connection).unwrap()
+    );
+}
@@ -0,0 +1,117 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::recipe;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005200000000").unwrap();
+    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let inserted =
+        recipe::insert(recipe::new(&app_user, 1, "soup".to_string()), &connection).unwrap();
+    assert!(inserted.id() > 0);
+    assert_eq!(app_user.id(), inserted.app_user_id());
+    assert_eq!(1, inserted.app_user_recipe_id());
+    assert_eq!("soup", inserted.name());
+
+    let selected = recipe::select_by_id(inserted.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(inserted, selected);
+    let selected = recipe::select_by_app_user_recipe_id(app_user.id(), 1, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(inserted, selected);
+    assert!(
+        recipe::select_by_app_user_recipe_id(app_user.id(), 2, &connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn recipes_with_same_id_cannot_depend_on_single_app_user() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005200000001").unwrap();
+    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    recipe::insert(recipe::new(&app_user, 1, "soup".to_string()), &connection).unwrap();
+    let second_insertion_result =
+        recipe::insert(recipe::new(&app_user, 1, "salad".to_string()), &connection);
+    match second_insertion_result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Unexpected result: {:?}", second_insertion_result),
+    }
+}
+
+#[test]
+fn selection_of_all_recipes_of_user() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005200000002").unwrap();
+    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let recipe2 =
+        recipe::insert(recipe::new(&app_user, 2, "soup".to_string()), &connection).unwrap();
+    let recipe1 =
+        recipe::insert(recipe::new(&app_user, 1, "salad".to_string()), &connection).unwrap();
+    let selected = recipe::select_by_app_user_id(app_user.id(), &connection).unwrap();
+    assert_eq!(vec![recipe1, recipe2], selected);
+}
+
+#[test]
+fn update_and_deletion() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005200000003").unwrap();
+    delete_entries_with(&app_user_uid);
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+
+    let inserted =
+        recipe::insert(recipe::new(&app_user, 1, "soup".to_string()), &connection).unwrap();
+    let id = inserted.id();
+    let updated = recipe::update_name(inserted, "borsch".to_string(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!("borsch", updated.name());
+    assert_eq!(
+        updated,
+        recipe::select_by_id(id, &connection).unwrap().unwrap()
+    );
+
+    recipe::delete_by_id(id, &connection).unwrap();
+    assert!(recipe::select_by_id(id, &connection).unwrap().is_none());
+}
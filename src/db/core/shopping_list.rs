@@ -0,0 +1,127 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+
+table! {
+    shopping_list {
+        id -> Integer,
+        app_user_id -> Integer,
+        app_user_shopping_list_id -> Integer,
+        name -> VarChar,
+    }
+}
+use self::shopping_list as shopping_list_schema;
+
+#[derive(Insertable)]
+#[table_name = "shopping_list"]
+pub struct NewShoppingList {
+    app_user_id: i32,
+    app_user_shopping_list_id: i32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct ShoppingList {
+    id: i32,
+    app_user_id: i32,
+    app_user_shopping_list_id: i32,
+    name: String,
+}
+
+impl ShoppingList {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn app_user_id(&self) -> i32 {
+        self.app_user_id
+    }
+
+    pub fn app_user_shopping_list_id(&self) -> i32 {
+        self.app_user_shopping_list_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+pub fn new(app_user: &AppUser, app_user_shopping_list_id: i32, name: String) -> NewShoppingList {
+    NewShoppingList {
+        app_user_id: app_user.id(),
+        app_user_shopping_list_id,
+        name,
+    }
+}
+
+pub fn insert(
+    shopping_list: NewShoppingList,
+    connection: &dyn DBConnection,
+) -> Result<ShoppingList, Error> {
+    insert!(
+        ShoppingList,
+        shopping_list,
+        shopping_list_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_id(id: i32, connection: &dyn DBConnection) -> Result<Option<ShoppingList>, Error> {
+    select_by_column!(
+        ShoppingList,
+        shopping_list_schema::table,
+        shopping_list_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_app_user_shopping_list_id(
+    app_user_id: i32,
+    app_user_shopping_list_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<ShoppingList>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = shopping_list_schema::table
+        .filter(shopping_list_schema::app_user_id.eq(app_user_id))
+        .filter(shopping_list_schema::app_user_shopping_list_id.eq(app_user_shopping_list_id))
+        .first::<ShoppingList>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// Selects all shopping lists of the user, ordered by |app_user_shopping_list_id|.
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<ShoppingList>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = shopping_list_schema::table
+        .filter(shopping_list_schema::app_user_id.eq(app_user_id))
+        .order(shopping_list_schema::app_user_shopping_list_id.asc())
+        .get_results::<ShoppingList>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// NOTE: items and shares of the shopping list must be deleted before the list itself.
+pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    delete_by_column!(
+        shopping_list_schema::table,
+        shopping_list_schema::id,
+        id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./shopping_list_test.rs"]
+mod shopping_list_test;
@@ -0,0 +1,196 @@
+use diesel;
+
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+use super::foodstuff::Foodstuff;
+use super::shopping_list::ShoppingList;
+
+table! {
+    shopping_list_item {
+        id -> Integer,
+        shopping_list_id -> Integer,
+        list_item_id -> Integer,
+        foodstuff_id -> Integer,
+        weight -> Integer,
+        is_checked -> Bool,
+    }
+}
+use self::shopping_list_item as shopping_list_item_schema;
+
+#[derive(Insertable)]
+#[table_name = "shopping_list_item"]
+pub struct NewShoppingListItem {
+    shopping_list_id: i32,
+    list_item_id: i32,
+    foodstuff_id: i32,
+    weight: i32,
+    is_checked: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct ShoppingListItem {
+    id: i32,
+    shopping_list_id: i32,
+    list_item_id: i32,
+    foodstuff_id: i32,
+    weight: i32,
+    is_checked: bool,
+}
+
+impl ShoppingListItem {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn shopping_list_id(&self) -> i32 {
+        self.shopping_list_id
+    }
+
+    /// ID of the item within its shopping list.
+    pub fn list_item_id(&self) -> i32 {
+        self.list_item_id
+    }
+
+    /// Server-side ID of the foodstuff (not |app_user_foodstuff_id|).
+    /// NOTE: the foodstuff can belong either to the owner of the list or to a partner of the owner.
+    pub fn foodstuff_id(&self) -> i32 {
+        self.foodstuff_id
+    }
+
+    /// Weight of the item in grams.
+    pub fn weight(&self) -> i32 {
+        self.weight
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.is_checked
+    }
+}
+
+/// New items are not checked.
+pub fn new(
+    shopping_list: &ShoppingList,
+    list_item_id: i32,
+    foodstuff: &Foodstuff,
+    weight: i32,
+) -> NewShoppingListItem {
+    NewShoppingListItem {
+        shopping_list_id: shopping_list.id(),
+        list_item_id,
+        foodstuff_id: foodstuff.id(),
+        weight,
+        is_checked: false,
+    }
+}
+
+pub fn insert(
+    item: NewShoppingListItem,
+    connection: &dyn DBConnection,
+) -> Result<ShoppingListItem, Error> {
+    insert!(
+        ShoppingListItem,
+        item,
+        shopping_list_item_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+/// Selects items of the shopping list ordered by |list_item_id|.
+pub fn select_by_shopping_list_id(
+    shopping_list_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<ShoppingListItem>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = shopping_list_item_schema::table
+        .filter(shopping_list_item_schema::shopping_list_id.eq(shopping_list_id))
+        .order(shopping_list_item_schema::list_item_id.asc())
+        .get_results::<ShoppingListItem>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Returns None if there's no such item in the shopping list.
+#[allow(clippy::comparison_chain)]
+pub fn update_is_checked(
+    shopping_list_id: i32,
+    list_item_id: i32,
+    is_checked: bool,
+    connection: &dyn DBConnection,
+) -> Result<Option<ShoppingListItem>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let target = shopping_list_item_schema::table
+        .filter(shopping_list_item_schema::shopping_list_id.eq(shopping_list_id))
+        .filter(shopping_list_item_schema::list_item_id.eq(list_item_id));
+    let result = diesel::update(target)
+        .set(shopping_list_item_schema::is_checked.eq(is_checked))
+        .get_results::<ShoppingListItem>(diesel_connection(connection));
+
+    match result {
+        Ok(mut vec) => {
+            if vec.len() > 1 {
+                panic!(
+                    "Count of updated shopping list items is {}! Data in DB most likely was just corrupted!",
+                    vec.len()
+                );
+            } else if vec.len() == 1 {
+                Ok(Some(vec.pop().expect("Expect 1 shopping list item")))
+            } else {
+                Ok(None)
+            }
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn delete_by_shopping_list_id(
+    shopping_list_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    delete_by_column!(
+        shopping_list_item_schema::table,
+        shopping_list_item_schema::shopping_list_id,
+        shopping_list_id,
+        diesel_connection(connection)
+    )
+}
+
+/// Deletes items of all shopping lists of the user, and also items of foreign
+/// shopping lists which reference foodstuffs of the user.
+pub fn delete_by_app_user_id(app_user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    use super::foodstuff::foodstuff as foodstuff_schema;
+    use super::shopping_list::shopping_list as shopping_list_schema;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let users_shopping_lists = shopping_list_schema::table
+        .filter(shopping_list_schema::app_user_id.eq(app_user_id))
+        .select(shopping_list_schema::id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    diesel::delete(
+        shopping_list_item_schema::table
+            .filter(shopping_list_item_schema::shopping_list_id.eq_any(users_shopping_lists)),
+    )
+    .execute(diesel_connection(connection))?;
+
+    let users_foodstuffs = foodstuff_schema::table
+        .filter(foodstuff_schema::app_user_id.eq(app_user_id))
+        .select(foodstuff_schema::id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    let result = diesel::delete(
+        shopping_list_item_schema::table
+            .filter(shopping_list_item_schema::foodstuff_id.eq_any(users_foodstuffs)),
+    )
+    .execute(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
+#[cfg(test)]
+#[path = "./shopping_list_item_test.rs"]
+mod shopping_list_item_test;
@@ -0,0 +1,163 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list::ShoppingList;
+use crate::db::core::shopping_list_item;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_foodstuff(user: &AppUser, id: i32, connection: &dyn DBConnection) -> Foodstuff {
+    foodstuff::insert(
+        foodstuff::new(user, id, "apple".to_string(), 1, 2, 3, 4, true),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_list(user: &AppUser, id: i32, connection: &dyn DBConnection) -> ShoppingList {
+    shopping_list::insert(shopping_list::new(user, id, "list".to_string()), connection).unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-0000-0000-0000-005900000000", &connection);
+    let foodstuff1 = insert_foodstuff(&user, 1, &connection);
+    let foodstuff2 = insert_foodstuff(&user, 2, &connection);
+    let list = insert_list(&user, 1, &connection);
+
+    let item2 = shopping_list_item::insert(
+        shopping_list_item::new(&list, 2, &foodstuff2, 200),
+        &connection,
+    )
+    .unwrap();
+    let item1 = shopping_list_item::insert(
+        shopping_list_item::new(&list, 1, &foodstuff1, 100),
+        &connection,
+    )
+    .unwrap();
+    assert!(item1.id() > 0);
+    assert_eq!(list.id(), item1.shopping_list_id());
+    assert_eq!(1, item1.list_item_id());
+    assert_eq!(foodstuff1.id(), item1.foodstuff_id());
+    assert_eq!(100, item1.weight());
+    assert!(!item1.is_checked());
+
+    let selected = shopping_list_item::select_by_shopping_list_id(list.id(), &connection).unwrap();
+    assert_eq!(vec![item1, item2], selected);
+}
+
+#[test]
+fn same_item_id_cannot_be_used_twice_in_list() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-0000-0000-0000-005900000001", &connection);
+    let foodstuff = insert_foodstuff(&user, 1, &connection);
+    let list = insert_list(&user, 1, &connection);
+
+    shopping_list_item::insert(
+        shopping_list_item::new(&list, 1, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+    let result = shopping_list_item::insert(
+        shopping_list_item::new(&list, 1, &foodstuff, 200),
+        &connection,
+    );
+    match result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Expected unique violation, got: {:?}", result),
+    }
+}
+
+#[test]
+fn is_checked_update() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-0000-0000-0000-005900000002", &connection);
+    let foodstuff = insert_foodstuff(&user, 1, &connection);
+    let list = insert_list(&user, 1, &connection);
+
+    let item = shopping_list_item::insert(
+        shopping_list_item::new(&list, 1, &foodstuff, 100),
+        &connection,
+    )
+    .unwrap();
+    let updated = shopping_list_item::update_is_checked(list.id(), 1, true, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(item.id(), updated.id());
+    assert!(updated.is_checked());
+    let selected = shopping_list_item::select_by_shopping_list_id(list.id(), &connection).unwrap();
+    assert_eq!(vec![updated], selected);
+
+    // Not existing item
+    assert!(
+        shopping_list_item::update_is_checked(list.id(), 2, true, &connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn deletion_by_app_user_id() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005900000003", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005900000004", &connection);
+    let foodstuff_of_user1 = insert_foodstuff(&user1, 1, &connection);
+    let foodstuff_of_user2 = insert_foodstuff(&user2, 1, &connection);
+    let list_of_user1 = insert_list(&user1, 1, &connection);
+    let list_of_user2 = insert_list(&user2, 1, &connection);
+
+    shopping_list_item::insert(
+        shopping_list_item::new(&list_of_user1, 1, &foodstuff_of_user2, 100),
+        &connection,
+    )
+    .unwrap();
+    shopping_list_item::insert(
+        shopping_list_item::new(&list_of_user2, 1, &foodstuff_of_user1, 100),
+        &connection,
+    )
+    .unwrap();
+    let untouched_item = shopping_list_item::insert(
+        shopping_list_item::new(&list_of_user2, 2, &foodstuff_of_user2, 100),
+        &connection,
+    )
+    .unwrap();
+
+    shopping_list_item::delete_by_app_user_id(user1.id(), &connection).unwrap();
+    assert!(
+        shopping_list_item::select_by_shopping_list_id(list_of_user1.id(), &connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        vec![untouched_item],
+        shopping_list_item::select_by_shopping_list_id(list_of_user2.id(), &connection).unwrap()
+    );
+}
@@ -0,0 +1,178 @@
+use diesel;
+
+use super::app_user::AppUser;
+use super::connection::DBConnection;
+use super::diesel_connection;
+use super::error::Error;
+use super::shopping_list::ShoppingList;
+
+table! {
+    shopping_list_share {
+        id -> Integer,
+        shopping_list_id -> Integer,
+        partner_user_id -> Integer,
+    }
+}
+use self::shopping_list_share as shopping_list_share_schema;
+
+#[derive(Insertable)]
+#[table_name = "shopping_list_share"]
+pub struct NewShoppingListShare {
+    shopping_list_id: i32,
+    partner_user_id: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, Queryable)]
+pub struct ShoppingListShare {
+    id: i32,
+    shopping_list_id: i32,
+    partner_user_id: i32,
+}
+
+impl ShoppingListShare {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn shopping_list_id(&self) -> i32 {
+        self.shopping_list_id
+    }
+
+    pub fn partner_user_id(&self) -> i32 {
+        self.partner_user_id
+    }
+}
+
+/// Shares the shopping list of its owner with the |partner_user|.
+pub fn new(shopping_list: &ShoppingList, partner_user: &AppUser) -> NewShoppingListShare {
+    NewShoppingListShare {
+        shopping_list_id: shopping_list.id(),
+        partner_user_id: partner_user.id(),
+    }
+}
+
+pub fn insert(
+    share: NewShoppingListShare,
+    connection: &dyn DBConnection,
+) -> Result<ShoppingListShare, Error> {
+    insert!(
+        ShoppingListShare,
+        share,
+        shopping_list_share_schema::table,
+        diesel_connection(connection)
+    )
+}
+
+pub fn select_by_shopping_list_id_and_partner_user_id(
+    shopping_list_id: i32,
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<ShoppingListShare>, Error> {
+    use crate::db::core::transform_diesel_single_result;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = shopping_list_share_schema::table
+        .filter(shopping_list_share_schema::shopping_list_id.eq(shopping_list_id))
+        .filter(shopping_list_share_schema::partner_user_id.eq(partner_user_id))
+        .first::<ShoppingListShare>(diesel_connection(connection));
+    transform_diesel_single_result(result)
+}
+
+/// Selects all shares of the shopping list, ordered by their IDs.
+pub fn select_by_shopping_list_id(
+    shopping_list_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<ShoppingListShare>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = shopping_list_share_schema::table
+        .filter(shopping_list_share_schema::shopping_list_id.eq(shopping_list_id))
+        .order(shopping_list_share_schema::id.asc())
+        .get_results::<ShoppingListShare>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+/// Selects all shopping lists shares with the partner, ordered by their IDs.
+pub fn select_by_partner_user_id(
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<ShoppingListShare>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let result = shopping_list_share_schema::table
+        .filter(shopping_list_share_schema::partner_user_id.eq(partner_user_id))
+        .order(shopping_list_share_schema::id.asc())
+        .get_results::<ShoppingListShare>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
+pub fn delete_by_shopping_list_id(
+    shopping_list_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    delete_by_column!(
+        shopping_list_share_schema::table,
+        shopping_list_share_schema::shopping_list_id,
+        shopping_list_id,
+        diesel_connection(connection)
+    )
+}
+
+/// Deletes all shares of shopping lists of the owner with the partner (but not of the partner
+/// with the owner).
+pub fn delete_by_owner_and_partner_user_ids(
+    owner_user_id: i32,
+    partner_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<(), Error> {
+    use super::shopping_list::shopping_list as shopping_list_schema;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let owners_shopping_lists = shopping_list_schema::table
+        .filter(shopping_list_schema::app_user_id.eq(owner_user_id))
+        .select(shopping_list_schema::id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    let result = diesel::delete(
+        shopping_list_share_schema::table
+            .filter(shopping_list_share_schema::shopping_list_id.eq_any(owners_shopping_lists))
+            .filter(shopping_list_share_schema::partner_user_id.eq(partner_user_id)),
+    )
+    .execute(diesel_connection(connection));
+    result.map(|_| ()).map_err(|err| err.into())
+}
+
+/// Deletes all shares both of the user's shopping lists and of shopping lists shared with the user.
+pub fn delete_by_user_id(user_id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
+    use super::shopping_list::shopping_list as shopping_list_schema;
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+    use diesel::RunQueryDsl;
+
+    let users_shopping_lists = shopping_list_schema::table
+        .filter(shopping_list_schema::app_user_id.eq(user_id))
+        .select(shopping_list_schema::id)
+        .get_results::<i32>(diesel_connection(connection))?;
+    diesel::delete(
+        shopping_list_share_schema::table
+            .filter(shopping_list_share_schema::shopping_list_id.eq_any(users_shopping_lists)),
+    )
+    .execute(diesel_connection(connection))?;
+    delete_by_column!(
+        shopping_list_share_schema::table,
+        shopping_list_share_schema::partner_user_id,
+        user_id,
+        diesel_connection(connection)
+    )
+}
+
+#[cfg(test)]
+#[path = "./shopping_list_share_test.rs"]
+mod shopping_list_share_test;
@@ -0,0 +1,137 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list::ShoppingList;
+use crate::db::core::shopping_list_share;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn insert_list(user: &AppUser, id: i32, connection: &dyn DBConnection) -> ShoppingList {
+    shopping_list::insert(shopping_list::new(user, id, "list".to_string()), connection).unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner = insert_user("00000000-0000-0000-0000-005a00000000", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005a00000001", &connection);
+    let list = insert_list(&owner, 1, &connection);
+
+    let share = shopping_list_share::insert(shopping_list_share::new(&list, &partner), &connection)
+        .unwrap();
+    assert!(share.id() > 0);
+    assert_eq!(list.id(), share.shopping_list_id());
+    assert_eq!(partner.id(), share.partner_user_id());
+
+    let selected = shopping_list_share::select_by_shopping_list_id_and_partner_user_id(
+        list.id(),
+        partner.id(),
+        &connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(share, selected);
+    assert!(
+        shopping_list_share::select_by_shopping_list_id_and_partner_user_id(
+            list.id(),
+            owner.id(),
+            &connection
+        )
+        .unwrap()
+        .is_none()
+    );
+    let selected = shopping_list_share::select_by_shopping_list_id(list.id(), &connection).unwrap();
+    assert_eq!(vec![share], selected);
+}
+
+#[test]
+fn list_cannot_be_shared_twice_with_same_partner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner = insert_user("00000000-0000-0000-0000-005a00000002", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005a00000003", &connection);
+    let list = insert_list(&owner, 1, &connection);
+
+    shopping_list_share::insert(shopping_list_share::new(&list, &partner), &connection).unwrap();
+    let result =
+        shopping_list_share::insert(shopping_list_share::new(&list, &partner), &connection);
+    match result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Expected unique violation, got: {:?}", result),
+    }
+}
+
+#[test]
+fn selection_by_partner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let owner1 = insert_user("00000000-0000-0000-0000-005a00000004", &connection);
+    let owner2 = insert_user("00000000-0000-0000-0000-005a00000005", &connection);
+    let partner = insert_user("00000000-0000-0000-0000-005a00000006", &connection);
+    let list1 = insert_list(&owner1, 1, &connection);
+    let list2 = insert_list(&owner2, 1, &connection);
+    let list3 = insert_list(&partner, 1, &connection);
+
+    let share1 =
+        shopping_list_share::insert(shopping_list_share::new(&list1, &partner), &connection)
+            .unwrap();
+    let share2 =
+        shopping_list_share::insert(shopping_list_share::new(&list2, &partner), &connection)
+            .unwrap();
+    // Shared by the partner, not with the partner
+    shopping_list_share::insert(shopping_list_share::new(&list3, &owner1), &connection).unwrap();
+
+    let selected =
+        shopping_list_share::select_by_partner_user_id(partner.id(), &connection).unwrap();
+    assert_eq!(vec![share1, share2], selected);
+}
+
+#[test]
+fn deletion_by_owner_and_partner() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005a00000007", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005a00000008", &connection);
+    let user3 = insert_user("00000000-0000-0000-0000-005a00000009", &connection);
+    let list1 = insert_list(&user1, 1, &connection);
+    let list2 = insert_list(&user2, 1, &connection);
+
+    shopping_list_share::insert(shopping_list_share::new(&list1, &user2), &connection).unwrap();
+    let share_1_to_3 =
+        shopping_list_share::insert(shopping_list_share::new(&list1, &user3), &connection).unwrap();
+    let share_2_to_1 =
+        shopping_list_share::insert(shopping_list_share::new(&list2, &user1), &connection).unwrap();
+
+    shopping_list_share::delete_by_owner_and_partner_user_ids(user1.id(), user2.id(), &connection)
+        .unwrap();
+    assert_eq!(
+        vec![share_1_to_3],
+        shopping_list_share::select_by_shopping_list_id(list1.id(), &connection).unwrap()
+    );
+    assert_eq!(
+        vec![share_2_to_1],
+        shopping_list_share::select_by_shopping_list_id(list2.id(), &connection).unwrap()
+    );
+}
@@ -0,0 +1,119 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::error::Error;
+use crate::db::core::error::ErrorKind;
+use crate::db::core::shopping_list;
+use crate::db::core::testing_util as dbtesting_utils;
+
+// Cleaning up before tests
+fn delete_entries_with(app_user_uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_entries_with(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn insertion_and_selection_work() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-0000-0000-0000-005800000000", &connection);
+
+    let list = shopping_list::insert(
+        shopping_list::new(&user, 1, "groceries".to_string()),
+        &connection,
+    )
+    .unwrap();
+    assert!(list.id() > 0);
+    assert_eq!(user.id(), list.app_user_id());
+    assert_eq!(1, list.app_user_shopping_list_id());
+    assert_eq!("groceries", list.name());
+
+    let selected = shopping_list::select_by_id(list.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(list, selected);
+    let selected = shopping_list::select_by_app_user_shopping_list_id(user.id(), 1, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(list, selected);
+    assert!(
+        shopping_list::select_by_app_user_shopping_list_id(user.id(), 2, &connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn same_id_cannot_be_used_twice_by_same_user() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005800000001", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005800000002", &connection);
+
+    shopping_list::insert(
+        shopping_list::new(&user1, 1, "list".to_string()),
+        &connection,
+    )
+    .unwrap();
+    // Other user can use same ID
+    shopping_list::insert(
+        shopping_list::new(&user2, 1, "list".to_string()),
+        &connection,
+    )
+    .unwrap();
+
+    let result = shopping_list::insert(
+        shopping_list::new(&user1, 1, "list2".to_string()),
+        &connection,
+    );
+    match result {
+        Err(Error(ErrorKind::UniqueViolation(_), _)) => {}
+        _ => panic!("Expected unique violation, got: {:?}", result),
+    }
+}
+
+#[test]
+fn selection_by_user_and_deletion() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = insert_user("00000000-0000-0000-0000-005800000003", &connection);
+    let user2 = insert_user("00000000-0000-0000-0000-005800000004", &connection);
+
+    let list2 = shopping_list::insert(
+        shopping_list::new(&user1, 2, "list2".to_string()),
+        &connection,
+    )
+    .unwrap();
+    let list1 = shopping_list::insert(
+        shopping_list::new(&user1, 1, "list1".to_string()),
+        &connection,
+    )
+    .unwrap();
+    shopping_list::insert(
+        shopping_list::new(&user2, 1, "list".to_string()),
+        &connection,
+    )
+    .unwrap();
+
+    let selected = shopping_list::select_by_app_user_id(user1.id(), &connection).unwrap();
+    assert_eq!(vec![list1, list2], selected);
+
+    shopping_list::delete_by_id(selected[0].id(), &connection).unwrap();
+    let selected_after_deletion =
+        shopping_list::select_by_app_user_id(user1.id(), &connection).unwrap();
+    assert_eq!(&selected[1..], &selected_after_deletion[..]);
+}
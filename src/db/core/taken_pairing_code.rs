@@ -131,6 +131,21 @@ pub fn select_by_app_user_id(
     transform_diesel_single_result(result)
 }
 
+/// Selects codes of the user in all families, ordered by their IDs.
+pub fn select_all_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Vec<TakenPairingCode>, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+
+    let result = taken_pairing_code_schema::table
+        .filter(taken_pairing_code_schema::app_user_id.eq(app_user_id))
+        .order(taken_pairing_code_schema::id.asc())
+        .get_results::<TakenPairingCode>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
 pub fn delete_family(family: &str, connection: &dyn DBConnection) -> Result<(), Error> {
     delete_by_column!(
         taken_pairing_code_schema::table,
@@ -187,6 +202,17 @@ pub fn select_any(
     transform_diesel_single_result(result)
 }
 
+pub fn count_family(family: &str, connection: &dyn DBConnection) -> Result<i64, Error> {
+    use diesel::ExpressionMethods;
+    use diesel::QueryDsl;
+
+    let result = taken_pairing_code_schema::table
+        .filter(taken_pairing_code_schema::family.eq(family))
+        .count()
+        .get_result::<i64>(diesel_connection(connection));
+    result.map_err(|err| err.into())
+}
+
 pub fn delete_by_id(id: i32, connection: &dyn DBConnection) -> Result<(), Error> {
     delete_by_column!(
         taken_pairing_code_schema::table,
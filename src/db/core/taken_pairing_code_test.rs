@@ -301,10 +301,16 @@ fn app_user_id_is_not_foreign_key() {
     let code = taken_pairing_code::new(&user, 10, 100, fam.to_owned());
     let inserted_code = taken_pairing_code::insert(code, &conn).unwrap();
 
-    // Let's delete the user
+    // Let's delete the user row only (delete_app_user would delete the code too)
     assert!(app_user::select_by_id(user.id(), &conn).unwrap().is_some());
     let serv_conn = dbtesting_utils::testing_connection_for_server_user().unwrap();
-    delete_app_user(&user.uid(), &serv_conn).unwrap();
+    delete_by_column!(
+        app_user::app_user::table,
+        app_user::app_user::id,
+        user.id(),
+        diesel_connection(&serv_conn)
+    )
+    .unwrap();
     assert!(app_user::select_by_id(user.id(), &conn).unwrap().is_none());
 
     // Let's verify that our code is still in place and is unchanged
@@ -367,3 +373,55 @@ fn delete_by_id() {
             .unwrap()
     );
 }
+
+#[test]
+fn selection_of_codes_in_all_families() {
+    let fam1 = format!("{}{}", file!(), line!());
+    let fam2 = format!("{}{}", file!(), line!());
+    delete_codes_with_family(&fam1);
+    delete_codes_with_family(&fam2);
+    let uid = Uuid::from_str("00000000-0000-0000-0000-002220000021").unwrap();
+    delete_user_with_uid(&uid);
+    let conn = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let user = app_user::insert(app_user::new(uid, "".to_owned(), Uuid::new_v4()), &conn);
+    let user = user.unwrap();
+
+    let code1 = taken_pairing_code::new(&user, 10, 100, fam1.to_owned());
+    let code2 = taken_pairing_code::new(&user, 10, 100, fam2.to_owned());
+    let code1 = taken_pairing_code::insert(code1, &conn).unwrap();
+    let code2 = taken_pairing_code::insert(code2, &conn).unwrap();
+
+    let selected = taken_pairing_code::select_all_by_app_user_id(user.id(), &conn).unwrap();
+    assert_eq!(vec![code1, code2], selected);
+}
+
+#[test]
+fn count_family() {
+    let fam1 = format!("{}{}", file!(), line!());
+    let fam2 = format!("{}{}", file!(), line!());
+    delete_codes_with_family(&fam1);
+    delete_codes_with_family(&fam2);
+    let uid1 = Uuid::from_str("00000000-0000-0000-0000-002220000022").unwrap();
+    let uid2 = Uuid::from_str("00000000-0000-0000-0000-002220000023").unwrap();
+    delete_user_with_uid(&uid1);
+    delete_user_with_uid(&uid2);
+    let conn = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let user1 = app_user::insert(app_user::new(uid1, "".to_owned(), Uuid::new_v4()), &conn);
+    let user1 = user1.unwrap();
+    let user2 = app_user::insert(app_user::new(uid2, "".to_owned(), Uuid::new_v4()), &conn);
+    let user2 = user2.unwrap();
+
+    assert_eq!(0, taken_pairing_code::count_family(&fam1, &conn).unwrap());
+
+    let code1 = taken_pairing_code::new(&user1, 10, 100, fam1.to_owned());
+    let code2 = taken_pairing_code::new(&user2, 11, 100, fam1.to_owned());
+    let code3 = taken_pairing_code::new(&user1, 10, 100, fam2.to_owned());
+    taken_pairing_code::insert(code1, &conn).unwrap();
+    taken_pairing_code::insert(code2, &conn).unwrap();
+    taken_pairing_code::insert(code3, &conn).unwrap();
+
+    assert_eq!(2, taken_pairing_code::count_family(&fam1, &conn).unwrap());
+    assert_eq!(1, taken_pairing_code::count_family(&fam2, &conn).unwrap());
+}
@@ -1,4 +1,5 @@
 use std::sync::Mutex;
+use std::sync::MutexGuard;
 
 use super::connection::DBConnection;
 use super::connection::DBConnectionImpl;
@@ -11,6 +12,17 @@ lazy_static! {
     static ref MIGRATIONS_MUTEX: Mutex<()> = Mutex::new(());
 }
 
+// Due notifications are claimed regardless of their users, so tests claiming them
+// must not run in parallel.
+lazy_static! {
+    static ref DUE_NOTIFICATIONS_MUTEX: Mutex<()> = Mutex::new(());
+}
+
+/// Tests which claim due notifications use times before this one, other tests must use
+/// later times, so that their notifications wouldn't be claimed.
+#[cfg(test)]
+pub const DUE_NOTIFICATIONS_TIME_LIMIT: i64 = 10_000;
+
 #[cfg(test)]
 pub fn testing_connection_for_client_user() -> Result<impl DBConnection, Error> {
     let _migration_lock = MIGRATIONS_MUTEX.lock();
@@ -32,3 +44,72 @@ fn migrate_with_timeout() -> Result<(), Error> {
         config.db_connection_attempts_timeout_seconds() as i64,
     )
 }
+
+#[derive(QueryableByName)]
+struct TableName {
+    #[sql_type = "diesel::sql_types::Text"]
+    table_name: String,
+}
+
+/// Selects names of all tables of the DB, including the table of Diesel's migrations.
+#[cfg(test)]
+pub fn select_all_tables_names(connection: &dyn DBConnection) -> Result<Vec<String>, Error> {
+    use diesel::RunQueryDsl;
+
+    let result = diesel::sql_query(
+        "SELECT table_name::text FROM information_schema.tables WHERE table_schema = 'public'",
+    )
+    .load::<TableName>(super::diesel_connection(connection));
+    result
+        .map(|names| names.into_iter().map(|name| name.table_name).collect())
+        .map_err(|err| err.into())
+}
+
+#[derive(QueryableByName)]
+struct ConvertedReal {
+    #[sql_type = "diesel::sql_types::Integer"]
+    value: i32,
+}
+
+/// Converts the REAL |value| into an integer the same way the remove_floats_from_foodstuff
+/// migration converted nutrients of foodstuffs.
+#[cfg(test)]
+pub fn convert_real_as_migration(value: &str, connection: &dyn DBConnection) -> Result<i32, Error> {
+    use diesel::RunQueryDsl;
+
+    let result = diesel::sql_query("SELECT ((CAST($1 AS REAL) * 1000000)::integer) AS value")
+        .bind::<diesel::sql_types::Text, _>(value)
+        .get_result::<ConvertedReal>(super::diesel_connection(connection));
+    result
+        .map(|converted| converted.value)
+        .map_err(|err| err.into())
+}
+
+/// Makes the DB close the connection, like it happens when the DB is restarted.
+#[cfg(test)]
+pub fn break_connection(connection: &dyn DBConnection) {
+    use diesel::RunQueryDsl;
+
+    // The query itself fails, because the connection is closed while it's performed
+    let _ = diesel::sql_query("SELECT pg_terminate_backend(pg_backend_pid())")
+        .execute(super::diesel_connection(connection));
+}
+
+/// Makes the test the only one which claims due notifications, and deletes pending
+/// notifications left by previous runs of such tests (e.g. failed ones).
+#[cfg(test)]
+pub fn lock_due_notifications() -> MutexGuard<'static, ()> {
+    use diesel::RunQueryDsl;
+
+    let lock = DUE_NOTIFICATIONS_MUTEX
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let connection = testing_connection_for_server_user().unwrap();
+    diesel::sql_query(format!(
+        "DELETE FROM notification_outbox WHERE state = 0 AND next_attempt_time < {}",
+        DUE_NOTIFICATIONS_TIME_LIMIT
+    ))
+    .execute(super::diesel_connection(&connection))
+    .unwrap();
+    lock
+}
@@ -23,14 +23,44 @@ where
     E: From<TransactionError<E>>,
 {
     let connection = diesel_connection(connection);
-    let transaction_result = connection.transaction::<Result<T, E>, TransactionError<E>, _>(|| {
-        let result = action();
-        match result {
-            Ok(_) => Ok(result),
-            Err(error) => Err(TransactionError::OperationFail::<E>(error)),
-        }
-    });
+    let transaction_result =
+        connection.transaction::<Result<T, E>, TransactionError<E>, _>(|| run_action(action));
+    unwrap_transaction_result(transaction_result)
+}
 
+/// Starts a read-only transaction which sees a snapshot of the DB taken at its first query,
+/// i.e. changes committed by other transactions after that are not visible to it.
+pub fn start_read_only_snapshot<T, E, F>(connection: &dyn DBConnection, action: F) -> Result<T, E>
+where
+    F: FnOnce() -> Result<T, E>,
+    E: From<TransactionError<E>>,
+{
+    let connection = diesel_connection(connection);
+    let transaction_result = connection
+        .build_transaction()
+        .read_only()
+        .repeatable_read()
+        .run::<Result<T, E>, TransactionError<E>, _>(|| run_action(action));
+    unwrap_transaction_result(transaction_result)
+}
+
+fn run_action<T, E, F>(action: F) -> Result<Result<T, E>, TransactionError<E>>
+where
+    F: FnOnce() -> Result<T, E>,
+{
+    let result = action();
+    match result {
+        Ok(_) => Ok(result),
+        Err(error) => Err(TransactionError::OperationFail::<E>(error)),
+    }
+}
+
+fn unwrap_transaction_result<T, E>(
+    transaction_result: Result<Result<T, E>, TransactionError<E>>,
+) -> Result<T, E>
+where
+    E: From<TransactionError<E>>,
+{
     match transaction_result {
         Ok(result) => result,
         Err(error) => Err(error.into()),
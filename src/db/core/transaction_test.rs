@@ -96,3 +96,53 @@ fn returns_correct_value() {
         }
     }
 }
+
+#[test]
+fn read_only_snapshot_doesnt_see_concurrent_changes() {
+    let uid = Uuid::from_str("00000000-0000-0000-0000-003000000001").unwrap();
+    delete_entry_with(&uid);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let other_connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let transaction_result =
+        transaction::start_read_only_snapshot::<(), TestError, _>(&connection, || {
+            let user = app_user::select_by_uid(&uid, &connection);
+            assert!(user.unwrap().is_none());
+
+            app_user::insert(
+                app_user::new(uid, "".to_string(), Uuid::new_v4()),
+                &other_connection,
+            )
+            .unwrap();
+            let user = app_user::select_by_uid(&uid, &connection);
+            assert!(user.unwrap().is_none());
+            Ok(())
+        });
+    assert!(transaction_result.is_ok());
+
+    let user = app_user::select_by_uid(&uid, &connection);
+    assert!(user.unwrap().is_some());
+}
+
+#[test]
+fn read_only_snapshot_cannot_write() {
+    let uid = Uuid::from_str("00000000-0000-0000-0000-003000000002").unwrap();
+    delete_entry_with(&uid);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let transaction_result =
+        transaction::start_read_only_snapshot::<(), TestError, _>(&connection, || {
+            let insertion_result = app_user::insert(
+                app_user::new(uid, "".to_string(), Uuid::new_v4()),
+                &connection,
+            );
+            assert!(insertion_result.is_err());
+            Err(TestError::new())
+        });
+    assert!(transaction_result.is_err());
+
+    let user = app_user::select_by_uid(&uid, &connection);
+    assert!(user.unwrap().is_none());
+}
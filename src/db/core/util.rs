@@ -7,10 +7,23 @@ pub fn delete_app_user(app_user_uid: &Uuid, connection: &dyn DBConnection) -> Re
     use super::app_user;
     use super::app_user::app_user as app_user_schema;
     use super::device::device as device_schema;
+    use super::failed_pairing_attempts;
     use super::fcm_token::fcm_token as fcm_token_schema;
     use super::foodstuff::foodstuff as foodstuff_schema;
+    use super::foodstuff_share;
     use super::gp_user::gp_user as gp_user_schema;
+    use super::history_entry::history_entry as history_entry_schema;
+    use super::inbox_message;
+    use super::meal_plan;
+    use super::meal_plan_item;
+    use super::notification_outbox;
     use super::paired_partners::paired_partners as paired_partners_schema;
+    use super::recipe::recipe as recipe_schema;
+    use super::recipe_ingredient;
+    use super::shopping_list::shopping_list as shopping_list_schema;
+    use super::shopping_list_item;
+    use super::shopping_list_share;
+    use super::taken_pairing_code::taken_pairing_code as taken_pairing_code_schema;
     use super::vk_user::vk_user as vk_user_schema;
     let raw_connection = diesel_connection(connection);
 
@@ -22,6 +35,17 @@ pub fn delete_app_user(app_user_uid: &Uuid, connection: &dyn DBConnection) -> Re
     }
     let app_user = app_user.unwrap();
 
+    inbox_message::delete_by_app_user_id(app_user.id(), connection)?;
+
+    // Tokens and queued notifications reference devices
+    notification_outbox::delete_by_app_user_id(app_user.id(), connection)?;
+    delete_by_column!(
+        fcm_token_schema::table,
+        fcm_token_schema::app_user_id,
+        app_user.id(),
+        raw_connection
+    )?;
+
     delete_by_column!(
         device_schema::table,
         device_schema::app_user_id,
@@ -43,6 +67,36 @@ pub fn delete_app_user(app_user_uid: &Uuid, connection: &dyn DBConnection) -> Re
         raw_connection
     )?;
 
+    foodstuff_share::delete_by_user_id(app_user.id(), connection)?;
+
+    meal_plan_item::delete_by_user_id(app_user.id(), connection)?;
+    meal_plan::delete_by_user_id(app_user.id(), connection)?;
+
+    shopping_list_share::delete_by_user_id(app_user.id(), connection)?;
+    shopping_list_item::delete_by_app_user_id(app_user.id(), connection)?;
+    delete_by_column!(
+        shopping_list_schema::table,
+        shopping_list_schema::app_user_id,
+        app_user.id(),
+        raw_connection
+    )?;
+
+    recipe_ingredient::delete_by_app_user_id(app_user.id(), connection)?;
+
+    delete_by_column!(
+        recipe_schema::table,
+        recipe_schema::app_user_id,
+        app_user.id(),
+        raw_connection
+    )?;
+
+    delete_by_column!(
+        history_entry_schema::table,
+        history_entry_schema::app_user_id,
+        app_user.id(),
+        raw_connection
+    )?;
+
     delete_by_column!(
         foodstuff_schema::table,
         foodstuff_schema::app_user_id,
@@ -50,9 +104,13 @@ pub fn delete_app_user(app_user_uid: &Uuid, connection: &dyn DBConnection) -> Re
         raw_connection
     )?;
 
+    failed_pairing_attempts::delete_by_app_user_id(app_user.id(), connection)?;
+
+    // NOTE: taken pairing codes are not connected to AppUser by a foreign key,
+    // so DB wouldn't tell us if we forgot to delete them
     delete_by_column!(
-        fcm_token_schema::table,
-        fcm_token_schema::app_user_id,
+        taken_pairing_code_schema::table,
+        taken_pairing_code_schema::app_user_id,
         app_user.id(),
         raw_connection
     )?;
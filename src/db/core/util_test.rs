@@ -3,10 +3,25 @@ use uuid::Uuid;
 
 use crate::db::core::app_user;
 use crate::db::core::device;
+use crate::db::core::failed_pairing_attempts;
 use crate::db::core::fcm_token;
 use crate::db::core::foodstuff;
+use crate::db::core::foodstuff_share;
+use crate::db::core::foodstuff_share::SharePermission;
+use crate::db::core::history_entry;
+use crate::db::core::inbox_message;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan_item;
+use crate::db::core::meal_plan_item::MealType;
+use crate::db::core::notification_outbox;
 use crate::db::core::paired_partners;
 use crate::db::core::paired_partners::PairingState;
+use crate::db::core::recipe;
+use crate::db::core::recipe_ingredient;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list_item;
+use crate::db::core::shopping_list_share;
+use crate::db::core::taken_pairing_code;
 use crate::db::core::testing_util as dbtesting_utils;
 use crate::db::core::util::delete_app_user;
 use crate::db::core::vk_user;
@@ -44,7 +59,79 @@ fn deleting_app_user_deletes_all_related_data() {
         &conn,
     )
     .unwrap();
-    let fcm_token = fcm_token::insert(fcm_token::new("val".to_owned(), &app_user1), &conn).unwrap();
+    let history_entry = history_entry::insert(
+        history_entry::new(&app_user1, 1, &foodstuff1, 100, 123),
+        &conn,
+    )
+    .unwrap();
+    let recipe = recipe::insert(recipe::new(&app_user1, 1, "recipe".to_string()), &conn).unwrap();
+    let recipe_ingredient =
+        recipe_ingredient::insert(recipe_ingredient::new(&recipe, &foodstuff2, 100), &conn)
+            .unwrap();
+    let foodstuff_of_user2 = foodstuff::insert(
+        foodstuff::new(&app_user2, 1, "name".to_string(), 1, 2, 3, 4, true),
+        &conn,
+    )
+    .unwrap();
+    let share1 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff1, &app_user2, SharePermission::ReadOnly),
+        &conn,
+    )
+    .unwrap();
+    let share2 = foodstuff_share::insert(
+        foodstuff_share::new(&foodstuff_of_user2, &app_user1, SharePermission::Editable),
+        &conn,
+    )
+    .unwrap();
+    let meal_plan = meal_plan::insert(meal_plan::new(&app_user1, &app_user2), &conn).unwrap();
+    let meal_plan_item1 = meal_plan_item::insert(
+        meal_plan_item::new_foodstuff_item(&meal_plan, 0, MealType::Lunch, &foodstuff1, 100),
+        &conn,
+    )
+    .unwrap();
+    let meal_plan_item2 = meal_plan_item::insert(
+        meal_plan_item::new_recipe_item(&meal_plan, 1, MealType::Dinner, &recipe, 200),
+        &conn,
+    )
+    .unwrap();
+    let shopping_list1 =
+        shopping_list::insert(shopping_list::new(&app_user1, 1, "list".to_string()), &conn)
+            .unwrap();
+    shopping_list_item::insert(
+        shopping_list_item::new(&shopping_list1, 1, &foodstuff1, 100),
+        &conn,
+    )
+    .unwrap();
+    let shopping_list_share1 =
+        shopping_list_share::insert(shopping_list_share::new(&shopping_list1, &app_user2), &conn)
+            .unwrap();
+    // A list of the partner which references a foodstuff of the user
+    let shopping_list2 =
+        shopping_list::insert(shopping_list::new(&app_user2, 1, "list".to_string()), &conn)
+            .unwrap();
+    shopping_list_item::insert(
+        shopping_list_item::new(&shopping_list2, 1, &foodstuff1, 100),
+        &conn,
+    )
+    .unwrap();
+    let shopping_list_share2 =
+        shopping_list_share::insert(shopping_list_share::new(&shopping_list2, &app_user1), &conn)
+            .unwrap();
+    let family = uid1.to_string();
+    taken_pairing_code::insert(
+        taken_pairing_code::new(&app_user1, 123, 456, family.clone()),
+        &conn,
+    )
+    .unwrap();
+    let fcm_token =
+        fcm_token::insert(fcm_token::new("val".to_owned(), &app_user1, &device), &conn).unwrap();
+    failed_pairing_attempts::increment(&app_user1, 123, &conn).unwrap();
+    let notification = notification_outbox::insert(
+        notification_outbox::new("msg".to_owned(), &app_user1, &device, 123),
+        &conn,
+    )
+    .unwrap();
+    inbox_message::insert(inbox_message::new("msg".to_owned(), &app_user1, 123), &conn).unwrap();
 
     let paired_partners1 = paired_partners::new(&app_user1, &app_user2, PairingState::Done, 123);
     let paired_partners2 =
@@ -63,9 +150,66 @@ fn deleting_app_user_deletes_all_related_data() {
     assert!(foodstuff::select_by_id(foodstuff2.id(), &conn)
         .unwrap()
         .is_some());
+    assert!(history_entry::select_by_id(history_entry.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert!(recipe::select_by_id(recipe.id(), &conn).unwrap().is_some());
+    assert_eq!(
+        vec![recipe_ingredient],
+        recipe_ingredient::select_by_recipe_id(recipe.id(), &conn).unwrap()
+    );
+    assert!(foodstuff_share::select_by_id(share1.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert!(foodstuff_share::select_by_id(share2.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert!(meal_plan::select_by_id(meal_plan.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        vec![meal_plan_item1, meal_plan_item2],
+        meal_plan_item::select_by_meal_plan_id(meal_plan.id(), &conn).unwrap()
+    );
+    assert!(shopping_list::select_by_id(shopping_list1.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        1,
+        shopping_list_item::select_by_shopping_list_id(shopping_list2.id(), &conn)
+            .unwrap()
+            .len()
+    );
+    assert_eq!(
+        vec![shopping_list_share1],
+        shopping_list_share::select_by_partner_user_id(app_user2.id(), &conn).unwrap()
+    );
+    assert_eq!(
+        vec![shopping_list_share2],
+        shopping_list_share::select_by_partner_user_id(app_user1.id(), &conn).unwrap()
+    );
+    assert!(
+        taken_pairing_code::select_by_app_user_id(app_user1.id(), &family, &conn)
+            .unwrap()
+            .is_some()
+    );
     assert!(fcm_token::select_by_id(fcm_token.id(), &conn)
         .unwrap()
         .is_some());
+    assert!(
+        failed_pairing_attempts::select_by_app_user_id(app_user1.id(), &conn)
+            .unwrap()
+            .is_some()
+    );
+    assert!(notification_outbox::select_by_id(notification.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        1,
+        inbox_message::select_by_app_user_id(app_user1.id(), &conn)
+            .unwrap()
+            .len()
+    );
     assert!(
         paired_partners::select_by_partners_user_ids(app_user1.id(), app_user2.id(), &conn)
             .unwrap()
@@ -88,9 +232,80 @@ fn deleting_app_user_deletes_all_related_data() {
     assert!(foodstuff::select_by_id(foodstuff2.id(), &conn)
         .unwrap()
         .is_none());
+    assert!(history_entry::select_by_id(history_entry.id(), &conn)
+        .unwrap()
+        .is_none());
+    assert!(recipe::select_by_id(recipe.id(), &conn).unwrap().is_none());
+    assert!(recipe_ingredient::select_by_recipe_id(recipe.id(), &conn)
+        .unwrap()
+        .is_empty());
+    assert!(foodstuff_share::select_by_id(share1.id(), &conn)
+        .unwrap()
+        .is_none());
+    assert!(foodstuff_share::select_by_id(share2.id(), &conn)
+        .unwrap()
+        .is_none());
+    // Foodstuffs of the partner are not deleted
+    assert!(foodstuff::select_by_id(foodstuff_of_user2.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert!(meal_plan::select_by_id(meal_plan.id(), &conn)
+        .unwrap()
+        .is_none());
+    assert!(
+        meal_plan_item::select_by_meal_plan_id(meal_plan.id(), &conn)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(shopping_list::select_by_id(shopping_list1.id(), &conn)
+        .unwrap()
+        .is_none());
+    assert!(
+        shopping_list_item::select_by_shopping_list_id(shopping_list1.id(), &conn)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        shopping_list_share::select_by_partner_user_id(app_user2.id(), &conn)
+            .unwrap()
+            .is_empty()
+    );
+    // The list of the partner is not deleted, but it loses the foodstuff of the user
+    assert!(shopping_list::select_by_id(shopping_list2.id(), &conn)
+        .unwrap()
+        .is_some());
+    assert!(
+        shopping_list_item::select_by_shopping_list_id(shopping_list2.id(), &conn)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        shopping_list_share::select_by_shopping_list_id(shopping_list2.id(), &conn)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        taken_pairing_code::select_by_app_user_id(app_user1.id(), &family, &conn)
+            .unwrap()
+            .is_none()
+    );
     assert!(fcm_token::select_by_id(fcm_token.id(), &conn)
         .unwrap()
         .is_none());
+    assert!(
+        failed_pairing_attempts::select_by_app_user_id(app_user1.id(), &conn)
+            .unwrap()
+            .is_none()
+    );
+    assert!(notification_outbox::select_by_id(notification.id(), &conn)
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        0,
+        inbox_message::select_by_app_user_id(app_user1.id(), &conn)
+            .unwrap()
+            .len()
+    );
     assert!(
         paired_partners::select_by_partners_user_ids(app_user1.id(), app_user2.id(), &conn)
             .unwrap()
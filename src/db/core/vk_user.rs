@@ -76,6 +76,19 @@ pub fn select_by_vk_uid(uid: &str, connection: &dyn DBConnection) -> Result<Opti
     );
 }
 
+pub fn select_by_app_user_id(
+    app_user_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<Option<VkUser>, Error> {
+    select_by_column!(
+        VkUser,
+        vk_user_schema::table,
+        vk_user_schema::app_user_id,
+        app_user_id,
+        diesel_connection(connection)
+    )
+}
+
 #[cfg(test)]
 #[path = "./vk_user_test.rs"]
 mod vk_user_test;
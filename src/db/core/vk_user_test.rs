@@ -46,6 +46,10 @@ fn insertion_and_selection_work() {
     let selected_by_uid_user = vk_user::select_by_vk_uid(inserted_vk_user.vk_uid(), &connection);
     let selected_by_uid_user = selected_by_uid_user.unwrap().unwrap();
     assert_eq!(inserted_vk_user, selected_by_uid_user);
+
+    let selected_by_app_user = vk_user::select_by_app_user_id(app_user.id(), &connection);
+    let selected_by_app_user = selected_by_app_user.unwrap().unwrap();
+    assert_eq!(inserted_vk_user, selected_by_app_user);
 }
 
 #[test]
@@ -1,14 +1,28 @@
 use super::error::Error;
+use super::error::ErrorKind;
 use crate::config::Config;
 use crate::db::core::connection::DBConnection;
 use crate::db::core::connection::DBConnectionImpl;
 use crate::db::core::connection::UnderlyingConnectionSource;
 use crate::db::core::error::Error as DBCoreError;
+use crate::metrics::DbPoolUsageReporter;
 
+use log::warn;
 use std::sync::Arc;
+use std::sync::Condvar;
 use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
 
 // Thread-safe connection pool for connections reusing.
+// The pool has a max size - when all of its connections are borrowed, borrowers wait
+// for a connection to be returned, but not longer than a timeout.
+// Connections which are idle for too long are closed, and idle connections
+// are validated before they're borrowed.
+// NOTE: a borrower must not borrow another connection while it holds one -
+// when all of the connections are held by such borrowers, each of them waits
+// for the others until the timeout. Pass the held connection around instead.
 
 pub enum ConnectionType {
     UserConnection,
@@ -19,25 +33,44 @@ pub enum ConnectionType {
 /// a cloned pool defacto is the same pool as the one it was cloned from.
 #[derive(Clone)]
 pub struct ConnectionPool {
-    pimpl: Arc<Mutex<ConnectionPoolImpl>>,
+    shared: Arc<SharedState>,
 }
 
 pub struct BorrowedDBConnection {
     connection: Option<DBConnectionImpl>,
-    pimpl: Option<Arc<Mutex<ConnectionPoolImpl>>>,
+    shared: Arc<SharedState>,
 }
 
-struct ConnectionPoolImpl {
+struct SharedState {
     connection_type: ConnectionType,
     config: Config,
-    connections: Vec<DBConnectionImpl>,
+    pimpl: Mutex<ConnectionPoolImpl>,
+    connection_returned: Condvar,
+}
+
+struct ConnectionPoolImpl {
+    max_size: usize,
+    max_idle_time: Duration,
+    idle_connections: Vec<IdleConnection>,
+    borrowed_connections_count: usize,
+    usage_reporter: DbPoolUsageReporter,
+}
+
+struct IdleConnection {
+    connection: DBConnectionImpl,
+    idle_since: Instant,
 }
 
 impl ConnectionPool {
     pub fn new(connection_type: ConnectionType, config: Config) -> Self {
-        let pimpl = ConnectionPoolImpl::new(connection_type, config);
+        let pimpl = ConnectionPoolImpl::new(&connection_type, &config);
         ConnectionPool {
-            pimpl: Arc::new(Mutex::new(pimpl)),
+            shared: Arc::new(SharedState {
+                connection_type,
+                config,
+                pimpl: Mutex::new(pimpl),
+                connection_returned: Condvar::new(),
+            }),
         }
     }
 
@@ -49,68 +82,98 @@ impl ConnectionPool {
         Self::new(ConnectionType::ServerConnection, config)
     }
 
+    /// See the NOTE about nested borrows at the top of the file.
     pub fn borrow_connection(&mut self) -> Result<BorrowedDBConnection, Error> {
-        let result = self
-            .pimpl
-            .lock()
-            .expect("expecting ok mutex")
-            .borrow_connection();
-        if let Ok(mut result) = result {
-            result.pimpl = Some(self.pimpl.clone());
-            Ok(result)
-        } else {
-            result
-        }
+        self.shared.borrow_connection()
     }
 
+    /// Number of idle connections.
     pub fn pooled_connections_count(&self) -> usize {
-        self.pimpl
-            .lock()
-            .expect("expecting ok mutex")
-            .pooled_connections_count()
+        self.shared.lock_pimpl().idle_connections.len()
+    }
+
+    pub fn borrowed_connections_count(&self) -> usize {
+        self.shared.lock_pimpl().borrowed_connections_count
     }
 }
 
 impl BorrowedDBConnection {
+    /// Borrows one more connection from the pool, so it's a nested borrow -
+    /// see the NOTE at the top of the file.
     pub fn try_clone(&self) -> Result<Self, Error> {
-        let pimpl = self
-            .pimpl
-            .as_ref()
-            .expect("Pimpl is expected to be always present");
-        let mut pimpl = pimpl.lock().expect("Expecting ok mutex");
-        let mut result = pimpl.borrow_connection()?;
-        result.pimpl = self.pimpl.clone();
-        Ok(result)
+        self.shared.borrow_connection()
     }
 }
 
-impl ConnectionPoolImpl {
-    fn new(connection_type: ConnectionType, config: Config) -> Self {
-        ConnectionPoolImpl {
-            connection_type,
-            config,
-            connections: Vec::new(),
-        }
+impl SharedState {
+    fn lock_pimpl(&self) -> MutexGuard<'_, ConnectionPoolImpl> {
+        self.pimpl.lock().expect("Expecting ok mutex")
     }
 
-    fn borrow_connection(&mut self) -> Result<BorrowedDBConnection, Error> {
-        let connection: Option<DBConnectionImpl>;
-        {
-            connection = self.connections.pop();
-        }
-        match connection {
-            Some(connection) => Ok(self.connection_to_borrowed(connection)),
-            None => {
-                let new_connection = self.new_connection()?;
-                Ok(self.connection_to_borrowed(new_connection))
+    fn borrow_connection(self: &Arc<Self>) -> Result<BorrowedDBConnection, Error> {
+        let timeout = Duration::from_millis(self.config.db_pool_borrow_timeout_millis());
+        let deadline = Instant::now() + timeout;
+
+        let mut pimpl = self.lock_pimpl();
+        loop {
+            pimpl.close_long_idle_connections();
+
+            if let Some(idle) = pimpl.idle_connections.pop() {
+                pimpl.borrowed_connections_count += 1;
+                pimpl.report_usage();
+                // The validation query is performed without the lock
+                drop(pimpl);
+                if idle.connection.is_alive() {
+                    return Ok(self.connection_to_borrowed(idle.connection));
+                }
+                warn!("Closing a broken DB connection");
+                drop(idle);
+                pimpl = self.lock_pimpl();
+                pimpl.borrowed_connections_count -= 1;
+                pimpl.report_usage();
+                continue;
             }
+
+            if pimpl.connections_count() < pimpl.max_size {
+                pimpl.borrowed_connections_count += 1;
+                pimpl.report_usage();
+                drop(pimpl);
+                return match self.new_connection() {
+                    Ok(connection) => Ok(self.connection_to_borrowed(connection)),
+                    Err(err) => {
+                        let mut pimpl = self.lock_pimpl();
+                        pimpl.borrowed_connections_count -= 1;
+                        pimpl.report_usage();
+                        drop(pimpl);
+                        self.connection_returned.notify_one();
+                        Err(err.into())
+                    }
+                };
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ErrorKind::BorrowTimeout(
+                    pimpl.max_size as u32,
+                    timeout.as_millis() as u64,
+                )
+                .into());
+            }
+            pimpl = self
+                .connection_returned
+                .wait_timeout(pimpl, deadline - now)
+                .expect("Expecting ok mutex")
+                .0;
         }
     }
 
-    fn connection_to_borrowed(&mut self, connection: DBConnectionImpl) -> BorrowedDBConnection {
+    fn connection_to_borrowed(
+        self: &Arc<Self>,
+        connection: DBConnectionImpl,
+    ) -> BorrowedDBConnection {
         BorrowedDBConnection {
             connection: Some(connection),
-            pimpl: None, // We'll init it later
+            shared: self.clone(),
         }
     }
 
@@ -121,12 +184,47 @@ impl ConnectionPoolImpl {
         }
     }
 
-    fn pooled_connections_count(&self) -> usize {
-        self.connections.len()
+    fn return_borrowed_connection(&self, connection: DBConnectionImpl) {
+        let mut pimpl = self.lock_pimpl();
+        pimpl.borrowed_connections_count -= 1;
+        pimpl.idle_connections.push(IdleConnection {
+            connection,
+            idle_since: Instant::now(),
+        });
+        pimpl.close_long_idle_connections();
+        self.connection_returned.notify_one();
+    }
+}
+
+impl ConnectionPoolImpl {
+    fn new(connection_type: &ConnectionType, config: &Config) -> Self {
+        let pool_name = match connection_type {
+            ConnectionType::UserConnection => "client",
+            ConnectionType::ServerConnection => "server",
+        };
+        ConnectionPoolImpl {
+            max_size: (config.db_pool_max_size() as usize).max(1),
+            max_idle_time: Duration::from_secs(config.db_pool_max_idle_seconds()),
+            idle_connections: Vec::new(),
+            borrowed_connections_count: 0,
+            usage_reporter: DbPoolUsageReporter::new(pool_name),
+        }
+    }
+
+    fn report_usage(&mut self) {
+        let (borrowed, idle) = (self.borrowed_connections_count, self.idle_connections.len());
+        self.usage_reporter.report(borrowed, idle);
     }
 
-    fn return_borrowed_connection(&mut self, connection: DBConnectionImpl) {
-        self.connections.push(connection)
+    fn connections_count(&self) -> usize {
+        self.idle_connections.len() + self.borrowed_connections_count
+    }
+
+    fn close_long_idle_connections(&mut self) {
+        let max_idle_time = self.max_idle_time;
+        self.idle_connections
+            .retain(|idle| idle.idle_since.elapsed() < max_idle_time);
+        self.report_usage();
     }
 }
 
@@ -136,12 +234,7 @@ impl Drop for BorrowedDBConnection {
             .connection
             .take()
             .expect("Connection expected to be moved out only in drop");
-        let pimpl = self
-            .pimpl
-            .as_ref()
-            .expect("Pimpl is expected to be always present");
-        let mut pimpl = pimpl.lock().expect("Expecting ok mutex");
-        pimpl.return_borrowed_connection(connection);
+        self.shared.return_borrowed_connection(connection);
     }
 }
 
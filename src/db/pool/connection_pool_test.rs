@@ -1,4 +1,13 @@
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+use uuid::Uuid;
+
 use super::ConnectionPool;
+use crate::db::core::app_user;
+use crate::db::core::testing_util::break_connection;
+use crate::db::pool::error::Error;
+use crate::db::pool::error::ErrorKind;
 use crate::testing_utils::config_in_tests;
 
 #[test]
@@ -54,3 +63,78 @@ fn borrowed_connection_can_be_cloned() {
         pool.pooled_connections_count()
     );
 }
+
+#[test]
+fn pool_size_is_limited() {
+    let config = config_in_tests()
+        .with_db_pool_max_size(2)
+        .with_db_pool_borrow_timeout_millis(100);
+
+    let mut pool = ConnectionPool::for_client_user(config);
+    let _connection1 = pool.borrow_connection().unwrap();
+    let _connection2 = pool.borrow_connection().unwrap();
+    assert_eq!(2, pool.borrowed_connections_count());
+
+    let start = Instant::now();
+    let result = pool.borrow_connection();
+    match result {
+        Err(Error(ErrorKind::BorrowTimeout(2, 100), _)) => {}
+        Err(err) => panic!("Unexpected error: {}", err),
+        Ok(_) => panic!("Pool size must be limited"),
+    }
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn returned_connection_is_given_to_waiting_borrower() {
+    let config = config_in_tests()
+        .with_db_pool_max_size(1)
+        .with_db_pool_borrow_timeout_millis(10_000);
+
+    let mut pool = ConnectionPool::for_client_user(config);
+    let connection = pool.borrow_connection().unwrap();
+
+    let mut pool_copy = pool.clone();
+    let waiter = thread::spawn(move || {
+        let start = Instant::now();
+        let _connection = pool_copy.borrow_connection().unwrap();
+        start.elapsed()
+    });
+    thread::sleep(Duration::from_millis(100));
+    drop(connection);
+
+    let waited = waiter.join().unwrap();
+    assert!(waited >= Duration::from_millis(100));
+    assert!(waited < Duration::from_millis(10_000));
+}
+
+#[test]
+fn long_idle_connections_are_closed() {
+    let config = config_in_tests().with_db_pool_max_idle_seconds(0);
+
+    let mut pool = ConnectionPool::for_client_user(config);
+    {
+        let _connection = pool.borrow_connection().unwrap();
+        assert_eq!(1, pool.borrowed_connections_count());
+    }
+    assert_eq!(0, pool.borrowed_connections_count());
+    assert_eq!(0, pool.pooled_connections_count());
+}
+
+#[test]
+fn broken_connections_are_not_reused() {
+    let config = config_in_tests();
+
+    let mut pool = ConnectionPool::for_client_user(config);
+    {
+        let connection = pool.borrow_connection().unwrap();
+        break_connection(&connection);
+        assert!(app_user::select_by_uid(&Uuid::new_v4(), &connection).is_err());
+    }
+    assert_eq!(1, pool.pooled_connections_count());
+
+    let connection = pool.borrow_connection().unwrap();
+    assert!(app_user::select_by_uid(&Uuid::new_v4(), &connection).is_ok());
+    assert_eq!(0, pool.pooled_connections_count());
+    assert_eq!(1, pool.borrowed_connections_count());
+}
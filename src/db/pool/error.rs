@@ -4,4 +4,11 @@ error_chain! {
     links {
         DBCoreError(db::core::error::Error, db::core::error::ErrorKind);
     }
+
+    errors {
+        BorrowTimeout(max_size: u32, timeout_millis: u64) {
+            description("Timed out waiting for a free DB connection"),
+            display("All {} DB connections of the pool are busy, waited for {} ms", max_size, timeout_millis),
+        }
+    }
 }
@@ -16,6 +16,8 @@ pub mod config;
 pub mod db;
 pub mod error;
 pub mod logs;
+pub mod metrics;
+pub mod nutrition;
 pub mod outside;
 pub mod pairing;
 pub mod server;
@@ -1,12 +1,22 @@
 use std::net::ToSocketAddrs;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use clap::{App, Arg};
+use futures::future::select;
 use log::info;
+use tokio::runtime::Builder;
+use tokio::signal::unix::{signal, SignalKind};
 
 use recipe_calculator_lib::config;
 use recipe_calculator_lib::db::core::migrator;
+use recipe_calculator_lib::db::pool::connection_pool::{ConnectionPool, ConnectionType};
 use recipe_calculator_lib::logs::init_logs;
+use recipe_calculator_lib::outside::fcm::FCM_ADDR;
+use recipe_calculator_lib::outside::http_client::HttpClient;
 use recipe_calculator_lib::server::entry_point;
+use recipe_calculator_lib::server::notification_outbox;
 use recipe_calculator_lib::server::requests_handler_impl::RequestsHandlerImpl;
 
 const CONFIG_ARG: &str = "config";
@@ -70,7 +80,7 @@ fn main() {
 
     let mut address = address.to_socket_addrs().unwrap();
     let address = address.next().unwrap();
-    let shutdown_signal = futures::future::pending();
+    let shutdown_signal = Box::pin(shutdown_signal());
 
     info!("Performing migrations");
     migrator::migrate_with_timeout(
@@ -79,10 +89,48 @@ fn main() {
     )
     .unwrap();
 
+    info!("Starting notifications worker");
+    start_notifications_worker(config.clone());
+
     info!("Starting listening to address: {}", address);
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_timeout_seconds());
     entry_point::start_server(
         &address,
         shutdown_signal,
+        drain_timeout,
         RequestsHandlerImpl::new(config).unwrap(),
     );
+
+    // The requests handler is dropped by now, and so are its pooled DB connections
+    info!("Server is shut down");
+    log::logger().flush();
+}
+
+// Sends queued notifications on a thread of its own, so that the worker wouldn't take
+// threads of requests handling. The worker doesn't need to be stopped on shutdown -
+// notifications it doesn't finish sending are sent once the server is started again.
+fn start_notifications_worker(config: config::Config) {
+    let connections_pool = ConnectionPool::new(ConnectionType::UserConnection, config.clone());
+    let http_client = Arc::new(HttpClient::new().unwrap());
+    thread::spawn(move || {
+        let mut tokio_runtime = Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .unwrap();
+        tokio_runtime.block_on(notification_outbox::run_worker(
+            connections_pool,
+            config,
+            FCM_ADDR.to_owned(),
+            http_client,
+        ));
+    });
+}
+
+// Resolves when SIGTERM (sent by `docker stop`) or SIGINT (Ctrl+C) is received.
+// NOTE: must be polled within Tokio runtime.
+async fn shutdown_signal() {
+    let mut sigterm = signal(SignalKind::terminate()).unwrap();
+    let mut sigint = signal(SignalKind::interrupt()).unwrap();
+    select(Box::pin(sigterm.recv()), Box::pin(sigint.recv())).await;
 }
@@ -0,0 +1,153 @@
+use prometheus::register_histogram_vec;
+use prometheus::register_int_counter;
+use prometheus::register_int_counter_vec;
+use prometheus::register_int_gauge_vec;
+use prometheus::Encoder;
+use prometheus::HistogramVec;
+use prometheus::IntCounter;
+use prometheus::IntCounterVec;
+use prometheus::IntGauge;
+use prometheus::IntGaugeVec;
+use prometheus::TextEncoder;
+use std::time::Duration;
+
+// Metrics of the server in the Prometheus format.
+// All metrics are registered in the default Prometheus registry on first use.
+
+/// Content type of the text format.
+pub const TEXT_FORMAT: &str = "text/plain; version=0.0.4";
+
+const FCM_SEND_SUCCESS: &str = "success";
+const FCM_SEND_FAILURE: &str = "failure";
+const DB_POOL_BORROWED: &str = "borrowed";
+const DB_POOL_IDLE: &str = "idle";
+
+lazy_static! {
+    static ref CMD_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "recipe_calculator_cmd_requests_total",
+        "Number of handled requests by command",
+        &["cmd"]
+    )
+    .expect("Expecting valid metric");
+    static ref CMD_DURATION: HistogramVec = register_histogram_vec!(
+        "recipe_calculator_cmd_duration_seconds",
+        "Time of commands execution",
+        &["cmd"]
+    )
+    .expect("Expecting valid metric");
+    static ref REQUEST_ERRORS: IntCounterVec = register_int_counter_vec!(
+        "recipe_calculator_request_errors_total",
+        "Number of error responses by status",
+        &["status"]
+    )
+    .expect("Expecting valid metric");
+    static ref FCM_SENDS: IntCounterVec = register_int_counter_vec!(
+        "recipe_calculator_fcm_sends_total",
+        "Number of FCM messages sends by result",
+        &["result"]
+    )
+    .expect("Expecting valid metric");
+    static ref DEAD_NOTIFICATIONS: IntCounter = register_int_counter!(
+        "recipe_calculator_dead_notifications_total",
+        "Number of notifications which weren't sent after all attempts"
+    )
+    .expect("Expecting valid metric");
+    static ref DB_POOL_CONNECTIONS: IntGaugeVec = register_int_gauge_vec!(
+        "recipe_calculator_db_pool_connections",
+        "Number of DB connections in pools by pool type and connection state",
+        &["pool", "state"]
+    )
+    .expect("Expecting valid metric");
+    static ref PAIRING_CODES_TAKEN: IntGaugeVec = register_int_gauge_vec!(
+        "recipe_calculator_pairing_codes_taken",
+        "Number of taken pairing codes by family",
+        &["family"]
+    )
+    .expect("Expecting valid metric");
+    static ref PAIRING_CODES_CAPACITY: IntGaugeVec = register_int_gauge_vec!(
+        "recipe_calculator_pairing_codes_capacity",
+        "Number of all pairing codes by family",
+        &["family"]
+    )
+    .expect("Expecting valid metric");
+}
+
+/// |cmd| must be a known command, so that the number of labels would be limited.
+pub fn observe_cmd(cmd: &str, duration: Duration) {
+    CMD_REQUESTS.with_label_values(&[cmd]).inc();
+    CMD_DURATION
+        .with_label_values(&[cmd])
+        .observe(duration.as_secs_f64());
+}
+
+pub fn observe_request_error(status: &str) {
+    REQUEST_ERRORS.with_label_values(&[status]).inc();
+}
+
+pub fn observe_fcm_send(success: bool) {
+    let result = if success {
+        FCM_SEND_SUCCESS
+    } else {
+        FCM_SEND_FAILURE
+    };
+    FCM_SENDS.with_label_values(&[result]).inc();
+}
+
+pub fn observe_dead_notification() {
+    DEAD_NOTIFICATIONS.inc();
+}
+
+pub fn set_pairing_codes_occupancy(family: &str, taken: i64, capacity: i64) {
+    PAIRING_CODES_TAKEN.with_label_values(&[family]).set(taken);
+    PAIRING_CODES_CAPACITY
+        .with_label_values(&[family])
+        .set(capacity);
+}
+
+/// All metrics in the Prometheus text format.
+pub fn gather() -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&prometheus::gather(), &mut buffer)
+        .expect("Expecting metrics to be encodable");
+    String::from_utf8(buffer).expect("Expecting metrics to be UTF-8")
+}
+
+/// Reports connections of a DB connections pool. Connections of all pools of the same type
+/// are summed up - a reporter reports only the changes of its pool, and its pool's connections
+/// are subtracted when the reporter is dropped.
+pub struct DbPoolUsageReporter {
+    borrowed: IntGauge,
+    idle: IntGauge,
+    reported_borrowed: i64,
+    reported_idle: i64,
+}
+
+impl DbPoolUsageReporter {
+    pub fn new(pool: &str) -> Self {
+        DbPoolUsageReporter {
+            borrowed: DB_POOL_CONNECTIONS.with_label_values(&[pool, DB_POOL_BORROWED]),
+            idle: DB_POOL_CONNECTIONS.with_label_values(&[pool, DB_POOL_IDLE]),
+            reported_borrowed: 0,
+            reported_idle: 0,
+        }
+    }
+
+    pub fn report(&mut self, borrowed: usize, idle: usize) {
+        let (borrowed, idle) = (borrowed as i64, idle as i64);
+        self.borrowed.add(borrowed - self.reported_borrowed);
+        self.idle.add(idle - self.reported_idle);
+        self.reported_borrowed = borrowed;
+        self.reported_idle = idle;
+    }
+}
+
+impl Drop for DbPoolUsageReporter {
+    fn drop(&mut self) {
+        self.report(0, 0);
+    }
+}
+
+#[cfg(test)]
+#[path = "./metrics_test.rs"]
+mod metrics_test;
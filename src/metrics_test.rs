@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use super::*;
+
+#[test]
+fn cmd_observations_are_gathered() {
+    observe_cmd("/testing/cmd", Duration::from_millis(10));
+    observe_cmd("/testing/cmd", Duration::from_millis(20));
+
+    let metrics = gather();
+    assert!(metrics.contains("recipe_calculator_cmd_requests_total{cmd=\"/testing/cmd\"} 2"));
+    assert!(
+        metrics.contains("recipe_calculator_cmd_duration_seconds_count{cmd=\"/testing/cmd\"} 2")
+    );
+}
+
+#[test]
+fn db_pool_usage_reporters_are_summed_up() {
+    let borrowed = DB_POOL_CONNECTIONS.with_label_values(&["testing_pool", DB_POOL_BORROWED]);
+    let idle = DB_POOL_CONNECTIONS.with_label_values(&["testing_pool", DB_POOL_IDLE]);
+
+    let mut reporter1 = DbPoolUsageReporter::new("testing_pool");
+    let mut reporter2 = DbPoolUsageReporter::new("testing_pool");
+    reporter1.report(2, 1);
+    reporter2.report(1, 3);
+    assert_eq!(3, borrowed.get());
+    assert_eq!(4, idle.get());
+
+    reporter1.report(1, 2);
+    assert_eq!(2, borrowed.get());
+    assert_eq!(5, idle.get());
+
+    drop(reporter2);
+    assert_eq!(1, borrowed.get());
+    assert_eq!(2, idle.get());
+    drop(reporter1);
+    assert_eq!(0, borrowed.get());
+    assert_eq!(0, idle.get());
+}
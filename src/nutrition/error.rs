@@ -0,0 +1,8 @@
+error_chain! {
+    errors {
+        InvalidDecimalString(value: String) {
+            description("Invalid decimal string"),
+            display("Invalid decimal string: {}", value),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::ops::Add;
+
+use super::error::Error;
+use super::error::ErrorKind;
+use crate::db::core::foodstuff::Foodstuff;
+
+/// Nutrients are stored as fixed-point integers - a real value multiplied by this scale.
+/// The encoding was introduced by the remove_floats_from_foodstuff migration.
+pub const FIXED_POINT_SCALE: i64 = 1_000_000;
+const FIXED_POINT_DIGITS: usize = 6;
+
+/// Nutrients of some amount of food, as fixed-point values (see |FIXED_POINT_SCALE|).
+/// Foodstuffs store their nutrients per 100 grams, and so do Nutrients built from them.
+///
+/// Values are i64, unlike the i32 columns of the DB - sums of nutrients of
+/// many meals wouldn't fit into i32. All arithmetic saturates instead of overflowing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Nutrients {
+    protein: i64,
+    fats: i64,
+    carbs: i64,
+    calories: i64,
+}
+
+impl Nutrients {
+    /// Expects fixed-point values.
+    pub fn new(protein: i64, fats: i64, carbs: i64, calories: i64) -> Self {
+        Nutrients {
+            protein,
+            fats,
+            carbs,
+            calories,
+        }
+    }
+
+    /// Nutrients of 100 grams of the foodstuff.
+    pub fn of_foodstuff(foodstuff: &Foodstuff) -> Self {
+        Nutrients::new(
+            i64::from(foodstuff.protein()),
+            i64::from(foodstuff.fats()),
+            i64::from(foodstuff.carbs()),
+            i64::from(foodstuff.calories()),
+        )
+    }
+
+    pub fn protein(&self) -> i64 {
+        self.protein
+    }
+
+    pub fn fats(&self) -> i64 {
+        self.fats
+    }
+
+    pub fn carbs(&self) -> i64 {
+        self.carbs
+    }
+
+    pub fn calories(&self) -> i64 {
+        self.calories
+    }
+
+    /// Treats |self| as nutrients of 100 grams of food and returns nutrients
+    /// of |mass| grams of it. Rounds to the nearest fixed-point value.
+    pub fn scaled_by_mass(&self, mass: i64) -> Self {
+        let scale = |value: i64| round_div(i128::from(value) * i128::from(mass), 100);
+        Nutrients::new(
+            scale(self.protein),
+            scale(self.fats),
+            scale(self.carbs),
+            scale(self.calories),
+        )
+    }
+
+    /// Treats each of |parts| as nutrients of 100 grams of food and its mass in grams,
+    /// returns nutrients of 100 grams of the mix of the parts (i.e. average of the parts
+    /// weighted by their masses). Returns zero nutrients if the total mass is not positive.
+    /// Rounds to the nearest fixed-point value.
+    pub fn merge(parts: &[(Nutrients, i64)]) -> Self {
+        let total_mass: i128 = parts.iter().map(|(_, mass)| i128::from(*mass)).sum();
+        if total_mass <= 0 {
+            return Nutrients::default();
+        }
+        let average = |nutrient: fn(&Nutrients) -> i64| {
+            let sum: i128 = parts
+                .iter()
+                .map(|(nutrients, mass)| i128::from(nutrient(nutrients)) * i128::from(*mass))
+                .sum();
+            round_div(sum, total_mass)
+        };
+        Nutrients::new(
+            average(Nutrients::protein),
+            average(Nutrients::fats),
+            average(Nutrients::carbs),
+            average(Nutrients::calories),
+        )
+    }
+}
+
+impl Add for Nutrients {
+    type Output = Nutrients;
+
+    fn add(self, other: Nutrients) -> Nutrients {
+        Nutrients::new(
+            self.protein.saturating_add(other.protein),
+            self.fats.saturating_add(other.fats),
+            self.carbs.saturating_add(other.carbs),
+            self.calories.saturating_add(other.calories),
+        )
+    }
+}
+
+/// Divides rounding half away from zero, saturates if the result doesn't fit into i64.
+fn round_div(dividend: i128, divisor: i128) -> i64 {
+    let half = divisor / 2;
+    let result = if (dividend >= 0) == (divisor >= 0) {
+        dividend.saturating_add(half) / divisor
+    } else {
+        dividend.saturating_sub(half) / divisor
+    };
+    if result > i128::from(i64::MAX) {
+        i64::MAX
+    } else if result < i128::from(i64::MIN) {
+        i64::MIN
+    } else {
+        result as i64
+    }
+}
+
+/// Converts a fixed-point value into the decimal string used by the API,
+/// e.g. 1_500_000 -> "1.5", 1 -> "0.000001", 2_000_000 -> "2".
+pub fn to_decimal_string(value: i64) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let abs = i128::from(value).abs();
+    let scale = i128::from(FIXED_POINT_SCALE);
+    let integer = abs / scale;
+    let fraction = abs % scale;
+    if fraction == 0 {
+        return format!("{}{}", sign, integer);
+    }
+    let fraction = format!("{:0width$}", fraction, width = FIXED_POINT_DIGITS);
+    format!("{}{}.{}", sign, integer, fraction.trim_end_matches('0'))
+}
+
+/// Converts a decimal string used by the API into a fixed-point value,
+/// e.g. "1.5" -> 1_500_000. Digits beyond the fixed-point precision are rounded.
+/// Accepts the whole i64 range, i.e. from "-9223372036854.775808" to "9223372036854.775807".
+pub fn parse_decimal_string(value: &str) -> Result<i64, Error> {
+    let invalid = || -> Error { ErrorKind::InvalidDecimalString(value.to_owned()).into() };
+
+    let (negative, unsigned) = if let Some(stripped) = value.strip_prefix('-') {
+        (true, stripped)
+    } else {
+        (false, value)
+    };
+    let mut parts = unsigned.splitn(2, '.');
+    let integer = parts.next().unwrap_or("");
+    let fraction = parts.next();
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(integer) || !fraction.map(is_digits).unwrap_or(true) {
+        return Err(invalid());
+    }
+    let fraction = fraction.unwrap_or("");
+
+    // The absolute value is accumulated in i128, because the absolute value
+    // of i64::MIN doesn't fit into i64
+    let scale = i128::from(FIXED_POINT_SCALE);
+    let mut result = integer
+        .parse::<i128>()
+        .ok()
+        .and_then(|integer| integer.checked_mul(scale))
+        .ok_or_else(invalid)?;
+    let mut digit_scale = scale;
+    for (index, digit) in fraction.bytes().enumerate() {
+        let digit = i128::from(digit - b'0');
+        if index < FIXED_POINT_DIGITS {
+            digit_scale /= 10;
+            result = result
+                .checked_add(digit * digit_scale)
+                .ok_or_else(invalid)?;
+        } else {
+            if digit >= 5 {
+                result = result.checked_add(1).ok_or_else(invalid)?;
+            }
+            break;
+        }
+    }
+    let result = if negative { -result } else { result };
+    if result < i128::from(i64::MIN) || i128::from(i64::MAX) < result {
+        return Err(invalid());
+    }
+    Ok(result as i64)
+}
+
+#[cfg(test)]
+#[path = "./nutrients_test.rs"]
+mod nutrients_test;
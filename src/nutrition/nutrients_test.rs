@@ -0,0 +1,249 @@
+use super::parse_decimal_string;
+use super::to_decimal_string;
+use super::Nutrients;
+use super::FIXED_POINT_SCALE;
+use crate::db::core::app_user;
+use crate::db::core::foodstuff;
+use crate::db::core::testing_util as dbtesting_utils;
+use crate::db::core::util::delete_app_user;
+use crate::nutrition::error::ErrorKind;
+use std::str::FromStr;
+use uuid::Uuid;
+
+fn nutrients(protein: i64, fats: i64, carbs: i64, calories: i64) -> Nutrients {
+    Nutrients::new(protein, fats, carbs, calories)
+}
+
+#[test]
+fn built_from_foodstuff() {
+    let app_user_uid = Uuid::from_str("00000000-0000-0000-0000-005400000000").unwrap();
+    delete_app_user(
+        &app_user_uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+
+    let app_user = app_user::insert(
+        app_user::new(app_user_uid, "name".to_string(), Uuid::new_v4()),
+        &connection,
+    )
+    .unwrap();
+    let foodstuff = foodstuff::new(&app_user, 1, "apple".to_string(), 1, 2, 3, 4, true);
+    let foodstuff = foodstuff::insert(foodstuff, &connection).unwrap();
+
+    assert_eq!(nutrients(1, 2, 3, 4), Nutrients::of_foodstuff(&foodstuff));
+}
+
+#[test]
+fn addition() {
+    let result = nutrients(1, 2, 3, 4) + nutrients(10, 20, 30, 40);
+    assert_eq!(nutrients(11, 22, 33, 44), result);
+}
+
+#[test]
+fn addition_saturates() {
+    let max = i64::MAX;
+    let min = i64::MIN;
+    let result = nutrients(max, max, min, 1) + nutrients(1, max, -1, 1);
+    assert_eq!(nutrients(max, max, min, 2), result);
+}
+
+#[test]
+fn scaling_by_mass() {
+    let per_100g = nutrients(10_000_000, 1_000_000, 0, 250_000_000);
+    assert_eq!(
+        nutrients(25_000_000, 2_500_000, 0, 625_000_000),
+        per_100g.scaled_by_mass(250)
+    );
+    assert_eq!(nutrients(0, 0, 0, 0), per_100g.scaled_by_mass(0));
+}
+
+#[test]
+fn scaling_by_mass_is_rounded_to_nearest() {
+    // 1 * 50 / 100 = 0.5, 3 * 10 / 100 = 0.3, 7 * 10 / 100 = 0.7
+    let result = nutrients(1, 3, 7, -1).scaled_by_mass(50);
+    assert_eq!(nutrients(1, 2, 4, -1), result);
+    let result = nutrients(1, 3, 7, 1).scaled_by_mass(10);
+    assert_eq!(nutrients(0, 0, 1, 0), result);
+}
+
+#[test]
+fn scaling_by_mass_saturates() {
+    let max = i64::MAX;
+    let result = nutrients(max, max / 2, 0, -max).scaled_by_mass(1000);
+    assert_eq!(nutrients(max, max, 0, i64::MIN), result);
+}
+
+#[test]
+fn merge_of_single_part_keeps_its_nutrients() {
+    let result = Nutrients::merge(&[(nutrients(1, 2, 3, 4), 250)]);
+    assert_eq!(nutrients(1, 2, 3, 4), result);
+}
+
+#[test]
+fn merge_weights_parts_by_their_masses() {
+    let result = Nutrients::merge(&[
+        (nutrients(10_000_000, 0, 20_000_000, 100_000_000), 100),
+        (nutrients(0, 4_000_000, 20_000_000, 400_000_000), 300),
+    ]);
+    assert_eq!(
+        nutrients(2_500_000, 3_000_000, 20_000_000, 325_000_000),
+        result
+    );
+}
+
+#[test]
+fn merge_is_rounded_to_nearest() {
+    // (1*1 + 2*2) / 3 = 1.666...
+    let result = Nutrients::merge(&[(nutrients(1, 1, 1, 1), 1), (nutrients(2, 2, 2, 2), 2)]);
+    assert_eq!(nutrients(2, 2, 2, 2), result);
+    // (1*2 + 2*1) / 3 = 1.333...
+    let result = Nutrients::merge(&[(nutrients(1, 1, 1, 1), 2), (nutrients(2, 2, 2, 2), 1)]);
+    assert_eq!(nutrients(1, 1, 1, 1), result);
+}
+
+#[test]
+fn merge_of_big_values_doesnt_overflow() {
+    let max = i64::MAX;
+    let result = Nutrients::merge(&[
+        (nutrients(max, max, max, max), max),
+        (nutrients(max, max, max, max), max),
+    ]);
+    assert_eq!(nutrients(max, max, max, max), result);
+}
+
+#[test]
+fn merge_of_nothing() {
+    assert_eq!(nutrients(0, 0, 0, 0), Nutrients::merge(&[]));
+    assert_eq!(
+        nutrients(0, 0, 0, 0),
+        Nutrients::merge(&[(nutrients(1, 2, 3, 4), 0)])
+    );
+}
+
+#[test]
+fn conversion_to_decimal_string() {
+    assert_eq!("0", to_decimal_string(0));
+    assert_eq!("1", to_decimal_string(FIXED_POINT_SCALE));
+    assert_eq!("1.5", to_decimal_string(1_500_000));
+    assert_eq!("0.000001", to_decimal_string(1));
+    assert_eq!("-2.25", to_decimal_string(-2_250_000));
+    assert_eq!("9223372036854.775807", to_decimal_string(i64::MAX));
+    assert_eq!("-9223372036854.775808", to_decimal_string(i64::MIN));
+}
+
+#[test]
+fn conversion_from_decimal_string() {
+    assert_eq!(0, parse_decimal_string("0").unwrap());
+    assert_eq!(FIXED_POINT_SCALE, parse_decimal_string("1").unwrap());
+    assert_eq!(1_500_000, parse_decimal_string("1.5").unwrap());
+    assert_eq!(1_500_000, parse_decimal_string("01.500").unwrap());
+    assert_eq!(1, parse_decimal_string("0.000001").unwrap());
+    assert_eq!(-2_250_000, parse_decimal_string("-2.25").unwrap());
+    assert_eq!(
+        i64::MAX,
+        parse_decimal_string("9223372036854.775807").unwrap()
+    );
+    assert_eq!(
+        i64::MIN,
+        parse_decimal_string("-9223372036854.775808").unwrap()
+    );
+}
+
+#[test]
+fn conversion_from_decimal_string_rounds_extra_digits() {
+    assert_eq!(1, parse_decimal_string("0.0000005").unwrap());
+    assert_eq!(0, parse_decimal_string("0.0000004999").unwrap());
+    assert_eq!(-1, parse_decimal_string("-0.0000005").unwrap());
+}
+
+#[test]
+fn conversion_from_invalid_decimal_string() {
+    let invalid = [
+        "",
+        "-",
+        ".",
+        "1.",
+        ".5",
+        "1.2.3",
+        "+1",
+        " 1",
+        "1e5",
+        "abc",
+        "1,5",
+        "--1",
+        "9223372036855",
+        "9223372036854.775808",
+        "-9223372036854.775809",
+        "9223372036854.7758075",
+    ];
+    for value in invalid.iter() {
+        match parse_decimal_string(value) {
+            Err(error) => match error.kind() {
+                ErrorKind::InvalidDecimalString(invalid_value) => {
+                    assert_eq!(value, invalid_value)
+                }
+                _ => panic!("Unexpected error: {:?}", error),
+            },
+            Ok(result) => panic!("Value '{}' was parsed into {}", value, result),
+        }
+    }
+}
+
+#[test]
+fn conversions_round_trip() {
+    let values = [
+        0,
+        1,
+        10,
+        999_999,
+        1_000_000,
+        1_000_001,
+        123_456_789,
+        -42,
+        i64::MAX,
+        i64::MIN,
+    ];
+    for value in values.iter() {
+        let string = to_decimal_string(*value);
+        assert_eq!(*value, parse_decimal_string(&string).unwrap());
+    }
+}
+
+// The remove_floats_from_foodstuff migration converted REAL nutrients into integers with
+// ((value * 1000000)::integer). REAL's precision is lower than the fixed-point's one,
+// so some of the stored integers aren't exact - the test asks Postgres what it stores.
+#[test]
+fn values_stored_by_migration() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let reals = ["0.4", "0.1", "12.3", "1000", "123.456", "33.33", "2147.48"];
+    for real in reals.iter() {
+        let stored = dbtesting_utils::convert_real_as_migration(real, &connection).unwrap();
+        let stored = i64::from(stored);
+
+        // The API gives exactly what was stored
+        let decimal_string = to_decimal_string(stored);
+        assert_eq!(stored, parse_decimal_string(&decimal_string).unwrap());
+        // But the stored value differs from the original REAL value at most
+        // by REAL's imprecision
+        let original = parse_decimal_string(real).unwrap();
+        assert!(
+            (original - stored).abs() * 1_000_000 <= original.max(1) * 10,
+            "{} is stored as {}",
+            real,
+            decimal_string
+        );
+    }
+}
+
+#[test]
+fn totals_of_max_column_values_dont_saturate() {
+    let max = i64::from(i32::MAX);
+    assert_eq!("2147.483647", to_decimal_string(max));
+    let per_100g = nutrients(max, max, max, max);
+    let total = per_100g.scaled_by_mass(max) + per_100g;
+    // max * max / 100 + max
+    let expected = 46_116_862_288_807_853;
+    assert_eq!(nutrients(expected, expected, expected, expected), total);
+}
@@ -4,6 +4,7 @@ error_chain! {
         InvalidUri(hyper::http::uri::InvalidUri);
         HyperError(hyper::Error);
         HyperHttpError(hyper::http::Error);
+        Jwt(jsonwebtoken::errors::Error);
     }
 
     errors {
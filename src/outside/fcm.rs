@@ -9,6 +9,7 @@ use super::error::Error;
 use super::error::ErrorKind::UnexpectedResponseFormat;
 use super::http_client::HttpClient;
 use super::http_client::RequestMethod;
+use crate::metrics;
 
 // request
 // curl -X POST
@@ -35,6 +36,16 @@ use super::http_client::RequestMethod;
 // "results":[{"error":"InvalidRegistration"}]
 // }
 
+// response with a canonical token - the message is sent, but the device
+// has another (newer) token
+// {
+// "multicast_id":2513734409441993719,
+// "success":1,
+// "failure":0,
+// "canonical_ids":1,
+// "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd","registration_id":"new_token"}]
+// }
+
 // response invalid server token
 //<HTML>
 //<HEAD>
@@ -48,10 +59,80 @@ use super::http_client::RequestMethod;
 
 pub const FCM_ADDR: &str = "https://fcm.googleapis.com/";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SendResult {
     Success,
-    Error(String),
+    /// The message is sent, but FCM asks to use the given (canonical) token
+    /// for the device from now on.
+    SuccessWithCanonicalToken(String),
+    Error(FcmError),
+}
+
+/// Errors FCM reports about sent messages, see
+/// https://firebase.google.com/docs/cloud-messaging/http-server-ref#error-codes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FcmError {
+    MissingRegistration,
+    InvalidRegistration,
+    NotRegistered,
+    InvalidPackageName,
+    MismatchSenderId,
+    InvalidParameters,
+    MessageTooBig,
+    InvalidDataKey,
+    InvalidTtl,
+    Unavailable,
+    InternalServerError,
+    DeviceMessageRateExceeded,
+    TopicsMessageRateExceeded,
+    InvalidApnsCredential,
+    /// The server key was rejected.
+    Unauthorized,
+    /// An error code unknown to the server.
+    Unknown(String),
+}
+
+impl FcmError {
+    fn from_code(code: &str) -> FcmError {
+        match code {
+            "MissingRegistration" => FcmError::MissingRegistration,
+            "InvalidRegistration" => FcmError::InvalidRegistration,
+            "NotRegistered" => FcmError::NotRegistered,
+            "InvalidPackageName" => FcmError::InvalidPackageName,
+            "MismatchSenderId" => FcmError::MismatchSenderId,
+            "InvalidParameters" => FcmError::InvalidParameters,
+            "MessageTooBig" => FcmError::MessageTooBig,
+            "InvalidDataKey" => FcmError::InvalidDataKey,
+            "InvalidTtl" => FcmError::InvalidTtl,
+            "Unavailable" => FcmError::Unavailable,
+            "InternalServerError" => FcmError::InternalServerError,
+            "DeviceMessageRateExceeded" => FcmError::DeviceMessageRateExceeded,
+            "TopicsMessageRateExceeded" => FcmError::TopicsMessageRateExceeded,
+            "InvalidApnsCredential" => FcmError::InvalidApnsCredential,
+            _ => FcmError::Unknown(code.to_owned()),
+        }
+    }
+
+    /// Whether the error is permanent for the token the message was sent to -
+    /// such a token will never work again and should be deleted.
+    pub fn is_permanent_for_token(&self) -> bool {
+        matches!(
+            self,
+            FcmError::MissingRegistration
+                | FcmError::InvalidRegistration
+                | FcmError::NotRegistered
+                | FcmError::MismatchSenderId
+        )
+    }
+}
+
+impl std::fmt::Display for FcmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FcmError::Unknown(code) => write!(f, "Unknown({})", code),
+            _ => write!(f, "{:?}", self),
+        }
+    }
 }
 
 /// Ok(SendResult::Error) for expected errors, Err(..) for unexpected
@@ -85,14 +166,19 @@ pub async fn send(
     info!("fcm::send, url: {}, body: {}", url, body);
     let response = http_client
         .req(url, RequestMethod::Post, headers, Some(body.to_string()))
-        .await?;
-    send_response_to_send_result(response.body)
+        .await;
+    let result = response.and_then(|response| send_response_to_send_result(response.body));
+    metrics::observe_fcm_send(matches!(
+        result,
+        Ok(SendResult::Success) | Ok(SendResult::SuccessWithCanonicalToken(_))
+    ));
+    result
 }
 
 /// Ok(SendResult::Error) for expected errors, Err(..) for unexpected
 pub fn send_response_to_send_result(response: String) -> Result<SendResult, Error> {
     if response.contains("Error 401") {
-        return Ok(SendResult::Error(response));
+        return Ok(SendResult::Error(FcmError::Unauthorized));
     }
     let response: serde_json::Value = match serde_json::from_str(&response) {
         Ok(response) => response,
@@ -123,7 +209,16 @@ pub fn send_response_to_send_result(response: String) -> Result<SendResult, Erro
     };
 
     if !result["error"].is_null() {
-        return Ok(SendResult::Error(response.to_string()));
+        let error = match result["error"].as_str() {
+            Some(code) => FcmError::from_code(code),
+            None => FcmError::Unknown(result["error"].to_string()),
+        };
+        return Ok(SendResult::Error(error));
+    }
+    if let Some(canonical_token) = result["registration_id"].as_str() {
+        return Ok(SendResult::SuccessWithCanonicalToken(
+            canonical_token.to_owned(),
+        ));
     }
 
     Ok(SendResult::Success)
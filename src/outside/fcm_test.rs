@@ -28,15 +28,10 @@ fn send_with_invalid_client_token() {
     );
     let send_result = exhaust_future(send_result).unwrap();
 
-    match send_result {
-        fcm::SendResult::Error(error) => {
-            assert!(error.contains("InvalidRegistration"), error);
-        }
-        _ => panic!(
-            "Expected send result to be err, but it was: {:?}",
-            send_result
-        ),
-    }
+    assert_eq!(
+        fcm::SendResult::Error(fcm::FcmError::InvalidRegistration),
+        send_result
+    );
 }
 
 #[test]
@@ -60,15 +55,10 @@ fn send_with_invalid_server_token() {
     );
     let send_result = exhaust_future(send_result).unwrap();
 
-    match send_result {
-        fcm::SendResult::Error(error) => {
-            assert!(error.contains("Error 401"), error);
-        }
-        _ => panic!(
-            "Expected send result to be err, but it was: {:?}",
-            send_result
-        ),
-    }
+    assert_eq!(
+        fcm::SendResult::Error(fcm::FcmError::Unauthorized),
+        send_result
+    );
 }
 
 #[test]
@@ -83,16 +73,25 @@ fn successful_send_response_is_parsed() {
     }"#;
 
     let send_result = fcm::send_response_to_send_result(response.to_string()).unwrap();
+    assert_eq!(fcm::SendResult::Success, send_result);
+}
 
-    match send_result {
-        fcm::SendResult::Success => {
-            // ok
-        }
-        _ => panic!(
-            "Expected send result to be success, but it was: {:?}",
-            send_result
-        ),
-    }
+#[test]
+fn canonical_token_send_response_is_parsed() {
+    let response = r#"
+    {
+        "multicast_id":2513734409441993719,
+        "success":1,
+        "failure":0,
+        "canonical_ids":1,
+        "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd","registration_id":"new_token"}]
+    }"#;
+
+    let send_result = fcm::send_response_to_send_result(response.to_string()).unwrap();
+    assert_eq!(
+        fcm::SendResult::SuccessWithCanonicalToken("new_token".to_owned()),
+        send_result
+    );
 }
 
 #[test]
@@ -103,18 +102,73 @@ fn failed_send_response_is_parsed() {
         "success":0,
         "failure":1,
         "canonical_ids":0,
-        "results":[{"error":"InvalidRegistration"}]
+        "results":[{"error":"Unavailable"}]
     }"#;
 
     let send_result = fcm::send_response_to_send_result(response.to_string()).unwrap();
+    assert_eq!(
+        fcm::SendResult::Error(fcm::FcmError::Unavailable),
+        send_result
+    );
+}
 
-    match send_result {
-        fcm::SendResult::Success => panic!(
-            "Expected send result to be failure, but it was: {:?}",
-            send_result
+#[test]
+fn error_codes_are_parsed() {
+    let errors = vec![
+        (
+            "InvalidRegistration",
+            fcm::FcmError::InvalidRegistration,
+            true,
+        ),
+        ("NotRegistered", fcm::FcmError::NotRegistered, true),
+        (
+            "MissingRegistration",
+            fcm::FcmError::MissingRegistration,
+            true,
         ),
-        fcm::SendResult::Error(_) => {
-            // ok
+        ("MismatchSenderId", fcm::FcmError::MismatchSenderId, true),
+        ("Unavailable", fcm::FcmError::Unavailable, false),
+        (
+            "InternalServerError",
+            fcm::FcmError::InternalServerError,
+            false,
+        ),
+        ("MessageTooBig", fcm::FcmError::MessageTooBig, false),
+        (
+            "DeviceMessageRateExceeded",
+            fcm::FcmError::DeviceMessageRateExceeded,
+            false,
+        ),
+        (
+            "SomethingNew",
+            fcm::FcmError::Unknown("SomethingNew".to_owned()),
+            false,
+        ),
+    ];
+    for (code, expected_error, permanent_for_token) in errors {
+        let response = json!({
+            "multicast_id": 1,
+            "success": 0,
+            "failure": 1,
+            "canonical_ids": 0,
+            "results": [{ "error": code }]
+        });
+
+        let send_result = fcm::send_response_to_send_result(response.to_string()).unwrap();
+        match send_result {
+            fcm::SendResult::Error(error) => {
+                assert_eq!(expected_error, error);
+                assert_eq!(
+                    permanent_for_token,
+                    error.is_permanent_for_token(),
+                    "{}",
+                    code
+                );
+            }
+            _ => panic!(
+                "Expected send result to be failure, but it was: {:?}",
+                send_result
+            ),
         }
     }
 }
@@ -134,16 +188,10 @@ fn invalid_server_token_response_is_parsed() {
         "#;
 
     let send_result = fcm::send_response_to_send_result(response.to_string()).unwrap();
-
-    match send_result {
-        fcm::SendResult::Success => panic!(
-            "Expected send result to be failure, but it was: {:?}",
-            send_result
-        ),
-        fcm::SendResult::Error(_) => {
-            // ok
-        }
-    }
+    assert_eq!(
+        fcm::SendResult::Error(fcm::FcmError::Unauthorized),
+        send_result
+    );
 }
 
 #[test]
@@ -0,0 +1,185 @@
+use hyper::Uri;
+use log::info;
+use serde_json::Map as JsonMap;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::error::Error;
+use super::error::ErrorKind::UnexpectedResponseFormat;
+use super::fcm::FcmError;
+use super::fcm::SendResult;
+use super::google_oauth;
+use super::google_oauth::ServiceAccountKey;
+use super::http_client::HttpClient;
+use super::http_client::RequestMethod;
+use crate::metrics;
+
+// request
+// curl -X POST
+// -H "Authorization: Bearer ya29.c.Ko8BvQdk..."
+// -H "Content-Type: application/json"
+// -d '{ "message":{ "token":"client_token", "data":{ "danil":"FCM Message" },
+//       "android":{ "priority":"high" } } }'
+// https://fcm.googleapis.com/v1/projects/project_id/messages:send
+
+// response ok
+// {
+// "name":"projects/project_id/messages/0:1579970411599831%8e9256aef9fd7ecd"
+// }
+
+// response invalid client token
+// {
+// "error":{
+//   "code":404,
+//   "message":"Requested entity was not found.",
+//   "status":"NOT_FOUND",
+//   "details":[{
+//     "@type":"type.googleapis.com/google.firebase.fcm.v1.FcmError",
+//     "errorCode":"UNREGISTERED"
+//   }]
+// }
+// }
+
+// response invalid access token
+// {
+// "error":{
+//   "code":401,
+//   "message":"Request had invalid authentication credentials.",
+//   "status":"UNAUTHENTICATED"
+// }
+// }
+
+/// OAuth2 scope of access tokens required by the API.
+pub const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const FCM_ERROR_TYPE: &str = "type.googleapis.com/google.firebase.fcm.v1.FcmError";
+
+/// Sends the message with the FCM HTTP v1 API.
+/// Values of the |data| JSON object are sent as strings, because the API accepts only them.
+/// Ok(SendResult::Error) for expected errors, Err(..) for unexpected
+pub async fn send(
+    data: String,
+    fcm_token: &str,
+    service_account_key: &ServiceAccountKey,
+    fcm_address: &str,
+    http_client: Arc<HttpClient>,
+) -> Result<SendResult, Error> {
+    let access_token =
+        google_oauth::access_token(service_account_key, FCM_SCOPE, &http_client).await?;
+
+    let mut headers = HashMap::new();
+    headers.insert(
+        "Authorization".to_owned(),
+        format!("Bearer {}", access_token),
+    );
+    headers.insert("Content-Type".to_owned(), "application/json".to_owned());
+
+    let body = json!({
+        "message": {
+            "token": fcm_token,
+            "data": data_to_string_values(data),
+            "android": {
+                "priority": "high"
+            }
+        }
+    });
+
+    let url = Uri::from_str(&format!(
+        "{}/v1/projects/{}/messages:send",
+        fcm_address.trim_end_matches('/'),
+        service_account_key.project_id()
+    ))?;
+    info!("fcm_v1::send, url: {}, body: {}", url, body);
+    let response = http_client
+        .req(url, RequestMethod::Post, headers, Some(body.to_string()))
+        .await;
+    let result = response.and_then(|response| send_response_to_send_result(response.body));
+    if let Ok(SendResult::Error(FcmError::Unauthorized)) = result {
+        // The cached token could be revoked
+        google_oauth::invalidate_access_token(service_account_key, FCM_SCOPE).await;
+    }
+    metrics::observe_fcm_send(matches!(result, Ok(SendResult::Success)));
+    result
+}
+
+/// Ok(SendResult::Error) for expected errors, Err(..) for unexpected
+pub fn send_response_to_send_result(response: String) -> Result<SendResult, Error> {
+    let response: JsonValue = match serde_json::from_str(&response) {
+        Ok(response) => response,
+        Err(_) => {
+            return Err(UnexpectedResponseFormat(format!(
+                "Response isn't valid JSON: {}",
+                response
+            ))
+            .into());
+        }
+    };
+
+    if response["name"].is_string() {
+        return Ok(SendResult::Success);
+    }
+
+    let error = &response["error"];
+    if !error.is_object() {
+        return Err(UnexpectedResponseFormat(format!(
+            "Response has neither a message name nor an error: {}",
+            response
+        ))
+        .into());
+    }
+
+    // FCM-specific error codes are more precise than the general statuses
+    let fcm_error_code = error["details"].as_array().and_then(|details| {
+        details
+            .iter()
+            .find(|detail| detail["@type"] == FCM_ERROR_TYPE)
+            .and_then(|detail| detail["errorCode"].as_str())
+    });
+    let error = match fcm_error_code.or_else(|| error["status"].as_str()) {
+        Some(code) => error_code_to_fcm_error(code),
+        None => FcmError::Unknown(error.to_string()),
+    };
+    Ok(SendResult::Error(error))
+}
+
+/// Maps the v1 error codes onto the legacy API errors, see
+/// https://firebase.google.com/docs/reference/fcm/rest/v1/ErrorCode
+fn error_code_to_fcm_error(code: &str) -> FcmError {
+    match code {
+        "UNREGISTERED" => FcmError::NotRegistered,
+        "SENDER_ID_MISMATCH" => FcmError::MismatchSenderId,
+        // Is reported both for invalid tokens and for invalid messages,
+        // so it's not treated as a token error
+        "INVALID_ARGUMENT" => FcmError::InvalidParameters,
+        "QUOTA_EXCEEDED" => FcmError::DeviceMessageRateExceeded,
+        "UNAVAILABLE" => FcmError::Unavailable,
+        "INTERNAL" => FcmError::InternalServerError,
+        "THIRD_PARTY_AUTH_ERROR" => FcmError::InvalidApnsCredential,
+        "UNAUTHENTICATED" | "PERMISSION_DENIED" => FcmError::Unauthorized,
+        _ => FcmError::Unknown(code.to_owned()),
+    }
+}
+
+fn data_to_string_values(data: String) -> JsonValue {
+    let data = match serde_json::from_str(&data) {
+        Ok(JsonValue::Object(data)) => data,
+        _ => {
+            let mut result = JsonMap::new();
+            result.insert("data".to_owned(), JsonValue::String(data));
+            return JsonValue::Object(result);
+        }
+    };
+    let data = data
+        .into_iter()
+        .map(|(key, value)| match value {
+            JsonValue::String(value) => (key, JsonValue::String(value)),
+            value => (key, JsonValue::String(value.to_string())),
+        })
+        .collect();
+    JsonValue::Object(data)
+}
+
+#[cfg(test)]
+#[path = "./fcm_v1_test.rs"]
+mod fcm_v1_test;
@@ -0,0 +1,208 @@
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+
+use crate::outside::error::Error;
+use crate::outside::error::ErrorKind;
+use crate::outside::fcm::FcmError;
+use crate::outside::fcm::SendResult;
+use crate::outside::fcm_v1;
+use crate::outside::http_client::HttpClient;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+use crate::testing_utils::exhaust_future;
+use crate::testing_utils::testing_service_account_key;
+
+/// Responds to access token requests by the token, and to send requests by |send_response|.
+fn fcm_responder(send_response: JsonValue) -> impl Fn(&FullRequest) -> Option<String> {
+    move |request| {
+        if request.request == "/token" {
+            let response = json!({
+                "access_token": "access_token1",
+                "expires_in": 3600,
+                "token_type": "Bearer"
+            });
+            Some(response.to_string())
+        } else {
+            Some(send_response.to_string())
+        }
+    }
+}
+
+fn error_response(code: i32, status: &str, fcm_error_code: Option<&str>) -> JsonValue {
+    let mut error = json!({
+        "error": {
+            "code": code,
+            "message": "Error message",
+            "status": status
+        }
+    });
+    if let Some(fcm_error_code) = fcm_error_code {
+        error["error"]["details"] = json!([
+            {
+                "@type": "type.googleapis.com/google.rpc.BadRequest",
+                "fieldViolations": []
+            },
+            {
+                "@type": "type.googleapis.com/google.firebase.fcm.v1.FcmError",
+                "errorCode": fcm_error_code
+            }
+        ]);
+    }
+    error
+}
+
+#[test]
+fn send_request_is_authorized_by_access_token() {
+    let send_response = json!({ "name": "projects/testing-project/messages/0:123" });
+    let (fcm_server, fcm_requests) = start_mock_server(
+        fcm_responder(send_response),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let token_uri = format!("http://{}/token", fcm_server.address());
+    let key = testing_service_account_key("send@testing.com", &token_uri);
+    let fcm_address = format!("http://{}", fcm_server.address());
+    let http_client = Arc::new(HttpClient::new().unwrap());
+
+    let data = json!({ "msg_type": "hello", "number": 123 }).to_string();
+    let send_result = fcm_v1::send(data, "fcm_token1", &key, &fcm_address, http_client);
+    let send_result = exhaust_future(send_result).unwrap();
+    assert_eq!(SendResult::Success, send_result);
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    assert_eq!(2, fcm_requests.len());
+    assert_eq!("/token", fcm_requests[0].request);
+    let send_request = &fcm_requests[1];
+    assert_eq!(
+        "/v1/projects/testing-project/messages:send",
+        send_request.request
+    );
+    assert_eq!(
+        "Bearer access_token1",
+        send_request.headers["authorization"]
+    );
+    let body: JsonValue = serde_json::from_str(&send_request.body).unwrap();
+    assert_eq!(
+        json!({
+            "message": {
+                "token": "fcm_token1",
+                // Values are strings only
+                "data": { "msg_type": "hello", "number": "123" },
+                "android": { "priority": "high" }
+            }
+        }),
+        body
+    );
+}
+
+#[test]
+fn access_token_is_requested_again_after_unauthorized_send() {
+    let send_response = error_response(401, "UNAUTHENTICATED", None);
+    let (fcm_server, fcm_requests) = start_mock_server(
+        fcm_responder(send_response),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let token_uri = format!("http://{}/token", fcm_server.address());
+    let key = testing_service_account_key("unauthorized@testing.com", &token_uri);
+    let fcm_address = format!("http://{}", fcm_server.address());
+    let http_client = Arc::new(HttpClient::new().unwrap());
+
+    for _ in 0..2 {
+        let send_result = fcm_v1::send(
+            json!({}).to_string(),
+            "fcm_token1",
+            &key,
+            &fcm_address,
+            http_client.clone(),
+        );
+        let send_result = exhaust_future(send_result).unwrap();
+        assert_eq!(SendResult::Error(FcmError::Unauthorized), send_result);
+    }
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let token_requests_count = fcm_requests
+        .iter()
+        .filter(|request| request.request == "/token")
+        .count();
+    assert_eq!(2, token_requests_count);
+}
+
+#[test]
+fn successful_send_response_is_parsed() {
+    let response = json!({ "name": "projects/testing-project/messages/0:123" });
+    let send_result = fcm_v1::send_response_to_send_result(response.to_string()).unwrap();
+    assert_eq!(SendResult::Success, send_result);
+}
+
+#[test]
+fn error_send_responses_are_parsed() {
+    let errors = vec![
+        (
+            error_response(404, "NOT_FOUND", Some("UNREGISTERED")),
+            FcmError::NotRegistered,
+            true,
+        ),
+        (
+            error_response(403, "PERMISSION_DENIED", Some("SENDER_ID_MISMATCH")),
+            FcmError::MismatchSenderId,
+            true,
+        ),
+        (
+            error_response(400, "INVALID_ARGUMENT", Some("INVALID_ARGUMENT")),
+            FcmError::InvalidParameters,
+            false,
+        ),
+        (
+            error_response(503, "UNAVAILABLE", Some("UNAVAILABLE")),
+            FcmError::Unavailable,
+            false,
+        ),
+        (
+            error_response(429, "RESOURCE_EXHAUSTED", Some("QUOTA_EXCEEDED")),
+            FcmError::DeviceMessageRateExceeded,
+            false,
+        ),
+        // Without FCM error codes statuses are used
+        (
+            error_response(401, "UNAUTHENTICATED", None),
+            FcmError::Unauthorized,
+            false,
+        ),
+        (
+            error_response(500, "INTERNAL", None),
+            FcmError::InternalServerError,
+            false,
+        ),
+        (
+            error_response(500, "SOMETHING_NEW", None),
+            FcmError::Unknown("SOMETHING_NEW".to_owned()),
+            false,
+        ),
+    ];
+    for (response, expected_error, permanent_for_token) in errors {
+        let send_result = fcm_v1::send_response_to_send_result(response.to_string()).unwrap();
+        match send_result {
+            SendResult::Error(error) => {
+                assert_eq!(expected_error, error);
+                assert_eq!(permanent_for_token, error.is_permanent_for_token());
+            }
+            _ => panic!(
+                "Expected send result to be failure, but it was: {:?}",
+                send_result
+            ),
+        }
+    }
+}
+
+#[test]
+fn unexpected_responses_transform_into_unexpected_response_errors() {
+    for response in &["<HTML>Not found!</HTML>", "{}", r#"{"error":"error"}"#] {
+        let send_result = fcm_v1::send_response_to_send_result(response.to_string());
+        match send_result {
+            Err(Error(ErrorKind::UnexpectedResponseFormat(_), _)) => {
+                // Ok
+            }
+            _ => panic!("Unexpected result: {:?}", send_result),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use hyper::Uri;
+use jsonwebtoken::Algorithm;
+use jsonwebtoken::EncodingKey;
+use jsonwebtoken::Header;
+use log::info;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use tokio::sync::Mutex;
+
+use super::error::Error;
+use super::error::ErrorKind::UnexpectedResponseFormat;
+use super::http_client::HttpClient;
+use super::http_client::RequestMethod;
+
+// request
+// curl -X POST
+// -H "Content-Type: application/x-www-form-urlencoded"
+// -d 'grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion=eyJhbGciOi...'
+// https://oauth2.googleapis.com/token
+
+// response ok
+// {
+// "access_token":"ya29.c.Ko8BvQdk...",
+// "expires_in":3599,
+// "token_type":"Bearer"
+// }
+
+// response error
+// {
+// "error":"invalid_grant",
+// "error_description":"Invalid JWT Signature."
+// }
+
+const GRANT_TYPE_JWT_BEARER: &str = "urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer";
+/// Max lifetime of a JWT allowed by Google.
+const JWT_LIFETIME_SECONDS: u64 = 3600;
+/// Cached access tokens are refreshed a bit before they expire, so that a token
+/// wouldn't expire while a request with it is in flight.
+const ACCESS_TOKEN_EXPIRATION_MARGIN: Duration = Duration::from_secs(60);
+
+/// Key of a Google service account - the JSON file downloaded from the Google Cloud
+/// (or Firebase) console. Fields of the file not needed by the server are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServiceAccountKey {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+impl ServiceAccountKey {
+    pub fn new(
+        project_id: String,
+        client_email: String,
+        private_key: String,
+        token_uri: String,
+    ) -> ServiceAccountKey {
+        ServiceAccountKey {
+            project_id,
+            client_email,
+            private_key,
+            token_uri,
+        }
+    }
+
+    pub fn project_id(&self) -> &str {
+        &self.project_id
+    }
+
+    pub fn client_email(&self) -> &str {
+        &self.client_email
+    }
+
+    pub fn private_key(&self) -> &str {
+        &self.private_key
+    }
+
+    pub fn token_uri(&self) -> &str {
+        &self.token_uri
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    iss: String,
+    scope: String,
+    aud: String,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct AccessTokenKey {
+    client_email: String,
+    token_uri: String,
+    scope: String,
+}
+
+struct AccessToken {
+    value: String,
+    expires_at: Instant,
+}
+
+lazy_static! {
+    static ref ACCESS_TOKENS: Mutex<HashMap<AccessTokenKey, AccessToken>> =
+        Mutex::new(HashMap::new());
+}
+
+impl AccessTokenKey {
+    fn new(service_account_key: &ServiceAccountKey, scope: &str) -> AccessTokenKey {
+        AccessTokenKey {
+            client_email: service_account_key.client_email.clone(),
+            token_uri: service_account_key.token_uri.clone(),
+            scope: scope.to_owned(),
+        }
+    }
+}
+
+/// Returns an OAuth2 access token of the service account for the |scope|.
+/// Tokens are cached until they are about to expire. The cache stays locked while
+/// a new token is requested, so that concurrent callers would wait for it instead
+/// of requesting tokens of their own.
+pub async fn access_token(
+    service_account_key: &ServiceAccountKey,
+    scope: &str,
+    http_client: &HttpClient,
+) -> Result<String, Error> {
+    let key = AccessTokenKey::new(service_account_key, scope);
+    let mut access_tokens = ACCESS_TOKENS.lock().await;
+    if let Some(access_token) = access_tokens.get(&key) {
+        if Instant::now() + ACCESS_TOKEN_EXPIRATION_MARGIN < access_token.expires_at {
+            return Ok(access_token.value.clone());
+        }
+    }
+
+    let access_token = request_access_token(service_account_key, scope, http_client).await?;
+    let value = access_token.value.clone();
+    access_tokens.insert(key, access_token);
+    Ok(value)
+}
+
+/// Forgets the cached access token, so that a new one would be requested next time
+/// (e.g. when the cached one was rejected).
+pub async fn invalidate_access_token(service_account_key: &ServiceAccountKey, scope: &str) {
+    let key = AccessTokenKey::new(service_account_key, scope);
+    ACCESS_TOKENS.lock().await.remove(&key);
+}
+
+async fn request_access_token(
+    service_account_key: &ServiceAccountKey,
+    scope: &str,
+    http_client: &HttpClient,
+) -> Result<AccessToken, Error> {
+    let jwt = create_jwt(service_account_key, scope, SystemTime::now())?;
+
+    let mut headers = HashMap::new();
+    headers.insert(
+        "Content-Type".to_owned(),
+        "application/x-www-form-urlencoded".to_owned(),
+    );
+    let body = format!("grant_type={}&assertion={}", GRANT_TYPE_JWT_BEARER, jwt);
+
+    let url = Uri::from_str(&service_account_key.token_uri)?;
+    info!("google_oauth::request_access_token, url: {}", url);
+    let requested_at = Instant::now();
+    let response = http_client
+        .req(url, RequestMethod::Post, headers, Some(body))
+        .await?;
+
+    let response: JsonValue = match serde_json::from_str(&response.body) {
+        Ok(response) => response,
+        Err(_) => {
+            return Err(UnexpectedResponseFormat(format!(
+                "Access token response isn't valid JSON: {}",
+                response.body
+            ))
+            .into());
+        }
+    };
+    match (
+        response["access_token"].as_str(),
+        response["expires_in"].as_u64(),
+    ) {
+        (Some(access_token), Some(expires_in)) => Ok(AccessToken {
+            value: access_token.to_owned(),
+            expires_at: requested_at + Duration::from_secs(expires_in),
+        }),
+        _ => Err(UnexpectedResponseFormat(format!(
+            "Access token response doesn't have a token: {}",
+            response
+        ))
+        .into()),
+    }
+}
+
+/// Creates a JWT which the service account exchanges for an access token.
+fn create_jwt(
+    service_account_key: &ServiceAccountKey,
+    scope: &str,
+    now: SystemTime,
+) -> Result<String, Error> {
+    let iat = now
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0);
+    let claims = Claims {
+        iss: service_account_key.client_email.clone(),
+        scope: scope.to_owned(),
+        aud: service_account_key.token_uri.clone(),
+        iat,
+        exp: iat + JWT_LIFETIME_SECONDS,
+    };
+    let key = EncodingKey::from_rsa_pem(service_account_key.private_key.as_bytes())?;
+    let jwt = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)?;
+    Ok(jwt)
+}
+
+#[cfg(test)]
+#[path = "./google_oauth_test.rs"]
+mod google_oauth_test;
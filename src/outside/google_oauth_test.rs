@@ -0,0 +1,135 @@
+use jsonwebtoken::Algorithm;
+use jsonwebtoken::DecodingKey;
+use jsonwebtoken::Validation;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::outside::error::Error;
+use crate::outside::error::ErrorKind;
+use crate::outside::google_oauth;
+use crate::outside::google_oauth::Claims;
+use crate::outside::http_client::HttpClient;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+use crate::testing_utils::exhaust_future;
+use crate::testing_utils::testing_service_account_key;
+use crate::testing_utils::TESTING_SERVICE_ACCOUNT_PUBLIC_KEY;
+
+const SCOPE: &str = "https://www.googleapis.com/auth/testing";
+
+fn token_response(request: &FullRequest, expires_in: u64) -> Option<String> {
+    assert!(request.body.starts_with(
+        "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion="
+    ));
+    let response = json!({
+        "access_token": "access_token1",
+        "expires_in": expires_in,
+        "token_type": "Bearer"
+    });
+    Some(response.to_string())
+}
+
+#[test]
+fn jwt_is_signed_by_service_account() {
+    let key = testing_service_account_key("jwt@testing.com", "https://oauth2.testing.com/token");
+    let now = SystemTime::now();
+
+    let jwt = google_oauth::create_jwt(&key, SCOPE, now).unwrap();
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    let mut audience = HashSet::new();
+    audience.insert("https://oauth2.testing.com/token".to_owned());
+    validation.aud = Some(audience);
+    validation.iss = Some("jwt@testing.com".to_owned());
+    let decoding_key = DecodingKey::from_rsa_pem(TESTING_SERVICE_ACCOUNT_PUBLIC_KEY.as_bytes());
+    let claims = jsonwebtoken::decode::<Claims>(&jwt, &decoding_key.unwrap(), &validation)
+        .unwrap()
+        .claims;
+    assert_eq!(SCOPE, claims.scope);
+    assert_eq!(3600, claims.exp - claims.iat);
+}
+
+#[test]
+fn access_tokens_are_cached() {
+    let (token_server, token_requests) = start_mock_server(
+        |request| token_response(request, 3600),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let token_uri = format!("http://{}/token", token_server.address());
+    let key = testing_service_account_key("cached@testing.com", &token_uri);
+    let http_client = Arc::new(HttpClient::new().unwrap());
+
+    let token1 = exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client)).unwrap();
+    let token2 = exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client)).unwrap();
+    assert_eq!("access_token1", token1);
+    assert_eq!("access_token1", token2);
+    assert_eq!(1, token_requests.lock().unwrap().len());
+
+    // Tokens of another scope are requested separately
+    exhaust_future(google_oauth::access_token(
+        &key,
+        "another_scope",
+        &http_client,
+    ))
+    .unwrap();
+    assert_eq!(2, token_requests.lock().unwrap().len());
+}
+
+#[test]
+fn expiring_access_tokens_are_requested_again() {
+    let (token_server, token_requests) = start_mock_server(
+        // Shorter than the expiration margin
+        |request| token_response(request, 10),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let token_uri = format!("http://{}/token", token_server.address());
+    let key = testing_service_account_key("expiring@testing.com", &token_uri);
+    let http_client = Arc::new(HttpClient::new().unwrap());
+
+    exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client)).unwrap();
+    exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client)).unwrap();
+    assert_eq!(2, token_requests.lock().unwrap().len());
+}
+
+#[test]
+fn invalidated_access_tokens_are_requested_again() {
+    let (token_server, token_requests) = start_mock_server(
+        |request| token_response(request, 3600),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let token_uri = format!("http://{}/token", token_server.address());
+    let key = testing_service_account_key("invalidated@testing.com", &token_uri);
+    let http_client = Arc::new(HttpClient::new().unwrap());
+
+    exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client)).unwrap();
+    futures::executor::block_on(google_oauth::invalidate_access_token(&key, SCOPE));
+    exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client)).unwrap();
+    assert_eq!(2, token_requests.lock().unwrap().len());
+}
+
+#[test]
+fn error_response_transforms_into_unexpected_response_error() {
+    let (token_server, _token_requests) = start_mock_server(
+        |_request| {
+            let response = json!({
+                "error": "invalid_grant",
+                "error_description": "Invalid JWT Signature."
+            });
+            Some(response.to_string())
+        },
+        testing_hostname::get_spare_hostname1(),
+    );
+    let token_uri = format!("http://{}/token", token_server.address());
+    let key = testing_service_account_key("error@testing.com", &token_uri);
+    let http_client = Arc::new(HttpClient::new().unwrap());
+
+    let result = exhaust_future(google_oauth::access_token(&key, SCOPE, &http_client));
+    match result {
+        Err(Error(ErrorKind::UnexpectedResponseFormat(_), _)) => {
+            // Ok
+        }
+        _ => panic!("Unexpected result: {:?}", result),
+    }
+}
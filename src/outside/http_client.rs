@@ -23,6 +23,8 @@ pub enum RequestMethod {
 pub struct Response {
     pub body: String,
     pub status_code: u16,
+    /// Names of headers are in lower case
+    pub headers: HashMap<String, String>,
 }
 
 pub struct HttpClient {
@@ -61,6 +63,12 @@ impl HttpClient {
     async fn transform_response(response: ResponseFuture) -> Result<Response, Error> {
         let response = response.await?;
         let status_code = response.status().as_u16();
+        let mut headers = HashMap::new();
+        for (key, val) in response.headers() {
+            if let Ok(val) = val.to_str() {
+                headers.insert(key.as_str().to_owned(), val.to_owned());
+            }
+        }
 
         let mut bytes = Vec::new();
         let mut body_stream = response.into_body();
@@ -69,7 +77,11 @@ impl HttpClient {
         }
 
         let body = String::from_utf8_lossy(&bytes).to_string();
-        Ok(Response { body, status_code })
+        Ok(Response {
+            body,
+            status_code,
+            headers,
+        })
     }
 
     fn create_request_obj(
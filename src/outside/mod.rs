@@ -1,5 +1,7 @@
 pub mod error;
 pub mod fcm;
+pub mod fcm_v1;
+pub mod google_oauth;
 pub mod gp;
 pub mod http_client;
 pub mod vk;
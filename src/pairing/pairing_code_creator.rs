@@ -12,6 +12,7 @@ use crate::db::core::pairing_code_range;
 use crate::db::core::taken_pairing_code;
 use crate::db::core::taken_pairing_code::TakenPairingCode;
 use crate::db::core::transaction;
+use crate::metrics;
 
 use crate::utils::now_source::DefaultNowSource;
 use crate::utils::now_source::NowSource;
@@ -118,7 +119,7 @@ where
         user: &AppUser,
         connection: &dyn DBConnection,
     ) -> Result<String, Error> {
-        transaction::start(connection, || {
+        let code = transaction::start(connection, || {
             let res = self.borrow_pairing_code_impl(&user, connection);
             error!("Data corruption detected in |borrow_pairing_code|");
             match &res {
@@ -128,7 +129,9 @@ where
                 }
                 _ => res,
             }
-        })
+        })?;
+        self.report_occupancy(connection);
+        Ok(code)
     }
 }
 
@@ -436,6 +439,19 @@ where
         Ok(())
     }
 
+    /// Codes are freed only when new codes are borrowed, so it's the only time
+    /// when the occupancy needs to be reported.
+    fn report_occupancy(&self, connection: &dyn DBConnection) {
+        let capacity = (self.codes_range_right - self.codes_range_left + 1) as i64;
+        match taken_pairing_code::count_family(&self.family, connection) {
+            Ok(taken) => metrics::set_pairing_codes_occupancy(&self.family, taken, capacity),
+            Err(err) => error!(
+                "Couldn't count taken pairing codes of {}: {}",
+                self.family, err
+            ),
+        }
+    }
+
     fn format_generated_code(&self, generated_code: i32) -> String {
         let required_digits_count = self.codes_range_right.to_string().len();
         let result_short = generated_code.to_string();
@@ -9,6 +9,7 @@ use crate::db::core::pairing_code_range;
 use crate::db::core::taken_pairing_code;
 use crate::db::core::testing_util as dbtesting_utils;
 use crate::db::core::util::delete_app_user;
+use crate::metrics;
 
 use crate::pairing::error::Error;
 use crate::pairing::error::ErrorKind::InvalidBoundsError;
@@ -1229,3 +1230,32 @@ fn immediate_code_generation_for_same_user() {
     let _code1 = creator.borrow_pairing_code(&user, &conn).unwrap();
     let _code2 = creator.borrow_pairing_code(&user, &conn).unwrap();
 }
+
+#[test]
+fn occupancy_is_reported_to_metrics() {
+    let fam = format!("{}{}", file!(), line!());
+    delete_codes_with_family(&fam);
+    let uid1 = Uuid::from_str("00000000-0000-0000-0000-002222000044").unwrap();
+    let uid2 = Uuid::from_str("00000000-0000-0000-0000-002222000045").unwrap();
+    delete_user_with_uid(&uid1);
+    delete_user_with_uid(&uid2);
+    let conn = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user1 = create_user_with_uid(&uid1);
+    let user2 = create_user_with_uid(&uid2);
+
+    let creator = super::new(fam.to_owned(), 0, 10, 60 * 4).unwrap();
+    creator.borrow_pairing_code(&user1, &conn).unwrap();
+    creator.borrow_pairing_code(&user2, &conn).unwrap();
+
+    let metrics = metrics::gather();
+    let taken = format!(
+        "recipe_calculator_pairing_codes_taken{{family=\"{}\"}} 2",
+        fam
+    );
+    let capacity = format!(
+        "recipe_calculator_pairing_codes_capacity{{family=\"{}\"}} 11",
+        fam
+    );
+    assert!(metrics.contains(&taken), "{}", metrics);
+    assert!(metrics.contains(&capacity), "{}", metrics);
+}
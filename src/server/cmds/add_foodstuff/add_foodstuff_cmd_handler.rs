@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::error::Error as DBError;
+use crate::db::core::error::ErrorKind as DBErrorKind;
+use crate::db::core::foodstuff;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::cmds::utils::parse_nutrient_or_request_error;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+#[derive(Default)]
+pub struct AddFoodstuffCmdHandler {}
+
+#[derive(Deserialize)]
+struct AddFoodstuffArgs {
+    foodstuff_id: i32,
+    foodstuff_name: String,
+    protein: String,
+    fats: String,
+    carbs: String,
+    calories: String,
+}
+
+impl CmdHandler for AddFoodstuffCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl AddFoodstuffCmdHandler {
+    pub fn new() -> Self {
+        AddFoodstuffCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: AddFoodstuffArgs = parse_args(&args)?;
+        let app_user_foodstuff_id = cmd_args.foodstuff_id;
+        let name = cmd_args.foodstuff_name;
+        let protein = parse_nutrient_or_request_error(constants::ARG_PROTEIN, &cmd_args.protein)?;
+        let fats = parse_nutrient_or_request_error(constants::ARG_FATS, &cmd_args.fats)?;
+        let carbs = parse_nutrient_or_request_error(constants::ARG_CARBS, &cmd_args.carbs)?;
+        let calories =
+            parse_nutrient_or_request_error(constants::ARG_CALORIES, &cmd_args.calories)?;
+
+        let new_foodstuff = foodstuff::new(
+            &user,
+            app_user_foodstuff_id,
+            name,
+            protein,
+            fats,
+            carbs,
+            calories,
+            true,
+        );
+        db_transaction(&connection, || {
+            foodstuff::lock_for_modification(user.id(), &connection)?;
+            foodstuff::insert(new_foodstuff, &connection).map_err(extract_duplication_error)
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+fn extract_duplication_error(db_error: DBError) -> RequestError {
+    match db_error {
+        DBError(DBErrorKind::UniqueViolation(_), _) => RequestError::new(
+            constants::FIELD_STATUS_FOODSTUFF_DUPLICATION.to_owned(),
+            "Foodstuff with given ID already exists".to_owned(),
+        ),
+        error => error.into(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./add_foodstuff_cmd_handler_test.rs"]
+mod add_foodstuff_cmd_handler_test;
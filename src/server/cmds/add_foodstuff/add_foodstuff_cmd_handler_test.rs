@@ -0,0 +1,344 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::foodstuff;
+use crate::db::core::testing_util::testing_connection_for_server_user;
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::add_foodstuff_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::make_request_with_json_body;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+#[test]
+fn add_foodstuff_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "0.4",
+        "0.1",
+        "9.8",
+        "47",
+    );
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    let foodstuff = foodstuff::select_by_app_user_foodstuff_id(user.id(), 1, &conn)
+        .unwrap()
+        .unwrap();
+    assert_eq!("apple", foodstuff.name());
+    assert_eq!(400_000, foodstuff.protein());
+    assert_eq!(100_000, foodstuff.fats());
+    assert_eq!(9_800_000, foodstuff.carbs());
+    assert_eq!(47_000_000, foodstuff.calories());
+    assert!(foodstuff.is_listed());
+}
+
+#[test]
+fn foodstuff_id_duplication() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let response = add_foodstuff_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_DUPLICATION);
+}
+
+#[test]
+fn same_foodstuff_id_of_different_users_is_not_duplication() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f200-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f200-0000-0000-000000000003").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+}
+
+#[test]
+fn invalid_nutrient_value() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_ADD_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        uid,
+        &constants::ARG_CLIENT_TOKEN,
+        client_token,
+        &constants::ARG_FOODSTUFF_ID,
+        1,
+        &constants::ARG_FOODSTUFF_NAME,
+        "apple",
+        &constants::ARG_PROTEIN,
+        "alot",
+        &constants::ARG_FATS,
+        2,
+        &constants::ARG_CARBS,
+        3,
+        &constants::ARG_CALORIES,
+        4,
+    );
+    let response = make_request(&url);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
+
+#[test]
+fn lack_of_foodstuff_id() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000005").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_ADD_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        uid,
+        &constants::ARG_CLIENT_TOKEN,
+        client_token,
+        &constants::ARG_FOODSTUFF_NAME,
+        "apple",
+        &constants::ARG_PROTEIN,
+        1,
+        &constants::ARG_FATS,
+        2,
+        &constants::ARG_CARBS,
+        3,
+        &constants::ARG_CALORIES,
+        4,
+    );
+    let response = make_request(&url);
+    assert_status(&response, constants::FIELD_STATUS_PARAM_MISSING);
+}
+
+#[test]
+fn negative_nutrient_value() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000006").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = add_foodstuff_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "1",
+        "-2",
+        "3",
+        "4",
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
+
+#[test]
+fn too_big_nutrient_value() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000007").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    // Max nutrient value is 2147.483647 - max i32 divided by the fixed-point scale
+    let response = add_foodstuff_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "2147.483648",
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
+
+#[test]
+fn add_foodstuff_with_args_in_json_body() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000008").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let url = format!(
+        "http://{}{}",
+        server.address(),
+        constants::CMD_ADD_FOODSTUFF
+    );
+    let response = make_request_with_json_body(
+        &url,
+        &json!({
+            constants::ARG_USER_ID: uid.to_string(),
+            constants::ARG_CLIENT_TOKEN: client_token,
+            constants::ARG_FOODSTUFF_ID: 1,
+            constants::ARG_FOODSTUFF_NAME: "apple",
+            constants::ARG_PROTEIN: 0.4,
+            constants::ARG_FATS: "0.1",
+            constants::ARG_CARBS: "9.8",
+            constants::ARG_CALORIES: 47,
+        }),
+    );
+    assert_status(&response, constants::FIELD_STATUS_OK);
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    let foodstuff = foodstuff::select_by_app_user_foodstuff_id(user.id(), 1, &conn)
+        .unwrap()
+        .unwrap();
+    assert_eq!("apple", foodstuff.name());
+    assert_eq!(400_000, foodstuff.protein());
+    assert_eq!(100_000, foodstuff.fats());
+    assert_eq!(9_800_000, foodstuff.carbs());
+    assert_eq!(47_000_000, foodstuff.calories());
+}
+
+#[test]
+fn json_body_args_conflicting_with_query_args() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-000000000009").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}=1",
+        server.address(),
+        constants::CMD_ADD_FOODSTUFF,
+        constants::ARG_USER_ID,
+        uid,
+        constants::ARG_CLIENT_TOKEN,
+        client_token,
+        constants::ARG_FOODSTUFF_ID,
+    );
+    let mut body = json!({
+        constants::ARG_FOODSTUFF_ID: 2,
+        constants::ARG_FOODSTUFF_NAME: "apple",
+        constants::ARG_PROTEIN: "0.4",
+        constants::ARG_FATS: "0.1",
+        constants::ARG_CARBS: "9.8",
+        constants::ARG_CALORIES: "47",
+    });
+    let response = make_request_with_json_body(&url, &body);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+
+    // Same values in the query and the body are fine
+    body[constants::ARG_FOODSTUFF_ID] = json!(1);
+    let response = make_request_with_json_body(&url, &body);
+    assert_status(&response, constants::FIELD_STATUS_OK);
+}
+
+#[test]
+fn invalid_typed_arg_in_json_body() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f200-0000-0000-00000000000a").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let url = format!(
+        "http://{}{}",
+        server.address(),
+        constants::CMD_ADD_FOODSTUFF
+    );
+    let mut body = json!({
+        constants::ARG_USER_ID: uid.to_string(),
+        constants::ARG_CLIENT_TOKEN: client_token,
+        constants::ARG_FOODSTUFF_ID: "one",
+        constants::ARG_FOODSTUFF_NAME: "apple",
+        constants::ARG_PROTEIN: "0.4",
+        constants::ARG_FATS: "0.1",
+        constants::ARG_CARBS: "9.8",
+        constants::ARG_CALORIES: "47",
+    });
+    let response = make_request_with_json_body(&url, &body);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+
+    body.as_object_mut()
+        .unwrap()
+        .remove(constants::ARG_FOODSTUFF_ID);
+    let response = make_request_with_json_body(&url, &body);
+    assert_status(&response, constants::FIELD_STATUS_PARAM_MISSING);
+}
@@ -0,0 +1 @@
+pub mod add_foodstuff_cmd_handler;
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::error::Error as DBError;
+use crate::db::core::error::ErrorKind as DBErrorKind;
+use crate::db::core::foodstuff;
+use crate::db::core::history_entry;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+#[derive(Default)]
+pub struct AddHistoryEntryCmdHandler {}
+
+#[derive(Deserialize)]
+struct AddHistoryEntryArgs {
+    history_entry_id: i32,
+    foodstuff_id: i32,
+    mass: i32,
+    time: i64,
+}
+
+impl CmdHandler for AddHistoryEntryCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl AddHistoryEntryCmdHandler {
+    pub fn new() -> Self {
+        AddHistoryEntryCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: AddHistoryEntryArgs = parse_args(&args)?;
+        let app_user_history_entry_id = cmd_args.history_entry_id;
+        let app_user_foodstuff_id = cmd_args.foodstuff_id;
+        let mass = cmd_args.mass;
+        let time = cmd_args.time;
+        if mass <= 0 {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                format!("Mass must be positive, got: {}", mass),
+            ));
+        }
+
+        // NOTE: unlisted foodstuffs are still valid for history entries -
+        // unlisting only hides a foodstuff from the foodstuffs list.
+        let foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+            user.id(),
+            app_user_foodstuff_id,
+            &connection,
+        )?;
+        let foodstuff = match foodstuff {
+            Some(foodstuff) => foodstuff,
+            None => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+                    format!("Foodstuff not found, ID: {}", app_user_foodstuff_id),
+                ))
+            }
+        };
+
+        let new_entry =
+            history_entry::new(&user, app_user_history_entry_id, &foodstuff, mass, time);
+        history_entry::insert(new_entry, &connection).map_err(extract_duplication_error)?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+fn extract_duplication_error(db_error: DBError) -> RequestError {
+    match db_error {
+        DBError(DBErrorKind::UniqueViolation(_), _) => RequestError::new(
+            constants::FIELD_STATUS_HISTORY_ENTRY_DUPLICATION.to_owned(),
+            "History entry with given ID already exists".to_owned(),
+        ),
+        error => error.into(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./add_history_entry_cmd_handler_test.rs"]
+mod add_history_entry_cmd_handler_test;
@@ -0,0 +1,158 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::add_history_entry;
+use crate::server::cmds::testing_cmds_utils::add_history_entry_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_history;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+#[test]
+fn add_history_entry_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f205-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_history_entry(server.address(), &client_token, &uid, 10, 1, 150, 1000);
+
+    let response = list_history(server.address(), &client_token, &uid, 0, 2000);
+    let expected_entries = json!([{
+        constants::FIELD_NAME_HISTORY_ENTRY_ID: 10,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_MASS: 150,
+        constants::FIELD_NAME_TIME: 1000,
+    }]);
+    assert_eq!(
+        expected_entries,
+        response[constants::FIELD_NAME_HISTORY_ENTRIES]
+    );
+}
+
+#[test]
+fn history_entry_id_duplication() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f205-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_history_entry(server.address(), &client_token, &uid, 10, 1, 150, 1000);
+    let response =
+        add_history_entry_without_ok_check(server.address(), &client_token, &uid, 10, 1, 200, 2000);
+    assert_status(&response, constants::FIELD_STATUS_HISTORY_ENTRY_DUPLICATION);
+}
+
+#[test]
+fn history_entry_with_not_existing_foodstuff() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f205-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = add_history_entry_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        10,
+        1,
+        150,
+        1000,
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
+
+#[test]
+fn history_entry_with_foodstuff_of_other_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f205-0000-0000-000000000003").unwrap();
+    let uid2 = Uuid::from_str("00000000-f205-0000-0000-000000000004").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let response = add_history_entry_without_ok_check(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        10,
+        1,
+        150,
+        1000,
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
+
+#[test]
+fn invalid_mass() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f205-0000-0000-000000000005").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let response =
+        add_history_entry_without_ok_check(server.address(), &client_token, &uid, 10, 1, 0, 1000);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
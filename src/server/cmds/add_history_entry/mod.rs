@@ -0,0 +1 @@
+pub mod add_history_entry_cmd_handler;
@@ -0,0 +1,197 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::shopping_list_item;
+use crate::db::core::shopping_list_share;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::outside::fcm::FCM_ADDR;
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::shopping_list_utils::select_accessible_shopping_list;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::notification_outbox;
+use crate::server::request_error::RequestError;
+
+/// Checks or unchecks an item of a shopping list either of the user or shared with the user.
+/// Everyone else who has access to the list (its owner and partners it's shared with)
+/// is notified about the change, so that the list would stay in sync on all devices.
+pub struct CheckShoppingListItemCmdHandler {
+    fcm_address: String,
+}
+
+#[derive(Deserialize)]
+struct CheckShoppingListItemArgs {
+    owner_user_id: String,
+    shopping_list_id: i32,
+    item_id: i32,
+    is_checked: bool,
+}
+
+impl CmdHandler for CheckShoppingListItemCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+            self.fcm_address.clone(),
+        ))
+    }
+}
+
+impl CheckShoppingListItemCmdHandler {
+    pub fn new(overrides: &JsonValue) -> Self {
+        let args = get_construction_args(overrides);
+        CheckShoppingListItemCmdHandler {
+            fcm_address: args.fcm_address,
+        }
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+        fcm_address: String,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: CheckShoppingListItemArgs = parse_args(&args)?;
+        let owner_uid = cmd_args.owner_user_id;
+        let app_user_shopping_list_id = cmd_args.shopping_list_id;
+        let item_id = cmd_args.item_id;
+        let is_checked = cmd_args.is_checked;
+
+        let (shopping_list, owner) = select_accessible_shopping_list(
+            &user,
+            &owner_uid,
+            app_user_shopping_list_id,
+            &connection,
+        )?;
+
+        let mut users_to_notify = Vec::new();
+        if owner.id() != user.id() {
+            users_to_notify.push(owner);
+        }
+        for share in
+            shopping_list_share::select_by_shopping_list_id(shopping_list.id(), &connection)?
+        {
+            if share.partner_user_id() == user.id() {
+                continue;
+            }
+            if let Some(partner) = app_user::select_by_id(share.partner_user_id(), &connection)? {
+                users_to_notify.push(partner);
+            }
+        }
+
+        let json = json!({
+            constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_SHOPPING_LIST_ITEM_CHECKED,
+            constants::SERV_FIELD_PARTNER_USER_ID: user.uid(),
+            constants::SERV_FIELD_PARTNER_NAME: user.name(),
+            constants::SERV_FIELD_OWNER_USER_ID: owner_uid,
+            constants::SERV_FIELD_SHOPPING_LIST_ID: app_user_shopping_list_id,
+            constants::SERV_FIELD_ITEM_ID: item_id,
+            constants::SERV_FIELD_IS_CHECKED: is_checked,
+        });
+        let notification_ids = db_transaction(&connection, || {
+            let item = shopping_list_item::update_is_checked(
+                shopping_list.id(),
+                item_id,
+                is_checked,
+                &connection,
+            )?;
+            if item.is_none() {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_SHOPPING_LIST_ITEM_NOT_FOUND.to_owned(),
+                    format!("Shopping list item not found, ID: {}", item_id),
+                ));
+            }
+            let mut notification_ids = Vec::new();
+            for user_to_notify in &users_to_notify {
+                notification_ids.extend(notification_outbox::enqueue(
+                    user_to_notify,
+                    json.to_string(),
+                    &connection,
+                )?);
+            }
+            Ok(notification_ids)
+        })?;
+        // NOTE: we don't use the '?' operator on the send result - we want to respond
+        // with OK status to our client even if notifications sending will fail
+        let _notif_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
+            connections_pool,
+            &config,
+            &fcm_address,
+            http_client,
+        )
+        .await;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+        }))
+    }
+}
+
+#[cfg(test)]
+pub fn insert_construction_overrides(overrides: &mut JsonValue, fcm_address: String) {
+    let overrides = overrides
+        .as_object_mut()
+        .expect("Can insert only into object");
+    overrides.insert("check_shopping_list_item_overrides".to_owned(), json!({}));
+    let overrides = overrides["check_shopping_list_item_overrides"]
+        .as_object_mut()
+        .unwrap();
+    overrides.insert("fcm_address_override".to_owned(), json!(fcm_address));
+}
+
+struct ConstructionArgs {
+    fcm_address: String,
+}
+
+fn get_construction_args(overrides: &JsonValue) -> ConstructionArgs {
+    if let Some(overrides) = extract_construction_overrides(overrides) {
+        overrides
+    } else {
+        ConstructionArgs {
+            fcm_address: FCM_ADDR.to_owned(),
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn extract_construction_overrides(_overrides: &JsonValue) -> Option<ConstructionArgs> {
+    None
+}
+
+#[cfg(test)]
+fn extract_construction_overrides(overrides: &JsonValue) -> Option<ConstructionArgs> {
+    match &overrides["check_shopping_list_item_overrides"].as_object() {
+        Some(overrides) => Some(ConstructionArgs {
+            fcm_address: overrides["fcm_address_override"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        }),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./check_shopping_list_item_cmd_handler_test.rs"]
+mod check_shopping_list_item_cmd_handler_test;
@@ -0,0 +1,217 @@
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+
+use crate::server::cmds::check_shopping_list_item::check_shopping_list_item_cmd_handler::insert_construction_overrides;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::check_shopping_list_item;
+use crate::server::cmds::testing_cmds_utils::check_shopping_list_item_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shopping_lists;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+
+/// Registers and pairs 2 users, creates a shopping list of the first user
+/// with 2 items and shares it with the second one.
+fn set_up_shared_list(server_addr: &str, uid1: &Uuid, uid2: &Uuid) -> (String, String) {
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(uid1);
+    delete_app_user_with(uid2);
+
+    let client_token1 = register_named_user_return_token(server_addr, uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server_addr, uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(server_addr, &client_token1, &uid1, &client_token2, &uid2);
+    add_foodstuff(
+        server_addr,
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server_addr,
+        &client_token1,
+        &uid1,
+        2,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_shopping_list(
+        server_addr,
+        &client_token1,
+        &uid1,
+        1,
+        "list",
+        json!({ "foodstuffs": [
+            {"foodstuff_id": 1, "weight": 100},
+            {"foodstuff_id": 2, "weight": 200},
+        ]}),
+    );
+    share_shopping_list(server_addr, &client_token1, &uid1, 1, &uid2);
+    (client_token1, client_token2)
+}
+
+fn checked_items(response: &JsonValue) -> Vec<bool> {
+    response[constants::FIELD_NAME_SHOPPING_LISTS][0][constants::FIELD_NAME_ITEMS]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item[constants::FIELD_NAME_IS_CHECKED].as_bool().unwrap())
+        .collect()
+}
+
+#[test]
+fn checked_items_are_synced_between_partners() {
+    let r = |_request: &FullRequest| {
+        let response = r#"
+        {
+            "multicast_id":2513734409441993719,
+            "success":1,
+            "failure":0,
+            "canonical_ids":0,
+            "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd"}]
+        }"#;
+        Some(response.to_owned())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    let server = start_server!(|overrides: &mut JsonValue| insert_construction_overrides(
+        overrides,
+        fcm_addr.clone()
+    ));
+
+    let uid1 = Uuid::from_str("00000000-f21a-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21a-0000-0000-000000000001").unwrap();
+    let fcm_token1 = format!("{}{}", uid1, "fcmtoken1");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    let (client_token1, client_token2) = set_up_shared_list(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    set_user_fcm_token(server.address(), &client_token1, &uid1, &fcm_token1);
+    set_user_fcm_token(server.address(), &client_token2, &uid2, &fcm_token2);
+
+    // The owner checks an item - the partner is notified
+    check_shopping_list_item(server.address(), &client_token1, &uid1, &uid1, 1, 2, true);
+    let response = list_shopping_lists(server.address(), &client_token2, &uid2);
+    assert_eq!(vec![false, true], checked_items(&response));
+
+    // The partner checks an item - the owner is notified
+    check_shopping_list_item(server.address(), &client_token2, &uid2, &uid1, 1, 1, true);
+    let response = list_shopping_lists(server.address(), &client_token1, &uid1);
+    assert_eq!(vec![true, true], checked_items(&response));
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let fcm_requests: Vec<JsonValue> = fcm_requests
+        .iter()
+        .map(|req| serde_json::from_str(&req.body).unwrap())
+        .collect();
+    assert_eq!(2, fcm_requests.len());
+    assert_eq!(fcm_requests[0]["to"], json!(fcm_token2));
+    assert_eq!(fcm_requests[1]["to"], json!(fcm_token1));
+    let data = &fcm_requests[1]["data"];
+    assert_eq!(
+        &data[constants::SERV_FIELD_MSG_TYPE],
+        constants::SERV_MSG_SHOPPING_LIST_ITEM_CHECKED,
+    );
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_USER_ID], &uid2);
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_NAME], "name2");
+    assert_eq!(&data[constants::SERV_FIELD_OWNER_USER_ID], &uid1);
+    assert_eq!(&data[constants::SERV_FIELD_SHOPPING_LIST_ID], 1);
+    assert_eq!(&data[constants::SERV_FIELD_ITEM_ID], 1);
+    assert_eq!(&data[constants::SERV_FIELD_IS_CHECKED], true);
+}
+
+#[test]
+fn unchecking_item() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f21a-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21a-0000-0000-000000000003").unwrap();
+    let (client_token1, _client_token2) = set_up_shared_list(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+
+    check_shopping_list_item(server.address(), &client_token1, &uid1, &uid1, 1, 1, true);
+    check_shopping_list_item(server.address(), &client_token1, &uid1, &uid1, 1, 1, false);
+    let response = list_shopping_lists(server.address(), &client_token1, &uid1);
+    assert_eq!(vec![false, false], checked_items(&response));
+}
+
+#[test]
+fn cannot_check_item_of_not_shared_list() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f21a-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21a-0000-0000-000000000005").unwrap();
+    let uid3 = Uuid::from_str("00000000-f21a-0000-0000-000000000006").unwrap();
+    let gpuid3 = format!("{}{}", uid3, "gpuid");
+    delete_app_user_with(&uid3);
+    let (client_token1, _client_token2) = set_up_shared_list(server.address(), &uid1, &uid2);
+    let client_token3 = register_named_user_return_token(server.address(), &uid3, &gpuid3, "name3");
+    let uid1 = uid1.to_string();
+    let uid3 = uid3.to_string();
+
+    let response = check_shopping_list_item_without_ok_check(
+        server.address(),
+        &client_token3,
+        &uid3,
+        &uid1,
+        1,
+        1,
+        true,
+    );
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND);
+
+    let response = list_shopping_lists(server.address(), &client_token1, &uid1);
+    assert_eq!(vec![false, false], checked_items(&response));
+}
+
+#[test]
+fn not_existing_item() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f21a-0000-0000-000000000007").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21a-0000-0000-000000000008").unwrap();
+    let (client_token1, _client_token2) = set_up_shared_list(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+
+    let response = check_shopping_list_item_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid1,
+        1,
+        3,
+        true,
+    );
+    assert_status(
+        &response,
+        constants::FIELD_STATUS_SHOPPING_LIST_ITEM_NOT_FOUND,
+    );
+    let response = check_shopping_list_item_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid1,
+        2,
+        1,
+        true,
+    );
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND);
+}
@@ -0,0 +1 @@
+pub mod check_shopping_list_item_cmd_handler;
@@ -0,0 +1,283 @@
+use serde::de;
+use serde::de::value::MapDeserializer;
+use serde::de::DeserializeOwned;
+use serde::de::IntoDeserializer;
+use serde::de::Visitor;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+// NOTE: hyper gives names of headers in lower case
+const AUTHORIZATION_HEADER: &str = "authorization";
+const BEARER_SCHEME: &str = "Bearer ";
+
+/// Deserializes typed arguments of a command, e.g.:
+/// #[derive(Deserialize)]
+/// struct Args { foodstuff_id: i32, foodstuff_name: String, comment: Option<String> }
+/// Args are strings (see |merge_json_body_args|), so numbers and booleans are parsed from
+/// their string representations.
+/// A missing non-optional arg is reported with the FIELD_STATUS_PARAM_MISSING status,
+/// an arg which cannot be parsed - with the FIELD_STATUS_INVALID_QUERY status.
+/// Args not mentioned in the struct are ignored.
+#[allow(clippy::implicit_hasher)]
+pub fn parse_args<T: DeserializeOwned>(args: &HashMap<String, String>) -> Result<T, RequestError> {
+    let deserializer = MapDeserializer::new(args.iter().map(|(key, value)| {
+        (
+            key.as_str(),
+            ArgDeserializer {
+                key: key.as_str(),
+                value: value.as_str(),
+            },
+        )
+    }));
+    T::deserialize(deserializer).map_err(|err| match err {
+        ArgsError::MissingParam(key) => RequestError::new(
+            constants::FIELD_STATUS_PARAM_MISSING.to_owned(),
+            format!("No param '{}' in query", key),
+        ),
+        ArgsError::InvalidParam(descr) => {
+            RequestError::new(constants::FIELD_STATUS_INVALID_QUERY.to_owned(), descr)
+        }
+    })
+}
+
+/// Puts top-level fields of a JSON object body into |args|, so that commands could
+/// be called with args in the body instead of the query.
+/// Strings, numbers and booleans become args, nulls are skipped.
+/// Arrays and objects are skipped too - they are payloads which commands
+/// parse from the body themselves.
+/// An arg present both in the query and in the body must have the same value in both.
+#[allow(clippy::implicit_hasher)]
+pub fn merge_json_body_args(
+    args: &mut HashMap<String, String>,
+    body: &str,
+) -> Result<(), RequestError> {
+    if body.trim().is_empty() {
+        return Ok(());
+    }
+    let body: JsonValue = serde_json::from_str(body).map_err(|err| {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Body is not a valid JSON: {}", err),
+        )
+    })?;
+    let body = match body {
+        JsonValue::Object(body) => body,
+        _ => {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                format!("Body is not a JSON object: {}", body),
+            ))
+        }
+    };
+
+    for (key, value) in body {
+        let value = match value {
+            JsonValue::String(value) => value,
+            JsonValue::Number(value) => value.to_string(),
+            JsonValue::Bool(value) => value.to_string(),
+            JsonValue::Null | JsonValue::Array(_) | JsonValue::Object(_) => continue,
+        };
+        insert_arg(args, key, value, "body")?;
+    }
+    Ok(())
+}
+
+/// Puts user credentials from an 'Authorization: Bearer <user_id>:<client_token>' header
+/// into |args|, so that they are checked by |extract_user_from_query_args| the same way
+/// as credentials from the query.
+/// Nothing is put when there's no header.
+#[allow(clippy::implicit_hasher)]
+pub fn merge_authorization_header_args(
+    args: &mut HashMap<String, String>,
+    headers: &HashMap<String, String>,
+) -> Result<(), RequestError> {
+    let authorization = match headers.get(AUTHORIZATION_HEADER) {
+        Some(authorization) => authorization.trim(),
+        None => return Ok(()),
+    };
+    let invalid = || {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_AUTHORIZATION.to_owned(),
+            format!(
+                "Authorization header must be 'Bearer <user_id>:<client_token>', got: {}",
+                authorization
+            ),
+        )
+    };
+
+    // Auth scheme is case-insensitive
+    let credentials = match authorization.get(..BEARER_SCHEME.len()) {
+        Some(scheme) if scheme.eq_ignore_ascii_case(BEARER_SCHEME) => {
+            authorization[BEARER_SCHEME.len()..].trim()
+        }
+        _ => return Err(invalid()),
+    };
+    let mut credentials = credentials.splitn(2, ':');
+    let (user_id, client_token) = match (credentials.next(), credentials.next()) {
+        (Some(user_id), Some(client_token)) if !user_id.is_empty() && !client_token.is_empty() => {
+            (user_id, client_token)
+        }
+        _ => return Err(invalid()),
+    };
+
+    insert_arg(
+        args,
+        constants::ARG_USER_ID.to_owned(),
+        user_id.to_owned(),
+        "Authorization header",
+    )?;
+    insert_arg(
+        args,
+        constants::ARG_CLIENT_TOKEN.to_owned(),
+        client_token.to_owned(),
+        "Authorization header",
+    )
+}
+
+/// Used when the server is configured to accept user credentials only in the Authorization
+/// header or in a body, because URLs with queries tend to end up in all kinds of logs.
+#[allow(clippy::implicit_hasher)]
+pub fn check_no_credentials_in_query(
+    query_args: &HashMap<String, String>,
+) -> Result<(), RequestError> {
+    for key in &[constants::ARG_USER_ID, constants::ARG_CLIENT_TOKEN] {
+        if query_args.contains_key(*key) {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_AUTHORIZATION.to_owned(),
+                format!(
+                    "Param '{}' is not accepted in query, use the Authorization header",
+                    key
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Inserts an arg from a |source| other than the query. If the arg is already present,
+/// the values must be the same.
+fn insert_arg(
+    args: &mut HashMap<String, String>,
+    key: String,
+    value: String,
+    source: &str,
+) -> Result<(), RequestError> {
+    match args.get(&key) {
+        Some(present_value) if *present_value != value => Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!(
+                "Param '{}' has different values in query and {}: {}, {}",
+                key, source, present_value, value
+            ),
+        )),
+        _ => {
+            args.insert(key, value);
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ArgsError {
+    MissingParam(String),
+    InvalidParam(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgsError::MissingParam(key) => write!(f, "No param '{}'", key),
+            ArgsError::InvalidParam(descr) => write!(f, "{}", descr),
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+impl de::Error for ArgsError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ArgsError::InvalidParam(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        ArgsError::MissingParam(field.to_owned())
+    }
+}
+
+/// Deserializer of a single arg, parses the arg's string when a number or a boolean is expected.
+struct ArgDeserializer<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a> ArgDeserializer<'a> {
+    fn parse<T>(&self, type_name: &str) -> Result<T, ArgsError>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        self.value.parse::<T>().map_err(|err| {
+            ArgsError::InvalidParam(format!(
+                "Param '{}' is not a valid {}: {}, err: {}",
+                self.key, type_name, self.value, err
+            ))
+        })
+    }
+}
+
+impl<'de, 'a> IntoDeserializer<'de, ArgsError> for ArgDeserializer<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident, $type_name:expr;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ArgsError> {
+                visitor.$visit(self.parse($type_name)?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ArgDeserializer<'a> {
+    type Error = ArgsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ArgsError> {
+        visitor.visit_str(self.value)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ArgsError> {
+        visitor.visit_some(self)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool, "boolean";
+        deserialize_i8 => visit_i8, "integer";
+        deserialize_i16 => visit_i16, "integer";
+        deserialize_i32 => visit_i32, "integer";
+        deserialize_i64 => visit_i64, "integer";
+        deserialize_u8 => visit_u8, "integer";
+        deserialize_u16 => visit_u16, "integer";
+        deserialize_u32 => visit_u32, "integer";
+        deserialize_u64 => visit_u64, "integer";
+        deserialize_f32 => visit_f32, "number";
+        deserialize_f64 => visit_f64, "number";
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct newtype_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+#[path = "./cmd_args_test.rs"]
+mod cmd_args_test;
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use super::check_no_credentials_in_query;
+use super::merge_authorization_header_args;
+use super::merge_json_body_args;
+use super::parse_args;
+use crate::server::constants;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Args {
+    id: i32,
+    time: i64,
+    name: String,
+    is_checked: bool,
+    comment: Option<String>,
+}
+
+fn args_of(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn typed_args_parsing() {
+    let args = args_of(&[
+        ("id", "1"),
+        ("time", "-123"),
+        ("name", "apple"),
+        ("is_checked", "true"),
+        ("comment", "tasty"),
+        ("unknown", "value"),
+    ]);
+    let expected = Args {
+        id: 1,
+        time: -123,
+        name: "apple".to_owned(),
+        is_checked: true,
+        comment: Some("tasty".to_owned()),
+    };
+    assert_eq!(expected, parse_args::<Args>(&args).unwrap());
+}
+
+#[test]
+fn optional_arg_can_be_missing() {
+    let args = args_of(&[
+        ("id", "1"),
+        ("time", "123"),
+        ("name", "apple"),
+        ("is_checked", "false"),
+    ]);
+    assert_eq!(None, parse_args::<Args>(&args).unwrap().comment);
+}
+
+#[test]
+fn missing_arg() {
+    let args = args_of(&[("id", "1"), ("name", "apple"), ("is_checked", "false")]);
+    let err = parse_args::<Args>(&args).unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_PARAM_MISSING, err.status());
+    assert!(err.error_description().contains("time"));
+}
+
+#[test]
+fn invalid_args() {
+    let args = args_of(&[
+        ("id", "one"),
+        ("time", "123"),
+        ("name", "apple"),
+        ("is_checked", "false"),
+    ]);
+    let err = parse_args::<Args>(&args).unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_QUERY, err.status());
+    assert!(err.error_description().contains("id"));
+
+    let args = args_of(&[
+        ("id", "1"),
+        ("time", "123"),
+        ("name", "apple"),
+        ("is_checked", "yes"),
+    ]);
+    let err = parse_args::<Args>(&args).unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_QUERY, err.status());
+    assert!(err.error_description().contains("is_checked"));
+}
+
+#[test]
+fn json_body_args_merging() {
+    let mut args = args_of(&[("id", "1")]);
+    let body = r#"{
+        "id": 1,
+        "time": 123,
+        "name": "apple",
+        "is_checked": true,
+        "comment": null,
+        "ingredients": [{"foodstuff_id": 1, "weight": 100}],
+        "sources": {}
+    }"#;
+    merge_json_body_args(&mut args, body).unwrap();
+    let expected = args_of(&[
+        ("id", "1"),
+        ("time", "123"),
+        ("name", "apple"),
+        ("is_checked", "true"),
+    ]);
+    assert_eq!(expected, args);
+}
+
+#[test]
+fn empty_body_args_merging() {
+    let mut args = args_of(&[("id", "1")]);
+    merge_json_body_args(&mut args, "").unwrap();
+    assert_eq!(args_of(&[("id", "1")]), args);
+}
+
+#[test]
+fn invalid_body_args_merging() {
+    let mut args = args_of(&[("id", "1")]);
+    let err = merge_json_body_args(&mut args, r#"{"id": 2}"#).unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_QUERY, err.status());
+
+    let err = merge_json_body_args(&mut args, "[1, 2]").unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_QUERY, err.status());
+
+    let err = merge_json_body_args(&mut args, "{").unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_QUERY, err.status());
+}
+
+fn headers_of(authorization: &str) -> HashMap<String, String> {
+    args_of(&[("authorization", authorization)])
+}
+
+#[test]
+fn authorization_header_args() {
+    let mut args = args_of(&[("name", "apple")]);
+    merge_authorization_header_args(&mut args, &headers_of("Bearer uid:token")).unwrap();
+    let expected = args_of(&[
+        ("name", "apple"),
+        (constants::ARG_USER_ID, "uid"),
+        (constants::ARG_CLIENT_TOKEN, "token"),
+    ]);
+    assert_eq!(expected, args);
+
+    // Scheme is case-insensitive
+    let mut args = HashMap::new();
+    merge_authorization_header_args(&mut args, &headers_of("bearer uid:token")).unwrap();
+    assert_eq!("token", args[constants::ARG_CLIENT_TOKEN]);
+}
+
+#[test]
+fn no_authorization_header() {
+    let mut args = args_of(&[("name", "apple")]);
+    merge_authorization_header_args(&mut args, &HashMap::new()).unwrap();
+    assert_eq!(args_of(&[("name", "apple")]), args);
+}
+
+#[test]
+fn invalid_authorization_header() {
+    for value in &[
+        "Basic uid:token",
+        "Bearer uid",
+        "Bearer :token",
+        "Bearer",
+        "Беарер uid:token",
+    ] {
+        let mut args = HashMap::new();
+        let err = merge_authorization_header_args(&mut args, &headers_of(value)).unwrap_err();
+        assert_eq!(constants::FIELD_STATUS_INVALID_AUTHORIZATION, err.status());
+    }
+}
+
+#[test]
+fn authorization_header_conflicting_with_query() {
+    let mut args = args_of(&[(constants::ARG_USER_ID, "uid2")]);
+    let err =
+        merge_authorization_header_args(&mut args, &headers_of("Bearer uid:token")).unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_QUERY, err.status());
+}
+
+#[test]
+fn credentials_in_query_check() {
+    assert!(check_no_credentials_in_query(&args_of(&[("name", "apple")])).is_ok());
+    let err = check_no_credentials_in_query(&args_of(&[(constants::ARG_CLIENT_TOKEN, "token")]))
+        .unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_INVALID_AUTHORIZATION, err.status());
+}
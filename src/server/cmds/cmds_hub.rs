@@ -2,24 +2,51 @@ use futures::future::err;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::config::Config;
 use crate::db::pool::connection_pool::{BorrowedDBConnection, ConnectionPool};
+use crate::metrics;
 use crate::outside::http_client::HttpClient;
 use crate::server::cmds::cmd_handler::CmdHandleResultFuture;
 use crate::server::constants;
 use crate::server::error::Error;
 use crate::server::request_error::RequestError;
 
+use super::add_foodstuff::add_foodstuff_cmd_handler::AddFoodstuffCmdHandler;
+use super::add_history_entry::add_history_entry_cmd_handler::AddHistoryEntryCmdHandler;
+use super::check_shopping_list_item::check_shopping_list_item_cmd_handler::CheckShoppingListItemCmdHandler;
 use super::cmd_handler::CmdHandler;
+use super::create_recipe::create_recipe_cmd_handler::CreateRecipeCmdHandler;
+use super::create_shopping_list::create_shopping_list_cmd_handler::CreateShoppingListCmdHandler;
+use super::delete_account::delete_account_cmd_handler::DeleteAccountCmdHandler;
+use super::delete_history_entry::delete_history_entry_cmd_handler::DeleteHistoryEntryCmdHandler;
+use super::delete_recipe::delete_recipe_cmd_handler::DeleteRecipeCmdHandler;
+use super::delete_shopping_list::delete_shopping_list_cmd_handler::DeleteShoppingListCmdHandler;
 use super::direct_partner_msg::direct_partner_msg_cmd_handler::DirectPartnerMsgCmdHandler;
+use super::edit_recipe::edit_recipe_cmd_handler::EditRecipeCmdHandler;
+use super::export_data::export_data_cmd_handler::ExportDataCmdHandler;
+use super::fetch_messages::fetch_messages_cmd_handler::FetchMessagesCmdHandler;
+use super::get_meal_plan::get_meal_plan_cmd_handler::GetMealPlanCmdHandler;
+use super::list_foodstuffs::list_foodstuffs_cmd_handler::ListFoodstuffsCmdHandler;
+use super::list_history::list_history_cmd_handler::ListHistoryCmdHandler;
 use super::list_partners::list_partners_cmd_handler::ListPartnersCmdHandler;
+use super::list_recipes::list_recipes_cmd_handler::ListRecipesCmdHandler;
+use super::list_shared_foodstuffs::list_shared_foodstuffs_cmd_handler::ListSharedFoodstuffsCmdHandler;
+use super::list_shopping_lists::list_shopping_lists_cmd_handler::ListShoppingListsCmdHandler;
 use super::move_device_account::move_device_account_cmd_handler::MoveDeviceAccountCmdHandler;
 use super::pairing_request::pairing_request_cmd_handler::PairingRequestCmdHandler;
 use super::register_user::register_user_cmd_handler::RegisterUserCmdHandler;
+use super::share_foodstuff::share_foodstuff_cmd_handler::ShareFoodstuffCmdHandler;
+use super::share_shopping_list::share_shopping_list_cmd_handler::ShareShoppingListCmdHandler;
 use super::start_pairing::start_pairing_cmd_handler::StartPairingCmdHandler;
+use super::sync_foodstuffs::sync_foodstuffs_cmd_handler::SyncFoodstuffsCmdHandler;
+use super::unlist_foodstuff::unlist_foodstuff_cmd_handler::UnlistFoodstuffCmdHandler;
 use super::unpair::unpair_cmd_handler::UnpairCmdHandler;
 use super::update_fcm_token::update_fcm_token_cmd_handler::UpdateFcmTokenCmdHandler;
+use super::update_foodstuff::update_foodstuff_cmd_handler::UpdateFoodstuffCmdHandler;
+use super::update_meal_plan_meal::update_meal_plan_meal_cmd_handler::UpdateMealPlanMealCmdHandler;
+use super::update_shared_foodstuff::update_shared_foodstuff_cmd_handler::UpdateSharedFoodstuffCmdHandler;
 use super::update_user_name::update_user_name_cmd_handler::UpdateUserNameCmdHandler;
 
 type CmdsHashMap = HashMap<&'static str, Box<dyn CmdHandler + Send + Sync>>;
@@ -64,6 +91,106 @@ impl CmdsHub {
             constants::CMD_UPDATE_USER_NAME,
             Box::new(UpdateUserNameCmdHandler::new()),
         );
+        cmd_handlers.insert(
+            constants::CMD_DELETE_ACCOUNT,
+            Box::new(DeleteAccountCmdHandler::new(overrides)),
+        );
+        cmd_handlers.insert(
+            constants::CMD_EXPORT_DATA,
+            Box::new(ExportDataCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_FETCH_MESSAGES,
+            Box::new(FetchMessagesCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_ADD_FOODSTUFF,
+            Box::new(AddFoodstuffCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_UPDATE_FOODSTUFF,
+            Box::new(UpdateFoodstuffCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_UNLIST_FOODSTUFF,
+            Box::new(UnlistFoodstuffCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_LIST_FOODSTUFFS,
+            Box::new(ListFoodstuffsCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_SYNC_FOODSTUFFS,
+            Box::new(SyncFoodstuffsCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_SHARE_FOODSTUFF,
+            Box::new(ShareFoodstuffCmdHandler::new(overrides)),
+        );
+        cmd_handlers.insert(
+            constants::CMD_LIST_SHARED_FOODSTUFFS,
+            Box::new(ListSharedFoodstuffsCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_UPDATE_SHARED_FOODSTUFF,
+            Box::new(UpdateSharedFoodstuffCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_ADD_HISTORY_ENTRY,
+            Box::new(AddHistoryEntryCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_DELETE_HISTORY_ENTRY,
+            Box::new(DeleteHistoryEntryCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_LIST_HISTORY,
+            Box::new(ListHistoryCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_CREATE_RECIPE,
+            Box::new(CreateRecipeCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_EDIT_RECIPE,
+            Box::new(EditRecipeCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_DELETE_RECIPE,
+            Box::new(DeleteRecipeCmdHandler::new(overrides)),
+        );
+        cmd_handlers.insert(
+            constants::CMD_LIST_RECIPES,
+            Box::new(ListRecipesCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_GET_MEAL_PLAN,
+            Box::new(GetMealPlanCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_UPDATE_MEAL_PLAN_MEAL,
+            Box::new(UpdateMealPlanMealCmdHandler::new(overrides)),
+        );
+        cmd_handlers.insert(
+            constants::CMD_CREATE_SHOPPING_LIST,
+            Box::new(CreateShoppingListCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_DELETE_SHOPPING_LIST,
+            Box::new(DeleteShoppingListCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_LIST_SHOPPING_LISTS,
+            Box::new(ListShoppingListsCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_SHARE_SHOPPING_LIST,
+            Box::new(ShareShoppingListCmdHandler::new()),
+        );
+        cmd_handlers.insert(
+            constants::CMD_CHECK_SHOPPING_LIST_ITEM,
+            Box::new(CheckShoppingListItemCmdHandler::new(overrides)),
+        );
         Ok(CmdsHub { cmd_handlers })
     }
 
@@ -76,9 +203,16 @@ impl CmdsHub {
         config: Config,
         http_client: Arc<HttpClient>,
     ) -> CmdHandleResultFuture {
-        let handler = self.cmd_handlers.get(request.as_str());
-        if let Some(handler) = handler {
-            handler.handle(args, body, connections_pool, config, http_client)
+        let handler = self.cmd_handlers.get_key_value(request.as_str());
+        if let Some((cmd, handler)) = handler {
+            let cmd: &'static str = cmd;
+            let start = Instant::now();
+            let result = handler.handle(args, body, connections_pool, config, http_client);
+            Box::pin(async move {
+                let result = result.await;
+                metrics::observe_cmd(cmd, start.elapsed());
+                result
+            })
         } else {
             Box::pin(err(RequestError::new(
                 constants::FIELD_STATUS_UNKNOWN_REQUEST.to_owned(),
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::error::Error as DBError;
+use crate::db::core::error::ErrorKind as DBErrorKind;
+use crate::db::core::recipe;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::recipe_utils::insert_ingredients;
+use crate::server::cmds::recipe_utils::parse_ingredients;
+use crate::server::cmds::recipe_utils::recipe_to_json;
+use crate::server::cmds::recipe_utils::select_foodstuffs_of;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Expects ingredients of the recipe in the body,
+/// see |recipe_utils::parse_ingredients| for its format.
+#[derive(Default)]
+pub struct CreateRecipeCmdHandler {}
+
+#[derive(Deserialize)]
+struct CreateRecipeArgs {
+    recipe_id: i32,
+    recipe_name: String,
+}
+
+impl CmdHandler for CreateRecipeCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            body,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl CreateRecipeCmdHandler {
+    pub fn new() -> Self {
+        CreateRecipeCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        body: String,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: CreateRecipeArgs = parse_args(&args)?;
+        let app_user_recipe_id = cmd_args.recipe_id;
+        let name = cmd_args.recipe_name;
+        let ingredients = parse_ingredients(&body)?;
+
+        let recipe_json = db_transaction(&connection, || {
+            let new_recipe = recipe::new(&user, app_user_recipe_id, name);
+            let recipe =
+                recipe::insert(new_recipe, &connection).map_err(extract_duplication_error)?;
+            insert_ingredients(&user, &recipe, &ingredients, &connection)?;
+            let foodstuffs = select_foodstuffs_of(&user, &connection)?;
+            recipe_to_json(&recipe, &foodstuffs, &connection)
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_RECIPE: recipe_json,
+        }))
+    }
+}
+
+fn extract_duplication_error(db_error: DBError) -> RequestError {
+    match db_error {
+        DBError(DBErrorKind::UniqueViolation(_), _) => RequestError::new(
+            constants::FIELD_STATUS_RECIPE_DUPLICATION.to_owned(),
+            "Recipe with given ID already exists".to_owned(),
+        ),
+        error => error.into(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./create_recipe_cmd_handler_test.rs"]
+mod create_recipe_cmd_handler_test;
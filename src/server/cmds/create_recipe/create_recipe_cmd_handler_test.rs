@@ -0,0 +1,271 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::create_recipe_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_recipes;
+use crate::server::cmds::testing_cmds_utils::make_request_with_json_body;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+#[test]
+fn create_recipe_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f208-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "12",
+        "6",
+        "60",
+        "350",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "milk",
+        "3",
+        "2",
+        "4",
+        "50",
+    );
+
+    let response = create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100), (2, 300)],
+    );
+    let expected_recipe = json!({
+        constants::FIELD_NAME_RECIPE_ID: 1,
+        constants::FIELD_NAME_RECIPE_NAME: "porridge",
+        constants::FIELD_NAME_INGREDIENTS: [
+            {
+                constants::FIELD_NAME_FOODSTUFF_ID: 1,
+                constants::FIELD_NAME_WEIGHT: 100,
+            },
+            {
+                constants::FIELD_NAME_FOODSTUFF_ID: 2,
+                constants::FIELD_NAME_WEIGHT: 300,
+            }
+        ],
+        constants::FIELD_NAME_PROTEIN: "5.25",
+        constants::FIELD_NAME_FATS: "3",
+        constants::FIELD_NAME_CARBS: "18",
+        constants::FIELD_NAME_CALORIES: "125",
+    });
+    assert_eq!(expected_recipe, response[constants::FIELD_NAME_RECIPE]);
+
+    let response = list_recipes(server.address(), &client_token, &uid);
+    assert_eq!(
+        json!([expected_recipe]),
+        response[constants::FIELD_NAME_RECIPES]
+    );
+}
+
+#[test]
+fn recipe_id_duplication() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f208-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+    let response = create_recipe_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "granola",
+        &[(1, 100)],
+    );
+    assert_status(&response, constants::FIELD_STATUS_RECIPE_DUPLICATION);
+}
+
+#[test]
+fn recipe_with_not_existing_foodstuff() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f208-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let response = create_recipe_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100), (2, 100)],
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+
+    // Recipe creation must be fully rolled back
+    let response = list_recipes(server.address(), &client_token, &uid);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_RECIPES]);
+}
+
+#[test]
+fn recipe_without_ingredients() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f208-0000-0000-000000000003").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = create_recipe_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "porridge",
+        &[],
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
+
+#[test]
+fn ingredient_with_invalid_weight() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f208-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let response = create_recipe_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 0)],
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
+
+#[test]
+fn create_recipe_with_args_and_ingredients_in_json_body() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f208-0000-0000-000000000005").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "12",
+        "6",
+        "60",
+        "350",
+    );
+
+    let url = format!(
+        "http://{}{}",
+        server.address(),
+        constants::CMD_CREATE_RECIPE
+    );
+    let response = make_request_with_json_body(
+        &url,
+        &json!({
+            constants::ARG_USER_ID: uid,
+            constants::ARG_CLIENT_TOKEN: client_token,
+            constants::ARG_RECIPE_ID: 1,
+            constants::ARG_RECIPE_NAME: "porridge",
+            constants::FIELD_NAME_INGREDIENTS: [
+                {
+                    constants::FIELD_NAME_FOODSTUFF_ID: 1,
+                    constants::FIELD_NAME_WEIGHT: 100,
+                }
+            ],
+        }),
+    );
+    assert_status(&response, constants::FIELD_STATUS_OK);
+    assert_eq!(
+        "porridge",
+        response[constants::FIELD_NAME_RECIPE][constants::FIELD_NAME_RECIPE_NAME]
+    );
+    assert_eq!(
+        "12",
+        response[constants::FIELD_NAME_RECIPE][constants::FIELD_NAME_PROTEIN]
+    );
+
+    let response = list_recipes(server.address(), &client_token, &uid);
+    assert_eq!(
+        1,
+        response[constants::FIELD_NAME_RECIPES]
+            .as_array()
+            .unwrap()
+            .len()
+    );
+}
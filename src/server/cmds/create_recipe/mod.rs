@@ -0,0 +1 @@
+pub mod create_recipe_cmd_handler;
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::error::Error as DBError;
+use crate::db::core::error::ErrorKind as DBErrorKind;
+use crate::db::core::shopping_list;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::shopping_list_utils::aggregate_sources;
+use crate::server::cmds::shopping_list_utils::insert_items;
+use crate::server::cmds::shopping_list_utils::parse_shopping_list_sources;
+use crate::server::cmds::shopping_list_utils::shopping_list_to_json;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Creates a shopping list with weights of foodstuffs summed over all sources
+/// of the list. Expects the sources in the body,
+/// see |shopping_list_utils::parse_shopping_list_sources| for its format.
+#[derive(Default)]
+pub struct CreateShoppingListCmdHandler {}
+
+#[derive(Deserialize)]
+struct CreateShoppingListArgs {
+    shopping_list_id: i32,
+    shopping_list_name: String,
+}
+
+impl CmdHandler for CreateShoppingListCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            body,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl CreateShoppingListCmdHandler {
+    pub fn new() -> Self {
+        CreateShoppingListCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        body: String,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: CreateShoppingListArgs = parse_args(&args)?;
+        let app_user_shopping_list_id = cmd_args.shopping_list_id;
+        let name = cmd_args.shopping_list_name;
+        let sources = parse_shopping_list_sources(&body)?;
+
+        let shopping_list_json = db_transaction(&connection, || {
+            let weighted_foodstuffs = aggregate_sources(&sources, &user, &connection)?;
+            let new_shopping_list = shopping_list::new(&user, app_user_shopping_list_id, name);
+            let shopping_list = shopping_list::insert(new_shopping_list, &connection)
+                .map_err(extract_duplication_error)?;
+            insert_items(&shopping_list, &weighted_foodstuffs, &connection)?;
+            shopping_list_to_json(&shopping_list, &user, &connection)
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_SHOPPING_LIST: shopping_list_json,
+        }))
+    }
+}
+
+fn extract_duplication_error(db_error: DBError) -> RequestError {
+    match db_error {
+        DBError(DBErrorKind::UniqueViolation(_), _) => RequestError::new(
+            constants::FIELD_STATUS_SHOPPING_LIST_DUPLICATION.to_owned(),
+            "Shopping list with given ID already exists".to_owned(),
+        ),
+        error => error.into(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./create_shopping_list_cmd_handler_test.rs"]
+mod create_shopping_list_cmd_handler_test;
@@ -0,0 +1,340 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shopping_lists;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal;
+
+#[test]
+fn weights_of_foodstuffs_and_recipes_are_summed() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f217-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "12",
+        "6",
+        "60",
+        "350",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "milk",
+        "3",
+        "2",
+        "4",
+        "50",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        3,
+        "banana",
+        "1",
+        "0.3",
+        "23",
+        "89",
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100), (2, 300)],
+    );
+
+    let response = create_shopping_list(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "groceries",
+        json!({
+            "foodstuffs": [
+                {"foodstuff_id": 3, "weight": 120},
+                {"foodstuff_id": 1, "weight": 50},
+            ],
+            // A half of the porridge
+            "recipes": [{"recipe_id": 1, "weight": 200}],
+        }),
+    );
+    let expected_list = json!({
+        constants::FIELD_NAME_OWNER_USER_ID: uid,
+        constants::FIELD_NAME_SHOPPING_LIST_ID: 1,
+        constants::FIELD_NAME_SHOPPING_LIST_NAME: "groceries",
+        constants::FIELD_NAME_ITEMS: [
+            {
+                constants::FIELD_NAME_ITEM_ID: 1,
+                constants::FIELD_NAME_OWNER_USER_ID: uid,
+                constants::FIELD_NAME_FOODSTUFF_ID: 3,
+                constants::FIELD_NAME_FOODSTUFF_NAME: "banana",
+                constants::FIELD_NAME_WEIGHT: 120,
+                constants::FIELD_NAME_IS_CHECKED: false,
+            },
+            {
+                constants::FIELD_NAME_ITEM_ID: 2,
+                constants::FIELD_NAME_OWNER_USER_ID: uid,
+                constants::FIELD_NAME_FOODSTUFF_ID: 1,
+                constants::FIELD_NAME_FOODSTUFF_NAME: "oats",
+                constants::FIELD_NAME_WEIGHT: 100,
+                constants::FIELD_NAME_IS_CHECKED: false,
+            },
+            {
+                constants::FIELD_NAME_ITEM_ID: 3,
+                constants::FIELD_NAME_OWNER_USER_ID: uid,
+                constants::FIELD_NAME_FOODSTUFF_ID: 2,
+                constants::FIELD_NAME_FOODSTUFF_NAME: "milk",
+                constants::FIELD_NAME_WEIGHT: 150,
+                constants::FIELD_NAME_IS_CHECKED: false,
+            }
+        ],
+    });
+    assert_eq!(expected_list, response[constants::FIELD_NAME_SHOPPING_LIST]);
+
+    let response = list_shopping_lists(server.address(), &client_token, &uid);
+    assert_eq!(
+        json!([expected_list]),
+        response[constants::FIELD_NAME_SHOPPING_LISTS]
+    );
+}
+
+#[test]
+fn list_from_meal_plan_includes_foodstuffs_of_partner() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f217-0000-0000-000000000001").unwrap();
+    let uid2 = Uuid::from_str("00000000-f217-0000-0000-000000000002").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_recipe(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "salad",
+        &[(1, 100)],
+    );
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        0,
+        constants::MEAL_TYPE_LUNCH,
+        0,
+        json!([
+            {constants::FIELD_NAME_OWNER_USER_ID: uid1, constants::FIELD_NAME_RECIPE_ID: 1, constants::FIELD_NAME_WEIGHT: 300},
+            {constants::FIELD_NAME_OWNER_USER_ID: uid2, constants::FIELD_NAME_FOODSTUFF_ID: 1, constants::FIELD_NAME_WEIGHT: 150},
+        ]),
+    );
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        1,
+        constants::MEAL_TYPE_SNACK,
+        1,
+        json!([
+            {constants::FIELD_NAME_OWNER_USER_ID: uid2, constants::FIELD_NAME_FOODSTUFF_ID: 1, constants::FIELD_NAME_WEIGHT: 50},
+        ]),
+    );
+
+    let response = create_shopping_list(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "week",
+        json!({ "meal_plan_partner_user_id": uid2 }),
+    );
+    let expected_items = json!([
+        {
+            constants::FIELD_NAME_ITEM_ID: 1,
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "apple",
+            constants::FIELD_NAME_WEIGHT: 300,
+            constants::FIELD_NAME_IS_CHECKED: false,
+        },
+        {
+            constants::FIELD_NAME_ITEM_ID: 2,
+            constants::FIELD_NAME_OWNER_USER_ID: uid2,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "pear",
+            constants::FIELD_NAME_WEIGHT: 200,
+            constants::FIELD_NAME_IS_CHECKED: false,
+        }
+    ]);
+    assert_eq!(
+        expected_items,
+        response[constants::FIELD_NAME_SHOPPING_LIST][constants::FIELD_NAME_ITEMS]
+    );
+}
+
+#[test]
+fn duplicated_list_id() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f217-0000-0000-000000000003").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let sources = json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 100}] });
+    create_shopping_list(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "list",
+        sources.clone(),
+    );
+    let response = create_shopping_list_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "list",
+        sources,
+    );
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_DUPLICATION);
+}
+
+#[test]
+fn invalid_sources() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f217-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = create_shopping_list_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "list",
+        json!({}),
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+
+    let response = create_shopping_list_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "list",
+        json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 0}] }),
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+
+    let response = create_shopping_list_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "list",
+        json!({ "foodstuffs": [{"foodstuff_id": 2, "weight": 100}] }),
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+
+    let response = create_shopping_list_without_ok_check(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "list",
+        json!({ "recipes": [{"recipe_id": 1, "weight": 100}] }),
+    );
+    assert_status(&response, constants::FIELD_STATUS_RECIPE_NOT_FOUND);
+
+    // Nothing is created
+    let response = list_shopping_lists(server.address(), &client_token, &uid);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_SHOPPING_LISTS]);
+}
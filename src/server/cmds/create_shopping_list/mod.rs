@@ -0,0 +1 @@
+pub mod create_shopping_list_cmd_handler;
@@ -0,0 +1,218 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::paired_partners;
+use crate::db::core::paired_partners::PairingState;
+use crate::db::core::util::delete_app_user;
+use crate::db::core::{gp_user, vk_user};
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::outside::fcm::FCM_ADDR;
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::register_user::social_network_token_check::{
+    check_token, TokenCheckSuccess,
+};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::notification_outbox;
+use crate::server::request_error::RequestError;
+
+/// Deletes the account of the user and all data of the user.
+/// Deletion is irreversible, so apart from the client token the user
+/// must prove the ownership of the account once again with a social network token.
+/// Partners of the user are notified about the deletion.
+pub struct DeleteAccountCmdHandler {
+    fcm_address: String,
+}
+
+#[derive(Deserialize)]
+struct DeleteAccountArgs {
+    social_network_type: String,
+    social_network_token: String,
+    overrides: Option<String>,
+}
+
+impl CmdHandler for DeleteAccountCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+            self.fcm_address.clone(),
+        ))
+    }
+}
+
+impl DeleteAccountCmdHandler {
+    pub fn new(overrides: &JsonValue) -> Self {
+        let args = get_construction_args(overrides);
+        DeleteAccountCmdHandler {
+            fcm_address: args.fcm_address,
+        }
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+        fcm_address: String,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: DeleteAccountArgs = parse_args(&args)?;
+        let social_network_type = cmd_args.social_network_type;
+        let social_network_token = cmd_args.social_network_token;
+        let overrides = cmd_args.overrides.unwrap_or_default();
+
+        let token_check_result = check_token(
+            social_network_type,
+            social_network_token,
+            &overrides,
+            http_client.clone(),
+            config.clone(),
+        )
+        .await?;
+        let token_owner_id =
+            match token_check_result {
+                TokenCheckSuccess::VK { uid } => vk_user::select_by_vk_uid(&uid, &connection)?
+                    .map(|vk_user| vk_user.app_user_id()),
+                TokenCheckSuccess::GP { uid } => gp_user::select_by_gp_uid(&uid, &connection)?
+                    .map(|gp_user| gp_user.app_user_id()),
+            };
+        if token_owner_id != Some(user.id()) {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_TOKEN_CHECK_FAIL.to_owned(),
+                "Social network token doesn't belong to given user".to_owned(),
+            ));
+        }
+
+        let partners = select_partners_of(&user, &connection)?;
+        let json = json!({
+            constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_PARTNER_DELETED_ACCOUNT,
+            constants::SERV_FIELD_PARTNER_USER_ID: user.uid(),
+            constants::SERV_FIELD_PARTNER_NAME: user.name(),
+        });
+        // The client DB user is not allowed to delete users' data,
+        // so the deletion is performed by the server DB user
+        let mut server_connections_pool = ConnectionPool::for_server_user(config.clone());
+        let server_connection = server_connections_pool.borrow_connection()?;
+        let notification_ids = db_transaction(&server_connection, || {
+            delete_app_user(user.uid(), &server_connection)?;
+            let mut notification_ids = Vec::new();
+            for partner in &partners {
+                notification_ids.extend(notification_outbox::enqueue(
+                    partner,
+                    json.to_string(),
+                    &server_connection,
+                )?);
+            }
+            Ok(notification_ids)
+        })?;
+        drop(server_connection);
+
+        // NOTE: we don't use the '?' operator on the send result - the account
+        // is already deleted, so failed notifications are not the client's problem
+        let _notif_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
+            connections_pool,
+            &config,
+            &fcm_address,
+            http_client,
+        )
+        .await;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+        }))
+    }
+}
+
+/// Selects users with whom the |user| is paired.
+fn select_partners_of(
+    user: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<Vec<AppUser>, RequestError> {
+    let partners_pairs = paired_partners::select_by_partner_user_id_and_state(
+        user.id(),
+        PairingState::Done,
+        connection,
+    )?;
+    let mut partners = Vec::with_capacity(partners_pairs.len());
+    for pair in &partners_pairs {
+        let partner_id = if pair.partner1_user_id() == user.id() {
+            pair.partner2_user_id()
+        } else {
+            pair.partner1_user_id()
+        };
+        if let Some(partner) = app_user::select_by_id(partner_id, connection)? {
+            partners.push(partner);
+        }
+    }
+    Ok(partners)
+}
+
+#[cfg(test)]
+pub fn insert_construction_overrides(overrides: &mut JsonValue, fcm_address: String) {
+    let overrides = overrides
+        .as_object_mut()
+        .expect("Can insert only into object");
+    overrides.insert("delete_account_overrides".to_owned(), json!({}));
+    let overrides = overrides["delete_account_overrides"]
+        .as_object_mut()
+        .unwrap();
+    overrides.insert("fcm_address_override".to_owned(), json!(fcm_address));
+}
+
+struct ConstructionArgs {
+    fcm_address: String,
+}
+
+fn get_construction_args(overrides: &JsonValue) -> ConstructionArgs {
+    if let Some(overrides) = extract_construction_overrides(overrides) {
+        overrides
+    } else {
+        ConstructionArgs {
+            fcm_address: FCM_ADDR.to_owned(),
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn extract_construction_overrides(_overrides: &JsonValue) -> Option<ConstructionArgs> {
+    None
+}
+
+#[cfg(test)]
+fn extract_construction_overrides(overrides: &JsonValue) -> Option<ConstructionArgs> {
+    match &overrides["delete_account_overrides"].as_object() {
+        Some(overrides) => Some(ConstructionArgs {
+            fcm_address: overrides["fcm_address_override"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        }),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./delete_account_cmd_handler_test.rs"]
+mod delete_account_cmd_handler_test;
@@ -0,0 +1,170 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::testing_util as dbtesting_utils;
+use crate::server::constants;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+
+use crate::server::cmds::delete_account::delete_account_cmd_handler::insert_construction_overrides;
+use crate::server::cmds::register_user::user_data_generators::create_gp_overrides;
+
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_partners;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+
+fn delete_account(
+    server_addr: &str,
+    client_token: &str,
+    uid: &Uuid,
+    token_gp_uid: &str,
+) -> JsonValue {
+    let gp_override = format!("{{ \"sub\": \"{}\" }}", token_gp_uid);
+    let override_str = create_gp_overrides(uid, &gp_override);
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_DELETE_ACCOUNT,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.to_string().as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_SOCIAL_NETWORK_TYPE,
+        "gp",
+        &constants::ARG_SOCIAL_NETWORK_TOKEN,
+        "token",
+        &constants::ARG_OVERRIDES,
+        override_str
+    );
+    make_request(&url)
+}
+
+#[test]
+fn delete_account_notifies_partner() {
+    let r = |_request: &FullRequest| {
+        let response = r#"
+        {
+            "multicast_id":2513734409441993719,
+            "success":1,
+            "failure":0,
+            "canonical_ids":0,
+            "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd"}]
+        }"#;
+        Some(response.to_owned())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    let server = start_server!(|overrides: &mut JsonValue| insert_construction_overrides(
+        overrides,
+        fcm_addr.clone()
+    ));
+
+    let uid1 = Uuid::from_str("00000000-f21c-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21c-0000-0000-000000000001").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &client_token2,
+        &uid2.to_string(),
+    );
+    set_user_fcm_token(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        &fcm_token2,
+    );
+    let connection = dbtesting_utils::testing_connection_for_server_user().unwrap();
+    let response = delete_account(server.address(), &client_token1, &uid1, &gpuid1);
+    assert_status_ok(&response);
+
+    assert!(app_user::select_by_uid(&uid1, &connection)
+        .unwrap()
+        .is_none());
+    let response = list_partners(server.address(), &client_token2, &uid2.to_string());
+    assert_eq!(json!([]), response[constants::FIELD_NAME_PARTNERS]);
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let fcm_requests: Vec<JsonValue> = fcm_requests
+        .iter()
+        .map(|req| serde_json::from_str(&req.body).unwrap())
+        .collect();
+    assert_eq!(1, fcm_requests.len());
+    assert_eq!(fcm_requests[0]["to"], json!(fcm_token2));
+    let data = &fcm_requests[0]["data"];
+    assert_eq!(
+        &data[constants::SERV_FIELD_MSG_TYPE],
+        constants::SERV_MSG_PARTNER_DELETED_ACCOUNT,
+    );
+    assert_eq!(
+        &data[constants::SERV_FIELD_PARTNER_USER_ID],
+        &uid1.to_string()
+    );
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_NAME], "name1");
+}
+
+#[test]
+fn cannot_delete_account_with_token_of_other_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f21c-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21c-0000-0000-000000000003").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+
+    // Social network token of the second user
+    let response = delete_account(server.address(), &client_token1, &uid1, &gpuid2);
+    assert_status(&response, constants::FIELD_STATUS_TOKEN_CHECK_FAIL);
+    // Social network token of nobody
+    let response = delete_account(server.address(), &client_token1, &uid1, "unknown");
+    assert_status(&response, constants::FIELD_STATUS_TOKEN_CHECK_FAIL);
+
+    let connection = dbtesting_utils::testing_connection_for_server_user().unwrap();
+    assert!(app_user::select_by_uid(&uid1, &connection)
+        .unwrap()
+        .is_some());
+    assert!(app_user::select_by_uid(&uid2, &connection)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn cannot_delete_account_with_invalid_client_token() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f21c-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+    register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+
+    let response = delete_account(server.address(), &Uuid::new_v4().to_string(), &uid, &gpuid);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_CLIENT_TOKEN);
+
+    let connection = dbtesting_utils::testing_connection_for_server_user().unwrap();
+    assert!(app_user::select_by_uid(&uid, &connection)
+        .unwrap()
+        .is_some());
+}
@@ -0,0 +1 @@
+pub mod delete_account_cmd_handler;
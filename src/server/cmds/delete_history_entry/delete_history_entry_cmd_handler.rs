@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::history_entry;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+#[derive(Default)]
+pub struct DeleteHistoryEntryCmdHandler {}
+
+#[derive(Deserialize)]
+struct DeleteHistoryEntryArgs {
+    history_entry_id: i32,
+}
+
+impl CmdHandler for DeleteHistoryEntryCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl DeleteHistoryEntryCmdHandler {
+    pub fn new() -> Self {
+        DeleteHistoryEntryCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: DeleteHistoryEntryArgs = parse_args(&args)?;
+        let app_user_history_entry_id = cmd_args.history_entry_id;
+
+        let deleted = history_entry::delete_by_app_user_history_entry_id(
+            user.id(),
+            app_user_history_entry_id,
+            &connection,
+        )?;
+        if deleted.is_none() {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_HISTORY_ENTRY_NOT_FOUND.to_owned(),
+                format!("History entry not found, ID: {}", app_user_history_entry_id),
+            ));
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./delete_history_entry_cmd_handler_test.rs"]
+mod delete_history_entry_cmd_handler_test;
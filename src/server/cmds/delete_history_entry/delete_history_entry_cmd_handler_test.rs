@@ -0,0 +1,121 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::add_history_entry;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_history;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+fn delete_history_entry(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    history_entry_id: i32,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_DELETE_HISTORY_ENTRY,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_HISTORY_ENTRY_ID,
+        history_entry_id,
+    );
+    make_request(&url)
+}
+
+#[test]
+fn delete_history_entry_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f206-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_history_entry(server.address(), &client_token, &uid, 10, 1, 150, 1000);
+    add_history_entry(server.address(), &client_token, &uid, 11, 1, 200, 1000);
+
+    let response = delete_history_entry(server.address(), &client_token, &uid, 10);
+    assert_status_ok(&response);
+
+    let response = list_history(server.address(), &client_token, &uid, 0, 2000);
+    let entries = response[constants::FIELD_NAME_HISTORY_ENTRIES]
+        .as_array()
+        .unwrap();
+    assert_eq!(1, entries.len());
+    assert_eq!(11, entries[0][constants::FIELD_NAME_HISTORY_ENTRY_ID]);
+}
+
+#[test]
+fn delete_not_existing_history_entry() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f206-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = delete_history_entry(server.address(), &client_token, &uid.to_string(), 10);
+    assert_status(&response, constants::FIELD_STATUS_HISTORY_ENTRY_NOT_FOUND);
+}
+
+#[test]
+fn cannot_delete_history_entry_of_other_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f206-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f206-0000-0000-000000000003").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_history_entry(server.address(), &client_token1, &uid1, 10, 1, 150, 1000);
+
+    let response = delete_history_entry(server.address(), &client_token2, &uid2.to_string(), 10);
+    assert_status(&response, constants::FIELD_STATUS_HISTORY_ENTRY_NOT_FOUND);
+
+    let response = list_history(server.address(), &client_token1, &uid1, 0, 2000);
+    let entries = response[constants::FIELD_NAME_HISTORY_ENTRIES]
+        .as_array()
+        .unwrap();
+    assert_eq!(1, entries.len());
+}
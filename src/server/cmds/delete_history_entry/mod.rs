@@ -0,0 +1 @@
+pub mod delete_history_entry_cmd_handler;
@@ -0,0 +1,186 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan_item;
+use crate::db::core::recipe;
+use crate::db::core::recipe_ingredient;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::outside::fcm::FCM_ADDR;
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::notification_outbox;
+use crate::server::request_error::RequestError;
+
+/// Deletes the recipe and removes it from all meal plans of the user.
+/// Versions of the modified plans are incremented and the partners are notified
+/// about the modifications.
+pub struct DeleteRecipeCmdHandler {
+    fcm_address: String,
+}
+
+#[derive(Deserialize)]
+struct DeleteRecipeArgs {
+    recipe_id: i32,
+}
+
+impl CmdHandler for DeleteRecipeCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+            self.fcm_address.clone(),
+        ))
+    }
+}
+
+impl DeleteRecipeCmdHandler {
+    pub fn new(overrides: &JsonValue) -> Self {
+        let args = get_construction_args(overrides);
+        DeleteRecipeCmdHandler {
+            fcm_address: args.fcm_address,
+        }
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+        fcm_address: String,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: DeleteRecipeArgs = parse_args(&args)?;
+        let app_user_recipe_id = cmd_args.recipe_id;
+
+        let notification_ids = db_transaction(&connection, || {
+            let recipe =
+                recipe::select_by_app_user_recipe_id(user.id(), app_user_recipe_id, &connection)?;
+            let recipe = match recipe {
+                Some(recipe) => recipe,
+                None => {
+                    return Err(RequestError::new(
+                        constants::FIELD_STATUS_RECIPE_NOT_FOUND.to_owned(),
+                        format!("Recipe not found, ID: {}", app_user_recipe_id),
+                    ))
+                }
+            };
+            recipe_ingredient::delete_by_recipe_id(recipe.id(), &connection)?;
+            let meal_plan_ids = meal_plan_item::delete_by_recipe_id(recipe.id(), &connection)?;
+            recipe::delete_by_id(recipe.id(), &connection)?;
+
+            let mut notification_ids = Vec::new();
+            for meal_plan_id in meal_plan_ids {
+                // The plan is modified regardless of the version the partner has seen
+                let meal_plan = meal_plan::force_increment_version(meal_plan_id, &connection)?;
+                let meal_plan = match meal_plan {
+                    Some(meal_plan) => meal_plan,
+                    None => continue, // Not possible while the plan has items
+                };
+                let partner_id = if meal_plan.partner1_user_id() == user.id() {
+                    meal_plan.partner2_user_id()
+                } else {
+                    meal_plan.partner1_user_id()
+                };
+                let partner = match app_user::select_by_id(partner_id, &connection)? {
+                    Some(partner) => partner,
+                    None => continue, // Not possible while the partner has a meal plan
+                };
+                let json = json!({
+                    constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_MEAL_PLAN_UPDATED_BY_PARTNER,
+                    constants::SERV_FIELD_PARTNER_USER_ID: user.uid(),
+                    constants::SERV_FIELD_PARTNER_NAME: user.name(),
+                    constants::SERV_FIELD_VERSION: meal_plan.version(),
+                });
+                notification_ids.append(&mut notification_outbox::enqueue(
+                    &partner,
+                    json.to_string(),
+                    &connection,
+                )?);
+            }
+            Ok(notification_ids)
+        })?;
+
+        // NOTE: we don't use the '?' operator on the send result - we want to respond
+        // with OK status to our client even if notifications sending will fail
+        let _notif_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
+            connections_pool,
+            &config,
+            &fcm_address,
+            http_client,
+        )
+        .await;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+#[cfg(test)]
+pub fn insert_construction_overrides(overrides: &mut JsonValue, fcm_address: String) {
+    let overrides = overrides
+        .as_object_mut()
+        .expect("Can insert only into object");
+    overrides.insert("delete_recipe_overrides".to_owned(), json!({}));
+    let overrides = overrides["delete_recipe_overrides"]
+        .as_object_mut()
+        .unwrap();
+    overrides.insert("fcm_address_override".to_owned(), json!(fcm_address));
+}
+
+struct ConstructionArgs {
+    fcm_address: String,
+}
+
+fn get_construction_args(overrides: &JsonValue) -> ConstructionArgs {
+    if let Some(overrides) = extract_construction_overrides(overrides) {
+        overrides
+    } else {
+        ConstructionArgs {
+            fcm_address: FCM_ADDR.to_owned(),
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn extract_construction_overrides(_overrides: &JsonValue) -> Option<ConstructionArgs> {
+    None
+}
+
+#[cfg(test)]
+fn extract_construction_overrides(overrides: &JsonValue) -> Option<ConstructionArgs> {
+    match &overrides["delete_recipe_overrides"].as_object() {
+        Some(overrides) => Some(ConstructionArgs {
+            fcm_address: overrides["fcm_address_override"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        }),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./delete_recipe_cmd_handler_test.rs"]
+mod delete_recipe_cmd_handler_test;
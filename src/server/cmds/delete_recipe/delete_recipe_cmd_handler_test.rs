@@ -0,0 +1,202 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+
+use crate::server::cmds::delete_recipe::delete_recipe_cmd_handler::insert_construction_overrides;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::get_meal_plan;
+use crate::server::cmds::testing_cmds_utils::list_recipes;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal;
+
+fn delete_recipe(server_addr: &str, client_token: &str, uid: &str, recipe_id: i32) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_DELETE_RECIPE,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_RECIPE_ID,
+        recipe_id,
+    );
+    make_request(&url)
+}
+
+#[test]
+fn delete_recipe_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f210-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "10",
+        "10",
+        "10",
+        "10",
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "granola",
+        &[(1, 100)],
+    );
+
+    let response = delete_recipe(server.address(), &client_token, &uid, 1);
+    assert_status_ok(&response);
+
+    let response = list_recipes(server.address(), &client_token, &uid);
+    let recipes = response[constants::FIELD_NAME_RECIPES].as_array().unwrap();
+    assert_eq!(1, recipes.len());
+    assert_eq!(2, recipes[0][constants::FIELD_NAME_RECIPE_ID]);
+}
+
+#[test]
+fn delete_not_existing_recipe() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f210-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = delete_recipe(server.address(), &client_token, &uid.to_string(), 1);
+    assert_status(&response, constants::FIELD_STATUS_RECIPE_NOT_FOUND);
+}
+
+#[test]
+fn deletion_of_planned_recipe_modifies_meal_plan() {
+    let r = |_request: &FullRequest| {
+        let response = r#"
+        {
+            "multicast_id":2513734409441993719,
+            "success":1,
+            "failure":0,
+            "canonical_ids":0,
+            "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd"}]
+        }"#;
+        Some(response.to_owned())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    let server = start_server!(|overrides: &mut JsonValue| insert_construction_overrides(
+        overrides,
+        fcm_addr.clone()
+    ));
+
+    let uuid1 = Uuid::from_str("00000000-f210-0000-0000-000000000002").unwrap();
+    let uuid2 = Uuid::from_str("00000000-f210-0000-0000-000000000003").unwrap();
+    let uid1 = uuid1.to_string();
+    let uid2 = uuid2.to_string();
+    delete_app_user_with(&uuid1);
+    delete_app_user_with(&uuid2);
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let client_token1 =
+        register_named_user_return_token(server.address(), &uuid1, &gpuid1, "name1");
+    let client_token2 =
+        register_named_user_return_token(server.address(), &uuid2, &gpuid2, "name2");
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_recipe(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple pie",
+        &[(1, 100)],
+    );
+    let recipe_item = json!({
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_RECIPE_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 300,
+    });
+    update_meal_plan_meal(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        0,
+        constants::MEAL_TYPE_DINNER,
+        0,
+        json!([recipe_item]),
+    );
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    set_user_fcm_token(server.address(), &client_token2, &uid2, &fcm_token2);
+
+    let response = delete_recipe(server.address(), &client_token1, &uid1, 1);
+    assert_status_ok(&response);
+
+    // The partner sees the modified plan with incremented version
+    let response = get_meal_plan(server.address(), &client_token2, &uid2, &uid1);
+    assert_eq!(2, response[constants::FIELD_NAME_VERSION]);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_MEALS]);
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let fcm_requests: Vec<JsonValue> = fcm_requests
+        .iter()
+        .map(|req| serde_json::from_str(&req.body).unwrap())
+        .collect();
+    assert_eq!(1, fcm_requests.len());
+    assert_eq!(fcm_requests[0]["to"], json!(fcm_token2));
+    let data = &fcm_requests[0]["data"];
+    assert_eq!(
+        &data[constants::SERV_FIELD_MSG_TYPE],
+        constants::SERV_MSG_MEAL_PLAN_UPDATED_BY_PARTNER,
+    );
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_USER_ID], &uid1);
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_NAME], "name1");
+    assert_eq!(&data[constants::SERV_FIELD_VERSION], 2);
+}
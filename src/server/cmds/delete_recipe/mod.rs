@@ -0,0 +1 @@
+pub mod delete_recipe_cmd_handler;
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list_item;
+use crate::db::core::shopping_list_share;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::shopping_list_utils::shopping_list_not_found_error;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+/// Only the owner of a shopping list can delete it.
+#[derive(Default)]
+pub struct DeleteShoppingListCmdHandler {}
+
+#[derive(Deserialize)]
+struct DeleteShoppingListArgs {
+    shopping_list_id: i32,
+}
+
+impl CmdHandler for DeleteShoppingListCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl DeleteShoppingListCmdHandler {
+    pub fn new() -> Self {
+        DeleteShoppingListCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: DeleteShoppingListArgs = parse_args(&args)?;
+        let app_user_shopping_list_id = cmd_args.shopping_list_id;
+
+        db_transaction(&connection, || {
+            let shopping_list = shopping_list::select_by_app_user_shopping_list_id(
+                user.id(),
+                app_user_shopping_list_id,
+                &connection,
+            )?;
+            let shopping_list = match shopping_list {
+                Some(shopping_list) => shopping_list,
+                None => return Err(shopping_list_not_found_error(app_user_shopping_list_id)),
+            };
+            shopping_list_share::delete_by_shopping_list_id(shopping_list.id(), &connection)?;
+            shopping_list_item::delete_by_shopping_list_id(shopping_list.id(), &connection)?;
+            shopping_list::delete_by_id(shopping_list.id(), &connection)?;
+            Ok(())
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./delete_shopping_list_cmd_handler_test.rs"]
+mod delete_shopping_list_cmd_handler_test;
@@ -0,0 +1,95 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shopping_lists;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list;
+use crate::server::constants;
+
+fn delete_shopping_list(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    shopping_list_id: i32,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_DELETE_SHOPPING_LIST,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_SHOPPING_LIST_ID,
+        shopping_list_id,
+    );
+    make_request(&url)
+}
+
+#[test]
+fn delete_shared_list() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f21b-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21b-0000-0000-000000000001").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_shopping_list(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "list",
+        json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 100}] }),
+    );
+    share_shopping_list(server.address(), &client_token1, &uid1, 1, &uid2);
+
+    // The partner cannot delete the list - it's not partner's list
+    let response = delete_shopping_list(server.address(), &client_token2, &uid2, 1);
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND);
+
+    let response = delete_shopping_list(server.address(), &client_token1, &uid1, 1);
+    assert_status_ok(&response);
+    let response = list_shopping_lists(server.address(), &client_token1, &uid1);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_SHOPPING_LISTS]);
+    let response = list_shopping_lists(server.address(), &client_token2, &uid2);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_SHOPPING_LISTS]);
+
+    let response = delete_shopping_list(server.address(), &client_token1, &uid1, 1);
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND);
+}
@@ -0,0 +1 @@
+pub mod delete_shopping_list_cmd_handler;
@@ -11,17 +11,27 @@ use crate::db::pool::connection_pool::ConnectionPool;
 use crate::outside::http_client::HttpClient;
 
 use crate::outside::fcm::FCM_ADDR;
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
 use crate::server::cmds::utils::extract_user_from_query_args;
-use crate::server::cmds::utils::notify_user;
-use crate::server::cmds::utils::HashMapAdditionalOperations;
 use crate::server::constants;
+use crate::server::inbox;
+use crate::server::notification_outbox;
 use crate::server::request_error::RequestError;
 
 pub struct DirectPartnerMsgCmdHandler {
     fcm_address: String,
 }
 
+/// The message is either the 'msg' arg (when args are sent in a JSON body)
+/// or the whole body.
+#[derive(Deserialize)]
+struct DirectPartnerMsgArgs {
+    partner_user_id: String,
+    msg: Option<String>,
+}
+
 impl CmdHandler for DirectPartnerMsgCmdHandler {
     fn handle(
         &self,
@@ -60,7 +70,9 @@ impl DirectPartnerMsgCmdHandler {
     ) -> CmdHandleResult {
         let connection = connections_pool.borrow_connection()?;
         let user = extract_user_from_query_args(&args, &connection)?;
-        let partner_uid = args.get_or_request_error(constants::ARG_PARTNER_USER_ID)?;
+        let cmd_args: DirectPartnerMsgArgs = parse_args(&args)?;
+        let partner_uid = cmd_args.partner_user_id;
+        let msg = cmd_args.msg.unwrap_or(body);
         let partner = app_user::select_by_uid(&Uuid::from_str(&partner_uid)?, &connection)?;
         let partner = match partner {
             Some(partner) => partner,
@@ -87,13 +99,17 @@ impl DirectPartnerMsgCmdHandler {
             constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_DIRECT_MSG_FROM_PARTNER,
             constants::SERV_FIELD_PARTNER_USER_ID: user.uid(),
             constants::SERV_FIELD_PARTNER_NAME: user.name(),
-            constants::SERV_FIELD_MSG: body,
+            constants::SERV_FIELD_MSG: msg,
         });
+        let notification_ids = db_transaction(&connection, || {
+            inbox::store(&partner, json.to_string(), &connection)?;
+            notification_outbox::enqueue(&partner, json.to_string(), &connection)
+        })?;
         // NOTE: we don't use the '?' operator on the send result - we want to respond
         // with OK status to our client even if notifications sending will fail
-        let _notif_res = notify_user(
-            &partner,
-            json.to_string(),
+        let _notif_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
             connections_pool,
             &config,
             &fcm_address,
@@ -2,11 +2,15 @@ use percent_encoding::percent_encode;
 use percent_encoding::DEFAULT_ENCODE_SET;
 use serde_json::Value as JsonValue;
 use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
 use uuid::Uuid;
 
 use crate::server::constants;
 use crate::server::testing_hostname;
 use crate::server::testing_mock_server::FullRequest;
+use crate::testing_utils::config_in_tests;
+use crate::testing_utils::testing_service_account_key;
 
 use crate::server::cmds::direct_partner_msg::direct_partner_msg_cmd_handler::insert_construction_overrides;
 
@@ -16,6 +20,7 @@ use crate::server::cmds::testing_cmds_utils::pair;
 use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
 use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
 use crate::server::cmds::testing_cmds_utils::start_mock_server;
+use crate::server::cmds::testing_cmds_utils::start_server_with_config;
 use crate::server::cmds::testing_cmds_utils::start_server_with_overrides;
 use crate::server::cmds::testing_cmds_utils::{assert_status, assert_status_ok};
 
@@ -104,6 +109,93 @@ fn pairing_with_fcm() {
     assert_eq!(&fcm_requests[0]["data"][constants::SERV_FIELD_MSG], &msg);
 }
 
+#[test]
+fn msg_is_sent_with_fcm_v1_api_when_service_account_is_configured() {
+    let r = |request: &FullRequest| {
+        let response = if request.request == "/token" {
+            json!({
+                "access_token": "access_token1",
+                "expires_in": 3600,
+                "token_type": "Bearer"
+            })
+        } else {
+            json!({ "name": "projects/testing-project/messages/0:123" })
+        };
+        Some(response.to_string())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+
+    let mut overrides = json!({});
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    insert_construction_overrides(&mut overrides, fcm_addr);
+    let token_uri = format!("http://{}/token", fcm_server.address());
+    let service_account_key = testing_service_account_key("direct_msg@testing.com", &token_uri);
+    let config = config_in_tests().with_fcm_service_account_key(Some(service_account_key));
+    let server = start_server_with_config(config, &overrides);
+
+    let uid1 = Uuid::from_str("00000000-d101-0000-0000-000000000007").unwrap();
+    let uid2 = Uuid::from_str("00000000-d101-0000-0000-000000000008").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid1");
+    let gpuid2 = format!("{}{}", uid2, "gpuid2");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+
+    set_user_fcm_token(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        &fcm_token2,
+    );
+
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &client_token2,
+        &uid2.to_string(),
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_DIRECT_PARTNER_MSG,
+        &constants::ARG_USER_ID,
+        percent_encode(&uid1.to_string().as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(&client_token1.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(&uid2.to_string().as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+    );
+    let msg = "That is my msg";
+    let response = make_request_with_body(&url, msg.to_owned());
+    assert_status_ok(&response);
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    assert_eq!(2, fcm_requests.len());
+    assert_eq!("/token", fcm_requests[0].request);
+    assert_eq!(
+        "/v1/projects/testing-project/messages:send",
+        fcm_requests[1].request
+    );
+    let fcm_request: JsonValue = serde_json::from_str(&fcm_requests[1].body).unwrap();
+    let fcm_msg = &fcm_request["message"];
+
+    assert_eq!(fcm_msg["token"], json!(fcm_token2));
+    assert_eq!(
+        &fcm_msg["data"][constants::SERV_FIELD_MSG_TYPE],
+        constants::SERV_MSG_DIRECT_MSG_FROM_PARTNER,
+    );
+    assert_eq!(
+        &fcm_msg["data"][constants::SERV_FIELD_PARTNER_USER_ID],
+        &uid1.to_string()
+    );
+    assert_eq!(&fcm_msg["data"][constants::SERV_FIELD_MSG], &msg);
+}
+
 #[test]
 fn non_existing_partner_not_found() {
     let r = |_request: &FullRequest| Some("".to_owned());
@@ -218,3 +310,73 @@ fn user_which_is_not_partner_is_not_found_as_partner() {
         .collect();
     assert_eq!(0, fcm_requests.len());
 }
+
+#[test]
+fn msg_is_sent_when_db_pool_has_single_connection() {
+    let r = |_request: &FullRequest| {
+        let response = json!({
+            "multicast_id": 1,
+            "success": 1,
+            "failure": 0,
+            "canonical_ids": 0,
+            "results": [{ "message_id": "1" }]
+        });
+        Some(response.to_string())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+
+    let mut overrides = json!({});
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    insert_construction_overrides(&mut overrides, fcm_addr);
+    // If the sending borrowed a second connection while the handler holds its one,
+    // the request would wait for the borrow timeout and the message wouldn't be sent
+    let config = config_in_tests()
+        .with_db_pool_max_size(1)
+        .with_db_pool_borrow_timeout_millis(5_000);
+    let server = start_server_with_config(config, &overrides);
+
+    let uid1 = Uuid::from_str("00000000-d101-0000-0000-000000000009").unwrap();
+    let uid2 = Uuid::from_str("00000000-d101-0000-0000-000000000010").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid1");
+    let gpuid2 = format!("{}{}", uid2, "gpuid2");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &client_token2,
+        &uid2.to_string(),
+    );
+    set_user_fcm_token(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        &fcm_token2,
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_DIRECT_PARTNER_MSG,
+        &constants::ARG_USER_ID,
+        percent_encode(uid1.to_string().as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token1.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(uid2.to_string().as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let start = Instant::now();
+    let response = make_request_with_body(&url, "msg".to_owned());
+    assert_status_ok(&response);
+    assert!(start.elapsed() < Duration::from_millis(5_000));
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    assert_eq!(1, fcm_requests.len());
+    let fcm_request: JsonValue = serde_json::from_str(&fcm_requests[0].body).unwrap();
+    assert_eq!(fcm_request["to"], json!(fcm_token2));
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::recipe;
+use crate::db::core::recipe_ingredient;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::recipe_utils::insert_ingredients;
+use crate::server::cmds::recipe_utils::parse_ingredients;
+use crate::server::cmds::recipe_utils::recipe_to_json;
+use crate::server::cmds::recipe_utils::select_foodstuffs_of;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Replaces name and all ingredients of the recipe.
+/// Expects ingredients of the recipe in the body,
+/// see |recipe_utils::parse_ingredients| for its format.
+#[derive(Default)]
+pub struct EditRecipeCmdHandler {}
+
+#[derive(Deserialize)]
+struct EditRecipeArgs {
+    recipe_id: i32,
+    recipe_name: String,
+}
+
+impl CmdHandler for EditRecipeCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            body,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl EditRecipeCmdHandler {
+    pub fn new() -> Self {
+        EditRecipeCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        body: String,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: EditRecipeArgs = parse_args(&args)?;
+        let app_user_recipe_id = cmd_args.recipe_id;
+        let name = cmd_args.recipe_name;
+        let ingredients = parse_ingredients(&body)?;
+
+        let recipe_json = db_transaction(&connection, || {
+            let recipe =
+                recipe::select_by_app_user_recipe_id(user.id(), app_user_recipe_id, &connection)?;
+            let recipe = match recipe {
+                Some(recipe) => recipe,
+                None => return Err(recipe_not_found_error(app_user_recipe_id)),
+            };
+            let recipe = match recipe::update_name(recipe, name, &connection)? {
+                Some(recipe) => recipe,
+                None => return Err(recipe_not_found_error(app_user_recipe_id)),
+            };
+            recipe_ingredient::delete_by_recipe_id(recipe.id(), &connection)?;
+            insert_ingredients(&user, &recipe, &ingredients, &connection)?;
+            let foodstuffs = select_foodstuffs_of(&user, &connection)?;
+            recipe_to_json(&recipe, &foodstuffs, &connection)
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_RECIPE: recipe_json,
+        }))
+    }
+}
+
+fn recipe_not_found_error(app_user_recipe_id: i32) -> RequestError {
+    RequestError::new(
+        constants::FIELD_STATUS_RECIPE_NOT_FOUND.to_owned(),
+        format!("Recipe not found, ID: {}", app_user_recipe_id),
+    )
+}
+
+#[cfg(test)]
+#[path = "./edit_recipe_cmd_handler_test.rs"]
+mod edit_recipe_cmd_handler_test;
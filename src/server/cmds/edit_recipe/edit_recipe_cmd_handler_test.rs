@@ -0,0 +1,191 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::ingredients_body;
+use crate::server::cmds::testing_cmds_utils::list_recipes;
+use crate::server::cmds::testing_cmds_utils::make_request_with_body;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+fn edit_recipe(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    recipe_id: i32,
+    name: &str,
+    ingredients: &[(i32, i32)],
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_EDIT_RECIPE,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_RECIPE_ID,
+        recipe_id,
+        &constants::ARG_RECIPE_NAME,
+        percent_encode(name.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request_with_body(&url, ingredients_body(ingredients))
+}
+
+#[test]
+fn edit_recipe_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f209-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "10",
+        "10",
+        "10",
+        "10",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "milk",
+        "20",
+        "20",
+        "20",
+        "20",
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+
+    let response = edit_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "milk porridge",
+        &[(1, 100), (2, 100)],
+    );
+    assert_status_ok(&response);
+
+    let response = list_recipes(server.address(), &client_token, &uid);
+    let expected_recipes = json!([{
+        constants::FIELD_NAME_RECIPE_ID: 1,
+        constants::FIELD_NAME_RECIPE_NAME: "milk porridge",
+        constants::FIELD_NAME_INGREDIENTS: [
+            {
+                constants::FIELD_NAME_FOODSTUFF_ID: 1,
+                constants::FIELD_NAME_WEIGHT: 100,
+            },
+            {
+                constants::FIELD_NAME_FOODSTUFF_ID: 2,
+                constants::FIELD_NAME_WEIGHT: 100,
+            }
+        ],
+        constants::FIELD_NAME_PROTEIN: "15",
+        constants::FIELD_NAME_FATS: "15",
+        constants::FIELD_NAME_CARBS: "15",
+        constants::FIELD_NAME_CALORIES: "15",
+    }]);
+    assert_eq!(expected_recipes, response[constants::FIELD_NAME_RECIPES]);
+}
+
+#[test]
+fn edit_not_existing_recipe() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f209-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "10",
+        "10",
+        "10",
+        "10",
+    );
+    let response = edit_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+    assert_status(&response, constants::FIELD_STATUS_RECIPE_NOT_FOUND);
+}
+
+#[test]
+fn failed_edit_keeps_recipe_untouched() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f209-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "10",
+        "10",
+        "10",
+        "10",
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+    let recipes_before = list_recipes(server.address(), &client_token, &uid);
+
+    let response = edit_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "milk porridge",
+        &[(1, 100), (2, 100)],
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+
+    let recipes_after = list_recipes(server.address(), &client_token, &uid);
+    assert_eq!(recipes_before, recipes_after);
+}
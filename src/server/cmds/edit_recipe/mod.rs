@@ -0,0 +1 @@
+pub mod edit_recipe_cmd_handler;
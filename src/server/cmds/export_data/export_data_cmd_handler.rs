@@ -0,0 +1,524 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::device;
+use crate::db::core::failed_pairing_attempts;
+use crate::db::core::fcm_token;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::foodstuff_share;
+use crate::db::core::gp_user;
+use crate::db::core::history_entry;
+use crate::db::core::inbox_message;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan_item;
+use crate::db::core::notification_outbox;
+use crate::db::core::notification_outbox::NotificationState;
+use crate::db::core::paired_partners;
+use crate::db::core::paired_partners::PairingState;
+use crate::db::core::recipe;
+use crate::db::core::recipe_ingredient;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list_item;
+use crate::db::core::shopping_list_share;
+use crate::db::core::taken_pairing_code;
+use crate::db::core::vk_user;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::foodstuff_share_utils::permission_to_str;
+use crate::server::cmds::meal_plan_utils::meal_type_to_str;
+use crate::server::cmds::sync_foodstuffs::sync_foodstuffs_cmd_handler::foodstuff_to_json;
+use crate::server::cmds::utils::db_read_only_snapshot;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Exports all data stored for the user.
+/// The data is a JSON object with a field per DB table, each field is an array
+/// of the table's rows related to the user, e.g.:
+/// {"app_user": [{"user_id": "uid", "user_name": "name"}], "device": [], ...}.
+/// Internal IDs of the DB are never exported - rows reference users by their UIDs and
+/// foodstuffs, recipes and shopping lists by the IDs known to clients.
+/// All of the data is read in a single snapshot of the DB, so that rows referencing
+/// each other are consistent.
+/// NOTE: a new table must either be exported or be explicitly marked as not
+/// containing users data in the handler's test.
+#[derive(Default)]
+pub struct ExportDataCmdHandler;
+
+impl CmdHandler for ExportDataCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ExportDataCmdHandler {
+    pub fn new() -> Self {
+        ExportDataCmdHandler::default()
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let user_data =
+            db_read_only_snapshot(&connection, || Exporter::new(&user, &connection).export())?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_USER_DATA: user_data,
+        }))
+    }
+}
+
+/// Walks all tables referencing the user and converts their rows to JSON.
+struct Exporter<'a> {
+    user: &'a AppUser,
+    connection: &'a dyn DBConnection,
+    /// Cache of UIDs of users referenced by the exported rows.
+    uids: HashMap<i32, String>,
+}
+
+impl<'a> Exporter<'a> {
+    fn new(user: &'a AppUser, connection: &'a dyn DBConnection) -> Self {
+        let mut uids = HashMap::new();
+        uids.insert(user.id(), user.uid().to_string());
+        Exporter {
+            user,
+            connection,
+            uids,
+        }
+    }
+
+    fn export(&mut self) -> Result<JsonValue, RequestError> {
+        // Keys are named after the tables, so that the tests could verify that
+        // all of the tables are exported.
+        Ok(json!({
+            "app_user": self.export_app_user(),
+            "vk_user": self.export_vk_user()?,
+            "gp_user": self.export_gp_user()?,
+            "device": self.export_device()?,
+            "fcm_token": self.export_fcm_token()?,
+            "foodstuff": self.export_foodstuff()?,
+            "history_entry": self.export_history_entry()?,
+            "recipe": self.export_recipe()?,
+            "recipe_ingredient": self.export_recipe_ingredient()?,
+            "foodstuff_share": self.export_foodstuff_share()?,
+            "meal_plan": self.export_meal_plan()?,
+            "meal_plan_item": self.export_meal_plan_item()?,
+            "shopping_list": self.export_shopping_list()?,
+            "shopping_list_item": self.export_shopping_list_item()?,
+            "shopping_list_share": self.export_shopping_list_share()?,
+            "paired_partners": self.export_paired_partners()?,
+            "taken_pairing_code": self.export_taken_pairing_code()?,
+            "failed_pairing_attempts": self.export_failed_pairing_attempts()?,
+            "notification_outbox": self.export_notification_outbox()?,
+            "inbox_message": self.export_inbox_message()?,
+        }))
+    }
+
+    fn export_app_user(&self) -> Vec<JsonValue> {
+        vec![json!({
+            constants::FIELD_NAME_USER_ID: self.user.uid().to_string(),
+            constants::FIELD_NAME_USER_NAME: self.user.name(),
+        })]
+    }
+
+    fn export_vk_user(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let vk_user = vk_user::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(vk_user
+            .iter()
+            .map(|vk_user| json!({ constants::FIELD_NAME_VK_UID: vk_user.vk_uid() }))
+            .collect())
+    }
+
+    fn export_gp_user(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let gp_user = gp_user::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(gp_user
+            .iter()
+            .map(|gp_user| json!({ constants::FIELD_NAME_GP_UID: gp_user.gp_uid() }))
+            .collect())
+    }
+
+    fn export_device(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let devices = device::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(devices
+            .iter()
+            .map(|device| json!({ constants::FIELD_NAME_DEVICE_ID: device.uuid().to_string() }))
+            .collect())
+    }
+
+    /// The token itself is an identifier of the device given by FCM,
+    /// so only its presence is exported.
+    fn export_fcm_token(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let fcm_tokens = fcm_token::select_by_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::new();
+        for fcm_token in &fcm_tokens {
+            let device = device::select_by_id(fcm_token.device_id(), self.connection)?;
+            let device = referenced(device, fcm_token)?;
+            result.push(json!({
+                constants::FIELD_NAME_DEVICE_ID: device.uuid().to_string(),
+                constants::FIELD_NAME_FCM_TOKEN_PRESENT: true,
+            }));
+        }
+        Ok(result)
+    }
+
+    fn export_foodstuff(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let foodstuffs = foodstuff::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(foodstuffs.iter().map(foodstuff_to_json).collect())
+    }
+
+    fn export_history_entry(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let entries = history_entry::select_by_app_user_id_and_time_range(
+            self.user.id(),
+            i64::MIN,
+            i64::MAX,
+            self.connection,
+        )?;
+        let mut result = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let foodstuff = self.select_foodstuff(entry.foodstuff_id(), entry)?;
+            result.push(json!({
+                constants::FIELD_NAME_HISTORY_ENTRY_ID: entry.app_user_history_entry_id(),
+                constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                constants::FIELD_NAME_MASS: entry.mass(),
+                constants::FIELD_NAME_TIME: entry.time(),
+            }));
+        }
+        Ok(result)
+    }
+
+    fn export_recipe(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let recipes = recipe::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(recipes
+            .iter()
+            .map(|recipe| {
+                json!({
+                    constants::FIELD_NAME_RECIPE_ID: recipe.app_user_recipe_id(),
+                    constants::FIELD_NAME_RECIPE_NAME: recipe.name(),
+                })
+            })
+            .collect())
+    }
+
+    fn export_recipe_ingredient(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let recipes = recipe::select_by_app_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::new();
+        for recipe in &recipes {
+            let ingredients = recipe_ingredient::select_by_recipe_id(recipe.id(), self.connection)?;
+            for ingredient in &ingredients {
+                let foodstuff = self.select_foodstuff(ingredient.foodstuff_id(), ingredient)?;
+                result.push(json!({
+                    constants::FIELD_NAME_RECIPE_ID: recipe.app_user_recipe_id(),
+                    constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                    constants::FIELD_NAME_WEIGHT: ingredient.weight(),
+                }));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Exports both foodstuffs shared by the user and foodstuffs shared with the user.
+    fn export_foodstuff_share(&mut self) -> Result<Vec<JsonValue>, RequestError> {
+        let mut shares = foodstuff_share::select_by_owner_user_id(self.user.id(), self.connection)?;
+        shares.append(&mut foodstuff_share::select_by_partner_user_id(
+            self.user.id(),
+            self.connection,
+        )?);
+        let mut result = Vec::with_capacity(shares.len());
+        for share in &shares {
+            let owner_uid = self.select_uid(share.owner_user_id(), share)?;
+            let partner_uid = self.select_uid(share.partner_user_id(), share)?;
+            let foodstuff = self.select_foodstuff(share.foodstuff_id(), share)?;
+            result.push(json!({
+                constants::FIELD_NAME_OWNER_USER_ID: owner_uid,
+                constants::FIELD_NAME_PARTNER_USER_ID: partner_uid,
+                constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                constants::FIELD_NAME_PERMISSION: permission_to_str(share.permission()),
+            }));
+        }
+        Ok(result)
+    }
+
+    fn export_meal_plan(&mut self) -> Result<Vec<JsonValue>, RequestError> {
+        let meal_plans = meal_plan::select_by_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::with_capacity(meal_plans.len());
+        for meal_plan in &meal_plans {
+            let partner_uid = self.select_meal_plan_partner_uid(meal_plan)?;
+            result.push(json!({
+                constants::FIELD_NAME_PARTNER_USER_ID: partner_uid,
+                constants::FIELD_NAME_VERSION: meal_plan.version(),
+            }));
+        }
+        Ok(result)
+    }
+
+    /// Exports items of all meal plans of the user, including items of the partners.
+    fn export_meal_plan_item(&mut self) -> Result<Vec<JsonValue>, RequestError> {
+        let meal_plans = meal_plan::select_by_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::new();
+        for meal_plan in &meal_plans {
+            let partner_uid = self.select_meal_plan_partner_uid(meal_plan)?;
+            let items = meal_plan_item::select_by_meal_plan_id(meal_plan.id(), self.connection)?;
+            for item in &items {
+                let mut json_item = json!({
+                    constants::FIELD_NAME_PARTNER_USER_ID: partner_uid,
+                    constants::FIELD_NAME_DAY: item.day(),
+                    constants::FIELD_NAME_MEAL_TYPE: meal_type_to_str(item.meal_type()),
+                    constants::FIELD_NAME_WEIGHT: item.weight(),
+                });
+                let (owner_id, id_field, id) = match (item.foodstuff_id(), item.recipe_id()) {
+                    (Some(foodstuff_id), _) => {
+                        let foodstuff = self.select_foodstuff(foodstuff_id, item)?;
+                        (
+                            foodstuff.app_user_id(),
+                            constants::FIELD_NAME_FOODSTUFF_ID,
+                            foodstuff.app_user_foodstuff_id(),
+                        )
+                    }
+                    (None, Some(recipe_id)) => {
+                        let recipe = recipe::select_by_id(recipe_id, self.connection)?;
+                        let recipe = referenced(recipe, item)?;
+                        (
+                            recipe.app_user_id(),
+                            constants::FIELD_NAME_RECIPE_ID,
+                            recipe.app_user_recipe_id(),
+                        )
+                    }
+                    (None, None) => {
+                        return Err(RequestError::new(
+                            constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                            format!("Meal item has neither foodstuff nor recipe: {:?}", item),
+                        ))
+                    }
+                };
+                let owner_uid = self.select_uid(owner_id, item)?;
+                json_item[constants::FIELD_NAME_OWNER_USER_ID] = json!(owner_uid);
+                json_item[id_field] = json!(id);
+                result.push(json_item);
+            }
+        }
+        Ok(result)
+    }
+
+    fn export_shopping_list(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let lists = shopping_list::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(lists
+            .iter()
+            .map(|list| {
+                json!({
+                    constants::FIELD_NAME_SHOPPING_LIST_ID: list.app_user_shopping_list_id(),
+                    constants::FIELD_NAME_SHOPPING_LIST_NAME: list.name(),
+                })
+            })
+            .collect())
+    }
+
+    fn export_shopping_list_item(&mut self) -> Result<Vec<JsonValue>, RequestError> {
+        let lists = shopping_list::select_by_app_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::new();
+        for list in &lists {
+            let items = shopping_list_item::select_by_shopping_list_id(list.id(), self.connection)?;
+            for item in &items {
+                let foodstuff = self.select_foodstuff(item.foodstuff_id(), item)?;
+                let foodstuff_owner_uid = self.select_uid(foodstuff.app_user_id(), &foodstuff)?;
+                result.push(json!({
+                    constants::FIELD_NAME_SHOPPING_LIST_ID: list.app_user_shopping_list_id(),
+                    constants::FIELD_NAME_ITEM_ID: item.list_item_id(),
+                    constants::FIELD_NAME_OWNER_USER_ID: foodstuff_owner_uid,
+                    constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                    constants::FIELD_NAME_WEIGHT: item.weight(),
+                    constants::FIELD_NAME_IS_CHECKED: item.is_checked(),
+                }));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Exports both lists shared by the user and lists shared with the user.
+    fn export_shopping_list_share(&mut self) -> Result<Vec<JsonValue>, RequestError> {
+        let mut shares = Vec::new();
+        let lists = shopping_list::select_by_app_user_id(self.user.id(), self.connection)?;
+        for list in &lists {
+            shares.append(&mut shopping_list_share::select_by_shopping_list_id(
+                list.id(),
+                self.connection,
+            )?);
+        }
+        shares.append(&mut shopping_list_share::select_by_partner_user_id(
+            self.user.id(),
+            self.connection,
+        )?);
+
+        let mut result = Vec::with_capacity(shares.len());
+        for share in &shares {
+            let list = shopping_list::select_by_id(share.shopping_list_id(), self.connection)?;
+            let list = referenced(list, share)?;
+            let owner_uid = self.select_uid(list.app_user_id(), &list)?;
+            let partner_uid = self.select_uid(share.partner_user_id(), share)?;
+            result.push(json!({
+                constants::FIELD_NAME_OWNER_USER_ID: owner_uid,
+                constants::FIELD_NAME_SHOPPING_LIST_ID: list.app_user_shopping_list_id(),
+                constants::FIELD_NAME_PARTNER_USER_ID: partner_uid,
+            }));
+        }
+        Ok(result)
+    }
+
+    fn export_paired_partners(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let pairs = paired_partners::select_by_partner_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::with_capacity(pairs.len());
+        for pair in &pairs {
+            let partner_id = if pair.partner1_user_id() == self.user.id() {
+                pair.partner2_user_id()
+            } else {
+                pair.partner1_user_id()
+            };
+            let partner = app_user::select_by_id(partner_id, self.connection)?;
+            let partner = referenced(partner, pair)?;
+            let pairing_state = match pair.pairing_state() {
+                PairingState::Done => constants::PAIRING_STATE_DONE,
+                PairingState::NotConfirmed => constants::PAIRING_STATE_NOT_CONFIRMED,
+            };
+            result.push(json!({
+                constants::FIELD_NAME_PARTNER_USER_ID: partner.uid().to_string(),
+                constants::FIELD_NAME_PARTNER_NAME: partner.name(),
+                constants::FIELD_NAME_PAIRING_STATE: pairing_state,
+                constants::FIELD_NAME_PAIRING_START_TIME: pair.pairing_start_time(),
+            }));
+        }
+        Ok(result)
+    }
+
+    fn export_taken_pairing_code(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let codes = taken_pairing_code::select_all_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(codes
+            .iter()
+            .map(|code| {
+                json!({
+                    constants::FIELD_NAME_PAIRING_CODE: code.val(),
+                    constants::FIELD_NAME_CREATION_TIME: code.creation_time(),
+                })
+            })
+            .collect())
+    }
+
+    fn export_failed_pairing_attempts(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let attempts =
+            failed_pairing_attempts::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(attempts
+            .iter()
+            .map(|attempts| {
+                json!({
+                    constants::FIELD_NAME_ATTEMPTS_COUNT: attempts.attempts_count(),
+                    constants::FIELD_NAME_LAST_ATTEMPT_TIME: attempts.last_attempt_time(),
+                })
+            })
+            .collect())
+    }
+
+    /// Exports notifications to the user which are not sent yet (or won't be sent at all).
+    fn export_notification_outbox(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let notifications =
+            notification_outbox::select_by_app_user_id(self.user.id(), self.connection)?;
+        let mut result = Vec::with_capacity(notifications.len());
+        for notification in &notifications {
+            let device = device::select_by_id(notification.device_id(), self.connection)?;
+            let device = referenced(device, notification)?;
+            let notification_state = match notification.state() {
+                NotificationState::Pending => constants::NOTIFICATION_STATE_PENDING,
+                NotificationState::Dead => constants::NOTIFICATION_STATE_DEAD,
+            };
+            result.push(json!({
+                constants::FIELD_NAME_DEVICE_ID: device.uuid().to_string(),
+                constants::FIELD_NAME_MSG: notification.msg(),
+                constants::FIELD_NAME_NOTIFICATION_STATE: notification_state,
+                constants::FIELD_NAME_ATTEMPTS_COUNT: notification.attempts_count(),
+                constants::FIELD_NAME_CREATION_TIME: notification.creation_time(),
+            }));
+        }
+        Ok(result)
+    }
+
+    fn export_inbox_message(&self) -> Result<Vec<JsonValue>, RequestError> {
+        let messages = inbox_message::select_by_app_user_id(self.user.id(), self.connection)?;
+        Ok(messages
+            .iter()
+            .map(|message| {
+                json!({
+                    constants::FIELD_NAME_MSG: message.msg(),
+                    constants::FIELD_NAME_CREATION_TIME: message.creation_time(),
+                })
+            })
+            .collect())
+    }
+
+    /// Selects the foodstuff referenced by the |row|.
+    fn select_foodstuff(&self, id: i32, row: &dyn Debug) -> Result<Foodstuff, RequestError> {
+        let foodstuff = foodstuff::select_by_id(id, self.connection)?;
+        referenced(foodstuff, row)
+    }
+
+    /// Selects UID of the user referenced by the |row|.
+    fn select_uid(&mut self, app_user_id: i32, row: &dyn Debug) -> Result<String, RequestError> {
+        if let Some(uid) = self.uids.get(&app_user_id) {
+            return Ok(uid.clone());
+        }
+        let user = app_user::select_by_id(app_user_id, self.connection)?;
+        let uid = referenced(user, row)?.uid().to_string();
+        self.uids.insert(app_user_id, uid.clone());
+        Ok(uid)
+    }
+
+    fn select_meal_plan_partner_uid(
+        &mut self,
+        meal_plan: &meal_plan::MealPlan,
+    ) -> Result<String, RequestError> {
+        let partner_id = if meal_plan.partner1_user_id() == self.user.id() {
+            meal_plan.partner2_user_id()
+        } else {
+            meal_plan.partner1_user_id()
+        };
+        self.select_uid(partner_id, meal_plan)
+    }
+}
+
+/// The export runs in a single snapshot of the DB, so a row referenced by an exported
+/// row can be missing only if the DB is inconsistent.
+fn referenced<T>(referenced_row: Option<T>, row: &dyn Debug) -> Result<T, RequestError> {
+    referenced_row.ok_or_else(|| {
+        RequestError::new(
+            constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+            format!("Exported row references a missing row: {:?}", row),
+        )
+    })
+}
+
+#[cfg(test)]
+#[path = "./export_data_cmd_handler_test.rs"]
+mod export_data_cmd_handler_test;
@@ -0,0 +1,324 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::device;
+use crate::db::core::notification_outbox;
+use crate::db::core::notification_outbox::NotificationState;
+use crate::db::core::testing_util as dbtesting_utils;
+use crate::db::core::vk_user;
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::add_history_entry;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::pairing_request_by_code_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
+use crate::server::cmds::testing_cmds_utils::share_foodstuff;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list;
+use crate::server::cmds::testing_cmds_utils::start_pairing;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal;
+
+/// Tables which don't store any data of users.
+const NOT_USERS_DATA_TABLES: &[&str] = &["__diesel_schema_migrations", "pairing_code_range"];
+
+fn export_data(server_addr: &str, client_token: &str, uid: &str) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server_addr,
+        &constants::CMD_EXPORT_DATA,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request(&url)
+}
+
+#[test]
+fn export_contains_data_of_all_tables() {
+    let server = start_server!();
+
+    let uuid1 = Uuid::from_str("00000000-f21d-0000-0000-000000000000").unwrap();
+    let uuid2 = Uuid::from_str("00000000-f21d-0000-0000-000000000001").unwrap();
+    let uid1 = uuid1.to_string();
+    let uid2 = uuid2.to_string();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uuid1);
+    delete_app_user_with(&uuid2);
+
+    let client_token1 =
+        register_named_user_return_token(server.address(), &uuid1, &gpuid1, "name1");
+    let client_token2 =
+        register_named_user_return_token(server.address(), &uuid2, &gpuid2, "name2");
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    start_pairing(server.address(), &client_token1, &uid1);
+    // A code out of the range of pairing codes, so that the attempt would fail
+    let response = pairing_request_by_code_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        "10000",
+        &json!({}),
+    );
+    assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+
+    // No commands write VK users and devices of users registered with GP,
+    // so they are inserted into DB directly.
+    let connection = dbtesting_utils::testing_connection_for_server_user().unwrap();
+    let user1 = app_user::select_by_uid(&uuid1, &connection)
+        .unwrap()
+        .unwrap();
+    let vkuid1 = format!("{}{}", uid1, "vkuid");
+    vk_user::insert(vk_user::new(vkuid1.clone(), &user1), &connection).unwrap();
+    let device_uuid = Uuid::new_v4();
+    let device = device::insert(device::new(device_uuid, &user1), &connection).unwrap();
+    // A dead notification, so that nothing would try to send it
+    let notification = notification_outbox::insert(
+        notification_outbox::new("msg1".to_owned(), &user1, &device, 123),
+        &connection,
+    )
+    .unwrap();
+    notification_outbox::record_failed_attempt(
+        &notification,
+        NotificationState::Dead,
+        124,
+        "error",
+        &connection,
+    )
+    .unwrap();
+
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    // Foodstuff of the partner must not be exported
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_history_entry(server.address(), &client_token1, &uid1, 1, 1, 100, 123);
+    create_recipe(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "salad",
+        &[(1, 100)],
+    );
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+    update_meal_plan_meal(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        0,
+        constants::MEAL_TYPE_LUNCH,
+        0,
+        json!([{
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_RECIPE_ID: 1,
+            constants::FIELD_NAME_WEIGHT: 250,
+        }]),
+    );
+    create_shopping_list(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "groceries",
+        json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 120}] }),
+    );
+    share_shopping_list(server.address(), &client_token1, &uid1, 1, &uid2);
+    // The token is set after the partner's commands, so that their notifications wouldn't
+    // stay queued for the user after failing to be sent to the real FCM
+    set_user_fcm_token(server.address(), &client_token1, &uid1, "fcmtoken1");
+
+    let response = export_data(server.address(), &client_token1, &uid1);
+    assert_status_ok(&response);
+    let user_data = &response[constants::FIELD_NAME_USER_DATA];
+    let legacy_device_uuid = device::select_legacy_by_app_user_id(user1.id(), &connection)
+        .unwrap()
+        .unwrap()
+        .uuid()
+        .to_string();
+
+    // Each table with users data must be exported
+    let tables = dbtesting_utils::select_all_tables_names(&connection).unwrap();
+    for table in &tables {
+        if NOT_USERS_DATA_TABLES.contains(&table.as_str()) {
+            continue;
+        }
+        let rows = user_data[table].as_array();
+        assert!(
+            rows.is_some() && !rows.unwrap().is_empty(),
+            "Table {} is not exported: {}",
+            table,
+            user_data
+        );
+    }
+    assert_eq!(
+        tables.len() - NOT_USERS_DATA_TABLES.len(),
+        user_data.as_object().unwrap().len()
+    );
+
+    assert_eq!(
+        json!([{
+            constants::FIELD_NAME_USER_ID: uid1,
+            constants::FIELD_NAME_USER_NAME: "name1",
+        }]),
+        user_data["app_user"]
+    );
+    assert_eq!(
+        json!([{ constants::FIELD_NAME_VK_UID: vkuid1 }]),
+        user_data["vk_user"]
+    );
+    assert_eq!(
+        json!([{ constants::FIELD_NAME_GP_UID: gpuid1 }]),
+        user_data["gp_user"]
+    );
+    assert_eq!(
+        json!([
+            { constants::FIELD_NAME_DEVICE_ID: device_uuid.to_string() },
+            { constants::FIELD_NAME_DEVICE_ID: legacy_device_uuid },
+        ]),
+        user_data["device"]
+    );
+    assert_eq!(
+        json!([{
+            constants::FIELD_NAME_DEVICE_ID: legacy_device_uuid,
+            constants::FIELD_NAME_FCM_TOKEN_PRESENT: true,
+        }]),
+        user_data["fcm_token"]
+    );
+    let foodstuffs = user_data["foodstuff"].as_array().unwrap();
+    assert_eq!(1, foodstuffs.len());
+    assert_eq!("apple", foodstuffs[0][constants::FIELD_NAME_FOODSTUFF_NAME]);
+    assert_eq!(
+        json!([{
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_PARTNER_USER_ID: uid2,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_PERMISSION: constants::PERMISSION_READ_ONLY,
+        }]),
+        user_data["foodstuff_share"]
+    );
+    assert_eq!(
+        json!([{
+            constants::FIELD_NAME_PARTNER_USER_ID: uid2,
+            constants::FIELD_NAME_DAY: 0,
+            constants::FIELD_NAME_MEAL_TYPE: constants::MEAL_TYPE_LUNCH,
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_RECIPE_ID: 1,
+            constants::FIELD_NAME_WEIGHT: 250,
+        }]),
+        user_data["meal_plan_item"]
+    );
+    let attempts = user_data["failed_pairing_attempts"].as_array().unwrap();
+    assert_eq!(1, attempts.len());
+    assert_eq!(1, attempts[0][constants::FIELD_NAME_ATTEMPTS_COUNT]);
+    assert_eq!(
+        json!([{
+            constants::FIELD_NAME_DEVICE_ID: device_uuid.to_string(),
+            constants::FIELD_NAME_MSG: "msg1",
+            constants::FIELD_NAME_NOTIFICATION_STATE: constants::NOTIFICATION_STATE_DEAD,
+            constants::FIELD_NAME_ATTEMPTS_COUNT: 1,
+            constants::FIELD_NAME_CREATION_TIME: 123,
+        }]),
+        user_data["notification_outbox"]
+    );
+    // The user was notified about the pairing
+    let inbox_messages = user_data["inbox_message"].as_array().unwrap();
+    assert_eq!(1, inbox_messages.len());
+    let msg: JsonValue = serde_json::from_str(
+        inbox_messages[0][constants::FIELD_NAME_MSG]
+            .as_str()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        constants::SERV_MSG_PAIRED_WITH_PARTNER,
+        msg[constants::SERV_FIELD_MSG_TYPE]
+    );
+    let partners = user_data["paired_partners"].as_array().unwrap();
+    assert_eq!(1, partners.len());
+    assert_eq!(uid2, partners[0][constants::FIELD_NAME_PARTNER_USER_ID]);
+    assert_eq!("name2", partners[0][constants::FIELD_NAME_PARTNER_NAME]);
+    assert_eq!(
+        constants::PAIRING_STATE_DONE,
+        partners[0][constants::FIELD_NAME_PAIRING_STATE]
+    );
+}
+
+#[test]
+fn export_of_user_without_data() {
+    let server = start_server!();
+
+    let uuid = Uuid::from_str("00000000-f21d-0000-0000-000000000002").unwrap();
+    let uid = uuid.to_string();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uuid);
+    let client_token = register_named_user_return_token(server.address(), &uuid, &gpuid, "name");
+
+    let response = export_data(server.address(), &client_token, &uid);
+    assert_status_ok(&response);
+    let user_data = &response[constants::FIELD_NAME_USER_DATA];
+    assert_eq!(1, user_data["app_user"].as_array().unwrap().len());
+    assert_eq!(1, user_data["gp_user"].as_array().unwrap().len());
+    assert_eq!(json!([]), user_data["vk_user"]);
+    assert_eq!(json!([]), user_data["fcm_token"]);
+    assert_eq!(json!([]), user_data["foodstuff"]);
+    assert_eq!(json!([]), user_data["paired_partners"]);
+}
+
+#[test]
+fn export_with_invalid_client_token() {
+    let server = start_server!();
+
+    let uuid = Uuid::from_str("00000000-f21d-0000-0000-000000000003").unwrap();
+    let uid = uuid.to_string();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uuid);
+    register_named_user_return_token(server.address(), &uuid, &gpuid, "name");
+
+    let response = export_data(server.address(), &Uuid::new_v4().to_string(), &uid);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_CLIENT_TOKEN);
+}
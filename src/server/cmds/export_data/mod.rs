@@ -0,0 +1 @@
+pub mod export_data_cmd_handler;
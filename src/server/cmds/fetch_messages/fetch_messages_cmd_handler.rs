@@ -0,0 +1,136 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::inbox_message;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Max number of messages returned at once.
+const FETCH_MESSAGES_LIMIT: i64 = 100;
+const CURSOR_IDS_SEPARATOR: &str = ",";
+
+/// Returns the oldest messages from the inbox of the user, so that clients without FCM
+/// could poll for them.
+/// The response contains a cursor - when the client sends it as the 'cursor' arg
+/// with the next request, the returned messages are acknowledged and deleted from
+/// the inbox, and the messages after them are returned.
+/// The cursor is opaque for clients. It lists IDs of the returned messages instead of
+/// being the greatest of them, because messages stored by concurrent transactions
+/// can be committed not in the order of their IDs - a message with a lesser ID
+/// can appear after the messages with greater IDs were returned.
+#[derive(Default)]
+pub struct FetchMessagesCmdHandler {}
+
+#[derive(Deserialize)]
+struct FetchMessagesArgs {
+    cursor: Option<String>,
+}
+
+impl CmdHandler for FetchMessagesCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl FetchMessagesCmdHandler {
+    pub fn new() -> Self {
+        FetchMessagesCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: FetchMessagesArgs = parse_args(&args)?;
+        let acknowledged_ids = match cmd_args.cursor {
+            Some(cursor) => parse_cursor(&cursor)?,
+            None => Vec::new(),
+        };
+
+        let messages = db_transaction(&connection, || {
+            inbox_message::delete_by_ids(user.id(), &acknowledged_ids, &connection)?;
+            let messages = inbox_message::select_first_by_app_user_id(
+                user.id(),
+                FETCH_MESSAGES_LIMIT,
+                &connection,
+            )?;
+            Ok(messages)
+        })?;
+
+        let new_cursor = messages
+            .iter()
+            .map(|message| message.id().to_string())
+            .collect::<Vec<String>>()
+            .join(CURSOR_IDS_SEPARATOR);
+        let mut json_messages = Vec::with_capacity(messages.len());
+        for message in &messages {
+            // Messages are stored as the JSON objects sent with FCM
+            let msg: JsonValue = serde_json::from_str(message.msg()).map_err(|err| {
+                RequestError::new(
+                    constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                    format!(
+                        "Inbox message {} is not a valid JSON: {}",
+                        message.id(),
+                        err
+                    ),
+                )
+            })?;
+            json_messages.push(json!({
+                constants::FIELD_NAME_MSG: msg,
+                constants::FIELD_NAME_CREATION_TIME: message.creation_time(),
+            }));
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_MESSAGES: json_messages,
+            constants::FIELD_NAME_CURSOR: new_cursor,
+        }))
+    }
+}
+
+fn parse_cursor(cursor: &str) -> Result<Vec<i32>, RequestError> {
+    if cursor.is_empty() {
+        return Ok(Vec::new());
+    }
+    cursor
+        .split(CURSOR_IDS_SEPARATOR)
+        .map(|id| {
+            id.parse::<i32>().map_err(|_| {
+                RequestError::new(
+                    constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                    format!("Invalid cursor: {}", cursor),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./fetch_messages_cmd_handler_test.rs"]
+mod fetch_messages_cmd_handler_test;
@@ -0,0 +1,243 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::testing_util as dbtesting_utils;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::constants;
+use crate::server::inbox;
+
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::fetch_messages;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::make_request_with_body;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+fn direct_partner_msg(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    partner_uid: &str,
+    msg: &str,
+) {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_DIRECT_PARTNER_MSG,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(partner_uid.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+    );
+    let response = make_request_with_body(&url, msg.to_owned());
+    assert_status_ok(&response);
+}
+
+fn msg_types(response: &JsonValue) -> Vec<&str> {
+    response[constants::FIELD_NAME_MESSAGES]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| {
+            message[constants::FIELD_NAME_MSG][constants::SERV_FIELD_MSG_TYPE]
+                .as_str()
+                .unwrap()
+        })
+        .collect()
+}
+
+fn cursor(response: &JsonValue) -> &str {
+    response[constants::FIELD_NAME_CURSOR].as_str().unwrap()
+}
+
+#[test]
+fn messages_are_stored_for_users_without_fcm_tokens() {
+    let server = start_server!();
+
+    let uuid1 = Uuid::from_str("00000000-f220-0000-0000-000000000000").unwrap();
+    let uuid2 = Uuid::from_str("00000000-f220-0000-0000-000000000001").unwrap();
+    let uid1 = uuid1.to_string();
+    let uid2 = uuid2.to_string();
+    delete_app_user_with(&uuid1);
+    delete_app_user_with(&uuid2);
+
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let client_token1 =
+        register_named_user_return_token(server.address(), &uuid1, &gpuid1, "name1");
+    let client_token2 =
+        register_named_user_return_token(server.address(), &uuid2, &gpuid2, "name2");
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    direct_partner_msg(server.address(), &client_token1, &uid1, &uid2, "hello");
+
+    let response = fetch_messages(server.address(), &client_token2, &uid2, "");
+    assert_eq!(
+        vec![
+            constants::SERV_MSG_PAIRING_REQUEST_FROM_PARTNER,
+            constants::SERV_MSG_PAIRED_WITH_PARTNER,
+            constants::SERV_MSG_DIRECT_MSG_FROM_PARTNER,
+        ],
+        msg_types(&response)
+    );
+    let messages = response[constants::FIELD_NAME_MESSAGES].as_array().unwrap();
+    assert_eq!(
+        uid1,
+        messages[0][constants::FIELD_NAME_MSG][constants::SERV_FIELD_PAIRING_PARTNER_USER_ID]
+    );
+    let direct_msg = &messages[2][constants::FIELD_NAME_MSG];
+    assert_eq!(uid1, direct_msg[constants::SERV_FIELD_PARTNER_USER_ID]);
+    assert_eq!("hello", direct_msg[constants::SERV_FIELD_MSG]);
+    assert!(
+        messages[2][constants::FIELD_NAME_CREATION_TIME]
+            .as_i64()
+            .unwrap()
+            > 0
+    );
+
+    let response = fetch_messages(server.address(), &client_token1, &uid1, "");
+    assert_eq!(
+        vec![constants::SERV_MSG_PAIRED_WITH_PARTNER],
+        msg_types(&response)
+    );
+}
+
+#[test]
+fn messages_are_acknowledged_by_cursor() {
+    let server = start_server!();
+
+    let uuid1 = Uuid::from_str("00000000-f220-0000-0000-000000000002").unwrap();
+    let uuid2 = Uuid::from_str("00000000-f220-0000-0000-000000000003").unwrap();
+    let uid1 = uuid1.to_string();
+    let uid2 = uuid2.to_string();
+    delete_app_user_with(&uuid1);
+    delete_app_user_with(&uuid2);
+
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let client_token1 =
+        register_named_user_return_token(server.address(), &uuid1, &gpuid1, "name1");
+    let client_token2 =
+        register_named_user_return_token(server.address(), &uuid2, &gpuid2, "name2");
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+
+    let response = fetch_messages(server.address(), &client_token2, &uid2, "");
+    assert_eq!(2, msg_types(&response).len());
+    let cursor1 = cursor(&response).to_owned();
+    assert!(!cursor1.is_empty());
+
+    // Messages are not acknowledged until the cursor is sent back
+    let response = fetch_messages(server.address(), &client_token2, &uid2, "");
+    assert_eq!(2, msg_types(&response).len());
+    assert_eq!(cursor1, cursor(&response));
+
+    let response = fetch_messages(server.address(), &client_token2, &uid2, &cursor1);
+    assert!(msg_types(&response).is_empty());
+    assert_eq!("", cursor(&response));
+
+    direct_partner_msg(server.address(), &client_token1, &uid1, &uid2, "hello");
+    let response = fetch_messages(server.address(), &client_token2, &uid2, &cursor1);
+    assert_eq!(
+        vec![constants::SERV_MSG_DIRECT_MSG_FROM_PARTNER],
+        msg_types(&response)
+    );
+    assert_ne!(cursor1, cursor(&response));
+
+    // Messages of the partner are not acknowledged by the user's cursor
+    let response = fetch_messages(server.address(), &client_token1, &uid1, &cursor1);
+    assert_eq!(1, msg_types(&response).len());
+}
+
+#[test]
+fn messages_committed_out_of_ids_order_are_not_lost() {
+    let server = start_server!();
+
+    let uuid = Uuid::from_str("00000000-f220-0000-0000-000000000005").unwrap();
+    let uid = uuid.to_string();
+    delete_app_user_with(&uuid);
+    let gpuid = format!("{}{}", uid, "gpuid");
+    let client_token = register_named_user_return_token(server.address(), &uuid, &gpuid, "name");
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = app_user::select_by_uid(&uuid, &connection)
+        .unwrap()
+        .unwrap();
+    let msg1 = json!({ constants::SERV_FIELD_MSG_TYPE: "msg1" }).to_string();
+    let msg2 = json!({ constants::SERV_FIELD_MSG_TYPE: "msg2" }).to_string();
+
+    // The first message gets the lesser ID, but is committed after the second one
+    // is fetched
+    let (stored_sender, stored_receiver) = mpsc::channel();
+    let (commit_sender, commit_receiver) = mpsc::channel::<()>();
+    let slow_transaction = thread::spawn(move || {
+        let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+        let user = app_user::select_by_uid(&uuid, &connection)
+            .unwrap()
+            .unwrap();
+        db_transaction(&connection, || {
+            inbox::store(&user, msg1, &connection)?;
+            stored_sender.send(()).unwrap();
+            commit_receiver.recv().unwrap();
+            Ok(())
+        })
+        .unwrap();
+    });
+    stored_receiver.recv().unwrap();
+    inbox::store(&user, msg2, &connection).unwrap();
+
+    let response = fetch_messages(server.address(), &client_token, &uid, "");
+    assert_eq!(vec!["msg2"], msg_types(&response));
+    let cursor1 = cursor(&response).to_owned();
+
+    commit_sender.send(()).unwrap();
+    slow_transaction.join().unwrap();
+
+    let response = fetch_messages(server.address(), &client_token, &uid, &cursor1);
+    assert_eq!(vec!["msg1"], msg_types(&response));
+}
+
+#[test]
+fn invalid_cursor() {
+    let server = start_server!();
+
+    let uuid = Uuid::from_str("00000000-f220-0000-0000-000000000004").unwrap();
+    let uid = uuid.to_string();
+    delete_app_user_with(&uuid);
+    let gpuid = format!("{}{}", uid, "gpuid");
+    let client_token = register_named_user_return_token(server.address(), &uuid, &gpuid, "name");
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_FETCH_MESSAGES,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CURSOR,
+        "abc",
+    );
+    let response = make_request(&url);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
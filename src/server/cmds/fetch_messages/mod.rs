@@ -0,0 +1 @@
+pub mod fetch_messages_cmd_handler;
@@ -0,0 +1,67 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::foodstuff_share::SharePermission;
+use crate::db::core::paired_partners;
+use crate::db::core::paired_partners::PairingState;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+pub fn parse_permission(permission: &str) -> Result<SharePermission, RequestError> {
+    match permission {
+        constants::PERMISSION_READ_ONLY => Ok(SharePermission::ReadOnly),
+        constants::PERMISSION_EDITABLE => Ok(SharePermission::Editable),
+        _ => Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid permission: {}", permission),
+        )),
+    }
+}
+
+pub fn permission_to_str(permission: SharePermission) -> &'static str {
+    match permission {
+        SharePermission::ReadOnly => constants::PERMISSION_READ_ONLY,
+        SharePermission::Editable => constants::PERMISSION_EDITABLE,
+    }
+}
+
+/// Selects the partner with the given UID only if the pairing of the user and
+/// the partner is done.
+pub fn select_paired_partner(
+    user: &AppUser,
+    partner_uid: &str,
+    connection: &dyn DBConnection,
+) -> Result<AppUser, RequestError> {
+    let partner = app_user::select_by_uid(&Uuid::from_str(partner_uid)?, connection)?;
+    let partner = match partner {
+        Some(partner) => partner,
+        None => return Err(partner_not_found_error(partner_uid)),
+    };
+
+    let pp1 = paired_partners::select_by_partners_user_ids_and_state(
+        user.id(),
+        partner.id(),
+        PairingState::Done,
+        connection,
+    )?;
+    let pp2 = paired_partners::select_by_partners_user_ids_and_state(
+        partner.id(),
+        user.id(),
+        PairingState::Done,
+        connection,
+    )?;
+    if pp1.is_none() && pp2.is_none() {
+        return Err(partner_not_found_error(partner_uid));
+    }
+    Ok(partner)
+}
+
+fn partner_not_found_error(partner_uid: &str) -> RequestError {
+    RequestError::new(
+        constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND.to_string(),
+        format!("Partner user was not found. Given uid: {:?}", partner_uid),
+    )
+}
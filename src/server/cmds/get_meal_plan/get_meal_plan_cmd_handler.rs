@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::meal_plan;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::foodstuff_share_utils::select_paired_partner;
+use crate::server::cmds::meal_plan_utils::meal_plan_to_json;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+/// Returns the meal plan shared by the user and the partner,
+/// see |meal_plan_utils::meal_plan_to_json| for its format.
+#[derive(Default)]
+pub struct GetMealPlanCmdHandler {}
+
+#[derive(Deserialize)]
+struct GetMealPlanArgs {
+    partner_user_id: String,
+}
+
+impl CmdHandler for GetMealPlanCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl GetMealPlanCmdHandler {
+    pub fn new() -> Self {
+        GetMealPlanCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: GetMealPlanArgs = parse_args(&args)?;
+        let partner_uid = cmd_args.partner_user_id;
+        let partner = select_paired_partner(&user, &partner_uid, &connection)?;
+
+        let meal_plan =
+            meal_plan::select_by_partners_user_ids(user.id(), partner.id(), &connection)?;
+        let meal_plan_json = meal_plan_to_json(meal_plan.as_ref(), &user, &partner, &connection)?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_VERSION: meal_plan_json[constants::FIELD_NAME_VERSION],
+            constants::FIELD_NAME_MEALS: meal_plan_json[constants::FIELD_NAME_MEALS],
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./get_meal_plan_cmd_handler_test.rs"]
+mod get_meal_plan_cmd_handler_test;
@@ -0,0 +1,218 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::get_meal_plan;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal;
+use crate::server::constants;
+
+/// Registers and pairs 2 users, returns their client tokens.
+fn register_partners(server_addr: &str, uid1: &Uuid, uid2: &Uuid) -> (String, String) {
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(uid1);
+    delete_app_user_with(uid2);
+
+    let client_token1 = register_named_user_return_token(server_addr, uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server_addr, uid2, &gpuid2, "name2");
+    pair(
+        server_addr,
+        &client_token1,
+        &uid1.to_string(),
+        &client_token2,
+        &uid2.to_string(),
+    );
+    (client_token1, client_token2)
+}
+
+#[test]
+fn not_created_meal_plan_is_empty() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f216-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f216-0000-0000-000000000001").unwrap();
+    let (client_token1, _) = register_partners(server.address(), &uid1, &uid2);
+
+    let response = get_meal_plan(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &uid2.to_string(),
+    );
+    assert_eq!(0, response[constants::FIELD_NAME_VERSION]);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_MEALS]);
+}
+
+#[test]
+fn meals_are_ordered_by_day_and_meal_type() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f216-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f216-0000-0000-000000000003").unwrap();
+    let (client_token1, client_token2) = register_partners(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let items = json!([{
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 100,
+    }]);
+
+    let meals = [
+        (1, constants::MEAL_TYPE_DINNER),
+        (0, constants::MEAL_TYPE_BREAKFAST),
+        (1, constants::MEAL_TYPE_BREAKFAST),
+    ];
+    for (index, (day, meal_type)) in meals.iter().enumerate() {
+        update_meal_plan_meal(
+            server.address(),
+            &client_token1,
+            &uid1,
+            &uid2,
+            *day,
+            meal_type,
+            index as i64,
+            items.clone(),
+        );
+    }
+
+    let response = get_meal_plan(server.address(), &client_token2, &uid2, &uid1);
+    assert_eq!(3, response[constants::FIELD_NAME_VERSION]);
+    let expected_meals = json!([
+        {
+            constants::FIELD_NAME_DAY: 0,
+            constants::FIELD_NAME_MEAL_TYPE: constants::MEAL_TYPE_BREAKFAST,
+            constants::FIELD_NAME_ITEMS: items,
+        },
+        {
+            constants::FIELD_NAME_DAY: 1,
+            constants::FIELD_NAME_MEAL_TYPE: constants::MEAL_TYPE_BREAKFAST,
+            constants::FIELD_NAME_ITEMS: items,
+        },
+        {
+            constants::FIELD_NAME_DAY: 1,
+            constants::FIELD_NAME_MEAL_TYPE: constants::MEAL_TYPE_DINNER,
+            constants::FIELD_NAME_ITEMS: items,
+        }
+    ]);
+    assert_eq!(expected_meals, response[constants::FIELD_NAME_MEALS]);
+}
+
+#[test]
+fn deleted_recipe_is_removed_from_meal_plan() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f216-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-f216-0000-0000-000000000005").unwrap();
+    let (client_token1, _) = register_partners(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_recipe(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple pie",
+        &[(1, 100)],
+    );
+    let foodstuff_item = json!({
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 100,
+    });
+    let recipe_item = json!({
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_RECIPE_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 300,
+    });
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        0,
+        constants::MEAL_TYPE_DINNER,
+        0,
+        json!([foodstuff_item, recipe_item]),
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_DELETE_RECIPE,
+        &constants::ARG_USER_ID,
+        percent_encode(uid1.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token1.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_RECIPE_ID,
+        1,
+    );
+    assert_status_ok(&make_request(&url));
+
+    let response = get_meal_plan(server.address(), &client_token1, &uid1, &uid2);
+    assert_eq!(
+        json!([foodstuff_item]),
+        response[constants::FIELD_NAME_MEALS][0][constants::FIELD_NAME_ITEMS]
+    );
+    // The plan is modified, so its version is incremented
+    assert_eq!(2, response[constants::FIELD_NAME_VERSION]);
+}
+
+#[test]
+fn cannot_get_meal_plan_with_not_paired_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f216-0000-0000-000000000006").unwrap();
+    let uid2 = Uuid::from_str("00000000-f216-0000-0000-000000000007").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_GET_MEAL_PLAN,
+        &constants::ARG_USER_ID,
+        percent_encode(uid1.to_string().as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token1.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(uid2.to_string().as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let response = make_request(&url);
+    assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+}
@@ -0,0 +1 @@
+pub mod get_meal_plan_cmd_handler;
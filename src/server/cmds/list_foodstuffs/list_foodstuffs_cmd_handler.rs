@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::foodstuff;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::nutrition::nutrients::to_decimal_string;
+use crate::nutrition::nutrients::Nutrients;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+#[derive(Default)]
+pub struct ListFoodstuffsCmdHandler {}
+
+impl CmdHandler for ListFoodstuffsCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ListFoodstuffsCmdHandler {
+    pub fn new() -> Self {
+        ListFoodstuffsCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let foodstuffs = foodstuff::select_by_app_user_id(user.id(), &connection)?;
+
+        // Unlisted foodstuffs are hidden from the user
+        let json_foodstuffs: Vec<_> = foodstuffs
+            .iter()
+            .filter(|foodstuff| foodstuff.is_listed())
+            .map(|foodstuff| {
+                let nutrients = Nutrients::of_foodstuff(foodstuff);
+                json!({
+                    constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                    constants::FIELD_NAME_FOODSTUFF_NAME: foodstuff.name(),
+                    constants::FIELD_NAME_PROTEIN: to_decimal_string(nutrients.protein()),
+                    constants::FIELD_NAME_FATS: to_decimal_string(nutrients.fats()),
+                    constants::FIELD_NAME_CARBS: to_decimal_string(nutrients.carbs()),
+                    constants::FIELD_NAME_CALORIES: to_decimal_string(nutrients.calories()),
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_FOODSTUFFS: json_foodstuffs
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./list_foodstuffs_cmd_handler_test.rs"]
+mod list_foodstuffs_cmd_handler_test;
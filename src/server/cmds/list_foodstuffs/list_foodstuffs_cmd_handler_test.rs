@@ -0,0 +1,124 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::constants;
+
+#[test]
+fn list_foodstuffs_test() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f203-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f203-0000-0000-000000000001").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        2,
+        "pear",
+        "5",
+        "6",
+        "7",
+        "8",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        1,
+        "plum",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = list_foodstuffs(server.address(), &client_token1, &uid1.to_string());
+    let expected_foodstuffs = json!([
+        {
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "apple",
+            constants::FIELD_NAME_PROTEIN: "1",
+            constants::FIELD_NAME_FATS: "2",
+            constants::FIELD_NAME_CARBS: "3",
+            constants::FIELD_NAME_CALORIES: "4",
+        },
+        {
+            constants::FIELD_NAME_FOODSTUFF_ID: 2,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "pear",
+            constants::FIELD_NAME_PROTEIN: "5",
+            constants::FIELD_NAME_FATS: "6",
+            constants::FIELD_NAME_CARBS: "7",
+            constants::FIELD_NAME_CALORIES: "8",
+        }
+    ]);
+    assert_eq!(
+        expected_foodstuffs,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+    );
+}
+
+#[test]
+fn unlisted_foodstuffs_are_not_listed() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f203-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_UNLIST_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.to_string().as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        1,
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+
+    let response = list_foodstuffs(server.address(), &client_token, &uid.to_string());
+    assert_eq!(json!([]), response[constants::FIELD_NAME_FOODSTUFFS]);
+}
@@ -0,0 +1 @@
+pub mod list_foodstuffs_cmd_handler;
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::foodstuff;
+use crate::db::core::history_entry;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Lists history entries of the user with time in range [time_from, time_to).
+#[derive(Default)]
+pub struct ListHistoryCmdHandler {}
+
+#[derive(Deserialize)]
+struct ListHistoryArgs {
+    time_from: i64,
+    time_to: i64,
+}
+
+impl CmdHandler for ListHistoryCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ListHistoryCmdHandler {
+    pub fn new() -> Self {
+        ListHistoryCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: ListHistoryArgs = parse_args(&args)?;
+        let time_from = cmd_args.time_from;
+        let time_to = cmd_args.time_to;
+        if time_to < time_from {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                format!("Invalid time range: [{}, {})", time_from, time_to),
+            ));
+        }
+
+        let entries = history_entry::select_by_app_user_id_and_time_range(
+            user.id(),
+            time_from,
+            time_to,
+            &connection,
+        )?;
+        // History entries reference foodstuffs by their server-side IDs,
+        // but clients know only their own IDs of the foodstuffs.
+        let foodstuffs_ids: HashMap<i32, i32> =
+            foodstuff::select_by_app_user_id(user.id(), &connection)?
+                .iter()
+                .map(|foodstuff| (foodstuff.id(), foodstuff.app_user_foodstuff_id()))
+                .collect();
+
+        let mut json_entries = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let app_user_foodstuff_id = match foodstuffs_ids.get(&entry.foodstuff_id()) {
+                Some(id) => *id,
+                None => {
+                    return Err(RequestError::new(
+                        constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                        format!("History entry has foreign foodstuff: {:?}", entry),
+                    ))
+                }
+            };
+            json_entries.push(json!({
+                constants::FIELD_NAME_HISTORY_ENTRY_ID: entry.app_user_history_entry_id(),
+                constants::FIELD_NAME_FOODSTUFF_ID: app_user_foodstuff_id,
+                constants::FIELD_NAME_MASS: entry.mass(),
+                constants::FIELD_NAME_TIME: entry.time(),
+            }));
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_HISTORY_ENTRIES: json_entries
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./list_history_cmd_handler_test.rs"]
+mod list_history_cmd_handler_test;
@@ -0,0 +1,97 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::add_history_entry;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_history;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+#[test]
+fn list_history_by_time_range() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f207-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_history_entry(server.address(), &client_token, &uid, 10, 1, 150, 3000);
+    add_history_entry(server.address(), &client_token, &uid, 11, 2, 200, 1000);
+    add_history_entry(server.address(), &client_token, &uid, 12, 1, 250, 2000);
+
+    let response = list_history(server.address(), &client_token, &uid, 1000, 3000);
+    assert_status_ok(&response);
+    let expected_entries = json!([
+        {
+            constants::FIELD_NAME_HISTORY_ENTRY_ID: 11,
+            constants::FIELD_NAME_FOODSTUFF_ID: 2,
+            constants::FIELD_NAME_MASS: 200,
+            constants::FIELD_NAME_TIME: 1000,
+        },
+        {
+            constants::FIELD_NAME_HISTORY_ENTRY_ID: 12,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_MASS: 250,
+            constants::FIELD_NAME_TIME: 2000,
+        }
+    ]);
+    assert_eq!(
+        expected_entries,
+        response[constants::FIELD_NAME_HISTORY_ENTRIES]
+    );
+}
+
+#[test]
+fn list_history_of_user_without_history() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f207-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = list_history(server.address(), &client_token, &uid.to_string(), 0, 1000);
+    assert_status_ok(&response);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_HISTORY_ENTRIES]);
+}
+
+#[test]
+fn invalid_time_range() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f207-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = list_history(server.address(), &client_token, &uid.to_string(), 1000, 0);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
@@ -0,0 +1 @@
+pub mod list_history_cmd_handler;
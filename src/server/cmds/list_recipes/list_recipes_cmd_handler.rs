@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::recipe;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::recipe_utils::recipe_to_json;
+use crate::server::cmds::recipe_utils::select_foodstuffs_of;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+#[derive(Default)]
+pub struct ListRecipesCmdHandler {}
+
+impl CmdHandler for ListRecipesCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ListRecipesCmdHandler {
+    pub fn new() -> Self {
+        ListRecipesCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+
+        let recipes = recipe::select_by_app_user_id(user.id(), &connection)?;
+        let foodstuffs = select_foodstuffs_of(&user, &connection)?;
+        let mut json_recipes = Vec::with_capacity(recipes.len());
+        for recipe in &recipes {
+            json_recipes.push(recipe_to_json(recipe, &foodstuffs, &connection)?);
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_RECIPES: json_recipes
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./list_recipes_cmd_handler_test.rs"]
+mod list_recipes_cmd_handler_test;
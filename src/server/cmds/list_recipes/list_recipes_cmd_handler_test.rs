@@ -0,0 +1,120 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_recipes;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+#[test]
+fn recipes_of_other_users_are_not_listed() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f211-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f211-0000-0000-000000000001").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "oats",
+        "10",
+        "10",
+        "10",
+        "10",
+    );
+    create_recipe(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+
+    let response = list_recipes(server.address(), &client_token2, &uid2);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_RECIPES]);
+    let response = list_recipes(server.address(), &client_token1, &uid1);
+    assert_eq!(
+        1,
+        response[constants::FIELD_NAME_RECIPES]
+            .as_array()
+            .unwrap()
+            .len()
+    );
+}
+
+#[test]
+fn recipe_nutrients_follow_foodstuffs_updates() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f211-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "oats",
+        "10",
+        "10",
+        "10",
+        "10",
+    );
+    create_recipe(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "porridge",
+        &[(1, 100)],
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_UPDATE_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        1,
+        &constants::ARG_FOODSTUFF_NAME,
+        "oats",
+        &constants::ARG_PROTEIN,
+        "20",
+        &constants::ARG_FATS,
+        "20",
+        &constants::ARG_CARBS,
+        "20",
+        &constants::ARG_CALORIES,
+        "20",
+    );
+    assert_status_ok(&make_request(&url));
+
+    let response = list_recipes(server.address(), &client_token, &uid);
+    let recipe = &response[constants::FIELD_NAME_RECIPES][0];
+    assert_eq!("20", recipe[constants::FIELD_NAME_PROTEIN]);
+    assert_eq!("20", recipe[constants::FIELD_NAME_CALORIES]);
+}
@@ -0,0 +1 @@
+pub mod list_recipes_cmd_handler;
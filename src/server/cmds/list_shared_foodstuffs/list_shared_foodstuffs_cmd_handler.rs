@@ -0,0 +1,102 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff_share;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::nutrition::nutrients::to_decimal_string;
+use crate::nutrition::nutrients::Nutrients;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::foodstuff_share_utils::permission_to_str;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+/// Lists foodstuffs which partners shared with the user.
+#[derive(Default)]
+pub struct ListSharedFoodstuffsCmdHandler {}
+
+impl CmdHandler for ListSharedFoodstuffsCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ListSharedFoodstuffsCmdHandler {
+    pub fn new() -> Self {
+        ListSharedFoodstuffsCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let shares = foodstuff_share::select_by_partner_user_id(user.id(), &connection)?;
+
+        let mut owners = HashMap::new();
+        let mut json_foodstuffs = Vec::with_capacity(shares.len());
+        for share in &shares {
+            let foodstuff = foodstuff::select_by_id(share.foodstuff_id(), &connection)?;
+            let foodstuff = match foodstuff {
+                Some(foodstuff) => foodstuff,
+                None => continue, // Foodstuff was deleted a couple of ms ago
+            };
+            // Unlisted foodstuffs are hidden from their owners, and so from partners
+            if !foodstuff.is_listed() {
+                continue;
+            }
+            let owner = match owners.entry(share.owner_user_id()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(app_user::select_by_id(share.owner_user_id(), &connection)?)
+                }
+            };
+            let owner = match owner {
+                Some(owner) => owner,
+                None => continue, // Owner was deleted a couple of ms ago
+            };
+
+            let nutrients = Nutrients::of_foodstuff(&foodstuff);
+            json_foodstuffs.push(json!({
+                constants::FIELD_NAME_PARTNER_USER_ID: owner.uid().to_string(),
+                constants::FIELD_NAME_PARTNER_NAME: owner.name(),
+                constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                constants::FIELD_NAME_FOODSTUFF_NAME: foodstuff.name(),
+                constants::FIELD_NAME_PROTEIN: to_decimal_string(nutrients.protein()),
+                constants::FIELD_NAME_FATS: to_decimal_string(nutrients.fats()),
+                constants::FIELD_NAME_CARBS: to_decimal_string(nutrients.carbs()),
+                constants::FIELD_NAME_CALORIES: to_decimal_string(nutrients.calories()),
+                constants::FIELD_NAME_PERMISSION: permission_to_str(share.permission()),
+            }));
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_FOODSTUFFS: json_foodstuffs
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./list_shared_foodstuffs_cmd_handler_test.rs"]
+mod list_shared_foodstuffs_cmd_handler_test;
@@ -0,0 +1,209 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shared_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::share_foodstuff;
+use crate::server::constants;
+
+#[test]
+fn only_foodstuffs_shared_with_user_are_listed() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f213-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f213-0000-0000-000000000001").unwrap();
+    let uid3 = Uuid::from_str("00000000-f213-0000-0000-000000000002").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let gpuid3 = format!("{}{}", uid3, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+    delete_app_user_with(&uid3);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let client_token3 = register_named_user_return_token(server.address(), &uid3, &gpuid3, "name3");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    let uid3 = uid3.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    pair(
+        server.address(),
+        &client_token3,
+        &uid3,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        2,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token3,
+        &uid3,
+        1,
+        "plum",
+        "5",
+        "6",
+        "7",
+        "8",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "kiwi",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        2,
+        &uid2,
+        constants::PERMISSION_EDITABLE,
+    );
+    share_foodstuff(
+        server.address(),
+        &client_token3,
+        &uid3,
+        1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+    // Shared by the user, not with the user
+    share_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        &uid1,
+        constants::PERMISSION_READ_ONLY,
+    );
+
+    let response = list_shared_foodstuffs(server.address(), &client_token2, &uid2);
+    let expected_foodstuffs = json!([
+        {
+            constants::FIELD_NAME_PARTNER_USER_ID: uid1,
+            constants::FIELD_NAME_PARTNER_NAME: "name1",
+            constants::FIELD_NAME_FOODSTUFF_ID: 2,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "pear",
+            constants::FIELD_NAME_PROTEIN: "1",
+            constants::FIELD_NAME_FATS: "2",
+            constants::FIELD_NAME_CARBS: "3",
+            constants::FIELD_NAME_CALORIES: "4",
+            constants::FIELD_NAME_PERMISSION: constants::PERMISSION_EDITABLE,
+        },
+        {
+            constants::FIELD_NAME_PARTNER_USER_ID: uid3,
+            constants::FIELD_NAME_PARTNER_NAME: "name3",
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "plum",
+            constants::FIELD_NAME_PROTEIN: "5",
+            constants::FIELD_NAME_FATS: "6",
+            constants::FIELD_NAME_CARBS: "7",
+            constants::FIELD_NAME_CALORIES: "8",
+            constants::FIELD_NAME_PERMISSION: constants::PERMISSION_READ_ONLY,
+        }
+    ]);
+    assert_eq!(
+        expected_foodstuffs,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+    );
+}
+
+#[test]
+fn unlisted_foodstuffs_are_not_listed() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f213-0000-0000-000000000003").unwrap();
+    let uid2 = Uuid::from_str("00000000-f213-0000-0000-000000000004").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_UNLIST_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid1.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token1.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        1,
+    );
+    assert_status_ok(&make_request(&url));
+
+    let response = list_shared_foodstuffs(server.address(), &client_token2, &uid2);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_FOODSTUFFS]);
+}
@@ -0,0 +1 @@
+pub mod list_shared_foodstuffs_cmd_handler;
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list_share;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::shopping_list_utils::shopping_list_to_json;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+/// Lists shopping lists of the user and then shopping lists shared with the user.
+#[derive(Default)]
+pub struct ListShoppingListsCmdHandler {}
+
+impl CmdHandler for ListShoppingListsCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ListShoppingListsCmdHandler {
+    pub fn new() -> Self {
+        ListShoppingListsCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+
+        let mut json_shopping_lists = Vec::new();
+        for shopping_list in shopping_list::select_by_app_user_id(user.id(), &connection)? {
+            json_shopping_lists.push(shopping_list_to_json(&shopping_list, &user, &connection)?);
+        }
+        for share in shopping_list_share::select_by_partner_user_id(user.id(), &connection)? {
+            let shopping_list = shopping_list::select_by_id(share.shopping_list_id(), &connection)?;
+            let shopping_list = match shopping_list {
+                Some(shopping_list) => shopping_list,
+                None => continue, // Shopping list was deleted a couple of ms ago
+            };
+            let owner = match app_user::select_by_id(shopping_list.app_user_id(), &connection)? {
+                Some(owner) => owner,
+                None => continue, // Owner was deleted a couple of ms ago
+            };
+            json_shopping_lists.push(shopping_list_to_json(&shopping_list, &owner, &connection)?);
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_SHOPPING_LISTS: json_shopping_lists,
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./list_shopping_lists_cmd_handler_test.rs"]
+mod list_shopping_lists_cmd_handler_test;
@@ -0,0 +1,123 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shopping_lists;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list;
+
+#[test]
+fn own_lists_are_listed_before_shared_ones() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f219-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f219-0000-0000-000000000001").unwrap();
+    let uid3 = Uuid::from_str("00000000-f219-0000-0000-000000000002").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let gpuid3 = format!("{}{}", uid3, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+    delete_app_user_with(&uid3);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let client_token3 = register_named_user_return_token(server.address(), &uid3, &gpuid3, "name3");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    let uid3 = uid3.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token3,
+        &uid3,
+    );
+    for (client_token, uid) in &[
+        (&client_token1, &uid1),
+        (&client_token2, &uid2),
+        (&client_token3, &uid3),
+    ] {
+        add_foodstuff(
+            server.address(),
+            client_token,
+            uid,
+            1,
+            "apple",
+            "1",
+            "2",
+            "3",
+            "4",
+        );
+    }
+    let sources = json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 100}] });
+    create_shopping_list(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "list of 2",
+        sources.clone(),
+    );
+    create_shopping_list(
+        server.address(),
+        &client_token3,
+        &uid3,
+        1,
+        "list of 3",
+        sources.clone(),
+    );
+    create_shopping_list(
+        server.address(),
+        &client_token3,
+        &uid3,
+        2,
+        "not shared list of 3",
+        sources.clone(),
+    );
+    create_shopping_list(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "list of 1",
+        sources,
+    );
+    share_shopping_list(server.address(), &client_token3, &uid3, 1, &uid1);
+    share_shopping_list(server.address(), &client_token2, &uid2, 1, &uid1);
+
+    let response = list_shopping_lists(server.address(), &client_token1, &uid1);
+    let lists: Vec<_> = response[constants::FIELD_NAME_SHOPPING_LISTS]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|list| {
+            (
+                list[constants::FIELD_NAME_OWNER_USER_ID].as_str().unwrap(),
+                list[constants::FIELD_NAME_SHOPPING_LIST_NAME]
+                    .as_str()
+                    .unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (uid1.as_str(), "list of 1"),
+            (uid3.as_str(), "list of 3"),
+            (uid2.as_str(), "list of 2"),
+        ],
+        lists
+    );
+}
@@ -0,0 +1 @@
+pub mod list_shopping_lists_cmd_handler;
@@ -0,0 +1,269 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::foodstuff;
+use crate::db::core::meal_plan::MealPlan;
+use crate::db::core::meal_plan_item;
+use crate::db::core::meal_plan_item::MealType;
+use crate::db::core::recipe;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+pub const DAYS_IN_MEAL_PLAN: i32 = 7;
+
+/// Item of a meal as it's sent by clients -
+/// |owner_user_id| is UID of either the user or the partner, and exactly one of
+/// |foodstuff_id| (an |app_user_foodstuff_id|) and |recipe_id|
+/// (an |app_user_recipe_id|) must be set.
+#[derive(Debug, Deserialize)]
+pub struct MealItemArg {
+    owner_user_id: String,
+    foodstuff_id: Option<i32>,
+    recipe_id: Option<i32>,
+    weight: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct MealItemsArgs {
+    items: Vec<MealItemArg>,
+}
+
+pub fn parse_meal_type(meal_type: &str) -> Result<MealType, RequestError> {
+    match meal_type {
+        constants::MEAL_TYPE_BREAKFAST => Ok(MealType::Breakfast),
+        constants::MEAL_TYPE_LUNCH => Ok(MealType::Lunch),
+        constants::MEAL_TYPE_DINNER => Ok(MealType::Dinner),
+        constants::MEAL_TYPE_SNACK => Ok(MealType::Snack),
+        _ => Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid meal type: {}", meal_type),
+        )),
+    }
+}
+
+pub fn meal_type_to_str(meal_type: MealType) -> &'static str {
+    match meal_type {
+        MealType::Breakfast => constants::MEAL_TYPE_BREAKFAST,
+        MealType::Lunch => constants::MEAL_TYPE_LUNCH,
+        MealType::Dinner => constants::MEAL_TYPE_DINNER,
+        MealType::Snack => constants::MEAL_TYPE_SNACK,
+    }
+}
+
+/// Days are numbered from 0 (Monday) to 6 (Sunday).
+pub fn check_day(day: i32) -> Result<i32, RequestError> {
+    if (0..DAYS_IN_MEAL_PLAN).contains(&day) {
+        Ok(day)
+    } else {
+        Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!(
+                "Day must be within [0, {}), got: {}",
+                DAYS_IN_MEAL_PLAN, day
+            ),
+        ))
+    }
+}
+
+/// Parses body of the form
+/// {"items": [{"owner_user_id": "uid", "foodstuff_id": 1, "weight": 100}]}.
+/// Empty items list is valid - it clears the meal.
+pub fn parse_meal_items(body: &str) -> Result<Vec<MealItemArg>, RequestError> {
+    let args = serde_json::from_str::<MealItemsArgs>(body).map_err(|err| {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid meal items: {}", err),
+        )
+    })?;
+    for item in &args.items {
+        if item.weight <= 0 {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                format!("Weight of meal item must be positive: {:?}", item),
+            ));
+        }
+        if item.foodstuff_id.is_some() == item.recipe_id.is_some() {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                format!("Meal item must have either foodstuff or recipe: {:?}", item),
+            ));
+        }
+    }
+    Ok(args.items)
+}
+
+/// Inserts items of the meal. Items can reference only foodstuffs and recipes
+/// of the |user| and of the |partner|.
+pub fn insert_meal_items(
+    meal_plan: &MealPlan,
+    day: i32,
+    meal_type: MealType,
+    items: &[MealItemArg],
+    user: &AppUser,
+    partner: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<(), RequestError> {
+    for item in items {
+        let owner_uid = Uuid::from_str(&item.owner_user_id)?;
+        let owner = if &owner_uid == user.uid() {
+            user
+        } else if &owner_uid == partner.uid() {
+            partner
+        } else {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_PERMISSION_DENIED.to_owned(),
+                format!(
+                    "Meal item must belong either to the user or to the partner: {:?}",
+                    item
+                ),
+            ));
+        };
+
+        let new_item = match (item.foodstuff_id, item.recipe_id) {
+            (Some(foodstuff_id), None) => {
+                let foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+                    owner.id(),
+                    foodstuff_id,
+                    connection,
+                )?;
+                let foodstuff = match foodstuff {
+                    Some(foodstuff) if foodstuff.is_listed() => foodstuff,
+                    _ => {
+                        return Err(RequestError::new(
+                            constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+                            format!("Foodstuff not found, ID: {}", foodstuff_id),
+                        ))
+                    }
+                };
+                meal_plan_item::new_foodstuff_item(
+                    meal_plan,
+                    day,
+                    meal_type,
+                    &foodstuff,
+                    item.weight,
+                )
+            }
+            (None, Some(recipe_id)) => {
+                let recipe =
+                    recipe::select_by_app_user_recipe_id(owner.id(), recipe_id, connection)?;
+                let recipe = match recipe {
+                    Some(recipe) => recipe,
+                    None => {
+                        return Err(RequestError::new(
+                            constants::FIELD_STATUS_RECIPE_NOT_FOUND.to_owned(),
+                            format!("Recipe not found, ID: {}", recipe_id),
+                        ))
+                    }
+                };
+                meal_plan_item::new_recipe_item(meal_plan, day, meal_type, &recipe, item.weight)
+            }
+            _ => panic!("Meal items must be checked by parse_meal_items: {:?}", item),
+        };
+        meal_plan_item::insert(new_item, connection)?;
+    }
+    Ok(())
+}
+
+/// Selects items of the meal plan and builds meal plan's JSON
+/// of the form {"version": 1, "meals": [{"day": 0, "meal_type": "lunch", "items": [...]}]},
+/// where items have the same format as items accepted by |parse_meal_items|.
+/// |meal_plan| is None if partners haven't created a plan yet.
+pub fn meal_plan_to_json(
+    meal_plan: Option<&MealPlan>,
+    user: &AppUser,
+    partner: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<JsonValue, RequestError> {
+    let meal_plan = match meal_plan {
+        Some(meal_plan) => meal_plan,
+        None => {
+            return Ok(json!({
+                constants::FIELD_NAME_VERSION: 0,
+                constants::FIELD_NAME_MEALS: [],
+            }))
+        }
+    };
+
+    let owners: HashMap<i32, &AppUser> = vec![(user.id(), user), (partner.id(), partner)]
+        .into_iter()
+        .collect();
+    let items = meal_plan_item::select_by_meal_plan_id(meal_plan.id(), connection)?;
+
+    let mut json_meals: Vec<JsonValue> = Vec::new();
+    let mut current_meal: Option<(i32, MealType)> = None;
+    for item in &items {
+        let (owner_id, json_item) = match (item.foodstuff_id(), item.recipe_id()) {
+            (Some(foodstuff_id), _) => match foodstuff::select_by_id(foodstuff_id, connection)? {
+                Some(foodstuff) => (
+                    foodstuff.app_user_id(),
+                    json!({
+                        constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                        constants::FIELD_NAME_WEIGHT: item.weight(),
+                    }),
+                ),
+                None => continue, // Foodstuff was deleted a couple of ms ago
+            },
+            (None, Some(recipe_id)) => match recipe::select_by_id(recipe_id, connection)? {
+                Some(recipe) => (
+                    recipe.app_user_id(),
+                    json!({
+                        constants::FIELD_NAME_RECIPE_ID: recipe.app_user_recipe_id(),
+                        constants::FIELD_NAME_WEIGHT: item.weight(),
+                    }),
+                ),
+                None => continue, // Recipe was deleted a couple of ms ago
+            },
+            (None, None) => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                    format!("Meal item has neither foodstuff nor recipe: {:?}", item),
+                ))
+            }
+        };
+        let owner = match owners.get(&owner_id) {
+            Some(owner) => owner,
+            None => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                    format!("Meal plan has foreign meal item: {:?}", item),
+                ))
+            }
+        };
+        let mut json_item = json_item;
+        json_item[constants::FIELD_NAME_OWNER_USER_ID] = json!(owner.uid().to_string());
+
+        let meal = (item.day(), item.meal_type());
+        if current_meal != Some(meal) {
+            current_meal = Some(meal);
+            json_meals.push(json!({
+                constants::FIELD_NAME_DAY: item.day(),
+                constants::FIELD_NAME_MEAL_TYPE: meal_type_to_str(item.meal_type()),
+                constants::FIELD_NAME_ITEMS: [],
+            }));
+        }
+        let last_meal = json_meals.last_mut().expect("Meal is pushed above");
+        last_meal[constants::FIELD_NAME_ITEMS]
+            .as_array_mut()
+            .expect("Items are an array")
+            .push(json_item);
+    }
+
+    Ok(json!({
+        constants::FIELD_NAME_VERSION: meal_plan.version(),
+        constants::FIELD_NAME_MEALS: json_meals,
+    }))
+}
+
+pub fn version_conflict_error(current_version: i64, given_version: i64) -> RequestError {
+    RequestError::new(
+        constants::FIELD_STATUS_VERSION_CONFLICT.to_owned(),
+        format!(
+            "Meal plan was modified, current version: {}, given version: {}",
+            current_version, given_version
+        ),
+    )
+}
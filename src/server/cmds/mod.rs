@@ -2,15 +2,45 @@
 #[macro_use]
 pub mod testing_cmds_utils;
 
+pub mod add_foodstuff;
+pub mod add_history_entry;
+pub mod check_shopping_list_item;
+pub mod cmd_args;
 pub mod cmd_handler;
 pub mod cmds_hub;
+pub mod create_recipe;
+pub mod create_shopping_list;
+pub mod delete_account;
+pub mod delete_history_entry;
+pub mod delete_recipe;
+pub mod delete_shopping_list;
 pub mod direct_partner_msg;
+pub mod edit_recipe;
+pub mod export_data;
+pub mod fetch_messages;
+pub mod foodstuff_share_utils;
+pub mod get_meal_plan;
+pub mod list_foodstuffs;
+pub mod list_history;
 pub mod list_partners;
+pub mod list_recipes;
+pub mod list_shared_foodstuffs;
+pub mod list_shopping_lists;
+pub mod meal_plan_utils;
 pub mod move_device_account;
 pub mod pairing_request;
+pub mod recipe_utils;
 pub mod register_user;
+pub mod share_foodstuff;
+pub mod share_shopping_list;
+pub mod shopping_list_utils;
 pub mod start_pairing;
+pub mod sync_foodstuffs;
+pub mod unlist_foodstuff;
 pub mod unpair;
 pub mod update_fcm_token;
+pub mod update_foodstuff;
+pub mod update_meal_plan_meal;
+pub mod update_shared_foodstuff;
 pub mod update_user_name;
 pub mod utils;
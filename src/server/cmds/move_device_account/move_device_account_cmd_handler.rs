@@ -6,11 +6,11 @@ use crate::db::core::app_user::AppUser;
 use crate::db::core::{app_user, gp_user, vk_user};
 use crate::db::pool::connection_pool::ConnectionPool;
 use crate::outside::http_client::HttpClient;
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
 use crate::server::cmds::register_user::social_network_token_check::{
     check_token, TokenCheckSuccess,
 };
-use crate::server::cmds::utils::HashMapAdditionalOperations;
 use crate::server::constants;
 use crate::server::request_error::RequestError;
 use uuid::Uuid;
@@ -18,6 +18,13 @@ use uuid::Uuid;
 #[derive(Default)]
 pub struct MoveDeviceAccountCmdHandler {}
 
+#[derive(Deserialize)]
+struct MoveDeviceAccountArgs {
+    social_network_type: String,
+    social_network_token: String,
+    overrides: Option<String>,
+}
+
 impl CmdHandler for MoveDeviceAccountCmdHandler {
     fn handle(
         &self,
@@ -48,10 +55,10 @@ impl MoveDeviceAccountCmdHandler {
         http_client: Arc<HttpClient>,
     ) -> CmdHandleResult {
         let connection = connections_pool.borrow_connection()?;
-        let social_network_type = args.get_or_request_error(constants::ARG_SOCIAL_NETWORK_TYPE)?;
-        let social_network_token =
-            args.get_or_request_error(constants::ARG_SOCIAL_NETWORK_TOKEN)?;
-        let overrides = args.get_or_empty(constants::ARG_OVERRIDES);
+        let cmd_args: MoveDeviceAccountArgs = parse_args(&args)?;
+        let social_network_type = cmd_args.social_network_type;
+        let social_network_token = cmd_args.social_network_token;
+        let overrides = cmd_args.overrides.unwrap_or_default();
 
         let token_check_result = check_token(
             social_network_type,
@@ -1,4 +1,3 @@
-use futures::join;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::future::Future;
@@ -9,18 +8,21 @@ use uuid::Uuid;
 use crate::config::Config;
 use crate::db::core::app_user;
 use crate::db::core::app_user::AppUser;
+use crate::db::core::failed_pairing_attempts;
 use crate::db::core::paired_partners;
 use crate::db::core::paired_partners::PairingState;
 use crate::db::core::taken_pairing_code;
 use crate::db::pool::connection_pool::{BorrowedDBConnection, ConnectionPool};
 use crate::outside::fcm::FCM_ADDR;
 use crate::outside::http_client::HttpClient;
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::CmdHandler;
 use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture};
 use crate::server::cmds::utils::db_transaction;
 use crate::server::cmds::utils::extract_user_from_query_args;
-use crate::server::cmds::utils::notify_user;
 use crate::server::constants;
+use crate::server::inbox;
+use crate::server::notification_outbox;
 use crate::server::request_error::RequestError;
 use crate::utils::now_source::{DefaultNowSource, NowSource};
 
@@ -32,6 +34,12 @@ pub struct PairingRequestCmdHandler {
     now_source: DefaultNowSource,
 }
 
+#[derive(Deserialize)]
+struct PairingRequestArgs {
+    partner_pairing_code: Option<String>,
+    partner_user_id: Option<String>,
+}
+
 impl CmdHandler for PairingRequestCmdHandler {
     fn handle(
         &self,
@@ -104,10 +112,14 @@ impl PairingRequestCmdHandler {
         )?;
 
         let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: PairingRequestArgs = parse_args(&args)?;
         let partner_user = extract_partner_user(
             &family,
-            args.get(constants::ARG_PARTNER_PAIRING_CODE),
-            args.get(constants::ARG_PARTNER_USER_ID),
+            &user,
+            cmd_args.partner_pairing_code.as_ref(),
+            cmd_args.partner_user_id.as_ref(),
+            &config,
+            now,
             &connection,
         )?;
 
@@ -123,16 +135,19 @@ impl PairingRequestCmdHandler {
         )?;
         if is_pairing_finished(&pp1) || is_pairing_finished(&pp2) {
             // Already paired!
+            let notification_ids = db_transaction(&connection, || {
+                enqueue_pairing_finish_notification(&user, &partner_user, &connection)
+            })?;
 
             // NOTE: we don't use the '?' operator on the send result - we want to respond
             // with OK status to our client even if notifications sending will fail
-            let _send_res = notify_of_pairing_finish(
-                &user,
-                &partner_user,
-                connections_pool.clone(),
+            let _send_res = notification_outbox::send_enqueued(
+                notification_ids,
+                connection,
+                connections_pool,
                 &config,
                 &fcm_address,
-                http_client.clone(),
+                http_client,
             )
             .await;
 
@@ -143,34 +158,34 @@ impl PairingRequestCmdHandler {
 
         // Confirm pairing, if partner2 already started it
         if let Some(pp2) = pp2 {
-            db_transaction(&connection, || {
+            let notification_ids = db_transaction(&connection, || {
                 // Partner2 already sent a pairing request to Partner1
                 let time = pp2.pairing_start_time();
                 paired_partners::delete_by_id(pp2.id(), &connection)?;
                 let pp2 = paired_partners::new(&partner_user, &user, PairingState::Done, time);
                 paired_partners::insert(pp2, &connection)?;
-                Ok(())
+
+                let mut notification_ids =
+                    enqueue_pairing_finish_notification(&user, &partner_user, &connection)?;
+                notification_ids.extend(enqueue_pairing_finish_notification(
+                    &partner_user,
+                    &user,
+                    &connection,
+                )?);
+                Ok(notification_ids)
             })?;
 
-            let send_fut1 = notify_of_pairing_finish(
-                &user,
-                &partner_user,
-                connections_pool.clone(),
-                &config,
-                &fcm_address,
-                http_client.clone(),
-            );
-            let send_fut2 = notify_of_pairing_finish(
-                &partner_user,
-                &user,
-                connections_pool.clone(),
+            // NOTE: we don't use the '?' operator on the send result - we want to respond
+            // with OK status to our client even if notifications sending will fail
+            let _send_res = notification_outbox::send_enqueued(
+                notification_ids,
+                connection,
+                connections_pool,
                 &config,
                 &fcm_address,
-                http_client.clone(),
-            );
-            // NOTE: we don't use the '?' operator on the send results - we want to respond
-            // with OK status to our client even if notifications sending will fail
-            let (_send_res1, _send_res2) = join!(send_fut1, send_fut2);
+                http_client,
+            )
+            .await;
 
             return Ok(json!({
                 constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
@@ -178,26 +193,25 @@ impl PairingRequestCmdHandler {
         }
 
         // Send pairing request
-        db_transaction(&connection, || {
+        let notification_ids = db_transaction(&connection, || {
             // Delete an unfinished pairing, if it exists.
             if let Some(pp1) = pp1 {
                 paired_partners::delete_by_id(pp1.id(), &connection)?;
             }
             let pp = paired_partners::new(&user, &partner_user, PairingState::NotConfirmed, now);
             paired_partners::insert(pp, &connection)?;
-            Ok(())
+            enqueue_pairing_request_notification(&partner_user, &user, now, &connection)
         })?;
 
         // NOTE: we don't use the '?' operator on the send result - we want to respond
         // with OK status to our client even if notifications sending will fail
-        let _send_res = notify_of_pairing_request(
-            &partner_user,
-            &user,
-            now,
-            connections_pool.clone(),
+        let _send_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
+            connections_pool,
             &config,
             &fcm_address,
-            http_client.clone(),
+            http_client,
         )
         .await;
         Ok(json!({
@@ -208,8 +222,11 @@ impl PairingRequestCmdHandler {
 
 fn extract_partner_user(
     family: &str,
+    user: &AppUser,
     partner_pairing_code: Option<&String>,
     partner_uid: Option<&String>,
+    config: &Config,
+    now: i64,
     connection: &BorrowedDBConnection,
 ) -> Result<AppUser, RequestError> {
     if partner_uid.is_none() && partner_pairing_code.is_none() {
@@ -220,26 +237,15 @@ fn extract_partner_user(
     }
 
     if let Some(partner_pairing_code) = partner_pairing_code {
-        let partner_pairing_code = match partner_pairing_code.parse::<i32>() {
-            Ok(partner_pairing_code) => partner_pairing_code,
-            Err(error) => {
-                return Err(RequestError::new(
-                    constants::FIELD_STATUS_INVALID_PARTNER_PAIRING_CODE.to_owned(),
-                    format!(
-                        "Partner code is invalid: {}, err: {}",
-                        partner_pairing_code, error
-                    ),
-                ))
-            }
-        };
-        let partner_pairing_code =
-            taken_pairing_code::select_by_value(partner_pairing_code, family, connection)?;
-        if let Some(partner_pairing_code) = partner_pairing_code {
-            let user_id = partner_pairing_code.app_user_id();
-            let user = app_user::select_by_id(user_id, connection)?;
-            if let Some(user) = user {
-                return Ok(user);
-            }
+        // The user is locked, so that concurrent requests of the user wouldn't
+        // all pass the lockout check before their failed attempts are counted
+        let partner_user = db_transaction(connection, || {
+            app_user::lock_for_modification(user, connection)?;
+            check_pairing_code_lockout(user, config, now, connection)?;
+            find_user_by_pairing_code(family, user, partner_pairing_code, now, connection)
+        })?;
+        if let Some(partner_user) = partner_user {
+            return Ok(partner_user);
         }
     }
 
@@ -260,6 +266,76 @@ fn extract_partner_user(
     ))
 }
 
+/// Counts a failed attempt of the |user| if the |partner_pairing_code|
+/// doesn't belong to any user.
+fn find_user_by_pairing_code(
+    family: &str,
+    user: &AppUser,
+    partner_pairing_code: &str,
+    now: i64,
+    connection: &BorrowedDBConnection,
+) -> Result<Option<AppUser>, RequestError> {
+    let partner_pairing_code = match partner_pairing_code.parse::<i32>() {
+        Ok(partner_pairing_code) => partner_pairing_code,
+        Err(error) => {
+            return Err(RequestError::new(
+                constants::FIELD_STATUS_INVALID_PARTNER_PAIRING_CODE.to_owned(),
+                format!(
+                    "Partner code is invalid: {}, err: {}",
+                    partner_pairing_code, error
+                ),
+            ))
+        }
+    };
+    let partner_pairing_code =
+        taken_pairing_code::select_by_value(partner_pairing_code, family, connection)?;
+    if let Some(partner_pairing_code) = partner_pairing_code {
+        let user_id = partner_pairing_code.app_user_id();
+        let partner_user = app_user::select_by_id(user_id, connection)?;
+        if partner_user.is_some() {
+            return Ok(partner_user);
+        }
+    }
+    // The code is either mistyped or guessed
+    failed_pairing_attempts::increment(user, now, connection)?;
+    Ok(None)
+}
+
+/// Pairing codes are short, so a user who fails to pair by codes too many times
+/// could be guessing them - such a user cannot pair by codes until the lockout ends.
+/// NOTE: successful attempts don't reset the failed ones, otherwise guesses could be
+/// interleaved with known codes.
+fn check_pairing_code_lockout(
+    user: &AppUser,
+    config: &Config,
+    now: i64,
+    connection: &BorrowedDBConnection,
+) -> Result<(), RequestError> {
+    let attempts = match failed_pairing_attempts::select_by_app_user_id(user.id(), connection)? {
+        Some(attempts) => attempts,
+        None => return Ok(()),
+    };
+
+    let lockout_end = attempts.last_attempt_time() + config.pairing_code_lockout_seconds() as i64;
+    if lockout_end <= now {
+        // The attempts are too old to be counted
+        failed_pairing_attempts::delete_by_app_user_id(user.id(), connection)?;
+        return Ok(());
+    }
+    if i64::from(attempts.attempts_count()) < i64::from(config.pairing_code_max_failed_attempts()) {
+        return Ok(());
+    }
+
+    Err(RequestError::new(
+        constants::FIELD_STATUS_PAIRING_LOCKED_OUT.to_owned(),
+        format!(
+            "Pairing by codes is locked out after {} failed attempts",
+            attempts.attempts_count()
+        ),
+    )
+    .with_retry_after_seconds((lockout_end - now) as u64))
+}
+
 fn is_pairing_finished(pp: &Option<paired_partners::PairedPartners>) -> bool {
     if let Some(pp) = pp {
         pp.pairing_state() == PairingState::Done
@@ -268,39 +344,26 @@ fn is_pairing_finished(pp: &Option<paired_partners::PairedPartners>) -> bool {
     }
 }
 
-async fn notify_of_pairing_finish(
+fn enqueue_pairing_finish_notification(
     user: &AppUser,
     paired_partner: &AppUser,
-    connections_pool: ConnectionPool,
-    config: &Config,
-    fcm_address: &str,
-    http_client: Arc<HttpClient>,
-) -> Result<(), RequestError> {
+    connection: &BorrowedDBConnection,
+) -> Result<Vec<i32>, RequestError> {
     let json = json!({
         constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_PAIRED_WITH_PARTNER,
         constants::SERV_FIELD_PAIRING_PARTNER_USER_ID: paired_partner.uid(),
         constants::SERV_FIELD_PARTNER_NAME: paired_partner.name()
     });
-    notify_user(
-        user,
-        json.to_string(),
-        connections_pool,
-        config,
-        fcm_address,
-        http_client,
-    )
-    .await
+    inbox::store(user, json.to_string(), connection)?;
+    notification_outbox::enqueue(user, json.to_string(), connection)
 }
 
-async fn notify_of_pairing_request(
+fn enqueue_pairing_request_notification(
     user: &AppUser,
     paired_partner: &AppUser,
     now: i64,
-    connections_pool: ConnectionPool,
-    config: &Config,
-    fcm_address: &str,
-    http_client: Arc<HttpClient>,
-) -> Result<(), RequestError> {
+    connection: &BorrowedDBConnection,
+) -> Result<Vec<i32>, RequestError> {
     let expiration_date = now + PAIRING_CONFIRMATION_EXPIRATION_DELAY_SECS;
     let json = json!({
         constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_PAIRING_REQUEST_FROM_PARTNER,
@@ -308,15 +371,8 @@ async fn notify_of_pairing_request(
         constants::SERV_FIELD_PARTNER_NAME: paired_partner.name(),
         constants::SERV_FIELD_REQUEST_EXPIRATION_DATE: expiration_date
     });
-    notify_user(
-        user,
-        json.to_string(),
-        connections_pool,
-        config,
-        fcm_address,
-        http_client,
-    )
-    .await
+    inbox::store(user, json.to_string(), connection)?;
+    notification_outbox::enqueue(user, json.to_string(), connection)
 }
 
 #[cfg(test)]
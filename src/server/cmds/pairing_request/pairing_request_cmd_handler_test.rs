@@ -5,25 +5,36 @@ use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::db::core::app_user;
+use crate::db::core::device;
+use crate::db::core::failed_pairing_attempts;
+use crate::db::core::fcm_token;
 use crate::db::core::paired_partners;
 use crate::db::core::testing_util::testing_connection_for_server_user;
 use crate::server::constants;
 use crate::server::testing_hostname;
 use crate::server::testing_mock_server::FullRequest;
+use crate::server::testing_server_wrapper::ServerWrapper;
+use crate::testing_utils::config_in_tests;
 
 use crate::server::cmds::pairing_request::pairing_request_cmd_handler;
+use crate::server::cmds::start_pairing::start_pairing_cmd_handler::insert_pairing_code_gen_family_override;
 
 use crate::server::cmds::testing_cmds_utils::assert_status;
 use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
 use crate::server::cmds::testing_cmds_utils::make_request;
 use crate::server::cmds::testing_cmds_utils::pairing_request_by_code;
+use crate::server::cmds::testing_cmds_utils::pairing_request_by_code_with_overrides;
+use crate::server::cmds::testing_cmds_utils::pairing_request_by_code_without_ok_check;
 use crate::server::cmds::testing_cmds_utils::pairing_request_by_uid;
 use crate::server::cmds::testing_cmds_utils::pairing_request_by_uid_with_overrides;
 use crate::server::cmds::testing_cmds_utils::register_named_user;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
 use crate::server::cmds::testing_cmds_utils::register_user;
+use crate::server::cmds::testing_cmds_utils::set_device_fcm_token;
 use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
 use crate::server::cmds::testing_cmds_utils::start_mock_server;
 use crate::server::cmds::testing_cmds_utils::start_pairing;
+use crate::server::cmds::testing_cmds_utils::start_server_with_config;
 
 #[test]
 fn pairing_by_pairing_codes() {
@@ -369,6 +380,158 @@ fn pairing_with_fcm() {
     );
 }
 
+#[test]
+fn pairing_notifications_are_sent_to_all_devices_and_invalid_tokens_are_deleted() {
+    let r = |request: &FullRequest| {
+        let body: JsonValue = serde_json::from_str(&request.body).unwrap();
+        let result = if body["to"].as_str().unwrap().ends_with("invalid") {
+            r#"{"error":"NotRegistered"}"#
+        } else {
+            r#"{"message_id":"0:1579970411599831%8e9256aef9fd7ecd"}"#
+        };
+        let response = format!(
+            r#"{{"multicast_id":1,"success":1,"failure":0,"canonical_ids":0,"results":[{}]}}"#,
+            result
+        );
+        Some(response)
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let server = start_server!(|overrides| {
+        let fcm_addr = format!("http://{}", fcm_server.address());
+        pairing_request_cmd_handler::insert_pairing_request_fcm_address_override(
+            overrides, fcm_addr,
+        );
+    });
+
+    let uid1 = Uuid::from_str("00000000-d100-0000-0000-000000000026").unwrap();
+    let uid2 = Uuid::from_str("00000000-d100-0000-0000-000000000027").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid1");
+    let gpuid2 = format!("{}{}", uid2, "gpuid2");
+    let device_id1 = Uuid::from_str("00000000-d100-0000-0000-100000000026").unwrap();
+    let device_id2 = Uuid::from_str("00000000-d100-0000-0000-100000000027").unwrap();
+    let fcm_token1 = format!("{}{}", uid2, "fcmtoken1");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2invalid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+
+    set_device_fcm_token(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        &device_id1.to_string(),
+        &fcm_token1,
+    );
+    set_device_fcm_token(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        &device_id2.to_string(),
+        &fcm_token2,
+    );
+
+    start_pairing(server.address(), &client_token1, &uid1.to_string());
+    let pairing_resp = start_pairing(server.address(), &client_token2, &uid2.to_string());
+    let pairing_code2 = &pairing_resp[constants::FIELD_NAME_PAIRING_CODE]
+        .as_str()
+        .unwrap();
+
+    // An invalid token is not an error of the request
+    pairing_request_by_code(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &pairing_code2,
+    );
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let mut receivers: Vec<String> = fcm_requests
+        .iter()
+        .map(|req| {
+            let body: JsonValue = serde_json::from_str(&req.body).unwrap();
+            body["to"].as_str().unwrap().to_owned()
+        })
+        .collect();
+    receivers.sort();
+    assert_eq!(vec![fcm_token1.clone(), fcm_token2], receivers);
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user2 = app_user::select_by_uid(&uid2, &conn).unwrap().unwrap();
+    let fcm_tokens = fcm_token::select_by_user_id(user2.id(), &conn).unwrap();
+    assert_eq!(1, fcm_tokens.len());
+    assert_eq!(fcm_token1, fcm_tokens[0].token_value());
+}
+
+#[test]
+fn canonical_fcm_tokens_replace_old_ones() {
+    let r = |request: &FullRequest| {
+        let body: JsonValue = serde_json::from_str(&request.body).unwrap();
+        let token = body["to"].as_str().unwrap();
+        let response = json!({
+            "multicast_id": 1,
+            "success": 1,
+            "failure": 0,
+            "canonical_ids": 1,
+            "results": [{
+                "message_id": "0:1579970411599831%8e9256aef9fd7ecd",
+                "registration_id": format!("{}canonical", token),
+            }]
+        });
+        Some(response.to_string())
+    };
+    let (fcm_server, _fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let server = start_server!(|overrides| {
+        let fcm_addr = format!("http://{}", fcm_server.address());
+        pairing_request_cmd_handler::insert_pairing_request_fcm_address_override(
+            overrides, fcm_addr,
+        );
+    });
+
+    let uid1 = Uuid::from_str("00000000-d100-0000-0000-000000000028").unwrap();
+    let uid2 = Uuid::from_str("00000000-d100-0000-0000-000000000029").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid1");
+    let gpuid2 = format!("{}{}", uid2, "gpuid2");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    set_user_fcm_token(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        &fcm_token2,
+    );
+
+    start_pairing(server.address(), &client_token1, &uid1.to_string());
+    let pairing_resp = start_pairing(server.address(), &client_token2, &uid2.to_string());
+    let pairing_code2 = &pairing_resp[constants::FIELD_NAME_PAIRING_CODE]
+        .as_str()
+        .unwrap();
+    pairing_request_by_code(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &pairing_code2,
+    );
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user2 = app_user::select_by_uid(&uid2, &conn).unwrap().unwrap();
+    let device2 = device::select_legacy_by_app_user_id(user2.id(), &conn)
+        .unwrap()
+        .unwrap();
+    let fcm_tokens = fcm_token::select_by_user_id(user2.id(), &conn).unwrap();
+    assert_eq!(1, fcm_tokens.len());
+    assert_eq!(
+        format!("{}canonical", fcm_token2),
+        fcm_tokens[0].token_value()
+    );
+    assert_eq!(device2.id(), fcm_tokens[0].device_id());
+}
+
 #[test]
 fn real_invalid_fcm_tokens_do_not_cause_pairing_fail() {
     let server = start_server!();
@@ -789,3 +952,167 @@ fn lack_of_client_token() {
     let response = make_request(&url);
     assert_status(&response, constants::FIELD_STATUS_PARAM_MISSING);
 }
+
+fn start_server_with_pairing_code_lockout(
+    family: String,
+    max_failed_attempts: u32,
+    lockout_seconds: u64,
+) -> ServerWrapper {
+    let config = config_in_tests()
+        .with_pairing_code_max_failed_attempts(max_failed_attempts)
+        .with_pairing_code_lockout_seconds(lockout_seconds);
+    let mut overrides = json!({});
+    insert_pairing_code_gen_family_override(&mut overrides, family.clone());
+    pairing_request_cmd_handler::insert_pairing_request_family_override(&mut overrides, family);
+    start_server_with_config(config, &overrides)
+}
+
+/// A code out of the range of pairing codes, so it's never taken.
+const NOT_TAKEN_PAIRING_CODE: &str = "10000";
+
+#[test]
+fn pairing_by_codes_is_locked_out_after_failed_attempts() {
+    let family = format!("{}{}", file!(), line!());
+    let server = start_server_with_pairing_code_lockout(family.clone(), 3, 100);
+
+    let uid1 = Uuid::from_str("00000000-d100-0000-0000-000000000022").unwrap();
+    let uid2 = Uuid::from_str("00000000-d100-0000-0000-000000000023").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid1");
+    let gpuid2 = format!("{}{}", uid2, "gpuid2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let pairing_resp = start_pairing(server.address(), &client_token2, &uid2.to_string());
+    let pairing_code2 = pairing_resp[constants::FIELD_NAME_PAIRING_CODE]
+        .as_str()
+        .unwrap();
+
+    let mut overrides = json!({});
+    pairing_request_cmd_handler::insert_cmd_now_override(&mut overrides, 1000);
+    for _ in 0..3 {
+        let response = pairing_request_by_code_without_ok_check(
+            server.address(),
+            &client_token1,
+            &uid1.to_string(),
+            NOT_TAKEN_PAIRING_CODE,
+            &overrides,
+        );
+        assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+    }
+
+    // Even a valid code is not accepted now
+    pairing_request_cmd_handler::insert_cmd_now_override(&mut overrides, 1010);
+    let response = pairing_request_by_code_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        pairing_code2,
+        &overrides,
+    );
+    assert_status(&response, constants::FIELD_STATUS_PAIRING_LOCKED_OUT);
+    assert_eq!(90, response[constants::FIELD_NAME_RETRY_AFTER_SECONDS]);
+
+    // The lockout is stored in DB, so it survives restarts
+    drop(server);
+    let server = start_server_with_pairing_code_lockout(family, 3, 100);
+    let response = pairing_request_by_code_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        pairing_code2,
+        &overrides,
+    );
+    assert_status(&response, constants::FIELD_STATUS_PAIRING_LOCKED_OUT);
+
+    // Codes of the locked out user still can be used by others,
+    // and the user still can pair by UIDs
+    let pairing_resp = start_pairing(server.address(), &client_token1, &uid1.to_string());
+    let pairing_code1 = pairing_resp[constants::FIELD_NAME_PAIRING_CODE]
+        .as_str()
+        .unwrap();
+    pairing_request_by_code(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        pairing_code1,
+    );
+    pairing_request_by_uid(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &uid2.to_string(),
+    );
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user1 = app_user::select_by_uid(&uid1, &conn).unwrap().unwrap();
+    let user2 = app_user::select_by_uid(&uid2, &conn).unwrap().unwrap();
+    let pp = paired_partners::select_by_partners_user_ids(user2.id(), user1.id(), &conn).unwrap();
+    assert_eq!(
+        paired_partners::PairingState::Done,
+        pp.unwrap().pairing_state()
+    );
+}
+
+#[test]
+fn pairing_code_lockout_ends() {
+    let family = format!("{}{}", file!(), line!());
+    let server = start_server_with_pairing_code_lockout(family, 3, 100);
+
+    let uid1 = Uuid::from_str("00000000-d100-0000-0000-000000000024").unwrap();
+    let uid2 = Uuid::from_str("00000000-d100-0000-0000-000000000025").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid1");
+    let gpuid2 = format!("{}{}", uid2, "gpuid2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let pairing_resp = start_pairing(server.address(), &client_token2, &uid2.to_string());
+    let pairing_code2 = pairing_resp[constants::FIELD_NAME_PAIRING_CODE]
+        .as_str()
+        .unwrap();
+
+    let mut overrides = json!({});
+    // Each failed attempt prolongs the lockout
+    for now in &[1000, 1050, 1100] {
+        pairing_request_cmd_handler::insert_cmd_now_override(&mut overrides, *now);
+        let response = pairing_request_by_code_without_ok_check(
+            server.address(),
+            &client_token1,
+            &uid1.to_string(),
+            NOT_TAKEN_PAIRING_CODE,
+            &overrides,
+        );
+        assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+    }
+
+    pairing_request_cmd_handler::insert_cmd_now_override(&mut overrides, 1199);
+    let response = pairing_request_by_code_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        pairing_code2,
+        &overrides,
+    );
+    assert_status(&response, constants::FIELD_STATUS_PAIRING_LOCKED_OUT);
+
+    pairing_request_cmd_handler::insert_cmd_now_override(&mut overrides, 1200);
+    pairing_request_by_code_with_overrides(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        pairing_code2,
+        &overrides,
+    );
+
+    // The attempts are forgotten
+    let conn = testing_connection_for_server_user().unwrap();
+    let user1 = app_user::select_by_uid(&uid1, &conn).unwrap().unwrap();
+    assert!(
+        failed_pairing_attempts::select_by_app_user_id(user1.id(), &conn)
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,135 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::recipe::Recipe;
+use crate::db::core::recipe_ingredient;
+use crate::nutrition::nutrients::to_decimal_string;
+use crate::nutrition::nutrients::Nutrients;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Ingredient of a recipe as it's sent by clients -
+/// |foodstuff_id| is the |app_user_foodstuff_id| of a foodstuff.
+#[derive(Debug, Deserialize)]
+pub struct IngredientArg {
+    foodstuff_id: i32,
+    weight: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct IngredientsArgs {
+    ingredients: Vec<IngredientArg>,
+}
+
+/// Parses body of the form {"ingredients": [{"foodstuff_id": 1, "weight": 100}]}.
+pub fn parse_ingredients(body: &str) -> Result<Vec<IngredientArg>, RequestError> {
+    let args = serde_json::from_str::<IngredientsArgs>(body).map_err(|err| {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid ingredients: {}", err),
+        )
+    })?;
+    if args.ingredients.is_empty() {
+        return Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            "Recipe must have at least 1 ingredient".to_owned(),
+        ));
+    }
+    if let Some(ingredient) = args.ingredients.iter().find(|i| i.weight <= 0) {
+        return Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Weight of ingredient must be positive: {:?}", ingredient),
+        ));
+    }
+    Ok(args.ingredients)
+}
+
+pub fn insert_ingredients(
+    user: &AppUser,
+    recipe: &Recipe,
+    ingredients: &[IngredientArg],
+    connection: &dyn DBConnection,
+) -> Result<(), RequestError> {
+    for ingredient in ingredients {
+        let foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+            user.id(),
+            ingredient.foodstuff_id,
+            connection,
+        )?;
+        let foodstuff = match foodstuff {
+            Some(foodstuff) => foodstuff,
+            None => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+                    format!("Foodstuff not found, ID: {}", ingredient.foodstuff_id),
+                ))
+            }
+        };
+        let new_ingredient = recipe_ingredient::new(recipe, &foodstuff, ingredient.weight);
+        recipe_ingredient::insert(new_ingredient, connection)?;
+    }
+    Ok(())
+}
+
+/// Selects all foodstuffs of the user (including unlisted ones), keyed by their IDs.
+pub fn select_foodstuffs_of(
+    user: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<HashMap<i32, Foodstuff>, RequestError> {
+    let foodstuffs = foodstuff::select_by_app_user_id(user.id(), connection)?;
+    Ok(foodstuffs
+        .into_iter()
+        .map(|foodstuff| (foodstuff.id(), foodstuff))
+        .collect())
+}
+
+/// Selects ingredients of the recipe and builds recipe's JSON.
+/// |foodstuffs| must contain all foodstuffs of the recipe's owner, see |select_foodstuffs_of|.
+pub fn recipe_to_json(
+    recipe: &Recipe,
+    foodstuffs: &HashMap<i32, Foodstuff>,
+    connection: &dyn DBConnection,
+) -> Result<JsonValue, RequestError> {
+    let ingredients = recipe_ingredient::select_by_recipe_id(recipe.id(), connection)?;
+    let mut weighted_foodstuffs = Vec::with_capacity(ingredients.len());
+    for ingredient in &ingredients {
+        match foodstuffs.get(&ingredient.foodstuff_id()) {
+            Some(foodstuff) => weighted_foodstuffs.push((foodstuff, ingredient.weight())),
+            None => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                    format!("Recipe has foreign foodstuff: {:?}", ingredient),
+                ))
+            }
+        }
+    }
+
+    let json_ingredients: Vec<_> = weighted_foodstuffs
+        .iter()
+        .map(|(foodstuff, weight)| {
+            json!({
+                constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                constants::FIELD_NAME_WEIGHT: weight,
+            })
+        })
+        .collect();
+    let weighted_nutrients: Vec<_> = weighted_foodstuffs
+        .iter()
+        .map(|(foodstuff, weight)| (Nutrients::of_foodstuff(foodstuff), i64::from(*weight)))
+        .collect();
+    // Nutrients of foodstuffs are per 100 grams, and so are nutrients of the recipe
+    let nutrients = Nutrients::merge(&weighted_nutrients);
+    Ok(json!({
+        constants::FIELD_NAME_RECIPE_ID: recipe.app_user_recipe_id(),
+        constants::FIELD_NAME_RECIPE_NAME: recipe.name(),
+        constants::FIELD_NAME_INGREDIENTS: json_ingredients,
+        constants::FIELD_NAME_PROTEIN: to_decimal_string(nutrients.protein()),
+        constants::FIELD_NAME_FATS: to_decimal_string(nutrients.fats()),
+        constants::FIELD_NAME_CARBS: to_decimal_string(nutrients.carbs()),
+        constants::FIELD_NAME_CALORIES: to_decimal_string(nutrients.calories()),
+    }))
+}
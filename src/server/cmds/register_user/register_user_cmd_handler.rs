@@ -8,14 +8,23 @@ use crate::outside::http_client::HttpClient;
 use crate::server::constants;
 use crate::server::request_error::RequestError;
 
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::CmdHandleResultFuture;
 use crate::server::cmds::cmd_handler::CmdHandler;
-use crate::server::cmds::utils::HashMapAdditionalOperations;
 
 use super::register_user_impl;
 
 #[derive(Default)]
 pub struct RegisterUserCmdHandler {}
+
+#[derive(Deserialize)]
+struct RegisterUserArgs {
+    name: String,
+    social_network_type: String,
+    social_network_token: String,
+    overrides: Option<String>,
+}
+
 impl RegisterUserCmdHandler {
     pub fn new() -> RegisterUserCmdHandler {
         RegisterUserCmdHandler {}
@@ -42,10 +51,11 @@ async fn handle_impl(
     http_client: Arc<HttpClient>,
 ) -> Result<JsonValue, RequestError> {
     let connection = connections_pool.borrow_connection()?;
-    let user_name = args.get_or_request_error(constants::ARG_USER_NAME)?;
-    let social_network_type = args.get_or_request_error(constants::ARG_SOCIAL_NETWORK_TYPE)?;
-    let social_network_token = args.get_or_request_error(constants::ARG_SOCIAL_NETWORK_TOKEN)?;
-    let overrides = args.get_or_empty(constants::ARG_OVERRIDES);
+    let cmd_args: RegisterUserArgs = parse_args(&args)?;
+    let user_name = cmd_args.name;
+    let social_network_type = cmd_args.social_network_type;
+    let social_network_token = cmd_args.social_network_token;
+    let overrides = cmd_args.overrides.unwrap_or_default();
 
     let result = register_user_impl::register_user(
         user_name,
@@ -0,0 +1 @@
+pub mod share_foodstuff_cmd_handler;
@@ -0,0 +1,185 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff_share;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::outside::fcm::FCM_ADDR;
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::foodstuff_share_utils::parse_permission;
+use crate::server::cmds::foodstuff_share_utils::select_paired_partner;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::notification_outbox;
+use crate::server::request_error::RequestError;
+
+/// Shares a foodstuff of the user with a paired partner, either read-only or editable.
+/// Sharing of an already shared foodstuff changes permission of the share.
+pub struct ShareFoodstuffCmdHandler {
+    fcm_address: String,
+}
+
+#[derive(Deserialize)]
+struct ShareFoodstuffArgs {
+    foodstuff_id: i32,
+    partner_user_id: String,
+    permission: String,
+}
+
+impl CmdHandler for ShareFoodstuffCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+            self.fcm_address.clone(),
+        ))
+    }
+}
+
+impl ShareFoodstuffCmdHandler {
+    pub fn new(overrides: &JsonValue) -> Self {
+        let args = get_construction_args(overrides);
+        ShareFoodstuffCmdHandler {
+            fcm_address: args.fcm_address,
+        }
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+        fcm_address: String,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: ShareFoodstuffArgs = parse_args(&args)?;
+        let app_user_foodstuff_id = cmd_args.foodstuff_id;
+        let partner_uid = cmd_args.partner_user_id;
+        let permission = parse_permission(&cmd_args.permission)?;
+        let partner = select_paired_partner(&user, &partner_uid, &connection)?;
+
+        let notification_ids = db_transaction(&connection, || {
+            foodstuff::lock_for_modification(user.id(), &connection)?;
+            let foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+                user.id(),
+                app_user_foodstuff_id,
+                &connection,
+            )?;
+            let foodstuff = match foodstuff {
+                Some(foodstuff) if foodstuff.is_listed() => foodstuff,
+                _ => {
+                    return Err(RequestError::new(
+                        constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+                        format!("Foodstuff not found, ID: {}", app_user_foodstuff_id),
+                    ))
+                }
+            };
+
+            let share = foodstuff_share::select_by_foodstuff_id_and_partner_user_id(
+                foodstuff.id(),
+                partner.id(),
+                &connection,
+            )?;
+            match share {
+                Some(share) => {
+                    foodstuff_share::update_permission(share, permission, &connection)?;
+                }
+                None => {
+                    let share = foodstuff_share::new(&foodstuff, &partner, permission);
+                    foodstuff_share::insert(share, &connection)?;
+                }
+            }
+
+            let json = json!({
+                constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_FOODSTUFF_SHARED_BY_PARTNER,
+                constants::SERV_FIELD_PARTNER_USER_ID: user.uid(),
+                constants::SERV_FIELD_PARTNER_NAME: user.name(),
+                constants::SERV_FIELD_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+                constants::SERV_FIELD_FOODSTUFF_NAME: foodstuff.name(),
+            });
+            let notification_ids =
+                notification_outbox::enqueue(&partner, json.to_string(), &connection)?;
+            Ok(notification_ids)
+        })?;
+
+        // NOTE: we don't use the '?' operator on the send result - the foodstuff
+        // is shared even if notifications sending will fail
+        let _notif_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
+            connections_pool,
+            &config,
+            &fcm_address,
+            http_client,
+        )
+        .await;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+#[cfg(test)]
+pub fn insert_construction_overrides(overrides: &mut JsonValue, fcm_address: String) {
+    let overrides = overrides
+        .as_object_mut()
+        .expect("Can insert only into object");
+    overrides.insert("share_foodstuff_overrides".to_owned(), json!({}));
+    let overrides = overrides["share_foodstuff_overrides"]
+        .as_object_mut()
+        .unwrap();
+    overrides.insert("fcm_address_override".to_owned(), json!(fcm_address));
+}
+
+struct ConstructionArgs {
+    fcm_address: String,
+}
+
+fn get_construction_args(overrides: &JsonValue) -> ConstructionArgs {
+    if let Some(overrides) = extract_construction_overrides(overrides) {
+        overrides
+    } else {
+        ConstructionArgs {
+            fcm_address: FCM_ADDR.to_owned(),
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn extract_construction_overrides(_overrides: &JsonValue) -> Option<ConstructionArgs> {
+    None
+}
+
+#[cfg(test)]
+fn extract_construction_overrides(overrides: &JsonValue) -> Option<ConstructionArgs> {
+    match &overrides["share_foodstuff_overrides"].as_object() {
+        Some(overrides) => Some(ConstructionArgs {
+            fcm_address: overrides["fcm_address_override"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        }),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./share_foodstuff_cmd_handler_test.rs"]
+mod share_foodstuff_cmd_handler_test;
@@ -0,0 +1,306 @@
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+
+use crate::server::cmds::share_foodstuff::share_foodstuff_cmd_handler::insert_construction_overrides;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shared_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
+use crate::server::cmds::testing_cmds_utils::share_foodstuff;
+use crate::server::cmds::testing_cmds_utils::share_foodstuff_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+
+#[test]
+fn share_foodstuff_with_fcm() {
+    let r = |_request: &FullRequest| {
+        let response = r#"
+        {
+            "multicast_id":2513734409441993719,
+            "success":1,
+            "failure":0,
+            "canonical_ids":0,
+            "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd"}]
+        }"#;
+        Some(response.to_owned())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    let server = start_server!(|overrides: &mut JsonValue| insert_construction_overrides(
+        overrides,
+        fcm_addr.clone()
+    ));
+
+    let uid1 = Uuid::from_str("00000000-f212-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f212-0000-0000-000000000001").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    set_user_fcm_token(server.address(), &client_token2, &uid2, &fcm_token2);
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "0.4",
+        "0.1",
+        "9.8",
+        "47",
+    );
+
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+
+    let response = list_shared_foodstuffs(server.address(), &client_token2, &uid2);
+    let expected_foodstuffs = json!([{
+        constants::FIELD_NAME_PARTNER_USER_ID: uid1,
+        constants::FIELD_NAME_PARTNER_NAME: "name1",
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_FOODSTUFF_NAME: "apple",
+        constants::FIELD_NAME_PROTEIN: "0.4",
+        constants::FIELD_NAME_FATS: "0.1",
+        constants::FIELD_NAME_CARBS: "9.8",
+        constants::FIELD_NAME_CALORIES: "47",
+        constants::FIELD_NAME_PERMISSION: constants::PERMISSION_READ_ONLY,
+    }]);
+    assert_eq!(
+        expected_foodstuffs,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+    );
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let fcm_requests: Vec<JsonValue> = fcm_requests
+        .iter()
+        .map(|req| serde_json::from_str(&req.body).unwrap())
+        .collect();
+    assert_eq!(1, fcm_requests.len());
+    assert_eq!(fcm_requests[0]["to"], json!(fcm_token2));
+    let data = &fcm_requests[0]["data"];
+    assert_eq!(
+        &data[constants::SERV_FIELD_MSG_TYPE],
+        constants::SERV_MSG_FOODSTUFF_SHARED_BY_PARTNER,
+    );
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_USER_ID], &uid1);
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_NAME], "name1");
+    assert_eq!(&data[constants::SERV_FIELD_FOODSTUFF_ID], 1);
+    assert_eq!(&data[constants::SERV_FIELD_FOODSTUFF_NAME], "apple");
+}
+
+#[test]
+fn sharing_again_changes_permission() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f212-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f212-0000-0000-000000000003").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_EDITABLE,
+    );
+
+    let response = list_shared_foodstuffs(server.address(), &client_token2, &uid2);
+    let foodstuffs = response[constants::FIELD_NAME_FOODSTUFFS]
+        .as_array()
+        .unwrap();
+    assert_eq!(1, foodstuffs.len());
+    assert_eq!(
+        constants::PERMISSION_EDITABLE,
+        foodstuffs[0][constants::FIELD_NAME_PERMISSION]
+    );
+}
+
+#[test]
+fn cannot_share_with_not_paired_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f212-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-f212-0000-0000-000000000005").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = share_foodstuff_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2.to_string(),
+        constants::PERMISSION_READ_ONLY,
+    );
+    assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+}
+
+#[test]
+fn cannot_share_not_existing_foodstuff() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f212-0000-0000-000000000006").unwrap();
+    let uid2 = Uuid::from_str("00000000-f212-0000-0000-000000000007").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    // Foodstuff of the partner is not a foodstuff of the user
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = share_foodstuff_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
+
+#[test]
+fn invalid_permission() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f212-0000-0000-000000000008").unwrap();
+    let uid2 = Uuid::from_str("00000000-f212-0000-0000-000000000009").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = share_foodstuff_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        "owner",
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
@@ -0,0 +1 @@
+pub mod share_shopping_list_cmd_handler;
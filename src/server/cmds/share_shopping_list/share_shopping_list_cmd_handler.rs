@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::error::Error as DBError;
+use crate::db::core::error::ErrorKind as DBErrorKind;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list_share;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::foodstuff_share_utils::select_paired_partner;
+use crate::server::cmds::shopping_list_utils::shopping_list_not_found_error;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+
+/// Shares a shopping list of the user with a partner the user is paired with.
+/// Sharing an already shared list is not an error.
+#[derive(Default)]
+pub struct ShareShoppingListCmdHandler {}
+
+#[derive(Deserialize)]
+struct ShareShoppingListArgs {
+    shopping_list_id: i32,
+    partner_user_id: String,
+}
+
+impl CmdHandler for ShareShoppingListCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl ShareShoppingListCmdHandler {
+    pub fn new() -> Self {
+        ShareShoppingListCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: ShareShoppingListArgs = parse_args(&args)?;
+        let app_user_shopping_list_id = cmd_args.shopping_list_id;
+        let partner_uid = cmd_args.partner_user_id;
+        let partner = select_paired_partner(&user, &partner_uid, &connection)?;
+
+        let shopping_list = shopping_list::select_by_app_user_shopping_list_id(
+            user.id(),
+            app_user_shopping_list_id,
+            &connection,
+        )?;
+        let shopping_list = match shopping_list {
+            Some(shopping_list) => shopping_list,
+            None => return Err(shopping_list_not_found_error(app_user_shopping_list_id)),
+        };
+
+        let new_share = shopping_list_share::new(&shopping_list, &partner);
+        match shopping_list_share::insert(new_share, &connection) {
+            Ok(_) => {}
+            // Already shared
+            Err(DBError(DBErrorKind::UniqueViolation(_), _)) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./share_shopping_list_cmd_handler_test.rs"]
+mod share_shopping_list_cmd_handler_test;
@@ -0,0 +1,128 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_shopping_lists;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list_without_ok_check;
+
+/// Registers 2 users and creates a shopping list of the first one.
+fn set_up_users_with_list(server_addr: &str, uid1: &Uuid, uid2: &Uuid) -> (String, String) {
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(uid1);
+    delete_app_user_with(uid2);
+
+    let client_token1 = register_named_user_return_token(server_addr, uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server_addr, uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    add_foodstuff(
+        server_addr,
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_shopping_list(
+        server_addr,
+        &client_token1,
+        &uid1,
+        1,
+        "list",
+        json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 100}] }),
+    );
+    (client_token1, client_token2)
+}
+
+#[test]
+fn share_shopping_list_test() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f218-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f218-0000-0000-000000000001").unwrap();
+    let (client_token1, client_token2) = set_up_users_with_list(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+
+    share_shopping_list(server.address(), &client_token1, &uid1, 1, &uid2);
+    // Sharing again is not an error
+    share_shopping_list(server.address(), &client_token1, &uid1, 1, &uid2);
+
+    let response = list_shopping_lists(server.address(), &client_token2, &uid2);
+    let expected_lists = json!([{
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_SHOPPING_LIST_ID: 1,
+        constants::FIELD_NAME_SHOPPING_LIST_NAME: "list",
+        constants::FIELD_NAME_ITEMS: [{
+            constants::FIELD_NAME_ITEM_ID: 1,
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "apple",
+            constants::FIELD_NAME_WEIGHT: 100,
+            constants::FIELD_NAME_IS_CHECKED: false,
+        }],
+    }]);
+    assert_eq!(
+        expected_lists,
+        response[constants::FIELD_NAME_SHOPPING_LISTS]
+    );
+}
+
+#[test]
+fn cannot_share_with_not_paired_user() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f218-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f218-0000-0000-000000000003").unwrap();
+    let (client_token1, client_token2) = set_up_users_with_list(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+
+    let response =
+        share_shopping_list_without_ok_check(server.address(), &client_token1, &uid1, 1, &uid2);
+    assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+
+    let response = list_shopping_lists(server.address(), &client_token2, &uid2);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_SHOPPING_LISTS]);
+}
+
+#[test]
+fn cannot_share_not_existing_list() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f218-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-f218-0000-0000-000000000005").unwrap();
+    let (client_token1, client_token2) = set_up_users_with_list(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+
+    let response =
+        share_shopping_list_without_ok_check(server.address(), &client_token1, &uid1, 2, &uid2);
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND);
+    // The partner cannot share lists of the user
+    let response =
+        share_shopping_list_without_ok_check(server.address(), &client_token2, &uid2, 1, &uid1);
+    assert_status(&response, constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND);
+}
@@ -0,0 +1,322 @@
+use serde_json::Value as JsonValue;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan_item;
+use crate::db::core::recipe;
+use crate::db::core::recipe::Recipe;
+use crate::db::core::recipe_ingredient;
+use crate::db::core::shopping_list;
+use crate::db::core::shopping_list::ShoppingList;
+use crate::db::core::shopping_list_item;
+use crate::db::core::shopping_list_share;
+use crate::server::cmds::foodstuff_share_utils::select_paired_partner;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Foodstuff source of a shopping list - |foodstuff_id| is the |app_user_foodstuff_id|
+/// of a foodstuff of the user.
+#[derive(Debug, Deserialize)]
+struct FoodstuffSourceArg {
+    foodstuff_id: i32,
+    weight: i32,
+}
+
+/// Recipe source of a shopping list - |recipe_id| is the |app_user_recipe_id|
+/// of a recipe of the user, |weight| is the weight of the whole cooked recipe.
+#[derive(Debug, Deserialize)]
+struct RecipeSourceArg {
+    recipe_id: i32,
+    weight: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShoppingListSourcesArgs {
+    #[serde(default)]
+    foodstuffs: Vec<FoodstuffSourceArg>,
+    #[serde(default)]
+    recipes: Vec<RecipeSourceArg>,
+    /// UID of a partner with whom the user shares a meal plan.
+    #[serde(default)]
+    meal_plan_partner_user_id: Option<String>,
+}
+
+/// Parses body of the form
+/// {"foodstuffs": [{"foodstuff_id": 1, "weight": 100}],
+///  "recipes": [{"recipe_id": 1, "weight": 300}],
+///  "meal_plan_partner_user_id": "uid"}.
+/// All fields are optional, but at least one source must be given.
+pub fn parse_shopping_list_sources(body: &str) -> Result<ShoppingListSourcesArgs, RequestError> {
+    let args = serde_json::from_str::<ShoppingListSourcesArgs>(body).map_err(|err| {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid shopping list sources: {}", err),
+        )
+    })?;
+    if args.foodstuffs.is_empty()
+        && args.recipes.is_empty()
+        && args.meal_plan_partner_user_id.is_none()
+    {
+        return Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            "Shopping list must have at least 1 source".to_owned(),
+        ));
+    }
+    if let Some(source) = args.foodstuffs.iter().find(|s| s.weight <= 0) {
+        return Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Weight of foodstuff must be positive: {:?}", source),
+        ));
+    }
+    if let Some(source) = args.recipes.iter().find(|s| s.weight <= 0) {
+        return Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Weight of recipe must be positive: {:?}", source),
+        ));
+    }
+    Ok(args)
+}
+
+/// Sums weights per foodstuff, keeping the order in which foodstuffs were first met.
+#[derive(Default)]
+struct WeightsAggregator {
+    foodstuffs: Vec<Foodstuff>,
+    weights: HashMap<i32, i64>,
+}
+
+impl WeightsAggregator {
+    fn add(&mut self, foodstuff: Foodstuff, weight: i64) {
+        match self.weights.get_mut(&foodstuff.id()) {
+            Some(total_weight) => *total_weight += weight,
+            None => {
+                self.weights.insert(foodstuff.id(), weight);
+                self.foodstuffs.push(foodstuff);
+            }
+        }
+    }
+
+    /// Adds ingredients of the recipe, weights of which are scaled so that
+    /// their sum is equal to |weight|.
+    fn add_recipe(
+        &mut self,
+        recipe: &Recipe,
+        weight: i32,
+        connection: &dyn DBConnection,
+    ) -> Result<(), RequestError> {
+        let ingredients = recipe_ingredient::select_by_recipe_id(recipe.id(), connection)?;
+        let ingredients_weight: i64 = ingredients.iter().map(|i| i64::from(i.weight())).sum();
+        if ingredients_weight == 0 {
+            return Ok(());
+        }
+        for ingredient in &ingredients {
+            let foodstuff = match foodstuff::select_by_id(ingredient.foodstuff_id(), connection)? {
+                Some(foodstuff) => foodstuff,
+                None => continue, // Foodstuff was deleted a couple of ms ago
+            };
+            // Rounded to the nearest gram
+            let scaled_weight = (2 * i64::from(ingredient.weight()) * i64::from(weight)
+                + ingredients_weight)
+                / (2 * ingredients_weight);
+            self.add(foodstuff, scaled_weight);
+        }
+        Ok(())
+    }
+
+    fn into_weighted_foodstuffs(self) -> Result<Vec<(Foodstuff, i32)>, RequestError> {
+        let weights = self.weights;
+        self.foodstuffs
+            .into_iter()
+            .filter(|foodstuff| weights[&foodstuff.id()] > 0)
+            .map(|foodstuff| {
+                let weight = weights[&foodstuff.id()];
+                if weight > i64::from(i32::MAX) {
+                    return Err(RequestError::new(
+                        constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+                        format!("Weight of foodstuff is too big: {}", weight),
+                    ));
+                }
+                Ok((foodstuff, weight as i32))
+            })
+            .collect()
+    }
+}
+
+/// Expands recipes and the meal plan of the sources into foodstuffs and sums
+/// weights per foodstuff.
+/// Foodstuffs and recipes can belong only to the |user|, but a meal plan
+/// can bring foodstuffs of the partner with whom the plan is shared.
+pub fn aggregate_sources(
+    sources: &ShoppingListSourcesArgs,
+    user: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<Vec<(Foodstuff, i32)>, RequestError> {
+    let mut aggregator = WeightsAggregator::default();
+
+    for source in &sources.foodstuffs {
+        let foodstuff =
+            foodstuff::select_by_app_user_foodstuff_id(user.id(), source.foodstuff_id, connection)?;
+        match foodstuff {
+            Some(foodstuff) if foodstuff.is_listed() => {
+                aggregator.add(foodstuff, i64::from(source.weight))
+            }
+            _ => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+                    format!("Foodstuff not found, ID: {}", source.foodstuff_id),
+                ))
+            }
+        }
+    }
+
+    for source in &sources.recipes {
+        let recipe = recipe::select_by_app_user_recipe_id(user.id(), source.recipe_id, connection)?;
+        match recipe {
+            Some(recipe) => aggregator.add_recipe(&recipe, source.weight, connection)?,
+            None => {
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_RECIPE_NOT_FOUND.to_owned(),
+                    format!("Recipe not found, ID: {}", source.recipe_id),
+                ))
+            }
+        }
+    }
+
+    if let Some(partner_uid) = &sources.meal_plan_partner_user_id {
+        let partner = select_paired_partner(user, partner_uid, connection)?;
+        let meal_plan =
+            meal_plan::select_by_partners_user_ids(user.id(), partner.id(), connection)?;
+        // Partners might not have created a plan yet
+        if let Some(meal_plan) = meal_plan {
+            let items = meal_plan_item::select_by_meal_plan_id(meal_plan.id(), connection)?;
+            for item in &items {
+                if let Some(foodstuff_id) = item.foodstuff_id() {
+                    if let Some(foodstuff) = foodstuff::select_by_id(foodstuff_id, connection)? {
+                        aggregator.add(foodstuff, i64::from(item.weight()));
+                    }
+                } else if let Some(recipe_id) = item.recipe_id() {
+                    if let Some(recipe) = recipe::select_by_id(recipe_id, connection)? {
+                        aggregator.add_recipe(&recipe, item.weight(), connection)?;
+                    }
+                }
+            }
+        }
+    }
+
+    aggregator.into_weighted_foodstuffs()
+}
+
+/// Inserts items of the shopping list, IDs of items start from 1.
+pub fn insert_items(
+    shopping_list: &ShoppingList,
+    weighted_foodstuffs: &[(Foodstuff, i32)],
+    connection: &dyn DBConnection,
+) -> Result<(), RequestError> {
+    for (index, (foodstuff, weight)) in weighted_foodstuffs.iter().enumerate() {
+        let item = shopping_list_item::new(shopping_list, index as i32 + 1, foodstuff, *weight);
+        shopping_list_item::insert(item, connection)?;
+    }
+    Ok(())
+}
+
+/// Selects the shopping list only if the |user| either owns it or it's
+/// shared with the |user|.
+/// Returns the list and its owner.
+pub fn select_accessible_shopping_list(
+    user: &AppUser,
+    owner_uid: &str,
+    app_user_shopping_list_id: i32,
+    connection: &dyn DBConnection,
+) -> Result<(ShoppingList, AppUser), RequestError> {
+    let owner = app_user::select_by_uid(&Uuid::from_str(owner_uid)?, connection)?;
+    let owner = match owner {
+        Some(owner) => owner,
+        None => return Err(shopping_list_not_found_error(app_user_shopping_list_id)),
+    };
+
+    let shopping_list = shopping_list::select_by_app_user_shopping_list_id(
+        owner.id(),
+        app_user_shopping_list_id,
+        connection,
+    )?;
+    let shopping_list = match shopping_list {
+        Some(shopping_list) => shopping_list,
+        None => return Err(shopping_list_not_found_error(app_user_shopping_list_id)),
+    };
+    if owner.id() != user.id() {
+        let share = shopping_list_share::select_by_shopping_list_id_and_partner_user_id(
+            shopping_list.id(),
+            user.id(),
+            connection,
+        )?;
+        if share.is_none() {
+            return Err(shopping_list_not_found_error(app_user_shopping_list_id));
+        }
+    }
+    Ok((shopping_list, owner))
+}
+
+pub fn shopping_list_not_found_error(app_user_shopping_list_id: i32) -> RequestError {
+    RequestError::new(
+        constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND.to_owned(),
+        format!("Shopping list not found, ID: {}", app_user_shopping_list_id),
+    )
+}
+
+/// Selects items of the shopping list and builds list's JSON of the form
+/// {"owner_user_id": "uid", "shopping_list_id": 1, "shopping_list_name": "name",
+///  "items": [{"item_id": 1, "owner_user_id": "uid", "foodstuff_id": 1,
+///             "foodstuff_name": "apple", "weight": 100, "is_checked": false}]},
+/// where items' |owner_user_id| is the UID of the owner of the foodstuff.
+pub fn shopping_list_to_json(
+    shopping_list: &ShoppingList,
+    owner: &AppUser,
+    connection: &dyn DBConnection,
+) -> Result<JsonValue, RequestError> {
+    let items = shopping_list_item::select_by_shopping_list_id(shopping_list.id(), connection)?;
+
+    let mut foodstuffs_owners: HashMap<i32, AppUser> = HashMap::new();
+    let mut json_items = Vec::with_capacity(items.len());
+    for item in &items {
+        let foodstuff = match foodstuff::select_by_id(item.foodstuff_id(), connection)? {
+            Some(foodstuff) => foodstuff,
+            None => continue, // Foodstuff was deleted a couple of ms ago
+        };
+        let foodstuff_owner_uid = if foodstuff.app_user_id() == owner.id() {
+            owner.uid().to_string()
+        } else {
+            let foodstuff_owner = match foodstuffs_owners.entry(foodstuff.app_user_id()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    match app_user::select_by_id(foodstuff.app_user_id(), connection)? {
+                        Some(foodstuff_owner) => entry.insert(foodstuff_owner),
+                        None => continue, // Owner was deleted a couple of ms ago
+                    }
+                }
+            };
+            foodstuff_owner.uid().to_string()
+        };
+        json_items.push(json!({
+            constants::FIELD_NAME_ITEM_ID: item.list_item_id(),
+            constants::FIELD_NAME_OWNER_USER_ID: foodstuff_owner_uid,
+            constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+            constants::FIELD_NAME_FOODSTUFF_NAME: foodstuff.name(),
+            constants::FIELD_NAME_WEIGHT: item.weight(),
+            constants::FIELD_NAME_IS_CHECKED: item.is_checked(),
+        }));
+    }
+
+    Ok(json!({
+        constants::FIELD_NAME_OWNER_USER_ID: owner.uid().to_string(),
+        constants::FIELD_NAME_SHOPPING_LIST_ID: shopping_list.app_user_shopping_list_id(),
+        constants::FIELD_NAME_SHOPPING_LIST_NAME: shopping_list.name(),
+        constants::FIELD_NAME_ITEMS: json_items,
+    }))
+}
@@ -0,0 +1 @@
+pub mod sync_foodstuffs_cmd_handler;
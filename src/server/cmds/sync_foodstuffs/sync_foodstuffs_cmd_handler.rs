@@ -0,0 +1,270 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::foodstuff;
+use crate::db::core::foodstuff::Foodstuff;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::nutrition::nutrients::to_decimal_string;
+use crate::nutrition::nutrients::Nutrients;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::cmds::utils::parse_nutrient_or_request_error;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+/// Syncs foodstuffs of a client with the server.
+///
+/// The client sends a sync token received from a previous sync (or no token for a full sync)
+/// and, optionally, a body with a log of its local changes:
+/// {"changes": [{"foodstuff_id": 1, "base_revision": 123, "foodstuff_name": "...",
+///   "protein": "1.5", "fats": "1", "carbs": "1", "calories": "100", "is_listed": true}]}
+/// where |base_revision| is the revision of the server version of the foodstuff which the
+/// change is based on (absent for foodstuffs the client created itself).
+///
+/// A change is applied only if its base revision matches the current revision of the
+/// foodstuff on the server. Otherwise the server version wins - the change is dropped,
+/// its foodstuff ID is put into the "conflicts" list and the server version is always
+/// included into the response, so that the client can overwrite its local version.
+/// Unlisting can't be undone - a change with "is_listed": true doesn't list back
+/// an unlisted foodstuff.
+///
+/// Response contains all foodstuffs modified since the given sync token (including
+/// the ones just modified by the client's changes) and a new sync token.
+#[derive(Default)]
+pub struct SyncFoodstuffsCmdHandler {}
+
+#[derive(Deserialize)]
+struct SyncFoodstuffsArgs {
+    sync_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientChangeLog {
+    changes: Vec<ClientChange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientChange {
+    foodstuff_id: i32,
+    base_revision: Option<i64>,
+    foodstuff_name: String,
+    protein: String,
+    fats: String,
+    carbs: String,
+    calories: String,
+    is_listed: bool,
+}
+
+/// Nutrients of a change, parsed from decimal strings into fixed-point values.
+struct ChangeNutrients {
+    protein: i32,
+    fats: i32,
+    carbs: i32,
+    calories: i32,
+}
+
+impl ChangeNutrients {
+    fn parse(change: &ClientChange) -> Result<Self, RequestError> {
+        Ok(ChangeNutrients {
+            protein: parse_nutrient_or_request_error(constants::ARG_PROTEIN, &change.protein)?,
+            fats: parse_nutrient_or_request_error(constants::ARG_FATS, &change.fats)?,
+            carbs: parse_nutrient_or_request_error(constants::ARG_CARBS, &change.carbs)?,
+            calories: parse_nutrient_or_request_error(constants::ARG_CALORIES, &change.calories)?,
+        })
+    }
+}
+
+impl CmdHandler for SyncFoodstuffsCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            body,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl SyncFoodstuffsCmdHandler {
+    pub fn new() -> Self {
+        SyncFoodstuffsCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        body: String,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: SyncFoodstuffsArgs = parse_args(&args)?;
+        let sync_token = parse_sync_token(cmd_args.sync_token)?;
+        let change_log = parse_change_log(&body)?;
+
+        let (foodstuffs, conflicts, new_sync_token) = db_transaction(&connection, || {
+            foodstuff::lock_for_modification(user.id(), &connection)?;
+
+            let mut conflicts = Vec::new();
+            for change in change_log.changes {
+                apply_change(&user, change, &mut conflicts, &connection)?;
+            }
+
+            let mut foodstuffs =
+                foodstuff::select_changed_since(user.id(), sync_token, &connection)?;
+            for conflict in &conflicts {
+                let already_included = foodstuffs
+                    .iter()
+                    .any(|foodstuff| foodstuff.app_user_foodstuff_id() == *conflict);
+                if already_included {
+                    continue;
+                }
+                let conflicting =
+                    foodstuff::select_by_app_user_foodstuff_id(user.id(), *conflict, &connection)?;
+                if let Some(conflicting) = conflicting {
+                    foodstuffs.push(conflicting);
+                }
+            }
+
+            let new_sync_token = foodstuff::select_max_revision(user.id(), &connection)?;
+            let new_sync_token = new_sync_token.unwrap_or(0).max(sync_token);
+            Ok((foodstuffs, conflicts, new_sync_token))
+        })?;
+
+        let json_foodstuffs: Vec<_> = foodstuffs.iter().map(foodstuff_to_json).collect();
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_SYNC_TOKEN: new_sync_token.to_string(),
+            constants::FIELD_NAME_FOODSTUFFS: json_foodstuffs,
+            constants::FIELD_NAME_CONFLICTS: conflicts,
+        }))
+    }
+}
+
+/// Sync token is opaque for clients, internally it's the max revision
+/// of the user's foodstuffs the client knows about.
+fn parse_sync_token(sync_token: Option<String>) -> Result<i64, RequestError> {
+    let sync_token = match sync_token {
+        Some(sync_token) if !sync_token.is_empty() => sync_token,
+        _ => return Ok(0),
+    };
+    match sync_token.parse::<i64>() {
+        Ok(sync_token) if sync_token >= 0 => Ok(sync_token),
+        _ => Err(RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid sync token: {}", sync_token),
+        )),
+    }
+}
+
+fn parse_change_log(body: &str) -> Result<ClientChangeLog, RequestError> {
+    if body.trim().is_empty() {
+        return Ok(ClientChangeLog {
+            changes: Vec::new(),
+        });
+    }
+    serde_json::from_str::<ClientChangeLog>(body).map_err(|err| {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!("Invalid change log: {}", err),
+        )
+    })
+}
+
+/// Puts ID of the foodstuff into |conflicts| instead of applying the change if the change
+/// is in conflict with the server version of the foodstuff.
+fn apply_change(
+    user: &AppUser,
+    change: ClientChange,
+    conflicts: &mut Vec<i32>,
+    connection: &dyn DBConnection,
+) -> Result<(), RequestError> {
+    let nutrients = ChangeNutrients::parse(&change)?;
+    let existing =
+        foodstuff::select_by_app_user_foodstuff_id(user.id(), change.foodstuff_id, connection)?;
+    let existing = match existing {
+        Some(existing) => existing,
+        None => {
+            let new_foodstuff = foodstuff::new(
+                user,
+                change.foodstuff_id,
+                change.foodstuff_name,
+                nutrients.protein,
+                nutrients.fats,
+                nutrients.carbs,
+                nutrients.calories,
+                change.is_listed,
+            );
+            foodstuff::insert(new_foodstuff, connection)?;
+            return Ok(());
+        }
+    };
+
+    if change.base_revision != Some(existing.revision()) {
+        conflicts.push(change.foodstuff_id);
+        return Ok(());
+    }
+
+    let updated = foodstuff::update(
+        existing,
+        change.foodstuff_name,
+        nutrients.protein,
+        nutrients.fats,
+        nutrients.carbs,
+        nutrients.calories,
+        connection,
+    )?;
+    let updated = match updated {
+        Some(updated) => updated,
+        None => return Err(foodstuff_not_found_error(change.foodstuff_id)),
+    };
+    if !change.is_listed && updated.is_listed() {
+        let unlisted = foodstuff::unlist(updated, connection)?;
+        if unlisted.is_none() {
+            return Err(foodstuff_not_found_error(change.foodstuff_id));
+        }
+    }
+    Ok(())
+}
+
+pub fn foodstuff_to_json(foodstuff: &Foodstuff) -> JsonValue {
+    let nutrients = Nutrients::of_foodstuff(foodstuff);
+    json!({
+        constants::FIELD_NAME_FOODSTUFF_ID: foodstuff.app_user_foodstuff_id(),
+        constants::FIELD_NAME_FOODSTUFF_NAME: foodstuff.name(),
+        constants::FIELD_NAME_PROTEIN: to_decimal_string(nutrients.protein()),
+        constants::FIELD_NAME_FATS: to_decimal_string(nutrients.fats()),
+        constants::FIELD_NAME_CARBS: to_decimal_string(nutrients.carbs()),
+        constants::FIELD_NAME_CALORIES: to_decimal_string(nutrients.calories()),
+        constants::FIELD_NAME_IS_LISTED: foodstuff.is_listed(),
+        constants::FIELD_NAME_REVISION: foodstuff.revision(),
+    })
+}
+
+fn foodstuff_not_found_error(app_user_foodstuff_id: i32) -> RequestError {
+    RequestError::new(
+        constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+        format!("Foodstuff not found, ID: {}", app_user_foodstuff_id),
+    )
+}
+
+#[cfg(test)]
+#[path = "./sync_foodstuffs_cmd_handler_test.rs"]
+mod sync_foodstuffs_cmd_handler_test;
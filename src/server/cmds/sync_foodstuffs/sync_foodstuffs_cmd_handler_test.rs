@@ -0,0 +1,357 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::make_request_with_body;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+fn sync_foodstuffs(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    sync_token: &str,
+    changes: JsonValue,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_SYNC_FOODSTUFFS,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_SYNC_TOKEN,
+        percent_encode(sync_token.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request_with_body(&url, json!({ "changes": changes }).to_string())
+}
+
+fn sync_token_of(response: &JsonValue) -> String {
+    response[constants::FIELD_NAME_SYNC_TOKEN]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+fn foodstuffs_of(response: &JsonValue) -> &Vec<JsonValue> {
+    response[constants::FIELD_NAME_FOODSTUFFS]
+        .as_array()
+        .unwrap()
+}
+
+fn revision_of(response: &JsonValue, foodstuff_id: i32) -> i64 {
+    foodstuffs_of(response)
+        .iter()
+        .find(|foodstuff| foodstuff[constants::FIELD_NAME_FOODSTUFF_ID] == foodstuff_id)
+        .unwrap()[constants::FIELD_NAME_REVISION]
+        .as_i64()
+        .unwrap()
+}
+
+#[test]
+fn full_and_incremental_sync() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f204-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "pear",
+        "5",
+        "6",
+        "7",
+        "8",
+    );
+
+    // Full sync
+    let response = sync_foodstuffs(server.address(), &client_token, &uid, "", json!([]));
+    assert_status_ok(&response);
+    let foodstuffs = foodstuffs_of(&response);
+    assert_eq!(2, foodstuffs.len());
+    assert_eq!("apple", foodstuffs[0][constants::FIELD_NAME_FOODSTUFF_NAME]);
+    assert_eq!(true, foodstuffs[0][constants::FIELD_NAME_IS_LISTED]);
+    assert_eq!("pear", foodstuffs[1][constants::FIELD_NAME_FOODSTUFF_NAME]);
+    let sync_token = sync_token_of(&response);
+
+    // Nothing changed
+    let response = sync_foodstuffs(
+        server.address(),
+        &client_token,
+        &uid,
+        &sync_token,
+        json!([]),
+    );
+    assert_status_ok(&response);
+    assert!(foodstuffs_of(&response).is_empty());
+    assert_eq!(sync_token, sync_token_of(&response));
+
+    // Only new foodstuff is expected
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        3,
+        "plum",
+        "1",
+        "1",
+        "1",
+        "1",
+    );
+    let response = sync_foodstuffs(
+        server.address(),
+        &client_token,
+        &uid,
+        &sync_token,
+        json!([]),
+    );
+    assert_status_ok(&response);
+    let foodstuffs = foodstuffs_of(&response);
+    assert_eq!(1, foodstuffs.len());
+    assert_eq!(3, foodstuffs[0][constants::FIELD_NAME_FOODSTUFF_ID]);
+    assert_ne!(sync_token, sync_token_of(&response));
+}
+
+#[test]
+fn client_changes_are_applied() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f204-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        2,
+        "pear",
+        "5",
+        "6",
+        "7",
+        "8",
+    );
+    let response = sync_foodstuffs(server.address(), &client_token, &uid, "", json!([]));
+    let sync_token = sync_token_of(&response);
+    let apple_revision = revision_of(&response, 1);
+    let pear_revision = revision_of(&response, 2);
+
+    let changes = json!([
+        {
+            "foodstuff_id": 1,
+            "base_revision": apple_revision,
+            "foodstuff_name": "green apple",
+            "protein": "10.5", "fats": "20", "carbs": "30", "calories": "40",
+            "is_listed": true,
+        },
+        {
+            "foodstuff_id": 2,
+            "base_revision": pear_revision,
+            "foodstuff_name": "pear",
+            "protein": "5", "fats": "6", "carbs": "7", "calories": "8",
+            "is_listed": false,
+        },
+        {
+            "foodstuff_id": 3,
+            "foodstuff_name": "plum",
+            "protein": "1", "fats": "1", "carbs": "1", "calories": "1",
+            "is_listed": true,
+        },
+    ]);
+    let response = sync_foodstuffs(server.address(), &client_token, &uid, &sync_token, changes);
+    assert_status_ok(&response);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_CONFLICTS]);
+    // Modified foodstuffs are returned with their new revisions
+    assert_eq!(3, foodstuffs_of(&response).len());
+    assert!(revision_of(&response, 1) > apple_revision);
+    assert!(revision_of(&response, 2) > pear_revision);
+
+    let response = list_foodstuffs(server.address(), &client_token, &uid);
+    let expected_foodstuffs = json!([
+        {
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "green apple",
+            constants::FIELD_NAME_PROTEIN: "10.5",
+            constants::FIELD_NAME_FATS: "20",
+            constants::FIELD_NAME_CARBS: "30",
+            constants::FIELD_NAME_CALORIES: "40",
+        },
+        {
+            constants::FIELD_NAME_FOODSTUFF_ID: 3,
+            constants::FIELD_NAME_FOODSTUFF_NAME: "plum",
+            constants::FIELD_NAME_PROTEIN: "1",
+            constants::FIELD_NAME_FATS: "1",
+            constants::FIELD_NAME_CARBS: "1",
+            constants::FIELD_NAME_CALORIES: "1",
+        }
+    ]);
+    assert_eq!(
+        expected_foodstuffs,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+    );
+}
+
+#[test]
+fn conflicting_edits_of_2_devices() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f204-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    // Both devices are synced
+    let response = sync_foodstuffs(server.address(), &client_token, &uid, "", json!([]));
+    let sync_token = sync_token_of(&response);
+    let base_revision = revision_of(&response, 1);
+
+    // First device edits the foodstuff
+    let changes = json!([{
+        "foodstuff_id": 1,
+        "base_revision": base_revision,
+        "foodstuff_name": "green apple",
+        "protein": "1", "fats": "2", "carbs": "3", "calories": "4",
+        "is_listed": true,
+    }]);
+    let response = sync_foodstuffs(server.address(), &client_token, &uid, &sync_token, changes);
+    assert_status_ok(&response);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_CONFLICTS]);
+
+    // Second device edits the same foodstuff without knowing about the first edit
+    let changes = json!([{
+        "foodstuff_id": 1,
+        "base_revision": base_revision,
+        "foodstuff_name": "red apple",
+        "protein": "1", "fats": "2", "carbs": "3", "calories": "4",
+        "is_listed": true,
+    }]);
+    let response = sync_foodstuffs(server.address(), &client_token, &uid, &sync_token, changes);
+    assert_status_ok(&response);
+    assert_eq!(json!([1]), response[constants::FIELD_NAME_CONFLICTS]);
+    // Server version wins and is returned to the second device
+    let foodstuffs = foodstuffs_of(&response);
+    assert_eq!(1, foodstuffs.len());
+    assert_eq!(
+        "green apple",
+        foodstuffs[0][constants::FIELD_NAME_FOODSTUFF_NAME]
+    );
+
+    // Second device creates a foodstuff with an ID which is already taken
+    let changes = json!([{
+        "foodstuff_id": 1,
+        "foodstuff_name": "plum",
+        "protein": "1", "fats": "1", "carbs": "1", "calories": "1",
+        "is_listed": true,
+    }]);
+    let last_sync_token = sync_token_of(&response);
+    let response = sync_foodstuffs(
+        server.address(),
+        &client_token,
+        &uid,
+        &last_sync_token,
+        changes,
+    );
+    assert_status_ok(&response);
+    assert_eq!(json!([1]), response[constants::FIELD_NAME_CONFLICTS]);
+    // The conflicting foodstuff is returned even though it's older than the sync token
+    let foodstuffs = foodstuffs_of(&response);
+    assert_eq!(1, foodstuffs.len());
+    assert_eq!(
+        "green apple",
+        foodstuffs[0][constants::FIELD_NAME_FOODSTUFF_NAME]
+    );
+}
+
+#[test]
+fn invalid_sync_token() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f204-0000-0000-000000000003").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = sync_foodstuffs(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        "not_a_token",
+        json!([]),
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
+
+#[test]
+fn invalid_change_log() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f204-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let changes = json!([{
+        "foodstuff_id": 1,
+        "foodstuff_name": "apple",
+    }]);
+    let response = sync_foodstuffs(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        "",
+        changes,
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+}
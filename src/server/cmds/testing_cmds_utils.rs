@@ -9,8 +9,9 @@ use serde_json::Value as JsonValue;
 use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::config::Config;
 use crate::db::core::testing_util::testing_connection_for_server_user;
-use crate::outside::http_client::{HttpClient, RequestMethod};
+use crate::outside::http_client::{HttpClient, RequestMethod, Response};
 use crate::server::cmds::register_user::user_data_generators::create_gp_overrides;
 use crate::server::constants;
 use crate::server::requests_handler_impl::RequestsHandlerImpl;
@@ -46,7 +47,10 @@ macro_rules! start_server {
 }
 
 pub fn start_server_with_overrides(overrides: &JsonValue) -> ServerWrapper {
-    let config = config_in_tests();
+    start_server_with_config(config_in_tests(), overrides)
+}
+
+pub fn start_server_with_config(config: Config, overrides: &JsonValue) -> ServerWrapper {
     // NOTE: address is acquired before handler requests creation for a reason -
     // address is a mutex lock, and multiple structs RequestsHandlerImpl can't live simultaneously
     let address = testing_hostname::get_hostname();
@@ -72,14 +76,21 @@ pub fn make_request(url: &str) -> JsonValue {
 }
 
 pub fn make_request_with_body(url: &str, body: String) -> JsonValue {
-    let http_client = Arc::new(HttpClient::new().unwrap());
-    let response = http_client.req(
-        Uri::from_str(url).unwrap(),
-        RequestMethod::Get,
-        HashMap::new(),
-        Some(body),
-    );
-    let response = exhaust_future(response).unwrap();
+    make_request_with_headers(url, HashMap::new(), body)
+}
+
+pub fn make_request_with_json_body(url: &str, body: &JsonValue) -> JsonValue {
+    let mut headers = HashMap::new();
+    headers.insert("content-type".to_owned(), "application/json".to_owned());
+    make_request_with_headers(url, headers, body.to_string())
+}
+
+pub fn make_request_with_headers(
+    url: &str,
+    headers: HashMap<String, String>,
+    body: String,
+) -> JsonValue {
+    let response = make_raw_request_with_headers(url, headers, body);
     serde_json::from_str(&response.body).unwrap_or_else(|_| {
         panic!(
             "Expected JSON response for query: {}, got: {:?}",
@@ -88,6 +99,26 @@ pub fn make_request_with_body(url: &str, body: String) -> JsonValue {
     })
 }
 
+/// Response with its HTTP status code and headers, its body is not required to be JSON.
+pub fn make_raw_request(url: &str) -> Response {
+    make_raw_request_with_headers(url, HashMap::new(), "".to_owned())
+}
+
+pub fn make_raw_request_with_headers(
+    url: &str,
+    headers: HashMap<String, String>,
+    body: String,
+) -> Response {
+    let http_client = Arc::new(HttpClient::new().unwrap());
+    let response = http_client.req(
+        Uri::from_str(url).unwrap(),
+        RequestMethod::Get,
+        headers,
+        Some(body),
+    );
+    exhaust_future(response).unwrap()
+}
+
 pub fn assert_status_ok(response: &JsonValue) {
     assert_status(response, constants::FIELD_STATUS_OK);
 }
@@ -147,6 +178,8 @@ pub fn register_named_user(serv_address: &str, uuid: &Uuid, gp_uid: &str, name:
     response
 }
 
+/// Sets the token the way clients not knowing about devices do - the token is set
+/// to the legacy device of the user.
 pub fn set_user_fcm_token(
     serv_address: &str,
     client_token: &str,
@@ -178,6 +211,47 @@ pub fn set_user_fcm_token_without_ok_check(
     make_request(&url)
 }
 
+pub fn set_device_fcm_token(
+    serv_address: &str,
+    client_token: &str,
+    uid: &str,
+    device_id: &str,
+    fcm_token: &str,
+) -> JsonValue {
+    let response = set_device_fcm_token_without_ok_check(
+        serv_address,
+        client_token,
+        uid,
+        device_id,
+        fcm_token,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+pub fn set_device_fcm_token_without_ok_check(
+    serv_address: &str,
+    client_token: &str,
+    uid: &str,
+    device_id: &str,
+    fcm_token: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}",
+        serv_address,
+        &constants::CMD_UPDATE_FCM_TOKEN,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(&client_token.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_DEVICE_ID,
+        percent_encode(&device_id.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_FCM_TOKEN,
+        percent_encode(&fcm_token.as_bytes(), DEFAULT_ENCODE_SET).to_string()
+    );
+    make_request(&url)
+}
+
 pub fn start_pairing(server_addr: &str, client_token: &str, uid: &str) -> JsonValue {
     let url = format!(
         "http://{}{}?{}={}&{}={}",
@@ -208,6 +282,19 @@ pub fn pairing_request_by_code_with_overrides(
     uid: &str,
     code: &str,
     overrides: &JsonValue,
+) -> JsonValue {
+    let response =
+        pairing_request_by_code_without_ok_check(server_addr, client_token, uid, code, overrides);
+    assert_status_ok(&response);
+    response
+}
+
+pub fn pairing_request_by_code_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    code: &str,
+    overrides: &JsonValue,
 ) -> JsonValue {
     let url = format!(
         "http://{}{}?{}={}&{}={}&{}={}&{}={}",
@@ -222,9 +309,7 @@ pub fn pairing_request_by_code_with_overrides(
         &constants::ARG_OVERRIDES,
         percent_encode(&overrides.to_string().as_bytes(), DEFAULT_ENCODE_SET).to_string(),
     );
-    let response = make_request(&url);
-    assert_status_ok(&response);
-    response
+    make_request(&url)
 }
 
 pub fn pairing_request_by_uid(
@@ -280,3 +365,543 @@ pub fn list_partners(server_addr: &str, client_token: &str, uid: &str) -> JsonVa
     assert_status_ok(&response);
     response
 }
+
+pub fn fetch_messages(server_addr: &str, client_token: &str, uid: &str, cursor: &str) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_FETCH_MESSAGES,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(&client_token.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CURSOR,
+        percent_encode(cursor.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_foodstuff(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    foodstuff_id: i32,
+    name: &str,
+    protein: &str,
+    fats: &str,
+    carbs: &str,
+    calories: &str,
+) -> JsonValue {
+    let response = add_foodstuff_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        foodstuff_id,
+        name,
+        protein,
+        fats,
+        carbs,
+        calories,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_foodstuff_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    foodstuff_id: i32,
+    name: &str,
+    protein: &str,
+    fats: &str,
+    carbs: &str,
+    calories: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_ADD_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        foodstuff_id,
+        &constants::ARG_FOODSTUFF_NAME,
+        percent_encode(name.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PROTEIN,
+        protein,
+        &constants::ARG_FATS,
+        fats,
+        &constants::ARG_CARBS,
+        carbs,
+        &constants::ARG_CALORIES,
+        calories,
+    );
+    make_request(&url)
+}
+
+pub fn list_foodstuffs(server_addr: &str, client_token: &str, uid: &str) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server_addr,
+        &constants::CMD_LIST_FOODSTUFFS,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+    response
+}
+
+pub fn add_history_entry(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    history_entry_id: i32,
+    foodstuff_id: i32,
+    mass: i32,
+    time: i64,
+) -> JsonValue {
+    let response = add_history_entry_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        history_entry_id,
+        foodstuff_id,
+        mass,
+        time,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+pub fn add_history_entry_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    history_entry_id: i32,
+    foodstuff_id: i32,
+    mass: i32,
+    time: i64,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_ADD_HISTORY_ENTRY,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_HISTORY_ENTRY_ID,
+        history_entry_id,
+        &constants::ARG_FOODSTUFF_ID,
+        foodstuff_id,
+        &constants::ARG_MASS,
+        mass,
+        &constants::ARG_TIME,
+        time,
+    );
+    make_request(&url)
+}
+
+pub fn list_history(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    time_from: i64,
+    time_to: i64,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_LIST_HISTORY,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_TIME_FROM,
+        time_from,
+        &constants::ARG_TIME_TO,
+        time_to,
+    );
+    make_request(&url)
+}
+
+/// |ingredients| is a list of pairs (foodstuff_id, weight).
+pub fn create_recipe(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    recipe_id: i32,
+    name: &str,
+    ingredients: &[(i32, i32)],
+) -> JsonValue {
+    let response = create_recipe_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        recipe_id,
+        name,
+        ingredients,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+pub fn create_recipe_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    recipe_id: i32,
+    name: &str,
+    ingredients: &[(i32, i32)],
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_CREATE_RECIPE,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_RECIPE_ID,
+        recipe_id,
+        &constants::ARG_RECIPE_NAME,
+        percent_encode(name.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request_with_body(&url, ingredients_body(ingredients))
+}
+
+/// |ingredients| is a list of pairs (foodstuff_id, weight).
+pub fn ingredients_body(ingredients: &[(i32, i32)]) -> String {
+    let ingredients: Vec<_> = ingredients
+        .iter()
+        .map(|(foodstuff_id, weight)| {
+            json!({
+                constants::FIELD_NAME_FOODSTUFF_ID: foodstuff_id,
+                constants::FIELD_NAME_WEIGHT: weight,
+            })
+        })
+        .collect();
+    json!({ constants::FIELD_NAME_INGREDIENTS: ingredients }).to_string()
+}
+
+pub fn list_recipes(server_addr: &str, client_token: &str, uid: &str) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server_addr,
+        &constants::CMD_LIST_RECIPES,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+    response
+}
+
+pub fn share_foodstuff(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    foodstuff_id: i32,
+    partner_uid: &str,
+    permission: &str,
+) -> JsonValue {
+    let response = share_foodstuff_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        foodstuff_id,
+        partner_uid,
+        permission,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+pub fn share_foodstuff_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    foodstuff_id: i32,
+    partner_uid: &str,
+    permission: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_SHARE_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        foodstuff_id,
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(partner_uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PERMISSION,
+        percent_encode(permission.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request(&url)
+}
+
+pub fn list_shared_foodstuffs(server_addr: &str, client_token: &str, uid: &str) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server_addr,
+        &constants::CMD_LIST_SHARED_FOODSTUFFS,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+    response
+}
+
+pub fn get_meal_plan(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    partner_uid: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_GET_MEAL_PLAN,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(partner_uid.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_meal_plan_meal(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    partner_uid: &str,
+    day: i32,
+    meal_type: &str,
+    version: i64,
+    items: JsonValue,
+) -> JsonValue {
+    let response = update_meal_plan_meal_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        partner_uid,
+        day,
+        meal_type,
+        version,
+        items,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+/// |items| is a JSON array of meal items,
+/// see |meal_plan_utils::parse_meal_items| for their format.
+#[allow(clippy::too_many_arguments)]
+pub fn update_meal_plan_meal_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    partner_uid: &str,
+    day: i32,
+    meal_type: &str,
+    version: i64,
+    items: JsonValue,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_UPDATE_MEAL_PLAN_MEAL,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(partner_uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_DAY,
+        day,
+        &constants::ARG_MEAL_TYPE,
+        meal_type,
+        &constants::ARG_VERSION,
+        version,
+    );
+    let body = json!({ constants::FIELD_NAME_ITEMS: items });
+    make_request_with_body(&url, body.to_string())
+}
+
+/// |sources| is a JSON object with sources of the list,
+/// see |shopping_list_utils::parse_shopping_list_sources| for its format.
+pub fn create_shopping_list(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    shopping_list_id: i32,
+    name: &str,
+    sources: JsonValue,
+) -> JsonValue {
+    let response = create_shopping_list_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        shopping_list_id,
+        name,
+        sources,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+pub fn create_shopping_list_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    shopping_list_id: i32,
+    name: &str,
+    sources: JsonValue,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_CREATE_SHOPPING_LIST,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_SHOPPING_LIST_ID,
+        shopping_list_id,
+        &constants::ARG_SHOPPING_LIST_NAME,
+        percent_encode(name.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request_with_body(&url, sources.to_string())
+}
+
+pub fn share_shopping_list(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    shopping_list_id: i32,
+    partner_uid: &str,
+) -> JsonValue {
+    let response = share_shopping_list_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        shopping_list_id,
+        partner_uid,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+pub fn share_shopping_list_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    shopping_list_id: i32,
+    partner_uid: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_SHARE_SHOPPING_LIST,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_SHOPPING_LIST_ID,
+        shopping_list_id,
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(partner_uid.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    make_request(&url)
+}
+
+pub fn list_shopping_lists(server_addr: &str, client_token: &str, uid: &str) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server_addr,
+        &constants::CMD_LIST_SHOPPING_LISTS,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+    );
+    let response = make_request(&url);
+    assert_status_ok(&response);
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn check_shopping_list_item(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    owner_uid: &str,
+    shopping_list_id: i32,
+    item_id: i32,
+    is_checked: bool,
+) -> JsonValue {
+    let response = check_shopping_list_item_without_ok_check(
+        server_addr,
+        client_token,
+        uid,
+        owner_uid,
+        shopping_list_id,
+        item_id,
+        is_checked,
+    );
+    assert_status_ok(&response);
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn check_shopping_list_item_without_ok_check(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    owner_uid: &str,
+    shopping_list_id: i32,
+    item_id: i32,
+    is_checked: bool,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_CHECK_SHOPPING_LIST_ITEM,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_OWNER_USER_ID,
+        percent_encode(owner_uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_SHOPPING_LIST_ID,
+        shopping_list_id,
+        &constants::ARG_ITEM_ID,
+        item_id,
+        &constants::ARG_IS_CHECKED,
+        is_checked,
+    );
+    make_request(&url)
+}
@@ -0,0 +1 @@
+pub mod unlist_foodstuff_cmd_handler;
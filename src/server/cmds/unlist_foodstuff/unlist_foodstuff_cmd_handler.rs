@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::foodstuff;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+#[derive(Default)]
+pub struct UnlistFoodstuffCmdHandler {}
+
+#[derive(Deserialize)]
+struct UnlistFoodstuffArgs {
+    foodstuff_id: i32,
+}
+
+impl CmdHandler for UnlistFoodstuffCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl UnlistFoodstuffCmdHandler {
+    pub fn new() -> Self {
+        UnlistFoodstuffCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: UnlistFoodstuffArgs = parse_args(&args)?;
+        let app_user_foodstuff_id = cmd_args.foodstuff_id;
+
+        db_transaction(&connection, || {
+            foodstuff::lock_for_modification(user.id(), &connection)?;
+            let foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+                user.id(),
+                app_user_foodstuff_id,
+                &connection,
+            )?;
+            let foodstuff = match foodstuff {
+                Some(foodstuff) => foodstuff,
+                None => {
+                    return Err(RequestError::new(
+                        constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+                        format!("Foodstuff not found, ID: {}", app_user_foodstuff_id),
+                    ))
+                }
+            };
+            foodstuff::unlist(foodstuff, &connection)?;
+            Ok(())
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+#[cfg(test)]
+#[path = "./unlist_foodstuff_cmd_handler_test.rs"]
+mod unlist_foodstuff_cmd_handler_test;
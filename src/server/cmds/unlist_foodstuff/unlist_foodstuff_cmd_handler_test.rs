@@ -0,0 +1,83 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::db::core::app_user;
+use crate::db::core::foodstuff;
+use crate::db::core::testing_util::testing_connection_for_server_user;
+use crate::server::constants;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+fn unlist_foodstuff(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    foodstuff_id: i32,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_UNLIST_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        foodstuff_id,
+    );
+    make_request(&url)
+}
+
+#[test]
+fn unlist_foodstuff_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f202-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = unlist_foodstuff(server.address(), &client_token, &uid.to_string(), 1);
+    assert_status_ok(&response);
+
+    // Unlisted foodstuff is not deleted
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    let foodstuff = foodstuff::select_by_app_user_foodstuff_id(user.id(), 1, &conn)
+        .unwrap()
+        .unwrap();
+    assert!(!foodstuff.is_listed());
+}
+
+#[test]
+fn unlist_of_not_existing_foodstuff() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f202-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = unlist_foodstuff(server.address(), &client_token, &uid.to_string(), 1);
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
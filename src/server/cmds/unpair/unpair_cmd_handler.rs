@@ -5,18 +5,28 @@ use uuid::Uuid;
 
 use crate::config::Config;
 use crate::db::core::app_user;
+use crate::db::core::foodstuff_share;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan_item;
 use crate::db::core::paired_partners;
+use crate::db::core::shopping_list_share;
 use crate::db::pool::connection_pool::ConnectionPool;
 use crate::outside::http_client::HttpClient;
 
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
 use crate::server::cmds::utils::extract_user_from_query_args;
-use crate::server::cmds::utils::HashMapAdditionalOperations;
 use crate::server::constants;
 
 #[derive(Default)]
 pub struct UnpairCmdHandler;
 
+#[derive(Deserialize)]
+struct UnpairArgs {
+    partner_user_id: String,
+}
+
 impl CmdHandler for UnpairCmdHandler {
     fn handle(
         &self,
@@ -48,12 +58,45 @@ impl UnpairCmdHandler {
     ) -> CmdHandleResult {
         let connection = connections_pool.borrow_connection()?;
         let user = extract_user_from_query_args(&args, &connection)?;
-        let partner_uid = args.get_or_request_error(constants::ARG_PARTNER_USER_ID)?;
+        let cmd_args: UnpairArgs = parse_args(&args)?;
+        let partner_uid = cmd_args.partner_user_id;
 
         let partner = app_user::select_by_uid(&Uuid::from_str(&partner_uid)?, &connection)?;
         if let Some(partner) = partner {
-            paired_partners::delete_by_partners_user_ids(user.id(), partner.id(), &connection)?;
-            paired_partners::delete_by_partners_user_ids(partner.id(), user.id(), &connection)?;
+            db_transaction(&connection, || {
+                paired_partners::delete_by_partners_user_ids(user.id(), partner.id(), &connection)?;
+                paired_partners::delete_by_partners_user_ids(partner.id(), user.id(), &connection)?;
+                // Foodstuffs are shared only with paired partners
+                foodstuff_share::delete_by_owner_and_partner_user_ids(
+                    user.id(),
+                    partner.id(),
+                    &connection,
+                )?;
+                foodstuff_share::delete_by_owner_and_partner_user_ids(
+                    partner.id(),
+                    user.id(),
+                    &connection,
+                )?;
+                // Meal plans are shared only by paired partners too
+                let meal_plan =
+                    meal_plan::select_by_partners_user_ids(user.id(), partner.id(), &connection)?;
+                if let Some(meal_plan) = meal_plan {
+                    meal_plan_item::delete_by_meal_plan_id(meal_plan.id(), &connection)?;
+                    meal_plan::delete_by_id(meal_plan.id(), &connection)?;
+                }
+                // And so are shopping lists
+                shopping_list_share::delete_by_owner_and_partner_user_ids(
+                    user.id(),
+                    partner.id(),
+                    &connection,
+                )?;
+                shopping_list_share::delete_by_owner_and_partner_user_ids(
+                    partner.id(),
+                    user.id(),
+                    &connection,
+                )?;
+                Ok(())
+            })?;
         }
         Ok(json!({
             constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
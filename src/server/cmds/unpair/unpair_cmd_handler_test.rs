@@ -3,12 +3,21 @@ use percent_encoding::DEFAULT_ENCODE_SET;
 use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
 use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::create_shopping_list;
 use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::get_meal_plan;
+use crate::server::cmds::testing_cmds_utils::list_foodstuffs;
 use crate::server::cmds::testing_cmds_utils::list_partners;
+use crate::server::cmds::testing_cmds_utils::list_shared_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::list_shopping_lists;
 use crate::server::cmds::testing_cmds_utils::make_request;
 use crate::server::cmds::testing_cmds_utils::pair;
 use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::share_foodstuff;
+use crate::server::cmds::testing_cmds_utils::share_shopping_list;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal;
 use crate::server::constants;
 
 #[test]
@@ -89,3 +98,221 @@ fn unpair_test_impl(direct_order: bool, uid1: &Uuid, uid2: &Uuid) {
     let partners = partners_json.as_array().unwrap();
     assert_eq!(0, partners.len());
 }
+
+#[test]
+fn unpair_removes_shared_foodstuffs() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-a200-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-a200-0000-0000-000000000005").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    share_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        &uid2,
+        constants::PERMISSION_EDITABLE,
+    );
+    let response = list_shared_foodstuffs(server.address(), &client_token2, &uid2);
+    assert_eq!(
+        1,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+            .as_array()
+            .unwrap()
+            .len()
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_UNPAIR,
+        &constants::ARG_USER_ID,
+        percent_encode(uid2.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token2.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(uid1.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+    );
+    assert_status_ok(&make_request(&url));
+
+    let response = list_shared_foodstuffs(server.address(), &client_token2, &uid2);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_FOODSTUFFS]);
+
+    // The foodstuff itself stays with its owner
+    let response = list_foodstuffs(server.address(), &client_token1, &uid1);
+    assert_eq!(
+        "apple",
+        response[constants::FIELD_NAME_FOODSTUFFS][0][constants::FIELD_NAME_FOODSTUFF_NAME]
+    );
+}
+
+#[test]
+fn unpair_deletes_meal_plan() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-a200-0000-0000-000000000006").unwrap();
+    let uid2 = Uuid::from_str("00000000-a200-0000-0000-000000000007").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let items = json!([{
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 100,
+    }]);
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        0,
+        constants::MEAL_TYPE_LUNCH,
+        0,
+        items,
+    );
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_UNPAIR,
+        &constants::ARG_USER_ID,
+        percent_encode(uid1.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token1.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(uid2.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+    );
+    assert_status_ok(&make_request(&url));
+
+    // After a new pairing the partners start with an empty plan
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    let response = get_meal_plan(server.address(), &client_token1, &uid1, &uid2);
+    assert_eq!(0, response[constants::FIELD_NAME_VERSION]);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_MEALS]);
+}
+
+#[test]
+fn unpair_removes_shared_shopping_lists() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-a200-0000-0000-000000000008").unwrap();
+    let uid2 = Uuid::from_str("00000000-a200-0000-0000-000000000009").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &client_token2,
+        &uid2,
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_shopping_list(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "list",
+        json!({ "foodstuffs": [{"foodstuff_id": 1, "weight": 100}] }),
+    );
+    share_shopping_list(server.address(), &client_token1, &uid1, 1, &uid2);
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}",
+        server.address(),
+        &constants::CMD_UNPAIR,
+        &constants::ARG_USER_ID,
+        percent_encode(uid2.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token2.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(uid1.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
+    );
+    assert_status_ok(&make_request(&url));
+
+    let response = list_shopping_lists(server.address(), &client_token2, &uid2);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_SHOPPING_LISTS]);
+    // The owner still has the list
+    let response = list_shopping_lists(server.address(), &client_token1, &uid1);
+    assert_eq!(
+        1,
+        response[constants::FIELD_NAME_SHOPPING_LISTS]
+            .as_array()
+            .unwrap()
+            .len()
+    );
+}
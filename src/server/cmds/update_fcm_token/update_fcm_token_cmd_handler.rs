@@ -1,21 +1,36 @@
 use std::collections::HashMap;
-
+use std::str::FromStr;
 use std::sync::Arc;
+use uuid::Uuid;
 
 use crate::config::Config;
+use crate::db::core::device;
 use crate::db::core::fcm_token;
 use crate::db::pool::connection_pool::ConnectionPool;
 use crate::outside::http_client::HttpClient;
 
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
 use crate::server::cmds::utils::db_transaction;
 use crate::server::cmds::utils::extract_user_from_query_args;
-use crate::server::cmds::utils::HashMapAdditionalOperations;
 use crate::server::constants;
+use crate::server::request_error::RequestError;
 
+/// Sets the FCM token of a device of the user - each device of the user gets
+/// its own notifications.
+/// Clients which don't know about devices don't send the 'device_id' arg, their tokens
+/// are set to the legacy device of the user, see |device::new_legacy|.
+/// A device of another user is not taken over - the device must be moved to the user
+/// with CMD_MOVE_DEVICE_ACCOUNT first.
 #[derive(Default)]
 pub struct UpdateFcmTokenCmdHandler;
 
+#[derive(Deserialize)]
+struct UpdateFcmTokenArgs {
+    fcm_token: String,
+    device_id: Option<String>,
+}
+
 impl CmdHandler for UpdateFcmTokenCmdHandler {
     fn handle(
         &self,
@@ -47,10 +62,34 @@ impl UpdateFcmTokenCmdHandler {
     ) -> CmdHandleResult {
         let connection = connections_pool.borrow_connection()?;
         let user = extract_user_from_query_args(&args, &connection)?;
-        let fcm_token_value = args.get_or_request_error(constants::ARG_FCM_TOKEN)?;
+        let cmd_args: UpdateFcmTokenArgs = parse_args(&args)?;
+        let fcm_token_value = cmd_args.fcm_token;
+        let device_uuid = match &cmd_args.device_id {
+            Some(device_id) => Some(Uuid::from_str(device_id)?),
+            None => None,
+        };
         db_transaction(&connection, || {
-            fcm_token::delete_by_user_id(user.id(), &connection)?;
-            fcm_token::insert(fcm_token::new(fcm_token_value, &user), &connection)?;
+            let device = match device_uuid {
+                Some(device_uuid) => match device::select_by_uuid(&device_uuid, &connection)? {
+                    Some(device) if device.app_user_id() == user.id() => device,
+                    Some(_) => {
+                        return Err(RequestError::new(
+                            constants::FIELD_STATUS_PERMISSION_DENIED.to_owned(),
+                            "Device belongs to another user".to_owned(),
+                        ))
+                    }
+                    None => device::insert(device::new(device_uuid, &user), &connection)?,
+                },
+                None => match device::select_legacy_by_app_user_id(user.id(), &connection)? {
+                    Some(device) => device,
+                    None => device::insert(device::new_legacy(&user), &connection)?,
+                },
+            };
+            // A token identifies an app installation, so a token which was used by
+            // another device (e.g. the app's data was cleared) is not its token anymore
+            fcm_token::delete_by_token_value(&fcm_token_value, &connection)?;
+            fcm_token::delete_by_device_id(device.id(), &connection)?;
+            fcm_token::insert(fcm_token::new(fcm_token_value, &user, &device), &connection)?;
             Ok(())
         })?;
         Ok(json!({
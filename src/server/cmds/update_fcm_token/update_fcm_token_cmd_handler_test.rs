@@ -2,6 +2,7 @@ use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::db::core::app_user;
+use crate::db::core::device;
 use crate::db::core::fcm_token;
 use crate::db::core::testing_util::testing_connection_for_server_user;
 use crate::server::constants;
@@ -13,6 +14,9 @@ use crate::server::cmds::testing_cmds_utils::assert_status_ok;
 use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
 use crate::server::cmds::testing_cmds_utils::make_request;
 use crate::server::cmds::testing_cmds_utils::register_user;
+use crate::server::cmds::testing_cmds_utils::set_device_fcm_token;
+use crate::server::cmds::testing_cmds_utils::set_device_fcm_token_without_ok_check;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
 
 #[test]
 fn by_default_there_is_no_fcm_token() {
@@ -27,8 +31,8 @@ fn by_default_there_is_no_fcm_token() {
 
     let conn = testing_connection_for_server_user().unwrap();
     let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
-    let fcm_token = fcm_token::select_by_user_id(user.id(), &conn).unwrap();
-    assert!(fcm_token.is_none());
+    let fcm_tokens = fcm_token::select_by_user_id(user.id(), &conn).unwrap();
+    assert!(fcm_tokens.is_empty());
 }
 
 #[test]
@@ -37,6 +41,7 @@ fn set_and_update_fcm_token() {
 
     let uid = Uuid::from_str("00000000-d000-0000-0000-000000000001").unwrap();
     let gpuid = format!("{}{}", uid, "gpuid1");
+    let device_id = Uuid::from_str("00000000-d000-0000-0000-100000000001").unwrap();
     let fcm_token0 = format!("{}{}", uid, "fcm0");
     let fcm_token1 = format!("{}{}", uid, "fcm1");
     delete_app_user_with(&uid);
@@ -47,29 +52,218 @@ fn set_and_update_fcm_token() {
         .unwrap();
 
     // Set
-    let url = format!(
-        "http://{}{}?{}={}&{}={}&{}={}",
+    set_device_fcm_token(
         server.address(),
-        &constants::CMD_UPDATE_FCM_TOKEN,
-        &constants::ARG_USER_ID,
-        percent_encode(uid.to_string().as_bytes(), DEFAULT_ENCODE_SET).to_string(),
-        &constants::ARG_CLIENT_TOKEN,
-        percent_encode(&client_token.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
-        &constants::ARG_FCM_TOKEN,
-        percent_encode(&fcm_token0.as_bytes(), DEFAULT_ENCODE_SET).to_string()
+        client_token,
+        &uid.to_string(),
+        &device_id.to_string(),
+        &fcm_token0,
     );
-    let resp = make_request(&url);
-    assert_status_ok(&resp);
 
     // Check
     let conn = testing_connection_for_server_user().unwrap();
     let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
-    let db_fcm_token = fcm_token::select_by_user_id(user.id(), &conn)
+    let device = device::select_by_uuid(&device_id, &conn).unwrap().unwrap();
+    assert_eq!(user.id(), device.app_user_id());
+    let db_fcm_tokens = fcm_token::select_by_user_id(user.id(), &conn).unwrap();
+    assert_eq!(1, db_fcm_tokens.len());
+    assert_eq!(fcm_token0, db_fcm_tokens[0].token_value());
+    assert_eq!(device.id(), db_fcm_tokens[0].device_id());
+
+    // Update
+    set_device_fcm_token(
+        server.address(),
+        client_token,
+        &uid.to_string(),
+        &device_id.to_string(),
+        &fcm_token1,
+    );
+
+    // Check again
+    let db_fcm_tokens = fcm_token::select_by_user_id(user.id(), &conn).unwrap();
+    assert_eq!(1, db_fcm_tokens.len());
+    assert_eq!(fcm_token1, db_fcm_tokens[0].token_value());
+    assert_eq!(device.id(), db_fcm_tokens[0].device_id());
+}
+
+#[test]
+fn each_device_has_its_own_fcm_token() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-d000-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid4");
+    let device_id1 = Uuid::from_str("00000000-d000-0000-0000-100000000004").unwrap();
+    let device_id2 = Uuid::from_str("00000000-d000-0000-0000-100000000005").unwrap();
+    let fcm_token1 = format!("{}{}", uid, "fcm1");
+    let fcm_token2 = format!("{}{}", uid, "fcm2");
+    delete_app_user_with(&uid);
+
+    let reg_resp = register_user(server.address(), &uid, &gpuid);
+    let client_token = &reg_resp[constants::FIELD_NAME_CLIENT_TOKEN]
+        .as_str()
+        .unwrap();
+
+    set_device_fcm_token(
+        server.address(),
+        client_token,
+        &uid.to_string(),
+        &device_id1.to_string(),
+        &fcm_token1,
+    );
+    set_device_fcm_token(
+        server.address(),
+        client_token,
+        &uid.to_string(),
+        &device_id2.to_string(),
+        &fcm_token2,
+    );
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    let db_fcm_tokens = fcm_token::select_by_user_id(user.id(), &conn).unwrap();
+    let db_fcm_tokens: Vec<&str> = db_fcm_tokens
+        .iter()
+        .map(|token| token.token_value())
+        .collect();
+    assert_eq!(
+        vec![fcm_token1.as_str(), fcm_token2.as_str()],
+        db_fcm_tokens
+    );
+
+    // The token of the 1st device is now given to the 2nd one
+    set_device_fcm_token(
+        server.address(),
+        client_token,
+        &uid.to_string(),
+        &device_id2.to_string(),
+        &fcm_token1,
+    );
+    let db_fcm_tokens = fcm_token::select_by_user_id(user.id(), &conn).unwrap();
+    let device2 = device::select_by_uuid(&device_id2, &conn).unwrap().unwrap();
+    assert_eq!(1, db_fcm_tokens.len());
+    assert_eq!(fcm_token1, db_fcm_tokens[0].token_value());
+    assert_eq!(device2.id(), db_fcm_tokens[0].device_id());
+}
+
+#[test]
+fn device_of_another_user_is_not_taken_over() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-d000-0000-0000-000000000005").unwrap();
+    let uid2 = Uuid::from_str("00000000-d000-0000-0000-000000000006").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid5");
+    let gpuid2 = format!("{}{}", uid2, "gpuid6");
+    let device_id = Uuid::from_str("00000000-d000-0000-0000-100000000006").unwrap();
+    let fcm_token1 = format!("{}{}", uid1, "fcm1");
+    let fcm_token2 = format!("{}{}", uid2, "fcm2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let reg_resp = register_user(server.address(), &uid1, &gpuid1);
+    let client_token1 = &reg_resp[constants::FIELD_NAME_CLIENT_TOKEN]
+        .as_str()
+        .unwrap();
+    let reg_resp = register_user(server.address(), &uid2, &gpuid2);
+    let client_token2 = &reg_resp[constants::FIELD_NAME_CLIENT_TOKEN]
+        .as_str()
+        .unwrap();
+
+    set_device_fcm_token(
+        server.address(),
+        client_token1,
+        &uid1.to_string(),
+        &device_id.to_string(),
+        &fcm_token1,
+    );
+    let resp = set_device_fcm_token_without_ok_check(
+        server.address(),
+        client_token2,
+        &uid2.to_string(),
+        &device_id.to_string(),
+        &fcm_token2,
+    );
+    assert_status(&resp, constants::FIELD_STATUS_PERMISSION_DENIED);
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user1 = app_user::select_by_uid(&uid1, &conn).unwrap().unwrap();
+    let user2 = app_user::select_by_uid(&uid2, &conn).unwrap().unwrap();
+    let device = device::select_by_uuid(&device_id, &conn).unwrap().unwrap();
+    assert_eq!(user1.id(), device.app_user_id());
+    let db_fcm_tokens = fcm_token::select_by_user_id(user1.id(), &conn).unwrap();
+    assert_eq!(1, db_fcm_tokens.len());
+    assert_eq!(fcm_token1, db_fcm_tokens[0].token_value());
+    assert!(fcm_token::select_by_user_id(user2.id(), &conn)
         .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn legacy_device_of_another_user_is_not_taken_over() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-d000-0000-0000-000000000008").unwrap();
+    let uid2 = Uuid::from_str("00000000-d000-0000-0000-000000000009").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid8");
+    let gpuid2 = format!("{}{}", uid2, "gpuid9");
+    let fcm_token1 = format!("{}{}", uid1, "fcm1");
+    let fcm_token2 = format!("{}{}", uid2, "fcm2");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let reg_resp = register_user(server.address(), &uid1, &gpuid1);
+    let client_token1 = &reg_resp[constants::FIELD_NAME_CLIENT_TOKEN]
+        .as_str()
+        .unwrap();
+    let reg_resp = register_user(server.address(), &uid2, &gpuid2);
+    let client_token2 = &reg_resp[constants::FIELD_NAME_CLIENT_TOKEN]
+        .as_str()
+        .unwrap();
+
+    set_user_fcm_token(
+        server.address(),
+        client_token1,
+        &uid1.to_string(),
+        &fcm_token1,
+    );
+    let conn = testing_connection_for_server_user().unwrap();
+    let user1 = app_user::select_by_uid(&uid1, &conn).unwrap().unwrap();
+    let legacy_device1 = device::select_legacy_by_app_user_id(user1.id(), &conn)
+        .unwrap()
+        .unwrap();
+
+    let resp = set_device_fcm_token_without_ok_check(
+        server.address(),
+        client_token2,
+        &uid2.to_string(),
+        &legacy_device1.uuid().to_string(),
+        &fcm_token2,
+    );
+    assert_status(&resp, constants::FIELD_STATUS_PERMISSION_DENIED);
+
+    let device = device::select_by_uuid(legacy_device1.uuid(), &conn)
+        .unwrap()
+        .unwrap();
+    assert_eq!(legacy_device1, device);
+    let db_fcm_tokens = fcm_token::select_by_user_id(user1.id(), &conn).unwrap();
+    assert_eq!(1, db_fcm_tokens.len());
+    assert_eq!(fcm_token1, db_fcm_tokens[0].token_value());
+    assert_eq!(legacy_device1.id(), db_fcm_tokens[0].device_id());
+}
+
+#[test]
+fn lack_of_device_id_means_legacy_device() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-d000-0000-0000-000000000007").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid7");
+    let fcm_token = format!("{}{}", uid, "fcm7");
+    delete_app_user_with(&uid);
+
+    let reg_resp = register_user(server.address(), &uid, &gpuid);
+    let client_token = &reg_resp[constants::FIELD_NAME_CLIENT_TOKEN]
+        .as_str()
         .unwrap();
-    assert_eq!(fcm_token0, db_fcm_token.token_value());
 
-    // Update
     let url = format!(
         "http://{}{}?{}={}&{}={}&{}={}",
         server.address(),
@@ -79,16 +273,32 @@ fn set_and_update_fcm_token() {
         &constants::ARG_CLIENT_TOKEN,
         percent_encode(&client_token.as_bytes(), DEFAULT_ENCODE_SET).to_string(),
         &constants::ARG_FCM_TOKEN,
-        percent_encode(&fcm_token1.as_bytes(), DEFAULT_ENCODE_SET).to_string()
+        percent_encode(&fcm_token.as_bytes(), DEFAULT_ENCODE_SET).to_string()
     );
     let resp = make_request(&url);
     assert_status_ok(&resp);
+    // Second request must update the token of the same device
+    let resp = make_request(&url);
+    assert_status_ok(&resp);
 
-    // Check again
-    let db_fcm_token = fcm_token::select_by_user_id(user.id(), &conn)
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    let devices = device::select_by_app_user_id(user.id(), &conn).unwrap();
+    assert_eq!(1, devices.len());
+    assert!(devices[0].is_legacy());
+    let token = fcm_token::select_by_device_id(devices[0].id(), &conn)
         .unwrap()
         .unwrap();
-    assert_eq!(fcm_token1, db_fcm_token.token_value());
+    assert_eq!(fcm_token, token.token_value());
+
+    let resp = set_device_fcm_token_without_ok_check(
+        server.address(),
+        client_token,
+        &uid.to_string(),
+        "not-a-uuid",
+        &fcm_token,
+    );
+    assert_status(&resp, constants::FIELD_STATUS_INVALID_UUID);
 }
 
 #[test]
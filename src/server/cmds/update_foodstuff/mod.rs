@@ -0,0 +1 @@
+pub mod update_foodstuff_cmd_handler;
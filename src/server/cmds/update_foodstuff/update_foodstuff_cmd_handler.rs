@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::foodstuff;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::cmds::utils::parse_nutrient_or_request_error;
+use crate::server::constants;
+use crate::server::request_error::RequestError;
+
+#[derive(Default)]
+pub struct UpdateFoodstuffCmdHandler {}
+
+#[derive(Deserialize)]
+struct UpdateFoodstuffArgs {
+    foodstuff_id: i32,
+    foodstuff_name: String,
+    protein: String,
+    fats: String,
+    carbs: String,
+    calories: String,
+}
+
+impl CmdHandler for UpdateFoodstuffCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        _body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            connections_pool,
+            config,
+            http_client,
+        ))
+    }
+}
+
+impl UpdateFoodstuffCmdHandler {
+    pub fn new() -> Self {
+        UpdateFoodstuffCmdHandler {}
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        mut connections_pool: ConnectionPool,
+        _config: Config,
+        _http_client: Arc<HttpClient>,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: UpdateFoodstuffArgs = parse_args(&args)?;
+        let app_user_foodstuff_id = cmd_args.foodstuff_id;
+        let name = cmd_args.foodstuff_name;
+        let protein = parse_nutrient_or_request_error(constants::ARG_PROTEIN, &cmd_args.protein)?;
+        let fats = parse_nutrient_or_request_error(constants::ARG_FATS, &cmd_args.fats)?;
+        let carbs = parse_nutrient_or_request_error(constants::ARG_CARBS, &cmd_args.carbs)?;
+        let calories =
+            parse_nutrient_or_request_error(constants::ARG_CALORIES, &cmd_args.calories)?;
+
+        db_transaction(&connection, || {
+            foodstuff::lock_for_modification(user.id(), &connection)?;
+            let foodstuff = foodstuff::select_by_app_user_foodstuff_id(
+                user.id(),
+                app_user_foodstuff_id,
+                &connection,
+            )?;
+            let foodstuff = match foodstuff {
+                Some(foodstuff) => foodstuff,
+                None => return Err(foodstuff_not_found_error(app_user_foodstuff_id)),
+            };
+            let updated =
+                foodstuff::update(foodstuff, name, protein, fats, carbs, calories, &connection)?;
+            match updated {
+                Some(_) => Ok(()),
+                None => Err(foodstuff_not_found_error(app_user_foodstuff_id)),
+            }
+        })?;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK
+        }))
+    }
+}
+
+fn foodstuff_not_found_error(app_user_foodstuff_id: i32) -> RequestError {
+    RequestError::new(
+        constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND.to_owned(),
+        format!("Foodstuff not found, ID: {}", app_user_foodstuff_id),
+    )
+}
+
+#[cfg(test)]
+#[path = "./update_foodstuff_cmd_handler_test.rs"]
+mod update_foodstuff_cmd_handler_test;
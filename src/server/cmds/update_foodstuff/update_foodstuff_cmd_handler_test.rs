@@ -0,0 +1,145 @@
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+
+fn update_foodstuff(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    foodstuff_id: i32,
+    name: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_UPDATE_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        foodstuff_id,
+        &constants::ARG_FOODSTUFF_NAME,
+        percent_encode(name.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PROTEIN,
+        "10.5",
+        &constants::ARG_FATS,
+        "20",
+        &constants::ARG_CARBS,
+        "30.25",
+        &constants::ARG_CALORIES,
+        "40",
+    );
+    make_request(&url)
+}
+
+#[test]
+fn update_foodstuff_test() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f201-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    add_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = update_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "green apple",
+    );
+    assert_status_ok(&response);
+
+    let response = list_foodstuffs(server.address(), &client_token, &uid.to_string());
+    let expected_foodstuffs = json!([{
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_FOODSTUFF_NAME: "green apple",
+        constants::FIELD_NAME_PROTEIN: "10.5",
+        constants::FIELD_NAME_FATS: "20",
+        constants::FIELD_NAME_CARBS: "30.25",
+        constants::FIELD_NAME_CALORIES: "40",
+    }]);
+    assert_eq!(
+        expected_foodstuffs,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+    );
+}
+
+#[test]
+fn update_of_not_existing_foodstuff() {
+    let server = start_server!();
+
+    let uid = Uuid::from_str("00000000-f201-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let response = update_foodstuff(
+        server.address(),
+        &client_token,
+        &uid.to_string(),
+        1,
+        "green apple",
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
+
+#[test]
+fn cannot_update_foodstuff_of_other_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f201-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f201-0000-0000-000000000003").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = update_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2.to_string(),
+        1,
+        "green apple",
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
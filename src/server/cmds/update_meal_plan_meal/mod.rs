@@ -0,0 +1 @@
+pub mod update_meal_plan_meal_cmd_handler;
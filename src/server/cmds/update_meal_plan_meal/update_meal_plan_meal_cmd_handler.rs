@@ -0,0 +1,212 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::core::error::Error as DBError;
+use crate::db::core::error::ErrorKind as DBErrorKind;
+use crate::db::core::meal_plan;
+use crate::db::core::meal_plan_item;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+
+use crate::outside::fcm::FCM_ADDR;
+use crate::server::cmds::cmd_args::parse_args;
+use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
+use crate::server::cmds::foodstuff_share_utils::select_paired_partner;
+use crate::server::cmds::meal_plan_utils::check_day;
+use crate::server::cmds::meal_plan_utils::insert_meal_items;
+use crate::server::cmds::meal_plan_utils::parse_meal_items;
+use crate::server::cmds::meal_plan_utils::parse_meal_type;
+use crate::server::cmds::meal_plan_utils::version_conflict_error;
+use crate::server::cmds::utils::db_transaction;
+use crate::server::cmds::utils::extract_user_from_query_args;
+use crate::server::constants;
+use crate::server::notification_outbox;
+
+/// Replaces all items of a meal of a day in the meal plan shared by the user and the partner.
+/// Expects items of the meal in the body, see |meal_plan_utils::parse_meal_items| for its format.
+///
+/// Clients must pass the |version| of the plan they've last seen (0 if they haven't
+/// seen the plan yet) - if the partner has modified the plan since then,
+/// the 'version_conflict' status is returned and the plan is not modified.
+pub struct UpdateMealPlanMealCmdHandler {
+    fcm_address: String,
+}
+
+#[derive(Deserialize)]
+struct UpdateMealPlanMealArgs {
+    partner_user_id: String,
+    day: i32,
+    meal_type: String,
+    version: i64,
+}
+
+impl CmdHandler for UpdateMealPlanMealCmdHandler {
+    fn handle(
+        &self,
+        args: HashMap<String, String>,
+        body: String,
+        connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+    ) -> CmdHandleResultFuture {
+        Box::pin(Self::handle_impl(
+            args,
+            body,
+            connections_pool,
+            config,
+            http_client,
+            self.fcm_address.clone(),
+        ))
+    }
+}
+
+impl UpdateMealPlanMealCmdHandler {
+    pub fn new(overrides: &JsonValue) -> Self {
+        let args = get_construction_args(overrides);
+        UpdateMealPlanMealCmdHandler {
+            fcm_address: args.fcm_address,
+        }
+    }
+
+    async fn handle_impl(
+        args: HashMap<String, String>,
+        body: String,
+        mut connections_pool: ConnectionPool,
+        config: Config,
+        http_client: Arc<HttpClient>,
+        fcm_address: String,
+    ) -> CmdHandleResult {
+        let connection = connections_pool.borrow_connection()?;
+        let user = extract_user_from_query_args(&args, &connection)?;
+        let cmd_args: UpdateMealPlanMealArgs = parse_args(&args)?;
+        let partner_uid = cmd_args.partner_user_id;
+        let day = check_day(cmd_args.day)?;
+        let meal_type = parse_meal_type(&cmd_args.meal_type)?;
+        let version = cmd_args.version;
+        let items = parse_meal_items(&body)?;
+        let partner = select_paired_partner(&user, &partner_uid, &connection)?;
+
+        let (meal_plan, notification_ids) = db_transaction(&connection, || {
+            let meal_plan =
+                meal_plan::select_by_partners_user_ids(user.id(), partner.id(), &connection)?;
+            let meal_plan = match meal_plan {
+                Some(meal_plan) => meal_plan,
+                None => {
+                    if version != 0 {
+                        return Err(version_conflict_error(0, version));
+                    }
+                    match meal_plan::insert(meal_plan::new(&user, &partner), &connection) {
+                        Ok(meal_plan) => meal_plan,
+                        // The partner has just created the plan
+                        Err(DBError(DBErrorKind::UniqueViolation(_), _)) => {
+                            return Err(version_conflict_error(1, version));
+                        }
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+            };
+            if meal_plan.version() != version {
+                return Err(version_conflict_error(meal_plan.version(), version));
+            }
+            // The version is compared once again by the update itself,
+            // because the partner could've modified the plan after it was selected
+            let meal_plan = match meal_plan::increment_version(meal_plan, &connection)? {
+                Some(meal_plan) => meal_plan,
+                None => return Err(version_conflict_error(version + 1, version)),
+            };
+
+            meal_plan_item::delete_by_meal_plan_id_and_meal(
+                meal_plan.id(),
+                day,
+                meal_type,
+                &connection,
+            )?;
+            insert_meal_items(
+                &meal_plan,
+                day,
+                meal_type,
+                &items,
+                &user,
+                &partner,
+                &connection,
+            )?;
+
+            let json = json!({
+                constants::SERV_FIELD_MSG_TYPE: constants::SERV_MSG_MEAL_PLAN_UPDATED_BY_PARTNER,
+                constants::SERV_FIELD_PARTNER_USER_ID: user.uid(),
+                constants::SERV_FIELD_PARTNER_NAME: user.name(),
+                constants::SERV_FIELD_VERSION: meal_plan.version(),
+            });
+            let notification_ids =
+                notification_outbox::enqueue(&partner, json.to_string(), &connection)?;
+            Ok((meal_plan, notification_ids))
+        })?;
+
+        // NOTE: we don't use the '?' operator on the send result - we want to respond
+        // with OK status to our client even if notifications sending will fail
+        let _notif_res = notification_outbox::send_enqueued(
+            notification_ids,
+            connection,
+            connections_pool,
+            &config,
+            &fcm_address,
+            http_client,
+        )
+        .await;
+
+        Ok(json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_VERSION: meal_plan.version(),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub fn insert_construction_overrides(overrides: &mut JsonValue, fcm_address: String) {
+    let overrides = overrides
+        .as_object_mut()
+        .expect("Can insert only into object");
+    overrides.insert("update_meal_plan_meal_overrides".to_owned(), json!({}));
+    let overrides = overrides["update_meal_plan_meal_overrides"]
+        .as_object_mut()
+        .unwrap();
+    overrides.insert("fcm_address_override".to_owned(), json!(fcm_address));
+}
+
+struct ConstructionArgs {
+    fcm_address: String,
+}
+
+fn get_construction_args(overrides: &JsonValue) -> ConstructionArgs {
+    if let Some(overrides) = extract_construction_overrides(overrides) {
+        overrides
+    } else {
+        ConstructionArgs {
+            fcm_address: FCM_ADDR.to_owned(),
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn extract_construction_overrides(_overrides: &JsonValue) -> Option<ConstructionArgs> {
+    None
+}
+
+#[cfg(test)]
+fn extract_construction_overrides(overrides: &JsonValue) -> Option<ConstructionArgs> {
+    match &overrides["update_meal_plan_meal_overrides"].as_object() {
+        Some(overrides) => Some(ConstructionArgs {
+            fcm_address: overrides["fcm_address_override"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        }),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./update_meal_plan_meal_cmd_handler_test.rs"]
+mod update_meal_plan_meal_cmd_handler_test;
@@ -0,0 +1,433 @@
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::constants;
+use crate::server::testing_hostname;
+use crate::server::testing_mock_server::FullRequest;
+
+use crate::server::cmds::update_meal_plan_meal::update_meal_plan_meal_cmd_handler::insert_construction_overrides;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::create_recipe;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::get_meal_plan;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::set_user_fcm_token;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal;
+use crate::server::cmds::testing_cmds_utils::update_meal_plan_meal_without_ok_check;
+
+/// Registers and pairs 2 users, returns their client tokens.
+fn register_partners(server_addr: &str, uid1: &Uuid, uid2: &Uuid) -> (String, String) {
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(uid1);
+    delete_app_user_with(uid2);
+
+    let client_token1 = register_named_user_return_token(server_addr, uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server_addr, uid2, &gpuid2, "name2");
+    pair(
+        server_addr,
+        &client_token1,
+        &uid1.to_string(),
+        &client_token2,
+        &uid2.to_string(),
+    );
+    (client_token1, client_token2)
+}
+
+#[test]
+fn update_meal_with_fcm() {
+    let r = |_request: &FullRequest| {
+        let response = r#"
+        {
+            "multicast_id":2513734409441993719,
+            "success":1,
+            "failure":0,
+            "canonical_ids":0,
+            "results":[{"message_id":"0:1579970411599831%8e9256aef9fd7ecd"}]
+        }"#;
+        Some(response.to_owned())
+    };
+    let (fcm_server, fcm_requests) = start_mock_server(r, testing_hostname::get_spare_hostname1());
+    let fcm_addr = format!("http://{}", fcm_server.address());
+    let server = start_server!(|overrides: &mut JsonValue| insert_construction_overrides(
+        overrides,
+        fcm_addr.clone()
+    ));
+
+    let uid1 = Uuid::from_str("00000000-f215-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f215-0000-0000-000000000001").unwrap();
+    let (client_token1, client_token2) = register_partners(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    let fcm_token2 = format!("{}{}", uid2, "fcmtoken2");
+    set_user_fcm_token(server.address(), &client_token2, &uid2, &fcm_token2);
+
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    add_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    create_recipe(
+        server.address(),
+        &client_token2,
+        &uid2,
+        1,
+        "pear salad",
+        &[(1, 100)],
+    );
+
+    // Both foodstuffs and recipes of both partners can be planned
+    let items = json!([
+        {
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_WEIGHT: 100,
+        },
+        {
+            constants::FIELD_NAME_OWNER_USER_ID: uid2,
+            constants::FIELD_NAME_RECIPE_ID: 1,
+            constants::FIELD_NAME_WEIGHT: 250,
+        }
+    ]);
+    let response = update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        0,
+        constants::MEAL_TYPE_LUNCH,
+        0,
+        items.clone(),
+    );
+    assert_eq!(1, response[constants::FIELD_NAME_VERSION]);
+
+    // The partner sees the plan
+    let response = get_meal_plan(server.address(), &client_token2, &uid2, &uid1);
+    assert_eq!(1, response[constants::FIELD_NAME_VERSION]);
+    let expected_meals = json!([{
+        constants::FIELD_NAME_DAY: 0,
+        constants::FIELD_NAME_MEAL_TYPE: constants::MEAL_TYPE_LUNCH,
+        constants::FIELD_NAME_ITEMS: items,
+    }]);
+    assert_eq!(expected_meals, response[constants::FIELD_NAME_MEALS]);
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    let fcm_requests: Vec<JsonValue> = fcm_requests
+        .iter()
+        .map(|req| serde_json::from_str(&req.body).unwrap())
+        .collect();
+    assert_eq!(1, fcm_requests.len());
+    assert_eq!(fcm_requests[0]["to"], json!(fcm_token2));
+    let data = &fcm_requests[0]["data"];
+    assert_eq!(
+        &data[constants::SERV_FIELD_MSG_TYPE],
+        constants::SERV_MSG_MEAL_PLAN_UPDATED_BY_PARTNER,
+    );
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_USER_ID], &uid1);
+    assert_eq!(&data[constants::SERV_FIELD_PARTNER_NAME], "name1");
+    assert_eq!(&data[constants::SERV_FIELD_VERSION], 1);
+}
+
+#[test]
+fn concurrent_modification_is_detected() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f215-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f215-0000-0000-000000000003").unwrap();
+    let (client_token1, client_token2) = register_partners(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let items = |weight: i32| {
+        json!([{
+            constants::FIELD_NAME_OWNER_USER_ID: uid1,
+            constants::FIELD_NAME_FOODSTUFF_ID: 1,
+            constants::FIELD_NAME_WEIGHT: weight,
+        }])
+    };
+
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        0,
+        constants::MEAL_TYPE_BREAKFAST,
+        0,
+        items(100),
+    );
+
+    // The partner hasn't seen the modification
+    let response = update_meal_plan_meal_without_ok_check(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        0,
+        constants::MEAL_TYPE_BREAKFAST,
+        0,
+        items(200),
+    );
+    assert_status(&response, constants::FIELD_STATUS_VERSION_CONFLICT);
+    let response = get_meal_plan(server.address(), &client_token2, &uid2, &uid1);
+    assert_eq!(1, response[constants::FIELD_NAME_VERSION]);
+    assert_eq!(
+        100,
+        response[constants::FIELD_NAME_MEALS][0][constants::FIELD_NAME_ITEMS][0]
+            [constants::FIELD_NAME_WEIGHT]
+    );
+
+    // And now the partner has seen it
+    let response = update_meal_plan_meal(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        0,
+        constants::MEAL_TYPE_BREAKFAST,
+        1,
+        items(200),
+    );
+    assert_eq!(2, response[constants::FIELD_NAME_VERSION]);
+    let response = get_meal_plan(server.address(), &client_token1, &uid1, &uid2);
+    assert_eq!(2, response[constants::FIELD_NAME_VERSION]);
+    assert_eq!(
+        200,
+        response[constants::FIELD_NAME_MEALS][0][constants::FIELD_NAME_ITEMS][0]
+            [constants::FIELD_NAME_WEIGHT]
+    );
+}
+
+#[test]
+fn empty_items_clear_meal() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f215-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-f215-0000-0000-000000000005").unwrap();
+    let (client_token1, _) = register_partners(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let items = json!([{
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 100,
+    }]);
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        6,
+        constants::MEAL_TYPE_SNACK,
+        0,
+        items,
+    );
+    update_meal_plan_meal(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        6,
+        constants::MEAL_TYPE_SNACK,
+        1,
+        json!([]),
+    );
+
+    let response = get_meal_plan(server.address(), &client_token1, &uid1, &uid2);
+    assert_eq!(2, response[constants::FIELD_NAME_VERSION]);
+    assert_eq!(json!([]), response[constants::FIELD_NAME_MEALS]);
+}
+
+#[test]
+fn cannot_update_meal_plan_with_not_paired_user() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f215-0000-0000-000000000006").unwrap();
+    let uid2 = Uuid::from_str("00000000-f215-0000-0000-000000000007").unwrap();
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name1");
+    register_named_user_return_token(server.address(), &uid2, &gpuid2, "name2");
+
+    let response = update_meal_plan_meal_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1.to_string(),
+        &uid2.to_string(),
+        0,
+        constants::MEAL_TYPE_LUNCH,
+        0,
+        json!([]),
+    );
+    assert_status(&response, constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND);
+}
+
+#[test]
+fn cannot_plan_foodstuff_of_not_partner() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f215-0000-0000-000000000008").unwrap();
+    let uid2 = Uuid::from_str("00000000-f215-0000-0000-000000000009").unwrap();
+    let uid3 = Uuid::from_str("00000000-f215-0000-0000-00000000000a").unwrap();
+    let gpuid3 = format!("{}{}", uid3, "gpuid");
+    let (client_token1, _) = register_partners(server.address(), &uid1, &uid2);
+    delete_app_user_with(&uid3);
+    let client_token3 = register_named_user_return_token(server.address(), &uid3, &gpuid3, "name3");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    let uid3 = uid3.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token3,
+        &uid3,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let items = json!([{
+        constants::FIELD_NAME_OWNER_USER_ID: uid3,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 100,
+    }]);
+    let response = update_meal_plan_meal_without_ok_check(
+        server.address(),
+        &client_token1,
+        &uid1,
+        &uid2,
+        0,
+        constants::MEAL_TYPE_LUNCH,
+        0,
+        items,
+    );
+    assert_status(&response, constants::FIELD_STATUS_PERMISSION_DENIED);
+
+    // The plan is not modified
+    let response = get_meal_plan(server.address(), &client_token1, &uid1, &uid2);
+    assert_eq!(0, response[constants::FIELD_NAME_VERSION]);
+}
+
+#[test]
+fn invalid_args() {
+    let server = start_server!();
+
+    let uid1 = Uuid::from_str("00000000-f215-0000-0000-00000000000b").unwrap();
+    let uid2 = Uuid::from_str("00000000-f215-0000-0000-00000000000c").unwrap();
+    let (client_token1, _) = register_partners(server.address(), &uid1, &uid2);
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    let valid_items = json!([{
+        constants::FIELD_NAME_OWNER_USER_ID: uid1,
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_WEIGHT: 100,
+    }]);
+
+    let cases = vec![
+        (7, constants::MEAL_TYPE_LUNCH, valid_items.clone()),
+        (-1, constants::MEAL_TYPE_LUNCH, valid_items.clone()),
+        (0, "brunch", valid_items),
+        // Both foodstuff and recipe
+        (
+            0,
+            constants::MEAL_TYPE_LUNCH,
+            json!([{
+                constants::FIELD_NAME_OWNER_USER_ID: uid1,
+                constants::FIELD_NAME_FOODSTUFF_ID: 1,
+                constants::FIELD_NAME_RECIPE_ID: 1,
+                constants::FIELD_NAME_WEIGHT: 100,
+            }]),
+        ),
+        // Neither foodstuff nor recipe
+        (
+            0,
+            constants::MEAL_TYPE_LUNCH,
+            json!([{
+                constants::FIELD_NAME_OWNER_USER_ID: uid1,
+                constants::FIELD_NAME_WEIGHT: 100,
+            }]),
+        ),
+        // Zero weight
+        (
+            0,
+            constants::MEAL_TYPE_LUNCH,
+            json!([{
+                constants::FIELD_NAME_OWNER_USER_ID: uid1,
+                constants::FIELD_NAME_FOODSTUFF_ID: 1,
+                constants::FIELD_NAME_WEIGHT: 0,
+            }]),
+        ),
+    ];
+    for (day, meal_type, items) in cases {
+        let response = update_meal_plan_meal_without_ok_check(
+            server.address(),
+            &client_token1,
+            &uid1,
+            &uid2,
+            day,
+            meal_type,
+            0,
+            items,
+        );
+        assert_status(&response, constants::FIELD_STATUS_INVALID_QUERY);
+    }
+}
@@ -0,0 +1 @@
+pub mod update_shared_foodstuff_cmd_handler;
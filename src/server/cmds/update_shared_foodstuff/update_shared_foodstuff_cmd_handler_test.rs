@@ -0,0 +1,190 @@
+use percent_encoding::percent_encode;
+use percent_encoding::DEFAULT_ENCODE_SET;
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::pair;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::share_foodstuff;
+use crate::server::constants;
+
+fn update_shared_foodstuff(
+    server_addr: &str,
+    client_token: &str,
+    uid: &str,
+    partner_uid: &str,
+    foodstuff_id: i32,
+    name: &str,
+) -> JsonValue {
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}&{}={}",
+        server_addr,
+        &constants::CMD_UPDATE_SHARED_FOODSTUFF,
+        &constants::ARG_USER_ID,
+        percent_encode(uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_CLIENT_TOKEN,
+        percent_encode(client_token.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PARTNER_USER_ID,
+        percent_encode(partner_uid.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_FOODSTUFF_ID,
+        foodstuff_id,
+        &constants::ARG_FOODSTUFF_NAME,
+        percent_encode(name.as_bytes(), DEFAULT_ENCODE_SET),
+        &constants::ARG_PROTEIN,
+        "10",
+        &constants::ARG_FATS,
+        "20",
+        &constants::ARG_CARBS,
+        "30",
+        &constants::ARG_CALORIES,
+        "40",
+    );
+    make_request(&url)
+}
+
+/// Registers and pairs 2 users, adds a foodstuff of the first user
+/// and shares it with the second one.
+fn set_up_shared_foodstuff(
+    server_addr: &str,
+    uid1: &Uuid,
+    uid2: &Uuid,
+    permission: &str,
+) -> (String, String) {
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    delete_app_user_with(uid1);
+    delete_app_user_with(uid2);
+
+    let client_token1 = register_named_user_return_token(server_addr, uid1, &gpuid1, "name1");
+    let client_token2 = register_named_user_return_token(server_addr, uid2, &gpuid2, "name2");
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    pair(server_addr, &client_token1, &uid1, &client_token2, &uid2);
+    add_foodstuff(
+        server_addr,
+        &client_token1,
+        &uid1,
+        1,
+        "apple",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+    share_foodstuff(server_addr, &client_token1, &uid1, 1, &uid2, permission);
+    (client_token1, client_token2)
+}
+
+#[test]
+fn update_editable_foodstuff() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f214-0000-0000-000000000000").unwrap();
+    let uid2 = Uuid::from_str("00000000-f214-0000-0000-000000000001").unwrap();
+    let (client_token1, client_token2) = set_up_shared_foodstuff(
+        server.address(),
+        &uid1,
+        &uid2,
+        constants::PERMISSION_EDITABLE,
+    );
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+
+    let response = update_shared_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        1,
+        "green apple",
+    );
+    assert_status_ok(&response);
+
+    // The owner sees the change
+    let response = list_foodstuffs(server.address(), &client_token1, &uid1);
+    let expected_foodstuffs = json!([{
+        constants::FIELD_NAME_FOODSTUFF_ID: 1,
+        constants::FIELD_NAME_FOODSTUFF_NAME: "green apple",
+        constants::FIELD_NAME_PROTEIN: "10",
+        constants::FIELD_NAME_FATS: "20",
+        constants::FIELD_NAME_CARBS: "30",
+        constants::FIELD_NAME_CALORIES: "40",
+    }]);
+    assert_eq!(
+        expected_foodstuffs,
+        response[constants::FIELD_NAME_FOODSTUFFS]
+    );
+}
+
+#[test]
+fn cannot_update_read_only_foodstuff() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f214-0000-0000-000000000002").unwrap();
+    let uid2 = Uuid::from_str("00000000-f214-0000-0000-000000000003").unwrap();
+    let (client_token1, client_token2) = set_up_shared_foodstuff(
+        server.address(),
+        &uid1,
+        &uid2,
+        constants::PERMISSION_READ_ONLY,
+    );
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+
+    let response = update_shared_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        1,
+        "green apple",
+    );
+    assert_status(&response, constants::FIELD_STATUS_PERMISSION_DENIED);
+
+    let response = list_foodstuffs(server.address(), &client_token1, &uid1);
+    assert_eq!(
+        "apple",
+        response[constants::FIELD_NAME_FOODSTUFFS][0][constants::FIELD_NAME_FOODSTUFF_NAME]
+    );
+}
+
+#[test]
+fn cannot_update_not_shared_foodstuff() {
+    let server = start_server!();
+    let uid1 = Uuid::from_str("00000000-f214-0000-0000-000000000004").unwrap();
+    let uid2 = Uuid::from_str("00000000-f214-0000-0000-000000000005").unwrap();
+    let (client_token1, client_token2) = set_up_shared_foodstuff(
+        server.address(),
+        &uid1,
+        &uid2,
+        constants::PERMISSION_EDITABLE,
+    );
+    let uid1 = uid1.to_string();
+    let uid2 = uid2.to_string();
+    add_foodstuff(
+        server.address(),
+        &client_token1,
+        &uid1,
+        2,
+        "pear",
+        "1",
+        "2",
+        "3",
+        "4",
+    );
+
+    let response = update_shared_foodstuff(
+        server.address(),
+        &client_token2,
+        &uid2,
+        &uid1,
+        2,
+        "green pear",
+    );
+    assert_status(&response, constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND);
+}
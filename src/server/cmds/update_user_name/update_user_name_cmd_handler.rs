@@ -7,15 +7,20 @@ use crate::db::core::app_user;
 use crate::db::pool::connection_pool::ConnectionPool;
 use crate::outside::http_client::HttpClient;
 
+use crate::server::cmds::cmd_args::parse_args;
 use crate::server::cmds::cmd_handler::{CmdHandleResult, CmdHandleResultFuture, CmdHandler};
 use crate::server::cmds::utils::db_transaction;
 use crate::server::cmds::utils::extract_user_from_query_args;
-use crate::server::cmds::utils::HashMapAdditionalOperations;
 use crate::server::constants;
 
 #[derive(Default)]
 pub struct UpdateUserNameCmdHandler;
 
+#[derive(Deserialize)]
+struct UpdateUserNameArgs {
+    name: String,
+}
+
 impl CmdHandler for UpdateUserNameCmdHandler {
     fn handle(
         &self,
@@ -47,7 +52,8 @@ impl UpdateUserNameCmdHandler {
     ) -> CmdHandleResult {
         let connection = connections_pool.borrow_connection()?;
         let user = extract_user_from_query_args(&args, &connection)?;
-        let user_name_value = args.get_or_request_error(constants::ARG_USER_NAME)?;
+        let cmd_args: UpdateUserNameArgs = parse_args(&args)?;
+        let user_name_value = cmd_args.name;
         db_transaction(&connection, || {
             app_user::update_user_name(user, &user_name_value, &connection)?;
             Ok(())
@@ -1,17 +1,11 @@
 use std::collections::HashMap;
-use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::core::app_user;
-use crate::db::core::app_user::AppUser;
 use crate::db::core::connection::DBConnection;
-use crate::db::core::fcm_token;
 use crate::db::core::transaction;
-use crate::db::pool::connection_pool::ConnectionPool;
 
-use crate::config::Config;
-use crate::outside::fcm;
-use crate::outside::http_client::HttpClient;
+use crate::nutrition::nutrients;
 use crate::server::constants;
 use crate::server::request_error::RequestError;
 
@@ -41,6 +35,31 @@ impl HashMapAdditionalOperations for HashMap<std::string::String, std::string::S
     }
 }
 
+/// Parses a nutrient sent as a decimal string (e.g. "12.5") into its fixed-point
+/// value, in which nutrients are stored in the DB.
+pub fn parse_nutrient_or_request_error(key: &str, value: &str) -> Result<i32, RequestError> {
+    let invalid = |descr: String| {
+        RequestError::new(
+            constants::FIELD_STATUS_INVALID_QUERY.to_owned(),
+            format!(
+                "Param '{}' is not a valid nutrient: {}, {}",
+                key, value, descr
+            ),
+        )
+    };
+    let fixed_point =
+        nutrients::parse_decimal_string(value).map_err(|err| invalid(err.to_string()))?;
+    if fixed_point < 0 {
+        return Err(invalid("it's negative".to_owned()));
+    }
+    if fixed_point > i64::from(i32::MAX) {
+        return Err(invalid("it's too big".to_owned()));
+    }
+    Ok(fixed_point as i32)
+}
+
+/// Credentials can come from the query, a JSON body or the Authorization header,
+/// see |cmd_args|.
 #[allow(clippy::implicit_hasher)]
 pub fn extract_user_from_query_args(
     args: &HashMap<String, String>,
@@ -77,41 +96,14 @@ where
     transaction::start::<T, RequestError, _>(connection, action)
 }
 
-/// Returns future which resolves when a notification is sent to the |user|.
-/// Or immediately if the user doesn't have a FCM-token.
-pub async fn notify_user(
-    user: &AppUser,
-    msg: String,
-    connections_pool: ConnectionPool,
-    config: &Config,
-    fcm_address: &str,
-    http_client: Arc<HttpClient>,
-) -> Result<(), RequestError> {
-    let mut connections_pool = connections_pool;
-    let connection = connections_pool.borrow_connection()?;
-
-    let fcm_token = fcm_token::select_by_user_id(user.id(), &connection)?;
-    let fcm_token = if let Some(fcm_token) = fcm_token {
-        fcm_token
-    } else {
-        return Ok(());
-    };
-
-    let send_res = fcm::send(
-        msg,
-        fcm_token.token_value(),
-        config.fcm_server_token(),
-        fcm_address,
-        http_client,
-    )
-    .await?;
-
-    if let fcm::SendResult::Error(error) = send_res {
-        Err(RequestError::new(
-            constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
-            format!("FCM error: {}", error),
-        ))
-    } else {
-        Ok(())
-    }
+/// Runs |action| in a read-only transaction in which all queries see the same state
+/// of the DB, see |transaction::start_read_only_snapshot|.
+pub fn db_read_only_snapshot<T, F>(
+    connection: &dyn DBConnection,
+    action: F,
+) -> Result<T, RequestError>
+where
+    F: FnOnce() -> Result<T, RequestError>,
+{
+    transaction::start_read_only_snapshot::<T, RequestError, _>(connection, action)
 }
@@ -7,6 +7,31 @@ pub const CMD_LIST_PARTNERS: &str = "/v1/user/list_partners";
 pub const CMD_UNPAIR: &str = "/v1/user/unpair";
 pub const CMD_DIRECT_PARTNER_MSG: &str = "/v1/user/direct_partner_msg";
 pub const CMD_UPDATE_USER_NAME: &str = "/v1/user/update_user_name";
+pub const CMD_DELETE_ACCOUNT: &str = "/v1/user/delete_account";
+pub const CMD_EXPORT_DATA: &str = "/v1/user/export_data";
+pub const CMD_FETCH_MESSAGES: &str = "/v1/user/fetch_messages";
+pub const CMD_ADD_FOODSTUFF: &str = "/v1/foodstuff/add";
+pub const CMD_UPDATE_FOODSTUFF: &str = "/v1/foodstuff/update";
+pub const CMD_UNLIST_FOODSTUFF: &str = "/v1/foodstuff/unlist";
+pub const CMD_LIST_FOODSTUFFS: &str = "/v1/foodstuff/list";
+pub const CMD_SYNC_FOODSTUFFS: &str = "/v1/foodstuff/sync";
+pub const CMD_SHARE_FOODSTUFF: &str = "/v1/foodstuff/share";
+pub const CMD_LIST_SHARED_FOODSTUFFS: &str = "/v1/foodstuff/list_shared";
+pub const CMD_UPDATE_SHARED_FOODSTUFF: &str = "/v1/foodstuff/update_shared";
+pub const CMD_ADD_HISTORY_ENTRY: &str = "/v1/history/add";
+pub const CMD_DELETE_HISTORY_ENTRY: &str = "/v1/history/delete";
+pub const CMD_LIST_HISTORY: &str = "/v1/history/list";
+pub const CMD_CREATE_RECIPE: &str = "/v1/recipe/create";
+pub const CMD_EDIT_RECIPE: &str = "/v1/recipe/edit";
+pub const CMD_DELETE_RECIPE: &str = "/v1/recipe/delete";
+pub const CMD_LIST_RECIPES: &str = "/v1/recipe/list";
+pub const CMD_GET_MEAL_PLAN: &str = "/v1/meal_plan/get";
+pub const CMD_UPDATE_MEAL_PLAN_MEAL: &str = "/v1/meal_plan/update_meal";
+pub const CMD_CREATE_SHOPPING_LIST: &str = "/v1/shopping_list/create";
+pub const CMD_DELETE_SHOPPING_LIST: &str = "/v1/shopping_list/delete";
+pub const CMD_LIST_SHOPPING_LISTS: &str = "/v1/shopping_list/list";
+pub const CMD_SHARE_SHOPPING_LIST: &str = "/v1/shopping_list/share";
+pub const CMD_CHECK_SHOPPING_LIST_ITEM: &str = "/v1/shopping_list/check_item";
 
 pub const ARG_USER_NAME: &str = "name";
 pub const ARG_SOCIAL_NETWORK_TYPE: &str = "social_network_type";
@@ -15,8 +40,34 @@ pub const ARG_OVERRIDES: &str = "overrides";
 pub const ARG_CLIENT_TOKEN: &str = "client_token";
 pub const ARG_USER_ID: &str = "user_id";
 pub const ARG_FCM_TOKEN: &str = "fcm_token";
+pub const ARG_DEVICE_ID: &str = "device_id";
 pub const ARG_PARTNER_PAIRING_CODE: &str = "partner_pairing_code";
 pub const ARG_PARTNER_USER_ID: &str = "partner_user_id";
+pub const ARG_FOODSTUFF_ID: &str = "foodstuff_id";
+pub const ARG_FOODSTUFF_NAME: &str = "foodstuff_name";
+pub const ARG_PROTEIN: &str = "protein";
+pub const ARG_FATS: &str = "fats";
+pub const ARG_CARBS: &str = "carbs";
+pub const ARG_CALORIES: &str = "calories";
+pub const ARG_SYNC_TOKEN: &str = "sync_token";
+pub const ARG_PERMISSION: &str = "permission";
+pub const ARG_HISTORY_ENTRY_ID: &str = "history_entry_id";
+pub const ARG_MASS: &str = "mass";
+pub const ARG_TIME: &str = "time";
+pub const ARG_TIME_FROM: &str = "time_from";
+pub const ARG_TIME_TO: &str = "time_to";
+pub const ARG_RECIPE_ID: &str = "recipe_id";
+pub const ARG_RECIPE_NAME: &str = "recipe_name";
+pub const ARG_DAY: &str = "day";
+pub const ARG_MEAL_TYPE: &str = "meal_type";
+pub const ARG_VERSION: &str = "version";
+pub const ARG_SHOPPING_LIST_ID: &str = "shopping_list_id";
+pub const ARG_SHOPPING_LIST_NAME: &str = "shopping_list_name";
+pub const ARG_OWNER_USER_ID: &str = "owner_user_id";
+pub const ARG_ITEM_ID: &str = "item_id";
+pub const ARG_IS_CHECKED: &str = "is_checked";
+pub const ARG_MSG: &str = "msg";
+pub const ARG_CURSOR: &str = "cursor";
 
 pub const FIELD_NAME_ERROR_DESCRIPTION: &str = "error_description";
 pub const FIELD_NAME_STATUS: &str = "status";
@@ -29,6 +80,55 @@ pub const FIELD_NAME_PAIRING_CODE: &str = "pairing_code";
 pub const FIELD_NAME_PARTNER_USER_ID: &str = "partner_user_id";
 pub const FIELD_NAME_PARTNER_NAME: &str = "partner_name";
 pub const FIELD_NAME_PARTNERS: &str = "partners";
+pub const FIELD_NAME_FOODSTUFF_ID: &str = "foodstuff_id";
+pub const FIELD_NAME_FOODSTUFF_NAME: &str = "foodstuff_name";
+pub const FIELD_NAME_PROTEIN: &str = "protein";
+pub const FIELD_NAME_FATS: &str = "fats";
+pub const FIELD_NAME_CARBS: &str = "carbs";
+pub const FIELD_NAME_CALORIES: &str = "calories";
+pub const FIELD_NAME_FOODSTUFFS: &str = "foodstuffs";
+pub const FIELD_NAME_IS_LISTED: &str = "is_listed";
+pub const FIELD_NAME_REVISION: &str = "revision";
+pub const FIELD_NAME_SYNC_TOKEN: &str = "sync_token";
+pub const FIELD_NAME_CONFLICTS: &str = "conflicts";
+pub const FIELD_NAME_PERMISSION: &str = "permission";
+pub const FIELD_NAME_HISTORY_ENTRY_ID: &str = "history_entry_id";
+pub const FIELD_NAME_MASS: &str = "mass";
+pub const FIELD_NAME_TIME: &str = "time";
+pub const FIELD_NAME_HISTORY_ENTRIES: &str = "history_entries";
+pub const FIELD_NAME_RECIPE_ID: &str = "recipe_id";
+pub const FIELD_NAME_RECIPE_NAME: &str = "recipe_name";
+pub const FIELD_NAME_INGREDIENTS: &str = "ingredients";
+pub const FIELD_NAME_WEIGHT: &str = "weight";
+pub const FIELD_NAME_RECIPE: &str = "recipe";
+pub const FIELD_NAME_RECIPES: &str = "recipes";
+pub const FIELD_NAME_VERSION: &str = "version";
+pub const FIELD_NAME_MEALS: &str = "meals";
+pub const FIELD_NAME_DAY: &str = "day";
+pub const FIELD_NAME_MEAL_TYPE: &str = "meal_type";
+pub const FIELD_NAME_ITEMS: &str = "items";
+pub const FIELD_NAME_OWNER_USER_ID: &str = "owner_user_id";
+pub const FIELD_NAME_SHOPPING_LIST_ID: &str = "shopping_list_id";
+pub const FIELD_NAME_SHOPPING_LIST_NAME: &str = "shopping_list_name";
+pub const FIELD_NAME_SHOPPING_LIST: &str = "shopping_list";
+pub const FIELD_NAME_SHOPPING_LISTS: &str = "shopping_lists";
+pub const FIELD_NAME_ITEM_ID: &str = "item_id";
+pub const FIELD_NAME_IS_CHECKED: &str = "is_checked";
+pub const FIELD_NAME_USER_DATA: &str = "user_data";
+pub const FIELD_NAME_VK_UID: &str = "vk_uid";
+pub const FIELD_NAME_GP_UID: &str = "gp_uid";
+pub const FIELD_NAME_DEVICE_ID: &str = "device_id";
+pub const FIELD_NAME_FCM_TOKEN_PRESENT: &str = "fcm_token_present";
+pub const FIELD_NAME_PAIRING_STATE: &str = "pairing_state";
+pub const FIELD_NAME_PAIRING_START_TIME: &str = "pairing_start_time";
+pub const FIELD_NAME_CREATION_TIME: &str = "creation_time";
+pub const FIELD_NAME_ATTEMPTS_COUNT: &str = "attempts_count";
+pub const FIELD_NAME_LAST_ATTEMPT_TIME: &str = "last_attempt_time";
+pub const FIELD_NAME_RETRY_AFTER_SECONDS: &str = "retry_after_seconds";
+pub const FIELD_NAME_MSG: &str = "msg";
+pub const FIELD_NAME_NOTIFICATION_STATE: &str = "notification_state";
+pub const FIELD_NAME_MESSAGES: &str = "messages";
+pub const FIELD_NAME_CURSOR: &str = "cursor";
 
 pub const FIELD_STATUS_OK: &str = "ok";
 pub const FIELD_STATUS_CONNECTION_BROKEN: &str = "connection_broken";
@@ -41,12 +141,25 @@ pub const FIELD_STATUS_FOODSTUFF_DUPLICATION: &str = "foodstuff_duplication";
 pub const FIELD_STATUS_HISTORY_ENTRY_DUPLICATION: &str = "history_entry_duplication";
 pub const FIELD_STATUS_FOODSTUFF_NOT_FOUND: &str = "foodstuff_not_found";
 pub const FIELD_STATUS_HISTORY_ENTRY_NOT_FOUND: &str = "history_entry_not_found";
+pub const FIELD_STATUS_RECIPE_DUPLICATION: &str = "recipe_duplication";
+pub const FIELD_STATUS_RECIPE_NOT_FOUND: &str = "recipe_not_found";
 pub const FIELD_STATUS_ALREADY_REGISTERED: &str = "already_registered";
 pub const FIELD_STATUS_TOKEN_CHECK_FAIL: &str = "token_check_fail";
 pub const FIELD_STATUS_USER_NOT_FOUND: &str = "user_not_found";
 pub const FIELD_STATUS_INVALID_CLIENT_TOKEN: &str = "invalid_client_token";
+pub const FIELD_STATUS_INVALID_AUTHORIZATION: &str = "invalid_authorization";
 pub const FIELD_STATUS_PARTNER_USER_NOT_FOUND: &str = "partner_user_not_found";
 pub const FIELD_STATUS_INVALID_PARTNER_PAIRING_CODE: &str = "invalid_partner_pairing_code";
+pub const FIELD_STATUS_PERMISSION_DENIED: &str = "permission_denied";
+pub const FIELD_STATUS_VERSION_CONFLICT: &str = "version_conflict";
+pub const FIELD_STATUS_SHOPPING_LIST_DUPLICATION: &str = "shopping_list_duplication";
+pub const FIELD_STATUS_SHOPPING_LIST_NOT_FOUND: &str = "shopping_list_not_found";
+pub const FIELD_STATUS_SHOPPING_LIST_ITEM_NOT_FOUND: &str = "shopping_list_item_not_found";
+pub const FIELD_STATUS_SERVER_BUSY: &str = "server_busy";
+pub const FIELD_STATUS_NOT_READY: &str = "not_ready";
+pub const FIELD_STATUS_RATE_LIMITED: &str = "rate_limited";
+pub const FIELD_STATUS_PAIRING_LOCKED_OUT: &str = "pairing_locked_out";
+pub const FIELD_STATUS_BODY_TOO_LARGE: &str = "body_too_large";
 
 pub const SERV_FIELD_MSG_TYPE: &str = "msg_type";
 pub const SERV_FIELD_PAIRING_PARTNER_USER_ID: &str = "pairing_partner_user_id";
@@ -54,9 +167,34 @@ pub const SERV_FIELD_PARTNER_USER_ID: &str = "partner_user_id";
 pub const SERV_FIELD_PARTNER_NAME: &str = "partner_name";
 pub const SERV_FIELD_REQUEST_EXPIRATION_DATE: &str = "request_expiration_date";
 pub const SERV_FIELD_MSG: &str = "msg";
+pub const SERV_FIELD_FOODSTUFF_ID: &str = "foodstuff_id";
+pub const SERV_FIELD_FOODSTUFF_NAME: &str = "foodstuff_name";
+pub const SERV_FIELD_VERSION: &str = "version";
+pub const SERV_FIELD_OWNER_USER_ID: &str = "owner_user_id";
+pub const SERV_FIELD_SHOPPING_LIST_ID: &str = "shopping_list_id";
+pub const SERV_FIELD_ITEM_ID: &str = "item_id";
+pub const SERV_FIELD_IS_CHECKED: &str = "is_checked";
 
 pub const SERV_MSG_PAIRING_REQUEST_FROM_PARTNER: &str = "pairing_request_from_partner";
 pub const SERV_MSG_PAIRED_WITH_PARTNER: &str = "paired_with_partner";
 pub const SERV_MSG_DIRECT_MSG_FROM_PARTNER: &str = "direct_msg_from_partner";
+pub const SERV_MSG_FOODSTUFF_SHARED_BY_PARTNER: &str = "foodstuff_shared_by_partner";
+pub const SERV_MSG_MEAL_PLAN_UPDATED_BY_PARTNER: &str = "meal_plan_updated_by_partner";
+pub const SERV_MSG_SHOPPING_LIST_ITEM_CHECKED: &str = "shopping_list_item_checked";
+pub const SERV_MSG_PARTNER_DELETED_ACCOUNT: &str = "partner_deleted_account";
+
+pub const PERMISSION_READ_ONLY: &str = "read_only";
+pub const PERMISSION_EDITABLE: &str = "editable";
+
+pub const PAIRING_STATE_DONE: &str = "done";
+pub const PAIRING_STATE_NOT_CONFIRMED: &str = "not_confirmed";
+
+pub const NOTIFICATION_STATE_PENDING: &str = "pending";
+pub const NOTIFICATION_STATE_DEAD: &str = "dead";
+
+pub const MEAL_TYPE_BREAKFAST: &str = "breakfast";
+pub const MEAL_TYPE_LUNCH: &str = "lunch";
+pub const MEAL_TYPE_DINNER: &str = "dinner";
+pub const MEAL_TYPE_SNACK: &str = "snack";
 
 pub const PAIRING_CODES_FAMILY_NAME: &str = "default";
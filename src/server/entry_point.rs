@@ -1,74 +1,125 @@
+use hyper::header::CONTENT_TYPE;
+use hyper::server::conn::AddrStream;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::Body;
 use hyper::Request;
 use hyper::Response;
 use hyper::Server;
 
+use futures::channel::oneshot;
 use futures::future::select;
+use futures::future::Either;
 use futures::future::Future;
 use futures::StreamExt;
 use log::error;
-use tokio::runtime::Runtime;
+use log::info;
+use log::warn;
+use tokio::runtime::Builder;
+use tokio::time::delay_for;
 
-use std::cell::RefCell;
+use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::sync::Mutex;
+use std::time::Duration;
 
+use super::constants;
+use super::health;
+use super::requests_handler::HandlerResponse;
 use super::requests_handler::RequestsHandler;
+use crate::metrics;
 use std::collections::HashMap;
 
 pub const MAX_BODY_SIZE: i32 = 1024 * 50;
+pub const METRICS_PATH: &str = "/metrics";
+
+const JSON_CONTENT_TYPE: &str = "application/json";
 
 struct EntryPoint<RH>
 where
     RH: RequestsHandler,
 {
-    requests_handler: Arc<Mutex<RefCell<RH>>>,
+    requests_handler: RH,
 }
 
 impl<RH> EntryPoint<RH>
 where
     RH: RequestsHandler,
 {
-    fn new(requests_handler: RefCell<RH>) -> EntryPoint<RH> {
-        EntryPoint {
-            requests_handler: Arc::new(Mutex::new(requests_handler)),
-        }
+    fn new(requests_handler: RH) -> EntryPoint<RH> {
+        EntryPoint { requests_handler }
     }
 }
 
 // Starts server on the calling thread, blocking it.
-pub fn start_server<F, RH>(address: &SocketAddr, shutdown_signal: F, requests_handler: RH)
-where
+// When |shutdown_signal| is received, the server stops accepting new connections and
+// waits for in-flight requests to be finished, but not longer than |drain_timeout|.
+// The function returns when the server is stopped and |requests_handler| is dropped.
+pub fn start_server<F, RH>(
+    address: &SocketAddr,
+    shutdown_signal: F,
+    drain_timeout: Duration,
+    requests_handler: RH,
+) where
     F: Future<Output = ()> + Unpin,
     RH: RequestsHandler + 'static,
 {
-    let requests_handler = RefCell::new(requests_handler);
     let entry_point = Arc::new(EntryPoint::new(requests_handler));
 
-    let mut tokio_runtime = Runtime::new().expect("Tokio expected to be ok");
+    // Requests are handled by as many threads as there are cores
+    let mut tokio_runtime = Builder::new()
+        .threaded_scheduler()
+        .enable_all()
+        .build()
+        .expect("Tokio expected to be ok");
 
     // Server::bind will panic if it's executed not in Tokio runtime, so we pack it into a Future
     let serve_future = async {
         // Create a server bound on the provided address
-        let serve_future = Server::bind(&address).serve(make_service_fn(move |_| {
-            let entry_point = entry_point.clone();
-            async move {
-                Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
-                    handle_request_by_entry_point(req, entry_point.clone())
-                }))
+        let serve_future =
+            Server::bind(&address).serve(make_service_fn(move |conn: &AddrStream| {
+                let entry_point = entry_point.clone();
+                let client_ip = conn.remote_addr().ip();
+                async move {
+                    Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
+                        handle_request_by_entry_point(req, client_ip, entry_point.clone())
+                    }))
+                }
+            }));
+
+        // Drain deadline starts ticking only when the shutdown signal is received
+        let (drain_started_sender, drain_started_receiver) = oneshot::channel::<()>();
+        let serve_future = serve_future.with_graceful_shutdown(async move {
+            shutdown_signal.await;
+            info!("Shutdown signal received, draining in-flight requests");
+            let _ = drain_started_sender.send(());
+        });
+        let drain_deadline = async move {
+            match drain_started_receiver.await {
+                Ok(_) => delay_for(drain_timeout).await,
+                // The server stopped by itself
+                Err(_) => futures::future::pending().await,
             }
-        }));
-        let server_with_shutdown_signal = select(shutdown_signal, serve_future);
-        server_with_shutdown_signal.await;
+        };
+
+        match select(Box::pin(serve_future), Box::pin(drain_deadline)).await {
+            Either::Left((Ok(_), _)) => info!("Server is stopped"),
+            Either::Left((Err(err), _)) => error!("Server error: {}", err),
+            Either::Right(_) => warn!(
+                "In-flight requests weren't finished in {:?}, dropping them",
+                drain_timeout
+            ),
+        }
     };
 
     tokio_runtime.block_on(serve_future);
+    // Commands of requests dropped at the drain deadline might still be running on blocking
+    // threads - they're not waited for.
+    tokio_runtime.shutdown_timeout(Duration::from_secs(0));
 }
 
 async fn handle_request_by_entry_point<RH>(
     req: Request<Body>,
+    client_ip: IpAddr,
     entry_point: Arc<EntryPoint<RH>>,
 ) -> Result<Response<Body>, hyper::Error>
 where
@@ -81,25 +132,51 @@ where
         Some(query) => query.to_string(),
         None => "".to_string(),
     };
+
+    // Probes and metrics are handled before the body is read, they don't need it
+    match request.as_str() {
+        health::HEALTHZ_PATH => return Ok(to_hyper_response(health::healthz_response())),
+        health::READYZ_PATH => {
+            let readiness = entry_point.requests_handler.check_readiness().await;
+            if let Err(description) = &readiness {
+                warn!("Not ready: {}", description);
+            }
+            return Ok(to_hyper_response(health::readyz_response(readiness)));
+        }
+        METRICS_PATH => {
+            let response =
+                HandlerResponse::new(metrics::gather()).with_content_type(metrics::TEXT_FORMAT);
+            return Ok(to_hyper_response(response));
+        }
+        _ => {}
+    }
+
     let headers = extract_headers(&req);
     let body = extract_body(&request, req).await;
 
     let body = match body {
         Ok(body) => body,
-        Err(err_resp) => return Ok(err_resp),
+        Err(err_resp) => return Ok(to_hyper_response(err_resp)),
     };
 
-    let response_str = {
-        // Lock is within narrowest scope
-        let requests_handler = entry_point
-            .requests_handler
-            .lock()
-            .expect("Broken mutex == broken app");
-        let mut requests_handler = requests_handler.borrow_mut();
-        requests_handler.handle(request, query, headers, body)
-    };
-    let response_str = response_str.await;
-    Ok(Response::new(Body::from(response_str)))
+    let response = entry_point
+        .requests_handler
+        .handle(request, query, headers, body, client_ip)
+        .await;
+    Ok(to_hyper_response(response))
+}
+
+fn to_hyper_response(response: HandlerResponse) -> Response<Body> {
+    let mut response_builder = Response::builder().status(response.status_code());
+    if let Some(content_type) = response.content_type() {
+        response_builder = response_builder.header(CONTENT_TYPE, content_type);
+    }
+    for (name, value) in response.headers() {
+        response_builder = response_builder.header(name.as_str(), value.as_str());
+    }
+    response_builder
+        .body(Body::from(response.into_body()))
+        .expect("Expecting valid response")
 }
 
 fn extract_headers(req: &Request<Body>) -> HashMap<String, String> {
@@ -115,7 +192,7 @@ fn extract_headers(req: &Request<Body>) -> HashMap<String, String> {
     headers
 }
 
-async fn extract_body(request: &str, req: Request<Body>) -> Result<String, Response<Body>> {
+async fn extract_body(request: &str, req: Request<Body>) -> Result<String, HandlerResponse> {
     let mut bytes = Vec::new();
 
     let mut body_stream = req.into_body();
@@ -125,18 +202,23 @@ async fn extract_body(request: &str, req: Request<Body>) -> Result<String, Respo
                 bytes.append(&mut chunk.to_vec());
                 if bytes.len() > MAX_BODY_SIZE as usize {
                     error!("Request body too large, uri: {}", request);
-                    return Err(Response::builder()
-                        .status(413) // Payload Too Large
-                        .body(Body::from(""))
-                        .expect("Expecting valid response"));
+                    return Err(error_response(
+                        413, // Payload Too Large
+                        constants::FIELD_STATUS_BODY_TOO_LARGE,
+                        format!("Request body is larger than {} bytes", MAX_BODY_SIZE),
+                    ));
                 }
             }
             Err(err) => {
-                error!("Request body reading error, uri: {}, error: {:?}", request, err);
-                return Err(Response::builder()
-                    .status(500)
-                    .body(Body::from(format!("{:?}", err)))
-                    .expect("Expecting valid response"));
+                error!(
+                    "Request body reading error, uri: {}, error: {:?}",
+                    request, err
+                );
+                return Err(error_response(
+                    500,
+                    constants::FIELD_STATUS_INTERNAL_ERROR,
+                    format!("Request body reading error: {:?}", err),
+                ));
             }
         }
     }
@@ -144,6 +226,18 @@ async fn extract_body(request: &str, req: Request<Body>) -> Result<String, Respo
     Ok(String::from_utf8_lossy(&bytes).to_string())
 }
 
+/// Errors of the entry point have the same format as errors of commands, but always
+/// have HTTP status codes - the requests fail before their API versions matter.
+fn error_response(status_code: u16, status: &str, error_description: String) -> HandlerResponse {
+    let response = json!({
+        constants::FIELD_NAME_STATUS: status,
+        constants::FIELD_NAME_ERROR_DESCRIPTION: error_description,
+    });
+    HandlerResponse::new(response.to_string())
+        .with_status_code(status_code)
+        .with_content_type(JSON_CONTENT_TYPE)
+}
+
 #[cfg(test)]
 #[path = "./entry_point_test.rs"]
 mod entry_point_test;
@@ -1,13 +1,23 @@
 use futures::future::ready;
 use futures::Future;
 use hyper::Uri;
+use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::pin::Pin;
 use std::str::FromStr;
+use std::sync::mpsc;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::outside::http_client::{HttpClient, RequestMethod, Response};
+use crate::server::constants;
 use crate::server::entry_point::MAX_BODY_SIZE;
+use crate::server::health;
+use crate::server::requests_handler::HandlerResponse;
 use crate::server::requests_handler::RequestsHandler;
 use crate::server::testing_hostname;
 use crate::server::testing_server_wrapper;
@@ -18,13 +28,14 @@ struct Echo {
 }
 impl RequestsHandler for Echo {
     fn handle(
-        &mut self,
+        &self,
         _request: String,
         _query: String,
         _headers: HashMap<String, String>,
         _body: String,
-    ) -> Pin<Box<dyn Future<Output = String> + Send>> {
-        Box::pin(ready(self.string.clone()))
+        _client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>> {
+        Box::pin(ready(self.string.clone().into()))
     }
 }
 
@@ -63,10 +74,9 @@ fn test_server_responses() {
 #[test]
 fn test_too_large_body() {
     let address = testing_hostname::get_hostname();
-    let expected_response = "";
     let server = testing_server_wrapper::start_server(
         Echo {
-            string: expected_response.to_string(),
+            string: "".to_string(),
         },
         address,
     );
@@ -77,6 +87,206 @@ fn test_too_large_body() {
     let response = make_request_with_body(&url, String::from_utf8(large_body).unwrap());
 
     let expected_status_code = 413;
-    assert_eq!(expected_response, response.body);
     assert_eq!(expected_status_code, response.status_code);
+    assert_eq!("application/json", response.headers["content-type"]);
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(
+        constants::FIELD_STATUS_BODY_TOO_LARGE,
+        response[constants::FIELD_NAME_STATUS]
+    );
+}
+
+struct FixedResponse {
+    response: HandlerResponse,
+}
+impl RequestsHandler for FixedResponse {
+    fn handle(
+        &self,
+        _request: String,
+        _query: String,
+        _headers: HashMap<String, String>,
+        _body: String,
+        _client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>> {
+        Box::pin(ready(self.response.clone()))
+    }
+}
+
+#[test]
+fn test_response_status_code_and_content_type() {
+    let address = testing_hostname::get_hostname();
+    let server = testing_server_wrapper::start_server(
+        FixedResponse {
+            response: HandlerResponse::new("{}".to_owned())
+                .with_status_code(404)
+                .with_content_type("application/json"),
+        },
+        address,
+    );
+
+    let url = format!("http://{}", server.address());
+    let response = make_request(&url);
+
+    assert_eq!("{}", response.body);
+    assert_eq!(404, response.status_code);
+    assert_eq!(
+        Some(&"application/json".to_owned()),
+        response.headers.get("content-type")
+    );
+}
+
+/// Notifies about started requests and finishes them only after the given delay.
+struct Slow {
+    request_started: Mutex<mpsc::Sender<()>>,
+    delay: Option<Duration>,
+}
+impl RequestsHandler for Slow {
+    fn handle(
+        &self,
+        _request: String,
+        _query: String,
+        _headers: HashMap<String, String>,
+        _body: String,
+        _client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>> {
+        self.request_started.lock().unwrap().send(()).unwrap();
+        match self.delay {
+            Some(delay) => Box::pin(async move {
+                tokio::time::delay_for(delay).await;
+                "finished".to_owned().into()
+            }),
+            None => Box::pin(futures::future::pending()),
+        }
+    }
+}
+
+#[test]
+fn shutdown_waits_for_in_flight_requests() {
+    let address = testing_hostname::get_hostname();
+    let (request_started_sender, request_started_receiver) = mpsc::channel();
+    let server = testing_server_wrapper::start_server(
+        Slow {
+            request_started: Mutex::new(request_started_sender),
+            delay: Some(Duration::from_millis(500)),
+        },
+        address,
+    );
+
+    let url = format!("http://{}", server.address());
+    let request = thread::spawn(move || make_request(&url));
+    request_started_receiver.recv().unwrap();
+
+    // Stops the server
+    drop(server);
+
+    let response = request.join().unwrap();
+    assert_eq!("finished", response.body);
+}
+
+#[test]
+fn shutdown_does_not_wait_for_requests_longer_than_drain_timeout() {
+    let address = testing_hostname::get_hostname();
+    let (request_started_sender, request_started_receiver) = mpsc::channel();
+    let server = testing_server_wrapper::start_server_with_drain_timeout(
+        Slow {
+            request_started: Mutex::new(request_started_sender),
+            delay: None,
+        },
+        address,
+        Duration::from_millis(200),
+    );
+
+    let url = format!("http://{}", server.address());
+    let request = thread::spawn(move || {
+        let http_client = Arc::new(HttpClient::new().unwrap());
+        let response = http_client.req(
+            Uri::from_str(&url).unwrap(),
+            RequestMethod::Post,
+            HashMap::new(),
+            Some("".to_owned()),
+        );
+        exhaust_future(response)
+    });
+    request_started_receiver.recv().unwrap();
+
+    let shutdown_start = Instant::now();
+    drop(server);
+    assert!(shutdown_start.elapsed() >= Duration::from_millis(200));
+    assert!(shutdown_start.elapsed() < Duration::from_secs(5));
+
+    // The request is never finished by the server, so it's dropped
+    assert!(request.join().unwrap().is_err());
+}
+
+struct NotReady {
+    description: String,
+}
+impl RequestsHandler for NotReady {
+    fn handle(
+        &self,
+        _request: String,
+        _query: String,
+        _headers: HashMap<String, String>,
+        _body: String,
+        _client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>> {
+        panic!("Probes must not be handled by the requests handler");
+    }
+
+    fn check_readiness(&self) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> {
+        Box::pin(ready(Err(self.description.clone())))
+    }
+}
+
+#[test]
+fn healthz_is_served_by_entry_point() {
+    let address = testing_hostname::get_hostname();
+    let server = testing_server_wrapper::start_server(
+        NotReady {
+            description: "not ready".to_owned(),
+        },
+        address,
+    );
+
+    let url = format!("http://{}{}", server.address(), health::HEALTHZ_PATH);
+    let response = make_request(&url);
+
+    assert_eq!(200, response.status_code);
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(
+        &json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+            constants::FIELD_NAME_VERSION: health::BUILD_VERSION,
+        }),
+        &response
+    );
+}
+
+#[test]
+fn readyz_reports_readiness_problem() {
+    let address = testing_hostname::get_hostname();
+    let server = testing_server_wrapper::start_server(
+        NotReady {
+            description: "DB is down".to_owned(),
+        },
+        address,
+    );
+
+    let url = format!("http://{}{}", server.address(), health::READYZ_PATH);
+    let response = make_request(&url);
+
+    assert_eq!(503, response.status_code);
+    assert_eq!(
+        Some(&"application/json".to_owned()),
+        response.headers.get("content-type")
+    );
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(
+        &json!({
+            constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_NOT_READY,
+            constants::FIELD_NAME_ERROR_DESCRIPTION: "DB is down",
+            constants::FIELD_NAME_VERSION: health::BUILD_VERSION,
+        }),
+        &response
+    );
 }
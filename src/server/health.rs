@@ -0,0 +1,45 @@
+use super::constants;
+use super::requests_handler::HandlerResponse;
+
+// Endpoints for probes of an orchestrator. They're served without authentication
+// and without going through the commands.
+
+/// Process is alive.
+pub const HEALTHZ_PATH: &str = "/healthz";
+/// Process can handle requests - e.g. it has a working DB connection.
+pub const READYZ_PATH: &str = "/readyz";
+
+pub const BUILD_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+pub fn healthz_response() -> HandlerResponse {
+    let response = json!({
+        constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+        constants::FIELD_NAME_VERSION: BUILD_VERSION,
+    });
+    HandlerResponse::new(response.to_string()).with_content_type(JSON_CONTENT_TYPE)
+}
+
+pub fn readyz_response(readiness: Result<(), String>) -> HandlerResponse {
+    let (status_code, response) = match readiness {
+        Ok(_) => (
+            200,
+            json!({
+                constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_OK,
+                constants::FIELD_NAME_VERSION: BUILD_VERSION,
+            }),
+        ),
+        Err(description) => (
+            503, // Service Unavailable
+            json!({
+                constants::FIELD_NAME_STATUS: constants::FIELD_STATUS_NOT_READY,
+                constants::FIELD_NAME_ERROR_DESCRIPTION: description,
+                constants::FIELD_NAME_VERSION: BUILD_VERSION,
+            }),
+        ),
+    };
+    HandlerResponse::new(response.to_string())
+        .with_status_code(status_code)
+        .with_content_type(JSON_CONTENT_TYPE)
+}
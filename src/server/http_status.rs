@@ -0,0 +1,54 @@
+use super::constants;
+
+/// Requests of API v2 are the same commands as of API v1 (their paths differ only in
+/// the version prefix), but responses of v2 have HTTP status codes corresponding to
+/// the statuses in their bodies. Responses of v1 always have the 200 code so that
+/// old clients wouldn't break.
+pub const API_V1_PREFIX: &str = "/v1/";
+pub const API_V2_PREFIX: &str = "/v2/";
+
+/// Turns a path of an API v2 request into the path of the corresponding command,
+/// returns None for paths of other API versions.
+pub fn v2_request_to_cmd(request: &str) -> Option<String> {
+    request
+        .strip_prefix(API_V2_PREFIX)
+        .map(|cmd| format!("{}{}", API_V1_PREFIX, cmd))
+}
+
+pub fn http_status_code_of(status: &str) -> u16 {
+    match status {
+        constants::FIELD_STATUS_OK => 200,
+        constants::FIELD_STATUS_PARAM_MISSING
+        | constants::FIELD_STATUS_INVALID_UUID
+        | constants::FIELD_STATUS_INVALID_QUERY
+        | constants::FIELD_STATUS_INVALID_PARTNER_PAIRING_CODE => 400,
+        constants::FIELD_STATUS_TOKEN_CHECK_FAIL
+        | constants::FIELD_STATUS_INVALID_CLIENT_TOKEN
+        | constants::FIELD_STATUS_INVALID_AUTHORIZATION => 401,
+        constants::FIELD_STATUS_PERMISSION_DENIED => 403,
+        constants::FIELD_STATUS_BODY_TOO_LARGE => 413,
+        constants::FIELD_STATUS_UNKNOWN_REQUEST
+        | constants::FIELD_STATUS_USER_NOT_FOUND
+        | constants::FIELD_STATUS_PARTNER_USER_NOT_FOUND
+        | constants::FIELD_STATUS_FOODSTUFF_NOT_FOUND
+        | constants::FIELD_STATUS_HISTORY_ENTRY_NOT_FOUND
+        | constants::FIELD_STATUS_RECIPE_NOT_FOUND
+        | constants::FIELD_STATUS_SHOPPING_LIST_NOT_FOUND
+        | constants::FIELD_STATUS_SHOPPING_LIST_ITEM_NOT_FOUND => 404,
+        constants::FIELD_STATUS_ALREADY_REGISTERED
+        | constants::FIELD_STATUS_FOODSTUFF_DUPLICATION
+        | constants::FIELD_STATUS_HISTORY_ENTRY_DUPLICATION
+        | constants::FIELD_STATUS_RECIPE_DUPLICATION
+        | constants::FIELD_STATUS_SHOPPING_LIST_DUPLICATION
+        | constants::FIELD_STATUS_VERSION_CONFLICT => 409,
+        constants::FIELD_STATUS_RATE_LIMITED | constants::FIELD_STATUS_PAIRING_LOCKED_OUT => 429,
+        constants::FIELD_STATUS_INTERNAL_ERROR | constants::FIELD_STATUS_CONNECTION_BROKEN => 500,
+        constants::FIELD_STATUS_SERVER_BUSY | constants::FIELD_STATUS_NOT_READY => 503,
+        // A status without a code is a server's bug
+        _ => 500,
+    }
+}
+
+#[cfg(test)]
+#[path = "./http_status_test.rs"]
+mod http_status_test;
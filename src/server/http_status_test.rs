@@ -0,0 +1,70 @@
+use super::http_status_code_of;
+use super::v2_request_to_cmd;
+use crate::server::constants;
+
+#[test]
+fn v2_requests_are_v1_cmds() {
+    assert_eq!(
+        Some(constants::CMD_ADD_FOODSTUFF.to_owned()),
+        v2_request_to_cmd(&constants::CMD_ADD_FOODSTUFF.replacen("/v1/", "/v2/", 1))
+    );
+    assert_eq!(None, v2_request_to_cmd(constants::CMD_ADD_FOODSTUFF));
+    assert_eq!(None, v2_request_to_cmd("/v3/foodstuff/add"));
+}
+
+#[test]
+fn http_status_codes() {
+    assert_eq!(200, http_status_code_of(constants::FIELD_STATUS_OK));
+    assert_eq!(
+        400,
+        http_status_code_of(constants::FIELD_STATUS_PARAM_MISSING)
+    );
+    assert_eq!(
+        400,
+        http_status_code_of(constants::FIELD_STATUS_INVALID_QUERY)
+    );
+    assert_eq!(
+        401,
+        http_status_code_of(constants::FIELD_STATUS_INVALID_CLIENT_TOKEN)
+    );
+    assert_eq!(
+        403,
+        http_status_code_of(constants::FIELD_STATUS_PERMISSION_DENIED)
+    );
+    assert_eq!(
+        404,
+        http_status_code_of(constants::FIELD_STATUS_UNKNOWN_REQUEST)
+    );
+    assert_eq!(
+        404,
+        http_status_code_of(constants::FIELD_STATUS_USER_NOT_FOUND)
+    );
+    assert_eq!(
+        409,
+        http_status_code_of(constants::FIELD_STATUS_FOODSTUFF_DUPLICATION)
+    );
+    assert_eq!(
+        409,
+        http_status_code_of(constants::FIELD_STATUS_VERSION_CONFLICT)
+    );
+    assert_eq!(
+        413,
+        http_status_code_of(constants::FIELD_STATUS_BODY_TOO_LARGE)
+    );
+    assert_eq!(
+        429,
+        http_status_code_of(constants::FIELD_STATUS_RATE_LIMITED)
+    );
+    assert_eq!(
+        429,
+        http_status_code_of(constants::FIELD_STATUS_PAIRING_LOCKED_OUT)
+    );
+    assert_eq!(
+        500,
+        http_status_code_of(constants::FIELD_STATUS_INTERNAL_ERROR)
+    );
+    assert_eq!(
+        503,
+        http_status_code_of(constants::FIELD_STATUS_SERVER_BUSY)
+    );
+}
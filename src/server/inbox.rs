@@ -0,0 +1,29 @@
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::inbox_message;
+use crate::utils::now_source::{DefaultNowSource, NowSource};
+
+use super::request_error::RequestError;
+
+/// Messages which are not fetched for this long are dropped, so that inboxes of users
+/// who receive messages with FCM and never poll wouldn't grow forever.
+const INBOX_MESSAGE_TTL_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// Stores the |msg| into the inbox of the |user|, so that the user could fetch it
+/// even if it's never delivered with FCM.
+/// Should be called in the transaction of the change which the message announces,
+/// see |notification_outbox::enqueue|.
+pub fn store(
+    user: &AppUser,
+    msg: String,
+    connection: &dyn DBConnection,
+) -> Result<(), RequestError> {
+    let now = DefaultNowSource::default().now_secs()?;
+    inbox_message::delete_created_before(
+        user.id(),
+        now.saturating_sub(INBOX_MESSAGE_TTL_SECONDS),
+        connection,
+    )?;
+    inbox_message::insert(inbox_message::new(msg, user, now), connection)?;
+    Ok(())
+}
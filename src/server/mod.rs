@@ -2,6 +2,11 @@ pub mod cmds;
 pub mod constants;
 pub mod entry_point;
 pub mod error;
+pub mod health;
+pub mod http_status;
+pub mod inbox;
+pub mod notification_outbox;
+pub mod rate_limiter;
 pub mod request_error;
 pub mod requests_handler;
 pub mod requests_handler_impl;
@@ -0,0 +1,330 @@
+use futures::future::join_all;
+use log::info;
+use log::warn;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::delay_for;
+
+use crate::config::Config;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::device;
+use crate::db::core::fcm_token;
+use crate::db::core::notification_outbox;
+use crate::db::core::notification_outbox::NotificationState;
+use crate::db::core::notification_outbox::OutboxNotification;
+use crate::db::pool::connection_pool::{BorrowedDBConnection, ConnectionPool};
+use crate::metrics;
+use crate::outside::error::Error as OutsideError;
+use crate::outside::fcm;
+use crate::outside::fcm_v1;
+use crate::outside::http_client::HttpClient;
+use crate::utils::now_source::{DefaultNowSource, NowSource};
+
+use super::cmds::utils::db_transaction;
+use super::request_error::RequestError;
+
+/// While notifications are being sent, their next attempts are postponed by the lease,
+/// so that they wouldn't be sent twice. If the sender dies, the notifications are sent
+/// again when the lease expires.
+const SEND_LEASE_SECONDS: i64 = 60;
+/// Max number of due notifications sent at once.
+const SEND_BATCH_SIZE: i64 = 100;
+
+/// Queues the |msg| for each device of the |user| which has an FCM token.
+/// Should be called in the transaction of the change which the message announces, so that
+/// the message would be queued if and only if the change is committed.
+/// Returns IDs of the queued notifications, so that they could be sent right after
+/// the transaction, see |send_enqueued|.
+pub fn enqueue(
+    user: &AppUser,
+    msg: String,
+    connection: &dyn DBConnection,
+) -> Result<Vec<i32>, RequestError> {
+    let now = DefaultNowSource::default().now_secs()?;
+    let devices_with_tokens: HashSet<i32> = fcm_token::select_by_user_id(user.id(), connection)?
+        .iter()
+        .map(|fcm_token| fcm_token.device_id())
+        .collect();
+
+    let mut ids = Vec::with_capacity(devices_with_tokens.len());
+    for device in device::select_by_app_user_id(user.id(), connection)? {
+        if !devices_with_tokens.contains(&device.id()) {
+            continue;
+        }
+        let notification = notification_outbox::new(msg.clone(), user, &device, now);
+        ids.push(notification_outbox::insert(notification, connection)?.id());
+    }
+    Ok(ids)
+}
+
+/// Sends the just queued notifications without waiting for the worker.
+/// Notifications which fail to be sent stay queued and are retried by the worker.
+/// Takes the |connection| of the caller, so that a request wouldn't hold
+/// 2 connections of the pool at once, see |send_claimed|.
+pub async fn send_enqueued(
+    ids: Vec<i32>,
+    connection: BorrowedDBConnection,
+    connections_pool: ConnectionPool,
+    config: &Config,
+    fcm_address: &str,
+    http_client: Arc<HttpClient>,
+) -> Result<(), RequestError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let now = DefaultNowSource::default().now_secs()?;
+    let notifications = db_transaction(&connection, || {
+        let claimed =
+            notification_outbox::claim_by_ids(&ids, now, now + SEND_LEASE_SECONDS, &connection)?;
+        Ok(claimed)
+    })?;
+    send_claimed(
+        notifications,
+        now,
+        connection,
+        connections_pool,
+        config,
+        fcm_address,
+        http_client,
+    )
+    .await
+}
+
+/// Sends notifications which are due at |now|.
+/// Returns the number of notifications there were attempts to send.
+pub async fn send_due(
+    now: i64,
+    connections_pool: ConnectionPool,
+    config: &Config,
+    fcm_address: &str,
+    http_client: Arc<HttpClient>,
+) -> Result<usize, RequestError> {
+    let mut connections_pool = connections_pool;
+    let connection = connections_pool.borrow_connection()?;
+
+    let notifications = db_transaction(&connection, || {
+        let claimed = notification_outbox::claim_due(
+            now,
+            now + SEND_LEASE_SECONDS,
+            SEND_BATCH_SIZE,
+            &connection,
+        )?;
+        Ok(claimed)
+    })?;
+    let count = notifications.len();
+    send_claimed(
+        notifications,
+        now,
+        connection,
+        connections_pool,
+        config,
+        fcm_address,
+        http_client,
+    )
+    .await?;
+    Ok(count)
+}
+
+/// Sends due notifications every |notification_worker_interval_seconds| of the config,
+/// never resolves.
+pub async fn run_worker(
+    connections_pool: ConnectionPool,
+    config: Config,
+    fcm_address: String,
+    http_client: Arc<HttpClient>,
+) {
+    let interval = Duration::from_secs(config.notification_worker_interval_seconds());
+    loop {
+        let result = match DefaultNowSource::default().now_secs() {
+            Ok(now) => {
+                send_due(
+                    now,
+                    connections_pool.clone(),
+                    &config,
+                    &fcm_address,
+                    http_client.clone(),
+                )
+                .await
+            }
+            Err(error) => Err(error.into()),
+        };
+        match result {
+            // There can be more due notifications
+            Ok(count) if count as i64 == SEND_BATCH_SIZE => continue,
+            Ok(_) => {}
+            Err(error) => warn!("Couldn't send due notifications: {:?}", error),
+        }
+        delay_for(interval).await;
+    }
+}
+
+/// Notifications are deleted when they are sent, or when they cannot be sent
+/// because their devices don't have valid FCM tokens anymore.
+/// Tokens which FCM reports as permanently invalid are deleted, so that they wouldn't be
+/// used again, tokens for which FCM reports canonical tokens are replaced by them.
+/// The |connection| is returned to the pool while FCM requests are in flight
+/// (the claimed notifications are leased, so nothing else sends them meanwhile),
+/// and a connection is borrowed again to record the results.
+async fn send_claimed(
+    notifications: Vec<OutboxNotification>,
+    now: i64,
+    connection: BorrowedDBConnection,
+    connections_pool: ConnectionPool,
+    config: &Config,
+    fcm_address: &str,
+    http_client: Arc<HttpClient>,
+) -> Result<(), RequestError> {
+    let mut notifications_with_tokens = Vec::with_capacity(notifications.len());
+    for notification in notifications {
+        match fcm_token::select_by_device_id(notification.device_id(), &connection)? {
+            Some(fcm_token) => notifications_with_tokens.push((notification, fcm_token)),
+            None => notification_outbox::delete_by_id(notification.id(), &connection)?,
+        }
+    }
+    drop(connection);
+    if notifications_with_tokens.is_empty() {
+        return Ok(());
+    }
+
+    let sends = notifications_with_tokens
+        .iter()
+        .map(|(notification, fcm_token)| {
+            send_fcm_msg(
+                notification.msg().to_owned(),
+                fcm_token.token_value(),
+                config,
+                fcm_address,
+                http_client.clone(),
+            )
+        });
+    let send_results = join_all(sends).await;
+
+    let mut connections_pool = connections_pool;
+    let connection = connections_pool.borrow_connection()?;
+
+    let mut pruned_tokens = HashSet::new();
+    for ((notification, fcm_token), send_res) in
+        notifications_with_tokens.into_iter().zip(send_results)
+    {
+        let error = match send_res {
+            Ok(fcm::SendResult::Success) => {
+                notification_outbox::delete_by_id(notification.id(), &connection)?;
+                continue;
+            }
+            Ok(fcm::SendResult::SuccessWithCanonicalToken(canonical_token)) => {
+                db_transaction(&connection, || {
+                    notification_outbox::delete_by_id(notification.id(), &connection)?;
+                    fcm_token::replace_token_value(fcm_token, canonical_token, &connection)?;
+                    Ok(())
+                })?;
+                continue;
+            }
+            Ok(fcm::SendResult::Error(error)) if error.is_permanent_for_token() => {
+                db_transaction(&connection, || {
+                    notification_outbox::delete_by_id(notification.id(), &connection)?;
+                    fcm_token::delete_by_id(fcm_token.id(), &connection)?;
+                    Ok(())
+                })?;
+                pruned_tokens.insert(fcm_token.id());
+                continue;
+            }
+            Ok(fcm::SendResult::Error(error)) => error.to_string(),
+            Err(error) => error.to_string(),
+        };
+        record_failed_attempt(&notification, &error, now, config, &connection)?;
+    }
+    if !pruned_tokens.is_empty() {
+        info!("Pruned {} FCM tokens rejected by FCM", pruned_tokens.len());
+    }
+    Ok(())
+}
+
+/// Schedules the next attempt with an exponential backoff, or gives up on the notification
+/// if it failed too many times.
+fn record_failed_attempt(
+    notification: &OutboxNotification,
+    error: &str,
+    now: i64,
+    config: &Config,
+    connection: &dyn DBConnection,
+) -> Result<(), RequestError> {
+    let failed_attempts_count = notification.attempts_count() + 1;
+    if i64::from(failed_attempts_count) >= i64::from(config.notification_max_attempts()) {
+        warn!(
+            "Notification {} wasn't sent after {} attempts, last error: {}",
+            notification.id(),
+            failed_attempts_count,
+            error
+        );
+        metrics::observe_dead_notification();
+        notification_outbox::record_failed_attempt(
+            notification,
+            NotificationState::Dead,
+            now,
+            error,
+            connection,
+        )?;
+        return Ok(());
+    }
+
+    let delay = retry_delay_seconds(failed_attempts_count, config);
+    notification_outbox::record_failed_attempt(
+        notification,
+        NotificationState::Pending,
+        now.saturating_add(delay),
+        error,
+        connection,
+    )?;
+    Ok(())
+}
+
+/// The first retry is delayed by the base delay, each next one - twice as long
+/// as the previous one.
+fn retry_delay_seconds(failed_attempts_count: i32, config: &Config) -> i64 {
+    let exponent = u32::try_from(failed_attempts_count - 1).unwrap_or(0);
+    let multiplier = 2u64.checked_pow(exponent).unwrap_or(u64::MAX);
+    let delay = config
+        .notification_retry_base_delay_seconds()
+        .saturating_mul(multiplier);
+    i64::try_from(delay).unwrap_or(i64::MAX)
+}
+
+/// Sends the message with the FCM HTTP v1 API when a service account is configured,
+/// or with the legacy API otherwise.
+async fn send_fcm_msg(
+    msg: String,
+    fcm_token: &str,
+    config: &Config,
+    fcm_address: &str,
+    http_client: Arc<HttpClient>,
+) -> Result<fcm::SendResult, OutsideError> {
+    match config.fcm_service_account_key() {
+        Some(service_account_key) => {
+            fcm_v1::send(
+                msg,
+                fcm_token,
+                service_account_key,
+                fcm_address,
+                http_client,
+            )
+            .await
+        }
+        None => {
+            fcm::send(
+                msg,
+                fcm_token,
+                config.fcm_server_token(),
+                fcm_address,
+                http_client,
+            )
+            .await
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./notification_outbox_test.rs"]
+mod notification_outbox_test;
@@ -0,0 +1,265 @@
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::core::app_user;
+use crate::db::core::app_user::AppUser;
+use crate::db::core::connection::DBConnection;
+use crate::db::core::device;
+use crate::db::core::device::Device;
+use crate::db::core::fcm_token;
+use crate::db::core::notification_outbox as db_notification_outbox;
+use crate::db::core::notification_outbox::NotificationState;
+use crate::db::core::notification_outbox::OutboxNotification;
+use crate::db::core::testing_util as dbtesting_utils;
+use crate::db::core::testing_util::lock_due_notifications;
+use crate::db::pool::connection_pool::ConnectionPool;
+use crate::outside::http_client::HttpClient;
+use crate::server::cmds::testing_cmds_utils::start_mock_server;
+use crate::server::notification_outbox;
+use crate::server::testing_hostname;
+use crate::testing_utils::config_in_tests;
+use crate::testing_utils::exhaust_future;
+
+// NOTE: notifications which are sent by |send_due| are inserted with times far in the past,
+// so that notifications queued by other tests wouldn't be due, see
+// |dbtesting_utils::DUE_NOTIFICATIONS_TIME_LIMIT|.
+
+fn insert_user(uid: &str, connection: &dyn DBConnection) -> AppUser {
+    let uid = Uuid::from_str(uid).unwrap();
+    delete_user(&uid);
+    app_user::insert(
+        app_user::new(uid, "".to_string(), Uuid::new_v4()),
+        connection,
+    )
+    .unwrap()
+}
+
+fn delete_user(uid: &Uuid) {
+    use crate::db::core::util::delete_app_user;
+    delete_app_user(
+        uid,
+        &dbtesting_utils::testing_connection_for_server_user().unwrap(),
+    )
+    .unwrap();
+}
+
+fn insert_device(
+    app_user: &AppUser,
+    fcm_token_value: Option<&str>,
+    connection: &dyn DBConnection,
+) -> Device {
+    let device = device::insert(device::new(Uuid::new_v4(), app_user), connection).unwrap();
+    if let Some(fcm_token_value) = fcm_token_value {
+        let fcm_token = fcm_token::new(fcm_token_value.to_owned(), app_user, &device);
+        fcm_token::insert(fcm_token, connection).unwrap();
+    }
+    device
+}
+
+fn insert_notification(
+    app_user: &AppUser,
+    device: &Device,
+    now: i64,
+    connection: &dyn DBConnection,
+) -> OutboxNotification {
+    let msg = json!({ "msg_type": "testing" }).to_string();
+    db_notification_outbox::insert(
+        db_notification_outbox::new(msg, app_user, device, now),
+        connection,
+    )
+    .unwrap()
+}
+
+fn select_notification(id: i32, connection: &dyn DBConnection) -> Option<OutboxNotification> {
+    db_notification_outbox::select_by_id(id, connection).unwrap()
+}
+
+/// Responds to all sends by the legacy API with the |result|.
+fn fcm_response(result: JsonValue) -> String {
+    json!({
+        "multicast_id": 1,
+        "success": 1,
+        "failure": 0,
+        "canonical_ids": 0,
+        "results": [result]
+    })
+    .to_string()
+}
+
+fn send_due(now: i64, config: &Config, fcm_address: &str) -> usize {
+    let connections_pool = ConnectionPool::for_client_user(config.clone());
+    let http_client = Arc::new(HttpClient::new().unwrap());
+    exhaust_future(notification_outbox::send_due(
+        now,
+        connections_pool,
+        config,
+        fcm_address,
+        http_client,
+    ))
+    .unwrap()
+}
+
+#[test]
+fn notifications_are_queued_for_devices_with_fcm_tokens() {
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-f21f-0000-0000-000000000000", &connection);
+    let device1 = insert_device(&user, Some("outbox_token1"), &connection);
+    let device2 = insert_device(&user, Some("outbox_token2"), &connection);
+    insert_device(&user, None, &connection);
+
+    let ids = notification_outbox::enqueue(&user, "msg1".to_owned(), &connection).unwrap();
+    assert_eq!(2, ids.len());
+
+    let notifications =
+        db_notification_outbox::select_by_app_user_id(user.id(), &connection).unwrap();
+    let ids_and_devices: Vec<(i32, i32)> = notifications
+        .iter()
+        .map(|notification| (notification.id(), notification.device_id()))
+        .collect();
+    assert_eq!(
+        vec![(ids[0], device1.id()), (ids[1], device2.id())],
+        ids_and_devices
+    );
+    for notification in &notifications {
+        assert_eq!("msg1", notification.msg());
+        assert_eq!(NotificationState::Pending, notification.state());
+        assert_eq!(0, notification.attempts_count());
+    }
+
+    delete_user(user.uid());
+}
+
+#[test]
+fn due_notifications_are_sent_and_deleted() {
+    let _lock = lock_due_notifications();
+    let (fcm_server, fcm_requests) = start_mock_server(
+        |_request| Some(fcm_response(json!({ "message_id": "1" }))),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let fcm_address = format!("http://{}", fcm_server.address());
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-f21f-0000-0000-000000000001", &connection);
+    let device = insert_device(&user, Some("outbox_token3"), &connection);
+    let due_notification = insert_notification(&user, &device, 100, &connection);
+    let future_notification = insert_notification(&user, &device, 2000, &connection);
+
+    assert_eq!(1, send_due(1000, &config_in_tests(), &fcm_address));
+    assert!(select_notification(due_notification.id(), &connection).is_none());
+    assert!(select_notification(future_notification.id(), &connection).is_some());
+
+    let fcm_requests = fcm_requests.lock().unwrap();
+    assert_eq!(1, fcm_requests.len());
+    let body: JsonValue = serde_json::from_str(&fcm_requests[0].body).unwrap();
+    assert_eq!("outbox_token3", body["to"]);
+    assert_eq!(json!({ "msg_type": "testing" }), body["data"]);
+
+    delete_user(user.uid());
+}
+
+#[test]
+fn failed_notifications_are_retried_with_exponential_backoff_until_they_die() {
+    let _lock = lock_due_notifications();
+    let (fcm_server, fcm_requests) = start_mock_server(
+        |_request| Some(fcm_response(json!({ "error": "Unavailable" }))),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let fcm_address = format!("http://{}", fcm_server.address());
+    let config = config_in_tests()
+        .with_notification_max_attempts(3)
+        .with_notification_retry_base_delay_seconds(10);
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-f21f-0000-0000-000000000002", &connection);
+    let device = insert_device(&user, Some("outbox_token4"), &connection);
+    let notification = insert_notification(&user, &device, 100, &connection);
+
+    assert_eq!(1, send_due(1000, &config, &fcm_address));
+    let notification = select_notification(notification.id(), &connection).unwrap();
+    assert_eq!(NotificationState::Pending, notification.state());
+    assert_eq!(1, notification.attempts_count());
+    assert_eq!(1010, notification.next_attempt_time());
+    assert_eq!(Some("Unavailable"), notification.last_error());
+
+    // Not due yet
+    assert_eq!(0, send_due(1009, &config, &fcm_address));
+
+    // The delay is doubled
+    assert_eq!(1, send_due(1010, &config, &fcm_address));
+    let notification = select_notification(notification.id(), &connection).unwrap();
+    assert_eq!(2, notification.attempts_count());
+    assert_eq!(1030, notification.next_attempt_time());
+
+    // The last attempt
+    assert_eq!(1, send_due(1030, &config, &fcm_address));
+    let notification = select_notification(notification.id(), &connection).unwrap();
+    assert_eq!(NotificationState::Dead, notification.state());
+    assert_eq!(3, notification.attempts_count());
+
+    // Dead notifications are not sent anymore
+    assert_eq!(0, send_due(5000, &config, &fcm_address));
+    assert_eq!(3, fcm_requests.lock().unwrap().len());
+
+    delete_user(user.uid());
+}
+
+#[test]
+fn tokens_rejected_by_fcm_are_pruned_with_their_notifications() {
+    let _lock = lock_due_notifications();
+    let (fcm_server, fcm_requests) = start_mock_server(
+        |_request| Some(fcm_response(json!({ "error": "NotRegistered" }))),
+        testing_hostname::get_spare_hostname1(),
+    );
+    let fcm_address = format!("http://{}", fcm_server.address());
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-f21f-0000-0000-000000000003", &connection);
+    let device1 = insert_device(&user, Some("outbox_token5"), &connection);
+    // The token of the device was pruned after the notification was queued
+    let device2 = insert_device(&user, None, &connection);
+    let notification1 = insert_notification(&user, &device1, 100, &connection);
+    let notification2 = insert_notification(&user, &device2, 100, &connection);
+
+    assert_eq!(2, send_due(1000, &config_in_tests(), &fcm_address));
+    assert!(select_notification(notification1.id(), &connection).is_none());
+    assert!(select_notification(notification2.id(), &connection).is_none());
+    assert!(fcm_token::select_by_device_id(device1.id(), &connection)
+        .unwrap()
+        .is_none());
+    // Nothing is sent to the device without a token
+    assert_eq!(1, fcm_requests.lock().unwrap().len());
+
+    delete_user(user.uid());
+}
+
+#[test]
+fn canonical_tokens_replace_old_ones() {
+    let _lock = lock_due_notifications();
+    let (fcm_server, _fcm_requests) = start_mock_server(
+        |_request| {
+            Some(fcm_response(json!({
+                "message_id": "1",
+                "registration_id": "outbox_token7"
+            })))
+        },
+        testing_hostname::get_spare_hostname1(),
+    );
+    let fcm_address = format!("http://{}", fcm_server.address());
+
+    let connection = dbtesting_utils::testing_connection_for_client_user().unwrap();
+    let user = insert_user("00000000-f21f-0000-0000-000000000004", &connection);
+    let device = insert_device(&user, Some("outbox_token6"), &connection);
+    let notification = insert_notification(&user, &device, 100, &connection);
+
+    assert_eq!(1, send_due(1000, &config_in_tests(), &fcm_address));
+    assert!(select_notification(notification.id(), &connection).is_none());
+    let fcm_token = fcm_token::select_by_device_id(device.id(), &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!("outbox_token7", fcm_token.token_value());
+
+    delete_user(user.uid());
+}
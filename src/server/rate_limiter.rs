@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::config::RateLimit;
+
+use super::constants;
+use super::request_error::RequestError;
+
+/// Buckets which are full are forgotten from time to time, so that the memory
+/// wouldn't be eaten by clients which come and go.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Limits requests to commands according to their budgets in Config.
+/// Each user and each IP has its own budget of requests to a command, which
+/// is replenished evenly during the budget's period (the token bucket algorithm).
+///
+/// Requests are checked before users are authenticated (which requires the DB), so
+/// users are identified by both their IDs and client tokens - otherwise anyone could
+/// exhaust budgets of other users just by knowing their IDs. Clients which try many
+/// different tokens are stopped by the per-IP budgets.
+pub struct RateLimiter {
+    config: Config,
+    state: Mutex<RateLimiterState>,
+}
+
+struct RateLimiterState {
+    buckets: HashMap<BucketKey, TokenBucket>,
+    last_cleanup: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BucketKey {
+    User {
+        cmd: String,
+        user_id: String,
+        client_token: String,
+    },
+    Ip {
+        cmd: String,
+        ip: IpAddr,
+    },
+}
+
+struct TokenBucket {
+    period: Duration,
+    capacity: f64,
+    tokens_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Credentials a request is made with, they aren't verified yet.
+pub struct RequestUser<'a> {
+    pub user_id: &'a str,
+    pub client_token: &'a str,
+}
+
+impl RateLimiter {
+    pub fn new(config: Config) -> Self {
+        RateLimiter {
+            config,
+            state: Mutex::new(RateLimiterState {
+                buckets: HashMap::new(),
+                last_cleanup: Instant::now(),
+            }),
+        }
+    }
+
+    /// Consumes a request from the budgets of the |user| and of the |ip|.
+    /// Fails with the FIELD_STATUS_RATE_LIMITED status when any of the budgets is exhausted,
+    /// in which case nothing is consumed.
+    pub fn check(
+        &self,
+        cmd: &str,
+        user: Option<RequestUser>,
+        ip: IpAddr,
+    ) -> Result<(), RequestError> {
+        self.check_at(cmd, user, ip, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        cmd: &str,
+        user: Option<RequestUser>,
+        ip: IpAddr,
+        now: Instant,
+    ) -> Result<(), RequestError> {
+        let rate_limit = match self.config.rate_limit(cmd) {
+            Some(rate_limit) => rate_limit,
+            None => return Ok(()),
+        };
+
+        let mut keys = vec![(
+            BucketKey::Ip {
+                cmd: cmd.to_owned(),
+                ip,
+            },
+            rate_limit.requests_per_ip(),
+        )];
+        if let Some(user) = user {
+            keys.push((
+                BucketKey::User {
+                    cmd: cmd.to_owned(),
+                    user_id: user.user_id.to_owned(),
+                    client_token: user.client_token.to_owned(),
+                },
+                rate_limit.requests_per_user(),
+            ));
+        }
+
+        let mut state = self.state.lock().expect("Expecting ok mutex");
+        state.maybe_cleanup(now);
+
+        // The IP bucket goes first, so that a client with an exhausted IP budget
+        // wouldn't create new user buckets.
+        for (key, capacity) in &keys {
+            let bucket = state
+                .buckets
+                .entry(key.clone())
+                .or_insert_with(|| TokenBucket::new(rate_limit, *capacity, now));
+            bucket.refill(now);
+            let wait_time = bucket.time_until_token();
+            if wait_time > Duration::from_secs(0) {
+                // Retry-after is rounded up, so that a client wouldn't retry too early
+                let retry_after_seconds = wait_time.as_secs_f64().ceil() as u64;
+                return Err(RequestError::new(
+                    constants::FIELD_STATUS_RATE_LIMITED.to_owned(),
+                    format!("Too many requests to {}", cmd),
+                )
+                .with_retry_after_seconds(retry_after_seconds));
+            }
+        }
+
+        for (key, _) in &keys {
+            if let Some(bucket) = state.buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RateLimiterState {
+    fn maybe_cleanup(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_cleanup) < CLEANUP_INTERVAL {
+            return;
+        }
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(now);
+            bucket.tokens < bucket.capacity
+        });
+        self.last_cleanup = now;
+    }
+}
+
+impl TokenBucket {
+    fn new(rate_limit: &RateLimit, capacity: u32, now: Instant) -> Self {
+        let period = Duration::from_secs(rate_limit.period_seconds().max(1));
+        let capacity = f64::from(capacity);
+        TokenBucket {
+            period,
+            capacity,
+            tokens_per_second: capacity / period.as_secs_f64(),
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.tokens_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn time_until_token(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::from_secs(0)
+        } else if self.tokens_per_second <= 0.0 {
+            // A budget of 0 requests is never replenished
+            self.period
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.tokens_per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./rate_limiter_test.rs"]
+mod rate_limiter_test;
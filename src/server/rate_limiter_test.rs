@@ -0,0 +1,166 @@
+use std::net::IpAddr;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::RateLimiter;
+use super::RequestUser;
+use super::CLEANUP_INTERVAL;
+use crate::config::RateLimit;
+use crate::server::constants;
+use crate::testing_utils::config_in_tests;
+
+const CMD: &str = "/v1/testing/cmd";
+
+fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+fn user<'a>(user_id: &'a str, client_token: &'a str) -> Option<RequestUser<'a>> {
+    Some(RequestUser {
+        user_id,
+        client_token,
+    })
+}
+
+fn rate_limiter(requests_per_user: u32, requests_per_ip: u32) -> RateLimiter {
+    let config = config_in_tests().with_rate_limit(
+        CMD,
+        Some(RateLimit::new(60, requests_per_user, requests_per_ip)),
+    );
+    RateLimiter::new(config)
+}
+
+fn assert_rate_limited(result: Result<(), super::RequestError>, retry_after_seconds: u64) {
+    let error = result.unwrap_err();
+    assert_eq!(constants::FIELD_STATUS_RATE_LIMITED, error.status());
+    assert_eq!(Some(retry_after_seconds), error.retry_after_seconds());
+}
+
+#[test]
+fn cmds_without_budgets_are_not_limited() {
+    let rate_limiter = rate_limiter(1, 1);
+    for _ in 0..100 {
+        rate_limiter
+            .check("/v1/other/cmd", user("uid", "token"), ip("1.1.1.1"))
+            .unwrap();
+    }
+}
+
+#[test]
+fn ip_budget() {
+    let rate_limiter = rate_limiter(100, 3);
+    let now = Instant::now();
+    for _ in 0..3 {
+        rate_limiter
+            .check_at(CMD, None, ip("1.1.1.1"), now)
+            .unwrap();
+    }
+    // A budget of 3 requests per 60 seconds is replenished by 1 request per 20 seconds
+    assert_rate_limited(rate_limiter.check_at(CMD, None, ip("1.1.1.1"), now), 20);
+    // Other IPs have their own budgets
+    rate_limiter
+        .check_at(CMD, None, ip("2.2.2.2"), now)
+        .unwrap();
+    rate_limiter.check_at(CMD, None, ip("::1"), now).unwrap();
+}
+
+#[test]
+fn user_budget() {
+    let rate_limiter = rate_limiter(2, 100);
+    let now = Instant::now();
+    rate_limiter
+        .check_at(CMD, user("uid", "token"), ip("1.1.1.1"), now)
+        .unwrap();
+    rate_limiter
+        .check_at(CMD, user("uid", "token"), ip("2.2.2.2"), now)
+        .unwrap();
+    // Same user from a third IP
+    assert_rate_limited(
+        rate_limiter.check_at(CMD, user("uid", "token"), ip("3.3.3.3"), now),
+        30,
+    );
+    // Requests with someone else's user ID but without the user's token
+    // don't touch the user's budget
+    rate_limiter
+        .check_at(CMD, user("uid", "wrong_token"), ip("3.3.3.3"), now)
+        .unwrap();
+    rate_limiter
+        .check_at(CMD, user("uid2", "token"), ip("3.3.3.3"), now)
+        .unwrap();
+}
+
+#[test]
+fn budget_is_replenished_over_time() {
+    let rate_limiter = rate_limiter(100, 3);
+    let now = Instant::now();
+    for _ in 0..3 {
+        rate_limiter
+            .check_at(CMD, None, ip("1.1.1.1"), now)
+            .unwrap();
+    }
+
+    let later = now + Duration::from_secs(15);
+    assert_rate_limited(rate_limiter.check_at(CMD, None, ip("1.1.1.1"), later), 5);
+
+    let later = now + Duration::from_secs(20);
+    rate_limiter
+        .check_at(CMD, None, ip("1.1.1.1"), later)
+        .unwrap();
+    assert_rate_limited(rate_limiter.check_at(CMD, None, ip("1.1.1.1"), later), 20);
+
+    // Budget is not accumulated beyond its size
+    let much_later = now + Duration::from_secs(60 * 60);
+    for _ in 0..3 {
+        rate_limiter
+            .check_at(CMD, None, ip("1.1.1.1"), much_later)
+            .unwrap();
+    }
+    assert!(rate_limiter
+        .check_at(CMD, None, ip("1.1.1.1"), much_later)
+        .is_err());
+}
+
+#[test]
+fn rejected_requests_do_not_consume_budgets() {
+    let rate_limiter = rate_limiter(1, 2);
+    let now = Instant::now();
+    rate_limiter
+        .check_at(CMD, user("uid", "token"), ip("1.1.1.1"), now)
+        .unwrap();
+    // The user budget is exhausted, the IP budget still has 1 request
+    for _ in 0..10 {
+        assert!(rate_limiter
+            .check_at(CMD, user("uid", "token"), ip("1.1.1.1"), now)
+            .is_err());
+    }
+    rate_limiter
+        .check_at(CMD, None, ip("1.1.1.1"), now)
+        .unwrap();
+}
+
+#[test]
+fn zero_budget_blocks_all_requests() {
+    let rate_limiter = rate_limiter(10, 0);
+    let now = Instant::now();
+    assert_rate_limited(rate_limiter.check_at(CMD, None, ip("1.1.1.1"), now), 60);
+}
+
+#[test]
+fn full_buckets_are_forgotten() {
+    let rate_limiter = rate_limiter(10, 10);
+    let now = Instant::now();
+    rate_limiter
+        .check_at(CMD, user("uid1", "token"), ip("1.1.1.1"), now)
+        .unwrap();
+    rate_limiter
+        .check_at(CMD, user("uid2", "token"), ip("2.2.2.2"), now)
+        .unwrap();
+    assert_eq!(4, rate_limiter.state.lock().unwrap().buckets.len());
+
+    // By then all buckets are full, only the buckets of the new request remain
+    let later = now + CLEANUP_INTERVAL;
+    rate_limiter
+        .check_at(CMD, user("uid1", "token"), ip("1.1.1.1"), later)
+        .unwrap();
+    assert_eq!(2, rate_limiter.state.lock().unwrap().buckets.len());
+}
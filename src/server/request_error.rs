@@ -3,6 +3,7 @@ use std::time::SystemTimeError;
 use crate::db::core::error::Error as DbCoreError;
 use crate::db::core::transaction::TransactionError as DbTransactionError;
 use crate::db::pool::error::Error as DbPoolError;
+use crate::db::pool::error::ErrorKind as DbPoolErrorKind;
 use crate::outside::error::Error as OutsideError;
 use crate::pairing::error::Error as PairingError;
 use crate::server::error::Error as ServerError;
@@ -10,9 +11,11 @@ use crate::server::error::ErrorKind as ServerErrorKind;
 
 use super::constants;
 
+#[derive(Debug)]
 pub struct RequestError {
     status: String,
     error_description: String,
+    retry_after_seconds: Option<u64>,
 }
 
 impl RequestError {
@@ -20,9 +23,16 @@ impl RequestError {
         RequestError {
             status,
             error_description,
+            retry_after_seconds: None,
         }
     }
 
+    /// A hint for the client when it can retry the request.
+    pub fn with_retry_after_seconds(mut self, retry_after_seconds: u64) -> Self {
+        self.retry_after_seconds = Some(retry_after_seconds);
+        self
+    }
+
     pub fn status(&self) -> &str {
         &self.status
     }
@@ -30,6 +40,10 @@ impl RequestError {
     pub fn error_description(&self) -> &str {
         &self.error_description
     }
+
+    pub fn retry_after_seconds(&self) -> Option<u64> {
+        self.retry_after_seconds
+    }
 }
 
 impl From<SystemTimeError> for RequestError {
@@ -52,10 +66,11 @@ impl From<DbCoreError> for RequestError {
 
 impl From<DbPoolError> for RequestError {
     fn from(error: DbPoolError) -> Self {
-        RequestError::new(
-            constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
-            format!("Pool error: {}", error),
-        )
+        let status = match error.kind() {
+            DbPoolErrorKind::BorrowTimeout(_, _) => constants::FIELD_STATUS_SERVER_BUSY,
+            _ => constants::FIELD_STATUS_INTERNAL_ERROR,
+        };
+        RequestError::new(status.to_owned(), format!("Pool error: {}", error))
     }
 }
 
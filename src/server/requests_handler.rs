@@ -1,13 +1,84 @@
+use futures::future::ready;
 use futures::Future;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::pin::Pin;
 
 pub trait RequestsHandler: Send + Sync {
     fn handle(
-        &mut self,
+        &self,
         request: String,
         query: String,
         headers: HashMap<String, String>,
         body: String,
-    ) -> Pin<Box<dyn Future<Output = String> + Send>>;
+        client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>>;
+
+    /// Checks whether the handler can handle requests (used by the readiness probe),
+    /// resolves to a description of the problem if it can't.
+    fn check_readiness(&self) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> {
+        Box::pin(ready(Ok(())))
+    }
+}
+
+/// What a RequestsHandler responds with - a body with an HTTP status code (200 by default)
+/// and, optionally, a content type and other headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerResponse {
+    status_code: u16,
+    content_type: Option<String>,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl HandlerResponse {
+    pub fn new(body: String) -> HandlerResponse {
+        HandlerResponse {
+            status_code: 200,
+            content_type: None,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn with_status_code(mut self, status_code: u16) -> HandlerResponse {
+        self.status_code = status_code;
+        self
+    }
+
+    pub fn with_content_type(mut self, content_type: &str) -> HandlerResponse {
+        self.content_type = Some(content_type.to_owned());
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: String) -> HandlerResponse {
+        self.headers.push((name.to_owned(), value));
+        self
+    }
+
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn into_body(self) -> String {
+        self.body
+    }
+}
+
+impl From<String> for HandlerResponse {
+    fn from(body: String) -> Self {
+        HandlerResponse::new(body)
+    }
 }
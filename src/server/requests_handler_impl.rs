@@ -2,27 +2,46 @@ use percent_encoding::percent_decode;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::future::Future;
+use std::net::IpAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use log::warn;
+use futures::executor;
+use tokio::task;
 
 use crate::config::Config;
+use crate::db::core::migrator;
 use crate::db::pool::connection_pool::ConnectionPool;
 use crate::db::pool::connection_pool::ConnectionType;
+use crate::metrics;
 use crate::outside::http_client::HttpClient;
+use crate::server::cmds::cmd_args::check_no_credentials_in_query;
+use crate::server::cmds::cmd_args::merge_authorization_header_args;
+use crate::server::cmds::cmd_args::merge_json_body_args;
 use crate::server::cmds::cmd_handler::CmdHandleResult;
 
 use super::cmds::cmds_hub::CmdsHub;
 use super::constants;
 use super::error::Error;
+use super::http_status::http_status_code_of;
+use super::http_status::v2_request_to_cmd;
+use super::rate_limiter::RateLimiter;
+use super::rate_limiter::RequestUser;
 use super::request_error::RequestError;
+use super::requests_handler::HandlerResponse;
 use super::requests_handler::RequestsHandler;
 
+// NOTE: hyper gives names of headers in lower case
+const CONTENT_TYPE_HEADER: &str = "content-type";
+const JSON_CONTENT_TYPE: &str = "application/json";
+const RETRY_AFTER_HEADER: &str = "retry-after";
+
 pub struct RequestsHandlerImpl {
     connection_pool: ConnectionPool,
     config: Config,
     http_client: Arc<HttpClient>,
     cmds_hub: Arc<CmdsHub>,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl RequestsHandlerImpl {
@@ -39,6 +58,7 @@ impl RequestsHandlerImpl {
 
         Ok(RequestsHandlerImpl {
             connection_pool: pool,
+            rate_limiter: Arc::new(RateLimiter::new(config.clone())),
             config,
             http_client: Arc::new(HttpClient::new()?),
             cmds_hub: Arc::new(CmdsHub::new(overrides, connection)?),
@@ -46,52 +66,165 @@ impl RequestsHandlerImpl {
     }
 
     fn handle_impl(
-        &mut self,
+        &self,
         request: String,
         query: String,
+        headers: HashMap<String, String>,
         body: String,
+        client_ip: IpAddr,
     ) -> impl Future<Output = CmdHandleResult> {
         let pool = self.connection_pool.clone();
         let config = self.config.clone();
         let http_client = self.http_client.clone();
         let cmds_hub = self.cmds_hub.clone();
+        let rate_limiter = self.rate_limiter.clone();
 
         async move {
-            let args = query_to_args(query)?;
-            cmds_hub
-                .handle(request, args, body, pool, config, http_client)
-                .await
+            let mut args = query_to_args(query)?;
+            if config.reject_query_credentials() {
+                check_no_credentials_in_query(&args)?;
+            }
+            if is_json_content(&headers) {
+                merge_json_body_args(&mut args, &body)?;
+            }
+            merge_authorization_header_args(&mut args, &headers)?;
+
+            let client_ip = client_ip_of(&headers, &config, client_ip);
+            let user = match (
+                args.get(constants::ARG_USER_ID),
+                args.get(constants::ARG_CLIENT_TOKEN),
+            ) {
+                (Some(user_id), Some(client_token)) => Some(RequestUser {
+                    user_id,
+                    client_token,
+                }),
+                _ => None,
+            };
+            rate_limiter.check(&request, user, client_ip)?;
+
+            // Commands work with the DB synchronously, so they are executed on
+            // the blocking threads pool - otherwise they would block the threads
+            // which serve other requests.
+            let cmd_result = task::spawn_blocking(move || {
+                executor::block_on(cmds_hub.handle(request, args, body, pool, config, http_client))
+            })
+            .await;
+            match cmd_result {
+                Ok(cmd_result) => cmd_result,
+                Err(err) => Err(RequestError::new(
+                    constants::FIELD_STATUS_INTERNAL_ERROR.to_owned(),
+                    format!("Command execution failed: {}", err),
+                )),
+            }
         }
     }
 }
 
 impl RequestsHandler for RequestsHandlerImpl {
     fn handle(
-        &mut self,
+        &self,
         request: String,
         query: String,
-        _headers: HashMap<String, String>,
+        headers: HashMap<String, String>,
         body: String,
-    ) -> Pin<Box<dyn Future<Output = String> + Send>> {
-        let response = self.handle_impl(request, query, body);
-        let result = async {
+        client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>> {
+        let (request, with_http_status_codes) = match v2_request_to_cmd(&request) {
+            Some(cmd) => (cmd, true),
+            None => (request, false),
+        };
+        let response = self.handle_impl(request, query, headers, body, client_ip);
+        let result = async move {
             let response = response.await;
-            match response {
-                Ok(response) => response.to_string(),
+            let mut retry_after_seconds = None;
+            let (status_code, response) = match response {
+                Ok(response) => (200, response.to_string()),
                 Err(error) => {
-                    let response = json!({
+                    let mut response = json!({
                         constants::FIELD_NAME_STATUS: error.status(),
                         constants::FIELD_NAME_ERROR_DESCRIPTION: error.error_description()
                     });
+                    retry_after_seconds = error.retry_after_seconds();
+                    if let Some(retry_after_seconds) = retry_after_seconds {
+                        response[constants::FIELD_NAME_RETRY_AFTER_SECONDS] =
+                            json!(retry_after_seconds);
+                    }
                     warn!("Error response: {}", &response);
-                    response.to_string()
+                    metrics::observe_request_error(error.status());
+                    (http_status_code_of(error.status()), response.to_string())
+                }
+            };
+            let mut response = HandlerResponse::new(response).with_content_type(JSON_CONTENT_TYPE);
+            if let Some(retry_after_seconds) = retry_after_seconds {
+                response =
+                    response.with_header(RETRY_AFTER_HEADER, retry_after_seconds.to_string());
+            }
+            if with_http_status_codes {
+                response.with_status_code(status_code)
+            } else {
+                response
+            }
+        };
+        Box::pin(result)
+    }
+
+    fn check_readiness(&self) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> {
+        let mut pool = self.connection_pool.clone();
+        let result = async move {
+            let readiness = task::spawn_blocking(move || {
+                let connection = pool
+                    .borrow_connection()
+                    .map_err(|err| format!("Can't borrow a DB connection: {}", err))?;
+                match migrator::are_migrations_applied(&connection) {
+                    Ok(true) => Ok(()),
+                    Ok(false) => Err("DB migrations are not applied".to_owned()),
+                    Err(err) => Err(format!("Can't check DB migrations: {}", err)),
                 }
+            })
+            .await;
+            match readiness {
+                Ok(readiness) => readiness,
+                Err(err) => Err(format!("Readiness check failed: {}", err)),
             }
         };
         Box::pin(result)
     }
 }
 
+/// Args of requests with JSON content can be passed in the body,
+/// see |merge_json_body_args|.
+fn is_json_content(headers: &HashMap<String, String>) -> bool {
+    match headers.get(CONTENT_TYPE_HEADER) {
+        Some(content_type) => content_type.starts_with(JSON_CONTENT_TYPE),
+        None => false,
+    }
+}
+
+/// When the server is behind a reverse proxy, the IP of a connection is the proxy's IP,
+/// and the IP of the client is in a header set by the proxy.
+/// For headers with lists of IPs (X-Forwarded-For) the first IP is the client's one.
+fn client_ip_of(
+    headers: &HashMap<String, String>,
+    config: &Config,
+    connection_ip: IpAddr,
+) -> IpAddr {
+    let header = match config.client_ip_header() {
+        Some(header) => header.to_lowercase(),
+        None => return connection_ip,
+    };
+    let ip = headers
+        .get(&header)
+        .map(|ips| ips.split(',').next().unwrap_or("").trim().parse::<IpAddr>());
+    match ip {
+        Some(Ok(ip)) => ip,
+        Some(Err(err)) => {
+            warn!("Invalid client IP in header {}: {}", header, err);
+            connection_ip
+        }
+        None => connection_ip,
+    }
+}
+
 fn query_to_args(query: String) -> Result<HashMap<String, String>, RequestError> {
     let mut result = HashMap::new();
     if query.is_empty() {
@@ -137,3 +270,7 @@ fn decode_query_part(part: &str) -> Result<String, RequestError> {
         )),
     }
 }
+
+#[cfg(test)]
+#[path = "./requests_handler_impl_test.rs"]
+mod requests_handler_impl_test;
@@ -0,0 +1,489 @@
+use hyper::Uri;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use crate::config::RateLimit;
+use crate::db::core::app_user;
+use crate::db::core::foodstuff;
+use crate::db::core::testing_util::testing_connection_for_server_user;
+use crate::outside::http_client::HttpClient;
+use crate::server::constants;
+use crate::server::entry_point::METRICS_PATH;
+use crate::server::health;
+use crate::testing_utils::config_in_tests;
+
+use crate::server::cmds::testing_cmds_utils::add_foodstuff;
+use crate::server::cmds::testing_cmds_utils::assert_status;
+use crate::server::cmds::testing_cmds_utils::assert_status_ok;
+use crate::server::cmds::testing_cmds_utils::delete_app_user_with;
+use crate::server::cmds::testing_cmds_utils::list_foodstuffs;
+use crate::server::cmds::testing_cmds_utils::make_raw_request;
+use crate::server::cmds::testing_cmds_utils::make_request;
+use crate::server::cmds::testing_cmds_utils::make_request_with_headers;
+use crate::server::cmds::testing_cmds_utils::register_named_user_return_token;
+use crate::server::cmds::testing_cmds_utils::start_server_with_config;
+use crate::server::cmds::testing_cmds_utils::start_server_with_overrides;
+use crate::server::cmds::utils::db_transaction;
+
+fn authorization_header(value: String) -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    headers.insert("authorization".to_owned(), value);
+    headers
+}
+
+fn update_user_name_url(server_addr: &str, name: &str) -> String {
+    format!(
+        "http://{}{}?{}={}",
+        server_addr,
+        constants::CMD_UPDATE_USER_NAME,
+        constants::ARG_USER_NAME,
+        name
+    )
+}
+
+#[test]
+fn authorization_header_credentials() {
+    let server = start_server_with_overrides(&json!({}));
+
+    let uid = Uuid::from_str("00000000-f21e-0000-0000-000000000000").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let headers = authorization_header(format!("Bearer {}:{}", uid, client_token));
+    let response = make_request_with_headers(
+        &update_user_name_url(server.address(), "newname"),
+        headers,
+        "".to_owned(),
+    );
+    assert_status_ok(&response);
+
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    assert_eq!("newname", user.name());
+
+    // Without credentials
+    let response = make_request(&update_user_name_url(server.address(), "newname2"));
+    assert_status(&response, constants::FIELD_STATUS_PARAM_MISSING);
+
+    // Credentials of the header are checked
+    let headers = authorization_header(format!("Bearer {}:{}", uid, Uuid::new_v4()));
+    let response = make_request_with_headers(
+        &update_user_name_url(server.address(), "newname2"),
+        headers,
+        "".to_owned(),
+    );
+    assert_status(&response, constants::FIELD_STATUS_INVALID_CLIENT_TOKEN);
+}
+
+#[test]
+fn invalid_authorization_header() {
+    let server = start_server_with_overrides(&json!({}));
+
+    let uid = Uuid::from_str("00000000-f21e-0000-0000-000000000001").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let invalid_values = vec![
+        format!("Basic {}:{}", uid, client_token),
+        format!("Bearer {}", client_token),
+        format!("Bearer {}:", uid),
+        "Bearer".to_owned(),
+    ];
+    for value in invalid_values {
+        let response = make_request_with_headers(
+            &update_user_name_url(server.address(), "newname"),
+            authorization_header(value),
+            "".to_owned(),
+        );
+        assert_status(&response, constants::FIELD_STATUS_INVALID_AUTHORIZATION);
+    }
+}
+
+#[test]
+fn query_credentials_rejected_when_configured() {
+    let config = config_in_tests().with_reject_query_credentials(true);
+    let server = start_server_with_config(config, &json!({}));
+
+    let uid = Uuid::from_str("00000000-f21e-0000-0000-000000000002").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    // Registration doesn't need credentials
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}&{}=newname",
+        server.address(),
+        constants::CMD_UPDATE_USER_NAME,
+        constants::ARG_USER_ID,
+        uid,
+        constants::ARG_CLIENT_TOKEN,
+        client_token,
+        constants::ARG_USER_NAME,
+    );
+    let response = make_request(&url);
+    assert_status(&response, constants::FIELD_STATUS_INVALID_AUTHORIZATION);
+
+    let headers = authorization_header(format!("Bearer {}:{}", uid, client_token));
+    let response = make_request_with_headers(
+        &update_user_name_url(server.address(), "newname"),
+        headers,
+        "".to_owned(),
+    );
+    assert_status_ok(&response);
+}
+
+#[test]
+fn v1_responses_have_ok_http_status_and_json_content_type() {
+    let server = start_server_with_overrides(&json!({}));
+
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server.address(),
+        constants::CMD_UPDATE_USER_NAME,
+        constants::ARG_USER_ID,
+        Uuid::new_v4(),
+        constants::ARG_CLIENT_TOKEN,
+        Uuid::new_v4(),
+    );
+    let response = make_raw_request(&url);
+    assert_eq!(200, response.status_code);
+    assert_eq!(
+        Some(&"application/json".to_owned()),
+        response.headers.get("content-type")
+    );
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_status(&response, constants::FIELD_STATUS_USER_NOT_FOUND);
+
+    let url = format!("http://{}/v1/unknown/cmd", server.address());
+    assert_eq!(200, make_raw_request(&url).status_code);
+}
+
+#[test]
+fn v2_responses_have_http_status_codes() {
+    let server = start_server_with_overrides(&json!({}));
+
+    let uid = Uuid::from_str("00000000-f21e-0000-0000-000000000003").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let v2_url = |cmd: &str, args: &str| {
+        format!(
+            "http://{}{}?{}",
+            server.address(),
+            cmd.replacen("/v1/", "/v2/", 1),
+            args
+        )
+    };
+    let credentials = format!(
+        "{}={}&{}={}",
+        constants::ARG_USER_ID,
+        uid,
+        constants::ARG_CLIENT_TOKEN,
+        client_token
+    );
+
+    let response = make_raw_request(&v2_url(
+        constants::CMD_UPDATE_USER_NAME,
+        &format!("{}&{}=newname", credentials, constants::ARG_USER_NAME),
+    ));
+    assert_eq!(200, response.status_code);
+    assert_eq!(
+        Some(&"application/json".to_owned()),
+        response.headers.get("content-type")
+    );
+    let conn = testing_connection_for_server_user().unwrap();
+    let user = app_user::select_by_uid(&uid, &conn).unwrap().unwrap();
+    assert_eq!("newname", user.name());
+
+    // No name
+    let response = make_raw_request(&v2_url(constants::CMD_UPDATE_USER_NAME, &credentials));
+    assert_eq!(400, response.status_code);
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_status(&response, constants::FIELD_STATUS_PARAM_MISSING);
+
+    // Invalid client token
+    let response = make_raw_request(&v2_url(
+        constants::CMD_UPDATE_USER_NAME,
+        &format!(
+            "{}={}&{}={}&{}=newname",
+            constants::ARG_USER_ID,
+            uid,
+            constants::ARG_CLIENT_TOKEN,
+            Uuid::new_v4(),
+            constants::ARG_USER_NAME
+        ),
+    ));
+    assert_eq!(401, response.status_code);
+
+    // Not existing foodstuff
+    let response = make_raw_request(&v2_url(
+        constants::CMD_UNLIST_FOODSTUFF,
+        &format!("{}&{}=1", credentials, constants::ARG_FOODSTUFF_ID),
+    ));
+    assert_eq!(404, response.status_code);
+
+    let response = make_raw_request(&v2_url("/v1/unknown/cmd", ""));
+    assert_eq!(404, response.status_code);
+}
+
+#[test]
+fn blocked_command_does_not_block_other_requests() {
+    const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+    let server = start_server_with_overrides(&json!({}));
+
+    let uid1 = Uuid::from_str("00000000-f21e-0000-0000-000000000005").unwrap();
+    let uid2 = Uuid::from_str("00000000-f21e-0000-0000-000000000006").unwrap();
+    delete_app_user_with(&uid1);
+    delete_app_user_with(&uid2);
+
+    let gpuid1 = format!("{}{}", uid1, "gpuid");
+    let gpuid2 = format!("{}{}", uid2, "gpuid");
+    let client_token1 = register_named_user_return_token(server.address(), &uid1, &gpuid1, "name");
+    let client_token2 = register_named_user_return_token(server.address(), &uid2, &gpuid2, "name");
+
+    // Foodstuffs of the first user are locked by another DB connection,
+    // so a command adding a foodstuff for the user will be blocked until the lock
+    // is released.
+    let conn = testing_connection_for_server_user().unwrap();
+    let user1 = app_user::select_by_uid(&uid1, &conn).unwrap().unwrap();
+    let (locked_sender, locked_receiver) = mpsc::channel();
+    let (release_sender, release_receiver) = mpsc::channel::<()>();
+    let locker = thread::spawn(move || {
+        let conn = testing_connection_for_server_user().unwrap();
+        db_transaction(&conn, || {
+            foodstuff::lock_for_modification(user1.id(), &conn)?;
+            locked_sender.send(()).unwrap();
+            // The lock is released either when the test says so, or by timeout,
+            // so that the test wouldn't hang if the server is blocked.
+            let _ = release_receiver.recv_timeout(LOCK_TIMEOUT);
+            Ok(())
+        })
+        .unwrap();
+    });
+    locked_receiver.recv().unwrap();
+
+    let server_addr = server.address().to_owned();
+    let uid1 = uid1.to_string();
+    let blocked_request = thread::spawn(move || {
+        add_foodstuff(
+            &server_addr,
+            &client_token1,
+            &uid1,
+            1,
+            "apple",
+            "0.4",
+            "0.1",
+            "9.8",
+            "47",
+        );
+    });
+    // Let the request to reach the lock
+    thread::sleep(Duration::from_millis(500));
+
+    let start = Instant::now();
+    let response = list_foodstuffs(server.address(), &client_token2, &uid2.to_string());
+    let elapsed = start.elapsed();
+    assert_status_ok(&response);
+
+    release_sender.send(()).unwrap();
+    locker.join().unwrap();
+    blocked_request.join().unwrap();
+    assert!(
+        elapsed < LOCK_TIMEOUT / 2,
+        "Request took {:?}, it must've been blocked by another request",
+        elapsed
+    );
+}
+
+#[test]
+fn rate_limited_requests() {
+    let config = config_in_tests().with_rate_limit(
+        constants::CMD_START_PAIRING,
+        Some(RateLimit::new(60, 10, 1)),
+    );
+    let server = start_server_with_config(config, &json!({}));
+    let url = format!(
+        "http://{}{}",
+        server.address(),
+        constants::CMD_START_PAIRING.replacen("/v1/", "/v2/", 1)
+    );
+
+    // The first request fails because of missing credentials, but still uses the budget
+    let response = make_raw_request(&url);
+    assert_ne!(429, response.status_code);
+
+    let response = make_raw_request(&url);
+    assert_eq!(429, response.status_code);
+    assert_eq!(Some(&"60".to_owned()), response.headers.get("retry-after"));
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_status(&response, constants::FIELD_STATUS_RATE_LIMITED);
+    assert_eq!(60, response[constants::FIELD_NAME_RETRY_AFTER_SECONDS]);
+}
+
+#[test]
+fn rate_limits_use_client_ip_header() {
+    let config = config_in_tests()
+        .with_rate_limit(
+            constants::CMD_START_PAIRING,
+            Some(RateLimit::new(60, 10, 1)),
+        )
+        .with_client_ip_header(Some("X-Forwarded-For".to_owned()));
+    let server = start_server_with_config(config, &json!({}));
+    let url = format!(
+        "http://{}{}",
+        server.address(),
+        constants::CMD_START_PAIRING
+    );
+    let forwarded_for = |ip: &str| {
+        let mut headers = HashMap::new();
+        headers.insert("x-forwarded-for".to_owned(), ip.to_owned());
+        headers
+    };
+
+    let response =
+        make_request_with_headers(&url, forwarded_for("1.1.1.1, 10.0.0.1"), "".to_owned());
+    assert_ne!(
+        constants::FIELD_STATUS_RATE_LIMITED,
+        response[constants::FIELD_NAME_STATUS]
+    );
+    let response =
+        make_request_with_headers(&url, forwarded_for("2.2.2.2, 10.0.0.1"), "".to_owned());
+    assert_ne!(
+        constants::FIELD_STATUS_RATE_LIMITED,
+        response[constants::FIELD_NAME_STATUS]
+    );
+    let response =
+        make_request_with_headers(&url, forwarded_for("1.1.1.1, 10.0.0.1"), "".to_owned());
+    assert_status(&response, constants::FIELD_STATUS_RATE_LIMITED);
+}
+
+#[test]
+fn ready_when_db_is_available_and_migrated() {
+    let server = start_server_with_overrides(&json!({}));
+
+    let url = format!("http://{}{}", server.address(), health::READYZ_PATH);
+    let response = make_raw_request(&url);
+    assert_eq!(200, response.status_code);
+    let response: JsonValue = serde_json::from_str(&response.body).unwrap();
+    assert_status_ok(&response);
+    assert_eq!(
+        health::BUILD_VERSION,
+        response[constants::FIELD_NAME_VERSION].as_str().unwrap()
+    );
+}
+
+#[test]
+fn metrics_are_served() {
+    let server = start_server_with_overrides(&json!({}));
+
+    // A request without required args
+    let url = format!(
+        "http://{}{}",
+        server.address(),
+        constants::CMD_REGISTER_USER
+    );
+    let response = make_request(&url);
+    assert_status(&response, constants::FIELD_STATUS_PARAM_MISSING);
+
+    let url = format!("http://{}{}", server.address(), METRICS_PATH);
+    let response = make_raw_request(&url);
+    assert_eq!(200, response.status_code);
+    let metrics = response.body;
+    let cmd_requests = format!(
+        "recipe_calculator_cmd_requests_total{{cmd=\"{}\"}}",
+        constants::CMD_REGISTER_USER
+    );
+    let errors = format!(
+        "recipe_calculator_request_errors_total{{status=\"{}\"}}",
+        constants::FIELD_STATUS_PARAM_MISSING
+    );
+    assert!(metrics.contains(&cmd_requests), "{}", metrics);
+    assert!(metrics.contains(&errors), "{}", metrics);
+    assert!(metrics.contains("recipe_calculator_db_pool_connections{pool=\"client\""));
+}
+
+/// Load test, compares throughput of sequential and concurrent requests.
+/// Run it with:
+/// cargo test load_test_concurrent_requests -- --ignored --nocapture
+#[test]
+#[ignore]
+fn load_test_concurrent_requests() {
+    const THREADS: usize = 8;
+    const REQUESTS_PER_THREAD: usize = 50;
+
+    let server = start_server_with_overrides(&json!({}));
+
+    let uid = Uuid::from_str("00000000-f21e-0000-0000-000000000004").unwrap();
+    let gpuid = format!("{}{}", uid, "gpuid");
+    delete_app_user_with(&uid);
+
+    let client_token = register_named_user_return_token(server.address(), &uid, &gpuid, "name");
+    let uid = uid.to_string();
+    for id in 0..20 {
+        add_foodstuff(
+            server.address(),
+            &client_token,
+            &uid,
+            id,
+            "apple",
+            "0.4",
+            "0.1",
+            "9.8",
+            "47",
+        );
+    }
+    let url = format!(
+        "http://{}{}?{}={}&{}={}",
+        server.address(),
+        constants::CMD_LIST_FOODSTUFFS,
+        constants::ARG_USER_ID,
+        uid,
+        constants::ARG_CLIENT_TOKEN,
+        client_token
+    );
+    // A client and a runtime are reused by each thread, so that their creation
+    // wouldn't dominate in measurements.
+    let make_requests = |url: Uri, count: usize| {
+        let http_client = HttpClient::new().unwrap();
+        let mut runtime = Runtime::new().unwrap();
+        for _ in 0..count {
+            let response = runtime.block_on(http_client.req_get(url.clone())).unwrap();
+            assert_eq!(200, response.status_code);
+        }
+    };
+
+    let start = Instant::now();
+    make_requests(Uri::from_str(&url).unwrap(), THREADS * REQUESTS_PER_THREAD);
+    let sequential = start.elapsed();
+
+    let start = Instant::now();
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let url = Uri::from_str(&url).unwrap();
+            thread::spawn(move || make_requests(url, REQUESTS_PER_THREAD))
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let concurrent = start.elapsed();
+
+    let requests = (THREADS * REQUESTS_PER_THREAD) as f64;
+    println!(
+        "Sequential: {:.0} requests/sec, concurrent ({} threads): {:.0} requests/sec",
+        requests / sequential.as_secs_f64(),
+        THREADS,
+        requests / concurrent.as_secs_f64()
+    );
+}
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::future::Future;
+use std::net::IpAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use crate::server::requests_handler::HandlerResponse;
 use crate::server::requests_handler::RequestsHandler;
 
 pub struct TestingMockServer<Responder>
@@ -39,12 +41,13 @@ where
     Responder: Fn(&FullRequest) -> Option<String> + Send + Sync,
 {
     fn handle(
-        &mut self,
+        &self,
         request: String,
         query: String,
         headers: HashMap<String, String>,
         body: String,
-    ) -> Pin<Box<dyn Future<Output = String> + Send>> {
+        _client_ip: IpAddr,
+    ) -> Pin<Box<dyn Future<Output = HandlerResponse> + Send>> {
         let req = FullRequest {
             request,
             query,
@@ -56,6 +59,6 @@ where
             None => "".to_owned(),
         };
         self.received_requests.lock().unwrap().push(req);
-        Box::pin(futures::future::ready(response))
+        Box::pin(futures::future::ready(response.into()))
     }
 }
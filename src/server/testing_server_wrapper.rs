@@ -3,10 +3,13 @@ use futures::future::Future;
 use futures::future::FutureExt;
 use std::sync::MutexGuard;
 use std::thread;
+use std::time::Duration;
 
 use crate::server::entry_point;
 use crate::server::requests_handler::RequestsHandler;
 
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
 // Wrapper for server started by 'entry_point::start_server'.
 // Starts server on background thread (so that tests would be blocked by the server), stops
 // server in its destructor.
@@ -18,7 +21,11 @@ pub struct ServerWrapper {
 }
 
 impl ServerWrapper {
-    fn new<RH>(requests_handler: RH, address: MutexGuard<'static, String>) -> ServerWrapper
+    fn new<RH>(
+        requests_handler: RH,
+        address: MutexGuard<'static, String>,
+        drain_timeout: Duration,
+    ) -> ServerWrapper
     where
         RH: RequestsHandler + 'static,
     {
@@ -38,7 +45,12 @@ impl ServerWrapper {
         thread::spawn(move || {
             start_event_sender.send(()).unwrap();
             let finish_cmd_receiver = finish_cmd_receiver.map(|_| ());
-            entry_point::start_server(&sock_address, finish_cmd_receiver, requests_handler);
+            entry_point::start_server(
+                &sock_address,
+                finish_cmd_receiver,
+                drain_timeout,
+                requests_handler,
+            );
             finish_event_sender.send(()).unwrap();
         });
 
@@ -81,5 +93,16 @@ pub fn start_server<RH>(requests_handler: RH, address: MutexGuard<'static, Strin
 where
     RH: RequestsHandler + 'static,
 {
-    ServerWrapper::new(requests_handler, address)
+    start_server_with_drain_timeout(requests_handler, address, DEFAULT_DRAIN_TIMEOUT)
+}
+
+pub fn start_server_with_drain_timeout<RH>(
+    requests_handler: RH,
+    address: MutexGuard<'static, String>,
+    drain_timeout: Duration,
+) -> ServerWrapper
+where
+    RH: RequestsHandler + 'static,
+{
+    ServerWrapper::new(requests_handler, address, drain_timeout)
 }